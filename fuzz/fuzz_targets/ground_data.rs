@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ragnarok_bytes::{ByteStream, FromBytes, TextEncoding};
+use ragnarok_formats::map::GroundData;
+use ragnarok_formats::version::InternalVersion;
+
+fuzz_target!(|data: &[u8]| {
+    let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(data).with_text_encoding(TextEncoding::Windows949);
+    let _ = GroundData::from_bytes(&mut byte_stream);
+});
@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ragnarok_bytes::ByteStream;
+use ragnarok_packets::handler::{HandlerResult, NoPacketCallback, PacketHandler};
+
+fn packet_handler() -> PacketHandler<(), (), NoPacketCallback> {
+    let mut packet_handler = PacketHandler::default();
+    packet_handler.register_all_noop();
+    packet_handler
+}
+
+thread_local! {
+    static PACKET_HANDLER: std::cell::RefCell<PacketHandler<(), (), NoPacketCallback>> = std::cell::RefCell::new(packet_handler());
+}
+
+fuzz_target!(|data: &[u8]| {
+    PACKET_HANDLER.with_borrow_mut(|packet_handler| {
+        let mut byte_stream = ByteStream::<()>::without_metadata(data);
+
+        while !byte_stream.is_empty() {
+            match packet_handler.process_one(&mut byte_stream) {
+                HandlerResult::Ok(()) | HandlerResult::SkippedPacket => {}
+                HandlerResult::UnhandledPacket | HandlerResult::PacketCutOff | HandlerResult::InternalError(_) => break,
+            }
+        }
+    });
+});
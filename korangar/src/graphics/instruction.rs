@@ -170,6 +170,8 @@ pub struct ModelInstruction {
     pub model_matrix: Matrix4<f32>,
     pub vertex_offset: usize,
     pub vertex_count: usize,
+    /// Offset of the texture coordinates, relative to the size of the texture.
+    pub texture_offset: Vector2<f32>,
 }
 
 #[derive(Clone, Debug)]
@@ -104,7 +104,7 @@ impl QuestIcon {
         let effect_id = quest_effect.effect as usize;
         let texture = texture_loader
             .get(&format!(
-                "À¯ÀúÀÎÅÍÆäÀÌ½º\\minimap\\quest_{}_{}.bmp",
+                "유저인터페이스\\minimap\\quest_{}_{}.bmp",
                 effect_id, 1 /* 1 - 3 */
             ))
             .unwrap();
@@ -28,6 +28,8 @@ const INITIAL_INSTRUCTION_SIZE: usize = 256;
 struct InstanceData {
     world: [[f32; 4]; 4],
     inv_world: [[f32; 4]; 4],
+    texture_offset: [f32; 2],
+    padding: [u32; 2],
 }
 
 pub(crate) struct ForwardModelDrawer {
@@ -241,6 +243,8 @@ impl Prepare for ForwardModelDrawer {
                     .unwrap_or(Matrix4::identity())
                     .transpose()
                     .into(),
+                texture_offset: instruction.texture_offset.into(),
+                padding: Default::default(),
             });
 
             self.instance_indices.push(instance_index as u32);
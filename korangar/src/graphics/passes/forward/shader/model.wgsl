@@ -30,6 +30,7 @@ struct PointLight {
 struct InstanceData {
     world: mat4x4<f32>,
     inv_world: mat4x4<f32>,
+    texture_offset: vec2<f32>,
 }
 
 struct TileLightIndices {
@@ -42,6 +43,9 @@ struct VertexOutput {
     @location(1) normal: vec3<f32>,
     @location(2) texture_coordinates: vec2<f32>,
     @location(3) color: vec3<f32>,
+    @location(4) lightmap_coordinates: vec2<f32>,
+    @location(5) @interpolate(flat) texture_rectangle: vec4<f32>,
+    @location(6) @interpolate(flat) texture_offset: vec2<f32>,
 }
 
 const TILE_SIZE: u32 = 16;
@@ -66,7 +70,9 @@ fn vs_main(
     @location(2) texture_coordinates: vec2<f32>,
     @location(3) color: vec3<f32>,
     @location(4) wind_affinity: f32,
-    @location(5) instance_id: u32
+    @location(5) instance_id: u32,
+    @location(6) lightmap_coordinates: vec2<f32>,
+    @location(7) texture_rectangle: vec4<f32>
 ) -> VertexOutput {
     let instance = instance_data[instance_id];
 
@@ -81,13 +87,21 @@ fn vs_main(
     output.normal = normalize((instance.inv_world * vec4<f32>(normal, 0.0)).xyz);
     output.texture_coordinates = texture_coordinates;
     output.color = color;
+    output.lightmap_coordinates = lightmap_coordinates;
+    output.texture_rectangle = texture_rectangle;
+    output.texture_offset = instance.texture_offset;
     return output;
 }
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    let diffuse_color = textureSample(texture, texture_sampler, input.texture_coordinates);
-    let alpha_channel = textureSample(texture, nearest_sampler, input.texture_coordinates).a;
+    // The gradients of the unwrapped coordinates are used, so there is no seam
+    // where animated texture coordinates wrap around.
+    let texture_coordinates = animate_texture_coordinates(input.texture_coordinates, input.texture_rectangle, input.texture_offset);
+    let texture_dx = dpdx(input.texture_coordinates);
+    let texture_dy = dpdy(input.texture_coordinates);
+    let diffuse_color = textureSampleGrad(texture, texture_sampler, texture_coordinates, texture_dx, texture_dy);
+    let alpha_channel = textureSampleGrad(texture, nearest_sampler, texture_coordinates, texture_dx, texture_dy).a;
 
     // Calculate which tile this fragment belongs to
     let pixel_position = vec2<u32>(floor(input.position.xy));
@@ -116,8 +130,14 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     let visibility = select(0.0, 1.0, light_coords.z - bias < shadow_map_depth);
     let directional_light = light_percent * directional_light.color.rgb * base_color * visibility;
 
-    // Combine base color, ambient light and directional light
-    var final_color = base_color * global_uniforms.ambient_color.rgb + directional_light;
+    // Baked lightmap, negative coordinates mean that the vertex has no lightmap
+    let has_lightmap = input.lightmap_coordinates.x >= 0.0;
+    let lightmap = textureSampleLevel(texture, linear_sampler, input.lightmap_coordinates, 0.0);
+    let lightmap_shadow = select(1.0, lightmap.a, has_lightmap);
+    let lightmap_color = select(vec3<f32>(0.0), lightmap.rgb, has_lightmap);
+
+    // Combine base color, ambient light, directional light and lightmap
+    var final_color = (base_color * global_uniforms.ambient_color.rgb + directional_light) * lightmap_shadow + lightmap_color;
 
     // Point lights
     for (var index = 0u; index < light_count; index++) {
@@ -143,6 +163,17 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     return vec4<f32>(final_color, alpha_channel);
 }
 
+// Offsets the texture coordinates and wraps them around inside of the area of
+// the texture in the texture atlas.
+fn animate_texture_coordinates(coordinates: vec2<f32>, rectangle: vec4<f32>, offset: vec2<f32>) -> vec2<f32> {
+    if (all(offset == vec2<f32>(0.0))) {
+        return coordinates;
+    }
+
+    let relative_coordinates = (coordinates - rectangle.xy) / rectangle.zw + offset;
+    return rectangle.xy + fract(relative_coordinates) * rectangle.zw;
+}
+
 // Quadratic Attenuation with smooth falloff
 fn calculate_attenuation(distance: f32, range: f32) -> f32 {
     let effective_distance = min(distance, range);
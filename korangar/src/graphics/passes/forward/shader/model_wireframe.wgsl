@@ -17,6 +17,7 @@ struct GlobalUniforms {
 struct InstanceData {
     world: mat4x4<f32>,
     inv_world: mat4x4<f32>,
+    texture_offset: vec2<f32>,
 }
 
 struct TileLightIndices {
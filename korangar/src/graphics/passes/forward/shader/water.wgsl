@@ -21,11 +21,23 @@ struct VertexOutput {
 
 @group(0) @binding(0) var<uniform> global_uniforms: GlobalUniforms;
 
+const TILE_SIZE: f32 = 10.0;
+const FRAMES_PER_SECOND: f32 = 60.0;
+
 @vertex
-fn vs_main(@location(0) position: vec3<f32>) -> VertexOutput {
+fn vs_main(@location(0) position: vec3<f32>, @location(1) wave: vec3<f32>) -> VertexOutput {
+    let wave_height = wave.x;
+    let wave_speed = wave.y;
+    let wave_pitch = wave.z;
+
+    // The wave advances by its speed every frame and neighboring tiles are
+    // offset by the pitch, both in degrees.
+    let tile_offset = (position.x + position.z) / TILE_SIZE;
+    let phase = radians(global_uniforms.animation_timer * FRAMES_PER_SECOND * wave_speed + tile_offset * wave_pitch);
+
     let adjusted_position = vec3<f32>(
         position.x,
-        position.y + sin(global_uniforms.animation_timer + position.x + position.z),
+        position.y + wave_height * sin(phase),
         position.z
     );
     let world_position = vec4<f32>(adjusted_position, 1.0);
@@ -12,6 +12,11 @@ pub struct ModelVertex {
     pub texture_coordinates: [f32; 2],
     pub color: [f32; 3],
     pub wind_affinity: f32,
+    /// Negative coordinates are used for vertices without a lightmap.
+    pub lightmap_coordinates: [f32; 2],
+    /// Position and size of the texture inside of the texture atlas, so
+    /// animated texture coordinates can wrap around inside of it.
+    pub texture_rectangle: [f32; 4],
 }
 
 impl ModelVertex {
@@ -21,16 +26,29 @@ impl ModelVertex {
         texture_coordinates: Vector2<f32>,
         color: Color,
         wind_affinity: f32,
+        lightmap_coordinates: Option<Vector2<f32>>,
     ) -> Self {
+        let lightmap_coordinates = match lightmap_coordinates {
+            Some(coordinates) => [coordinates.x, coordinates.y],
+            None => [-1.0, -1.0],
+        };
+
         Self {
             position: [position.x, position.y, position.z],
             normal: [normal.x, normal.y, normal.z],
             texture_coordinates: [texture_coordinates.x, texture_coordinates.y],
             color: [color.red, color.green, color.blue],
             wind_affinity,
+            lightmap_coordinates,
+            texture_rectangle: [0.0; 4],
         }
     }
 
+    pub fn with_texture_rectangle(mut self, position: Vector2<f32>, size: Vector2<f32>) -> Self {
+        self.texture_rectangle = [position.x, position.y, size.x, size.y];
+        self
+    }
+
     pub fn buffer_layout() -> VertexBufferLayout<'static> {
         static ATTRIBUTES: &[VertexAttribute] = &vertex_attr_array!(
                 0 => Float32x3,
@@ -38,6 +56,8 @@ impl ModelVertex {
                 2 => Float32x2,
                 3 => Float32x3,
                 4 => Float32,
+                6 => Float32x2,
+                7 => Float32x4,
         );
 
         VertexBufferLayout {
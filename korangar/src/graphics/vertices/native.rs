@@ -12,11 +12,25 @@ pub struct NativeModelVertex {
     pub texture_index: i32,
     pub color: Color,
     pub wind_affinity: f32,
+    #[new(default)]
+    pub lightmap_coordinates: Option<Vector2<f32>>,
 }
 
 impl NativeModelVertex {
-    fn convert_to_vertex(self, texture_mapping: &[AtlasAllocation]) -> ModelVertex {
+    pub fn with_lightmap_coordinates(mut self, lightmap_coordinates: Option<Vector2<f32>>) -> Self {
+        self.lightmap_coordinates = lightmap_coordinates;
+        self
+    }
+
+    fn convert_to_vertex(self, texture_mapping: &[AtlasAllocation], lightmap_mapping: Option<AtlasAllocation>) -> ModelVertex {
         let allocation = texture_mapping[self.texture_index as usize];
+        let lightmap_coordinates = self
+            .lightmap_coordinates
+            .zip(lightmap_mapping)
+            .map(|(coordinates, allocation)| allocation.map_to_atlas(coordinates));
+
+        let texture_position = allocation.map_to_atlas(Vector2::new(0.0, 0.0));
+        let texture_size = allocation.map_to_atlas(Vector2::new(1.0, 1.0)) - texture_position;
 
         ModelVertex::new(
             self.position,
@@ -24,10 +38,16 @@ impl NativeModelVertex {
             allocation.map_to_atlas(self.texture_coordinates),
             self.color,
             self.wind_affinity,
+            lightmap_coordinates,
         )
+        .with_texture_rectangle(texture_position, texture_size)
     }
 
-    pub fn to_vertices(mut native_vertices: Vec<NativeModelVertex>, texture_mapping: &[AtlasAllocation]) -> Vec<ModelVertex> {
+    pub fn to_vertices(
+        mut native_vertices: Vec<NativeModelVertex>,
+        texture_mapping: &[AtlasAllocation],
+        lightmap_mapping: Option<AtlasAllocation>,
+    ) -> Vec<ModelVertex> {
         let mut vertices = Vec::new();
         let mut drain_iterator = native_vertices.drain(..);
 
@@ -39,9 +59,9 @@ impl NativeModelVertex {
             second_partial.normal = second_partial.normal.normalize();
             third_partial.normal = third_partial.normal.normalize();
 
-            vertices.push(first_partial.convert_to_vertex(texture_mapping));
-            vertices.push(second_partial.convert_to_vertex(texture_mapping));
-            vertices.push(third_partial.convert_to_vertex(texture_mapping));
+            vertices.push(first_partial.convert_to_vertex(texture_mapping, lightmap_mapping));
+            vertices.push(second_partial.convert_to_vertex(texture_mapping, lightmap_mapping));
+            vertices.push(third_partial.convert_to_vertex(texture_mapping, lightmap_mapping));
         }
 
         vertices
@@ -6,16 +6,30 @@ use wgpu::{vertex_attr_array, VertexAttribute, VertexBufferLayout, VertexStepMod
 #[derive(Default, Debug, Clone, Copy, Zeroable, Pod)]
 pub struct WaterVertex {
     pub position: [f32; 3],
+    /// Height, speed and pitch of the waves.
+    pub wave: [f32; 3],
 }
 
 impl WaterVertex {
+    #[cfg(feature = "debug")]
     pub fn new(position: Point3<f32>) -> Self {
-        Self { position: position.into() }
+        Self {
+            position: position.into(),
+            wave: Default::default(),
+        }
+    }
+
+    pub fn with_wave(position: Point3<f32>, wave_height: f32, wave_speed: f32, wave_pitch: f32) -> Self {
+        Self {
+            position: position.into(),
+            wave: [wave_height, wave_speed, wave_pitch],
+        }
     }
 
     pub fn buffer_layout() -> VertexBufferLayout<'static> {
         static ATTRIBUTES: &[VertexAttribute] = &vertex_attr_array!(
             0 => Float32x3,
+            1 => Float32x3,
         );
 
         VertexBufferLayout {
@@ -3,7 +3,8 @@ use korangar_interface::event::ClickAction;
 use korangar_interface::ElementEvent;
 use korangar_networking::ShopItem;
 use ragnarok_packets::{
-    AccountId, BuyOrSellOption, CharacterId, CharacterServerInformation, EntityId, HotbarSlot, ShopId, SoldItemInformation, TilePosition,
+    AccountId, BuyOrSellOption, CharacterId, CharacterServerInformation, EntityId, HotbarSlot, PartyId, ShopId, SoldItemInformation,
+    TilePosition,
 };
 
 use crate::interface::application::{InterfaceSettings, InternalThemeKind};
@@ -33,6 +34,7 @@ pub enum UserEvent {
     OpenGraphicsSettingsWindow,
     OpenAudioSettingsWindow,
     OpenFriendsWindow,
+    OpenPartyWindow,
     ToggleShowInterface,
     SetThemeFile {
         theme_file: String,
@@ -74,6 +76,22 @@ pub enum UserEvent {
         account_id: AccountId,
         character_id: CharacterId,
     },
+    CreateParty(String),
+    InviteToParty(String),
+    LeaveParty,
+    ExpelPartyMember {
+        account_id: AccountId,
+        name: String,
+    },
+    ChangePartyLeader {
+        account_id: AccountId,
+    },
+    RejectPartyInvitation {
+        party_id: PartyId,
+    },
+    AcceptPartyInvitation {
+        party_id: PartyId,
+    },
     BuyItems {
         items: Vec<ShopItem<u32>>,
     },
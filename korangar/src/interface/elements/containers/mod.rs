@@ -6,6 +6,7 @@ mod hotbar;
 mod inventory;
 #[cfg(feature = "debug")]
 mod packet;
+mod party;
 mod skill_tree;
 
 pub use self::character::CharacterPreview;
@@ -16,4 +17,5 @@ pub use self::hotbar::HotbarContainer;
 pub use self::inventory::InventoryContainer;
 #[cfg(feature = "debug")]
 pub use self::packet::{PacketHistoryCallback, PacketHistoryRemote, PacketView};
+pub use self::party::PartyView;
 pub use self::skill_tree::SkillTreeContainer;
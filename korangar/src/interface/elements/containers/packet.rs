@@ -10,9 +10,9 @@ use korangar_interface::event::{ChangeEvent, HoverInformation};
 use korangar_interface::layout::PlacementResolver;
 use korangar_interface::size_bound;
 use korangar_interface::state::{PlainRemote, Remote, RemoteClone};
-use ragnarok_bytes::{ByteStream, ConversionError, ConversionResult, FromBytes};
+use ragnarok_bytes::{ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, FromBytes};
 use ragnarok_packets::handler::PacketCallback;
-use ragnarok_packets::{Packet, PacketHeader};
+use ragnarok_packets::{Packet, PacketHeader, PacketLength, PacketVersion};
 
 use crate::input::MouseInputMode;
 use crate::interface::application::InterfaceSettings;
@@ -30,12 +30,18 @@ impl Packet for UnknownPacket {
     const HEADER: PacketHeader = PacketHeader(0);
     const IS_PING: bool = false;
 
-    fn payload_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn length(packet_version: PacketVersion) -> PacketLength {
+        let _ = packet_version;
+        unimplemented!()
+    }
+
+    fn payload_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let _ = byte_stream;
         unimplemented!()
     }
 
-    fn payload_to_bytes(&self) -> ConversionResult<Vec<u8>> {
+    fn payload_to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        let _ = byte_writer;
         unimplemented!()
     }
 
@@ -75,12 +81,18 @@ impl Packet for ErrorPacket {
     const HEADER: PacketHeader = PacketHeader(0);
     const IS_PING: bool = false;
 
-    fn payload_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn length(packet_version: PacketVersion) -> PacketLength {
+        let _ = packet_version;
+        unimplemented!()
+    }
+
+    fn payload_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let _ = byte_stream;
         unimplemented!()
     }
 
-    fn payload_to_bytes(&self) -> ConversionResult<Vec<u8>> {
+    fn payload_to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        let _ = byte_writer;
         unimplemented!()
     }
 
@@ -1,8 +1,10 @@
-use korangar_interface::elements::{ContainerState, Element, ElementCell, ElementState, ElementWrap, Focus, WeakElementCell};
+use korangar_interface::elements::{
+    Container, ContainerState, Element, ElementCell, ElementState, ElementWrap, Focus, Text, WeakElementCell,
+};
 use korangar_interface::event::{ChangeEvent, HoverInformation};
 use korangar_interface::layout::PlacementResolver;
-use korangar_interface::size_bound;
 use korangar_interface::state::{PlainRemote, Remote};
+use korangar_interface::{dimension_bound, size_bound};
 
 use crate::input::MouseInputMode;
 use crate::interface::application::InterfaceSettings;
@@ -27,7 +29,17 @@ impl SkillTreeContainer {
             skills
                 .iter()
                 .cloned()
-                .map(|skill| SkillBox::new(Some(skill), SkillSource::SkillTree, Box::new(|_| false)).wrap())
+                .map(|skill| {
+                    let display = match skill.maximum_level {
+                        Some(maximum_level) => format!("{} ({}/{})", skill.display_name, skill.skill_level.0, maximum_level),
+                        None => format!("{} ({})", skill.display_name, skill.skill_level.0),
+                    };
+
+                    let text = Text::default().with_text(display).with_width(dimension_bound!(!)).wrap();
+                    let skill_box = SkillBox::new(Some(skill), SkillSource::SkillTree, Box::new(|_| false));
+
+                    Container::new(vec![skill_box.wrap(), text]).wrap()
+                })
                 .collect()
         };
 
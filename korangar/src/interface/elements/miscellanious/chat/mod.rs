@@ -108,6 +108,7 @@ impl Element<InterfaceSettings> for Chat {
                 korangar_networking::MessageColor::Server => theme.chat.server_color.get(),
                 korangar_networking::MessageColor::Error => theme.chat.error_color.get(),
                 korangar_networking::MessageColor::Information => theme.chat.information_color.get(),
+                korangar_networking::MessageColor::Party => theme.chat.party_color.get(),
             };
 
             // Dividing by the scaling is done to counteract the scaling being applied
@@ -12,6 +12,7 @@ use crate::interface::application::InterfaceSettings;
 use crate::interface::layout::{CornerRadius, ScreenClip, ScreenPosition, ScreenSize};
 use crate::interface::resource::{ItemSource, Move, PartialMove};
 use crate::interface::theme::InterfaceTheme;
+use crate::interface::windows::DescriptionWindow;
 use crate::loaders::{FontSize, ResourceMetadata, Scaling};
 use crate::renderer::{InterfaceRenderer, SpriteRenderer};
 
@@ -64,6 +65,17 @@ impl Element<InterfaceSettings> for ItemBox {
         Vec::new()
     }
 
+    fn right_click(&mut self, _force_update: &mut bool) -> Vec<ClickAction<InterfaceSettings>> {
+        if let Some(item) = &self.item {
+            return vec![ClickAction::OpenWindow(Box::new(DescriptionWindow::new(
+                item.metadata.name.clone(),
+                item.metadata.description.clone(),
+            )))];
+        }
+
+        Vec::new()
+    }
+
     fn drop_resource(&mut self, drop_resource: PartialMove) -> Option<Move> {
         let PartialMove::Item { source, item } = drop_resource else {
             return None;
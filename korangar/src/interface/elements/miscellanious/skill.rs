@@ -11,6 +11,7 @@ use crate::interface::application::InterfaceSettings;
 use crate::interface::layout::{CornerRadius, ScreenClip, ScreenPosition};
 use crate::interface::resource::{Move, PartialMove, SkillSource};
 use crate::interface::theme::InterfaceTheme;
+use crate::interface::windows::DescriptionWindow;
 use crate::inventory::Skill;
 use crate::loaders::FontSize;
 use crate::renderer::InterfaceRenderer;
@@ -64,6 +65,17 @@ impl Element<InterfaceSettings> for SkillBox {
         Vec::new()
     }
 
+    fn right_click(&mut self, _force_update: &mut bool) -> Vec<ClickAction<InterfaceSettings>> {
+        if let Some(skill) = &self.skill {
+            return vec![ClickAction::OpenWindow(Box::new(DescriptionWindow::new(
+                skill.display_name.clone(),
+                skill.description.clone(),
+            )))];
+        }
+
+        Vec::new()
+    }
+
     fn drop_resource(&mut self, drop_resource: PartialMove) -> Option<Move> {
         let PartialMove::Skill { source, skill } = drop_resource else {
             return None;
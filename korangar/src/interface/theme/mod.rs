@@ -868,6 +868,7 @@ pub struct ChatTheme {
     pub server_color: Mutable<Color, Render>,
     pub error_color: Mutable<Color, Render>,
     pub information_color: Mutable<Color, Render>,
+    pub party_color: Mutable<Color, Render>,
 }
 
 impl ThemeDefault<DefaultMenu> for ChatTheme {
@@ -879,6 +880,7 @@ impl ThemeDefault<DefaultMenu> for ChatTheme {
             server_color: Mutable::new(Color::rgb_u8(255, 255, 210)),
             error_color: Mutable::new(Color::rgb_u8(255, 150, 150)),
             information_color: Mutable::new(Color::rgb_u8(200, 255, 200)),
+            party_color: Mutable::new(Color::rgb_u8(255, 200, 150)),
         }
     }
 }
@@ -892,6 +894,7 @@ impl ThemeDefault<DefaultMain> for ChatTheme {
             server_color: Mutable::new(Color::rgb_u8(255, 255, 210)),
             error_color: Mutable::new(Color::rgb_u8(255, 150, 150)),
             information_color: Mutable::new(Color::rgb_u8(200, 255, 200)),
+            party_color: Mutable::new(Color::rgb_u8(255, 200, 150)),
         }
     }
 }
@@ -920,6 +923,10 @@ impl korangar_interface::theme::ChatTheme<InterfaceSettings> for ChatTheme {
     fn information_color(&self) -> Color {
         self.information_color.get()
     }
+
+    fn party_color(&self) -> Color {
+        self.party_color.get()
+    }
 }
 
 #[derive(Serialize, Deserialize, PrototypeElement)]
@@ -1058,6 +1065,7 @@ pub struct StatusBarTheme {
     pub background_color: Mutable<Color, Nothing>,
     pub player_health_color: Mutable<Color, Nothing>,
     pub enemy_health_color: Mutable<Color, Nothing>,
+    pub party_health_color: Mutable<Color, Nothing>,
     pub spell_point_color: Mutable<Color, Nothing>,
     pub activity_point_color: Mutable<Color, Nothing>,
     pub player_bar_width: MutableRange<f32, Render>,
@@ -1076,6 +1084,7 @@ impl Default for StatusBarTheme {
             background_color: Mutable::new(Color::monochrome_u8(40)),
             player_health_color: Mutable::new(Color::rgb_u8(67, 163, 83)),
             enemy_health_color: Mutable::new(Color::rgb_u8(206, 49, 116)),
+            party_health_color: Mutable::new(Color::rgb_u8(76, 120, 212)),
             spell_point_color: Mutable::new(Color::rgb_u8(0, 129, 163)),
             activity_point_color: Mutable::new(Color::rgb_u8(218, 145, 81)),
             player_bar_width: MutableRange::new(85.0, 20.0, 300.0),
@@ -53,6 +53,11 @@ impl PrototypeWindow<InterfaceSettings> for CharacterOverviewWindow {
                 .with_event(UserEvent::OpenFriendsWindow)
                 .build()
                 .wrap(),
+            ButtonBuilder::new()
+                .with_text("Party")
+                .with_event(UserEvent::OpenPartyWindow)
+                .build()
+                .wrap(),
             ButtonBuilder::new()
                 .with_text("Menu")
                 .with_event(UserEvent::OpenMenuWindow)
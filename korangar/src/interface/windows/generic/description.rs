@@ -0,0 +1,37 @@
+use derive_new::new;
+use korangar_interface::elements::{ElementWrap, Text};
+use korangar_interface::size_bound;
+use korangar_interface::windows::{PrototypeWindow, Window, WindowBuilder};
+
+use crate::interface::application::InterfaceSettings;
+use crate::interface::layout::ScreenSize;
+use crate::interface::windows::WindowCache;
+
+/// Shows the description of an item or skill from the tables of the client.
+#[derive(new)]
+pub struct DescriptionWindow {
+    title: String,
+    description: Vec<String>,
+}
+
+impl PrototypeWindow<InterfaceSettings> for DescriptionWindow {
+    fn to_window(
+        &self,
+        window_cache: &WindowCache,
+        application: &InterfaceSettings,
+        available_space: ScreenSize,
+    ) -> Window<InterfaceSettings> {
+        let elements = self
+            .description
+            .iter()
+            .map(|line| Text::default().with_text(line.clone()).wrap())
+            .collect();
+
+        WindowBuilder::new()
+            .with_title(self.title.clone())
+            .with_size_bound(size_bound!(200 > 300 < 400, ?))
+            .with_elements(elements)
+            .closable()
+            .build(window_cache, application, available_space)
+    }
+}
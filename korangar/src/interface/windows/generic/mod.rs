@@ -1,9 +1,11 @@
 mod chat;
+mod description;
 mod dialog;
 mod error;
 mod menu;
 
 pub use self::chat::{ChatMessage, ChatWindow};
+pub use self::description::DescriptionWindow;
 pub use self::dialog::DialogWindow;
 pub use self::error::ErrorWindow;
 pub use self::menu::MenuWindow;
@@ -6,6 +6,7 @@ mod debug;
 mod friends;
 mod generic;
 mod mutable;
+mod party;
 mod settings;
 mod shop;
 
@@ -17,5 +18,6 @@ pub use self::debug::*;
 pub use self::friends::*;
 pub use self::generic::*;
 pub use self::mutable::*;
+pub use self::party::*;
 pub use self::settings::*;
 pub use self::shop::*;
@@ -0,0 +1,5 @@
+mod invitation;
+mod members;
+
+pub use self::invitation::PartyInvitationWindow;
+pub use self::members::PartyWindow;
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use korangar_interface::state::{PlainRemote, PlainTrackedState, TrackedState};
 use ragnarok_packets::{ClientTick, SkillId, SkillInformation, SkillLevel, SkillType};
 
-use crate::loaders::{ActionLoader, Actions, AnimationState, Sprite, SpriteLoader};
+use crate::loaders::{ActionLoader, Actions, AnimationState, ScriptLoader, Sprite, SpriteLoader};
 
 #[derive(Clone, Debug)]
 pub struct Skill {
@@ -11,6 +11,11 @@ pub struct Skill {
     pub skill_level: SkillLevel,
     pub skill_type: SkillType,
     pub skill_name: String,
+    /// Name of the skill from the skill tables of the client. Falls back to
+    /// the internal skill name if the client doesn't have the tables.
+    pub display_name: String,
+    pub maximum_level: Option<usize>,
+    pub description: Vec<String>,
     pub sprite: Arc<Sprite>,
     pub actions: Arc<Actions>,
     pub animation_state: AnimationState,
@@ -22,18 +27,31 @@ pub struct SkillTree {
 }
 
 impl SkillTree {
-    pub fn fill(&mut self, sprite_loader: &mut SpriteLoader, action_loader: &mut ActionLoader, skill_data: Vec<SkillInformation>) {
+    pub fn fill(
+        &mut self,
+        sprite_loader: &mut SpriteLoader,
+        action_loader: &mut ActionLoader,
+        script_loader: &ScriptLoader,
+        skill_data: Vec<SkillInformation>,
+    ) {
         let skills = skill_data
             .into_iter()
             .map(|skill_data| {
-                let file_path = format!("¾ÆÀÌÅÛ\\{}", skill_data.skill_name);
+                let file_path = format!("아이템\\{}", skill_data.skill_name);
                 let sprite = sprite_loader.get(&format!("{file_path}.spr")).unwrap();
                 let actions = action_loader.get(&format!("{file_path}.act")).unwrap();
+                let skill_info = script_loader.get_skill(skill_data.skill_id);
 
                 Skill {
                     skill_id: skill_data.skill_id,
                     skill_level: skill_data.skill_level,
                     skill_type: skill_data.skill_type,
+                    display_name: skill_info
+                        .map(|skill_info| skill_info.name.clone())
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or_else(|| skill_data.skill_name.clone()),
+                    maximum_level: skill_info.map(|skill_info| skill_info.maximum_level),
+                    description: skill_info.map(|skill_info| skill_info.description.clone()).unwrap_or_default(),
                     skill_name: skill_data.skill_name,
                     sprite,
                     actions,
@@ -9,7 +9,7 @@ use korangar_debug::logging::{print_debug, Colorize, Timer};
 use korangar_interface::elements::PrototypeElement;
 use korangar_util::FileLoader;
 use ragnarok_bytes::{ByteStream, FromBytes};
-use ragnarok_formats::action::{Action, ActionsData};
+use ragnarok_formats::action::{Action, ActionsData, Event};
 use ragnarok_formats::version::InternalVersion;
 use ragnarok_packets::ClientTick;
 
@@ -18,7 +18,7 @@ use super::Sprite;
 use crate::graphics::Color;
 use crate::interface::application::InterfaceSettings;
 use crate::interface::layout::{ScreenClip, ScreenPosition, ScreenSize};
-use crate::loaders::{GameFileLoader, FALLBACK_ACTIONS_FILE};
+use crate::loaders::{GameFileLoader, FALLBACK_ACTIONS_FILE, GAME_FILE_TEXT_ENCODING};
 use crate::renderer::SpriteRenderer;
 
 #[derive(Clone, Debug, new)]
@@ -32,6 +32,12 @@ pub struct AnimationState {
     pub duration: Option<u32>,
     #[new(default)]
     pub factor: Option<f32>,
+    /// Time of the animation before the last update, or [`None`] if the
+    /// animation (re)started with the last update.
+    #[new(default)]
+    pub previous_time: Option<u32>,
+    #[new(value = "true")]
+    restarted: bool,
 }
 
 impl AnimationState {
@@ -40,6 +46,7 @@ impl AnimationState {
         self.start_time = client_tick;
         self.duration = None;
         self.factor = None;
+        self.restarted = true;
     }
 
     pub fn walk(&mut self, movement_speed: usize, client_tick: ClientTick) {
@@ -47,6 +54,7 @@ impl AnimationState {
         self.start_time = client_tick;
         self.duration = None;
         self.factor = Some(movement_speed as f32 * 100.0 / 150.0);
+        self.restarted = true;
     }
 
     pub fn dead(&mut self, client_tick: ClientTick) {
@@ -54,9 +62,14 @@ impl AnimationState {
         self.start_time = client_tick;
         self.duration = None;
         self.factor = None;
+        self.restarted = true;
     }
 
     pub fn update(&mut self, client_tick: ClientTick) {
+        let mut previous_time = match std::mem::take(&mut self.restarted) {
+            true => None,
+            false => Some(self.time),
+        };
         let mut time = client_tick.0.saturating_sub(self.start_time.0);
 
         // TODO: make everything have a duration so that we can update the start_time
@@ -71,8 +84,10 @@ impl AnimationState {
             self.duration = None;
 
             time = 0;
+            previous_time = None;
         }
 
+        self.previous_time = previous_time;
         self.time = time;
     }
 }
@@ -81,11 +96,23 @@ impl AnimationState {
 pub struct Actions {
     pub actions: Vec<Action>,
     pub delays: Vec<f32>,
+    pub events: Vec<Event>,
     #[cfg(feature = "debug")]
     actions_data: ActionsData,
 }
 
 impl Actions {
+    /// Get the name of the event that is triggered when a motion is shown,
+    /// for example the file name of a sound effect.
+    pub fn motion_event(&self, action_index: usize, motion_index: usize) -> Option<&str> {
+        let action = &self.actions[action_index % self.actions.len()];
+        let event_id = action.motions.get(motion_index)?.event_id?;
+
+        // An event id of -1 means that the motion has no event.
+        let event_id = usize::try_from(event_id).ok()?;
+        self.events.get(event_id).map(|event| event.name.as_str())
+    }
+
     pub fn render(
         &self,
         renderer: &impl SpriteRenderer,
@@ -177,7 +204,8 @@ impl ActionLoader {
             .game_file_loader
             .get(&format!("data\\sprite\\{path}"))
             .map_err(LoadError::File)?;
-        let mut byte_stream: ByteStream<Option<InternalVersion>> = ByteStream::without_metadata(&bytes);
+        let mut byte_stream: ByteStream<Option<InternalVersion>> =
+            ByteStream::without_metadata(&bytes).with_text_encoding(GAME_FILE_TEXT_ENCODING);
 
         let actions_data = match ActionsData::from_bytes(&mut byte_stream) {
             Ok(actions_data) => actions_data,
@@ -202,6 +230,7 @@ impl ActionLoader {
         let sprite = Arc::new(Actions {
             actions: actions_data.actions,
             delays,
+            events: actions_data.events,
             #[cfg(feature = "debug")]
             actions_data: saved_actions_data,
         });
@@ -221,3 +250,39 @@ impl ActionLoader {
         }
     }
 }
+
+#[cfg(test)]
+mod animation_state {
+    use ragnarok_packets::ClientTick;
+
+    use super::AnimationState;
+
+    #[test]
+    fn previous_time() {
+        let mut animation_state = AnimationState::new(ClientTick(100));
+
+        animation_state.update(ClientTick(150));
+        assert_eq!(animation_state.previous_time, None);
+        assert_eq!(animation_state.time, 50);
+
+        animation_state.update(ClientTick(200));
+        assert_eq!(animation_state.previous_time, Some(50));
+        assert_eq!(animation_state.time, 100);
+    }
+
+    #[test]
+    fn restart() {
+        let mut animation_state = AnimationState::new(ClientTick(0));
+        animation_state.update(ClientTick(100));
+
+        animation_state.walk(150, ClientTick(100));
+        animation_state.update(ClientTick(120));
+        assert_eq!(animation_state.previous_time, None);
+        assert_eq!(animation_state.time, 20);
+
+        animation_state.duration = Some(50);
+        animation_state.update(ClientTick(200));
+        assert_eq!(animation_state.previous_time, None);
+        assert_eq!(animation_state.time, 0);
+    }
+}
@@ -1,4 +1,4 @@
-use std::cmp::{max, min};
+use std::cmp::{max, min, Reverse};
 use std::sync::Arc;
 
 use cgmath::{Matrix4, Vector2};
@@ -11,11 +11,29 @@ use crate::loaders::{ActionLoader, SpriteLoader};
 use crate::world::{Animation, AnimationData, AnimationFrame, AnimationFramePart, AnimationPair};
 use crate::{Color, EntityType};
 
+/// Files of a single layer of an entity, such as the body or the head.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EntityPartFiles {
+    /// Path of the sprite and action files, without the extension.
+    pub path: String,
+    /// Palette that replaces the palette of the sprite, used for dyes.
+    pub palette_path: Option<String>,
+}
+
+/// All files that are combined into the animations of an entity.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EntityFiles {
+    /// Layers of the entity, drawn from back to front.
+    pub parts: Vec<EntityPartFiles>,
+    /// IMF file that changes the order of the layers in specific motions.
+    pub imf_path: Option<String>,
+}
+
 // TODO: NHA Create and use an easier to use cache.
 #[derive(new)]
 pub struct AnimationLoader {
     #[new(default)]
-    cache: HashMap<Vec<String>, Arc<AnimationData>>,
+    cache: HashMap<EntityFiles, Arc<AnimationData>>,
 }
 
 impl AnimationLoader {
@@ -24,15 +42,22 @@ impl AnimationLoader {
         sprite_loader: &mut SpriteLoader,
         action_loader: &mut ActionLoader,
         entity_type: EntityType,
-        entity_part_files: &[String],
+        entity_files: &EntityFiles,
     ) -> Result<Arc<AnimationData>, LoadError> {
-        let animation_pairs: Vec<AnimationPair> = entity_part_files
+        let animation_pairs: Vec<AnimationPair> = entity_files
+            .parts
             .iter()
-            .map(|file_path| AnimationPair {
-                sprites: sprite_loader.get(&format!("{file_path}.spr")).unwrap(),
-                actions: action_loader.get(&format!("{file_path}.act")).unwrap(),
+            .map(|part_files| AnimationPair {
+                sprites: sprite_loader
+                    .get_with_palette(&format!("{}.spr", part_files.path), part_files.palette_path.as_deref())
+                    .unwrap(),
+                actions: action_loader.get(&format!("{}.act", part_files.path)).unwrap(),
             })
             .collect();
+        let imf_data = entity_files
+            .imf_path
+            .as_deref()
+            .and_then(|imf_path| sprite_loader.get_imf(imf_path));
 
         let mut animations_list: Vec<Vec<Vec<AnimationFrame>>> = Vec::new();
 
@@ -178,16 +203,26 @@ impl AnimationLoader {
             let mut frames: Vec<AnimationFrame> = Vec::new();
 
             for motion_index in 0..motion_size {
-                let mut generate: Vec<AnimationFrame> = Vec::new();
+                let mut generate: Vec<(i32, AnimationFrame)> = Vec::new();
 
-                for pair in &animations_list[0..animation_pair_size] {
+                for (layer_index, pair) in animations_list[0..animation_pair_size].iter().enumerate() {
                     if pair.len() <= action_index || pair[action_index].len() <= motion_index {
                         continue;
                     }
 
-                    generate.push(pair[action_index][motion_index].clone());
+                    let priority = imf_data
+                        .as_ref()
+                        .map(|imf_data| imf_data.priority(layer_index, action_index, motion_index))
+                        .unwrap_or_default();
+
+                    generate.push((priority, pair[action_index][motion_index].clone()));
                 }
 
+                // Layers with a higher priority are drawn behind the others. The sort is
+                // stable, so layers with the same priority keep their order.
+                generate.sort_by_key(|(priority, _)| Reverse(*priority));
+                let mut generate: Vec<AnimationFrame> = generate.into_iter().map(|(_, frame)| frame).collect();
+
                 let frame = merge_frame(&mut generate);
                 frames.push(frame);
             }
@@ -274,7 +309,7 @@ impl AnimationLoader {
             entity_type,
         });
 
-        self.cache.insert(entity_part_files.to_vec(), animation_data.clone());
+        self.cache.insert(entity_files.clone(), animation_data.clone());
 
         Ok(animation_data)
     }
@@ -284,11 +319,11 @@ impl AnimationLoader {
         sprite_loader: &mut SpriteLoader,
         action_loader: &mut ActionLoader,
         entity_type: EntityType,
-        entity_part_files: &[String],
+        entity_files: &EntityFiles,
     ) -> Result<Arc<AnimationData>, LoadError> {
-        match self.cache.get(entity_part_files) {
+        match self.cache.get(entity_files) {
             Some(animation_data) => Ok(animation_data.clone()),
-            None => self.load(sprite_loader, action_loader, entity_type, entity_part_files),
+            None => self.load(sprite_loader, action_loader, entity_type, entity_files),
         }
     }
 }
@@ -1,7 +1,7 @@
 //! An OS folder containing game assets.
 use std::collections::HashMap;
-use std::fs;
 use std::path::{Path, PathBuf};
+use std::{fs, io};
 
 use walkdir::WalkDir;
 
@@ -75,20 +75,28 @@ impl Writable for FolderArchive {
             .unwrap_or_else(|_| panic!("error creating folder {} for FolderArchive", self.folder_path.display()));
     }
 
-    fn add_file(&mut self, file_path: &str, file_data: Vec<u8>) {
+    fn add_file(&mut self, file_path: &str, file_data: Vec<u8>) -> io::Result<()> {
         let normalized_asset_path = Self::os_specific_path(file_path);
         let full_path = self.folder_path.join(normalized_asset_path);
 
         // Create parent directories if needed
         if let Some(parent) = full_path.parent() {
             if !parent.exists() {
-                if let Err(err) = fs::create_dir_all(parent) {
-                    panic!("error creating directories: {}", err);
-                }
+                fs::create_dir_all(parent)?;
             }
         }
 
         // Write file contents to the file
-        fs::write(&full_path, file_data).unwrap_or_else(|_| panic!("error writing to file {}", full_path.display()));
+        fs::write(&full_path, file_data)
+    }
+
+    fn remove_file(&mut self, file_path: &str) -> io::Result<()> {
+        let normalized_asset_path = Self::os_specific_path(file_path);
+        let full_path = self.folder_path.join(normalized_asset_path);
+
+        match fs::remove_file(&full_path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
     }
 }
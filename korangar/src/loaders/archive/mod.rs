@@ -1,6 +1,8 @@
 pub mod folder;
 pub mod native;
+pub mod thor;
 
+use std::io;
 use std::path::Path;
 
 pub trait Archive: Send + Sync {
@@ -11,6 +13,12 @@ pub trait Archive: Send + Sync {
     /// Retrieve an asset from the Archive
     fn get_file_by_path(&self, asset_path: &str) -> Option<Vec<u8>>;
 
+    /// Check if the Archive removes an asset, hiding it in all archives that
+    /// were loaded before
+    fn is_file_removed(&self, _asset_path: &str) -> bool {
+        false
+    }
+
     /// Get a list of all Lua files
     fn get_lua_files(&self, lua_files: &mut Vec<String>);
 }
@@ -18,13 +26,18 @@ pub trait Archive: Send + Sync {
 pub enum ArchiveType {
     Folder,
     Native,
+    Thor,
 }
 
 /// A common trait to all writable archives
 pub trait Writable {
     fn create(&mut self) {}
 
-    fn add_file(&mut self, path: &str, asset: Vec<u8>);
+    fn add_file(&mut self, path: &str, asset: Vec<u8>) -> io::Result<()>;
+
+    fn remove_file(&mut self, path: &str) -> io::Result<()>;
 
-    fn save(&self) {}
+    fn save(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
@@ -1,44 +1,274 @@
 //! Implements an writable instance of a GRF File
-//! This way, we can provide a temporal storage to files before the final write
-//! occurs while keeping it outside of the
-//! [`NativeArchive`](super::NativeArchive) implementation
+//! Files are written to the archive on disk directly, so even very large
+//! archives can be modified without loading them into memory. Space of
+//! replaced and removed files is reused for new files and the file table is
+//! written to the end of the archive when saving. New and compacted archives
+//! are written to a temporary file that replaces the archive when saving, so
+//! the archive on disk is valid at all times.
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
-use ragnarok_bytes::ToBytes;
-use ragnarok_formats::archive::{AssetTable, FileTableRow, Header};
+use ragnarok_bytes::{ByteStream, ByteWriter, FixedByteSize, FromBytes, ToBytes};
+use ragnarok_formats::archive::{AssetTable, FileFlags, FileName, FileTableRow, Header};
 use yazi::{compress, CompressionLevel, Format};
 
-use super::FileTable;
+use super::{conversion_error, FileTable, NativeArchive};
 use crate::loaders::archive::Writable;
+use crate::loaders::GAME_FILE_TEXT_ENCODING;
 
 pub struct NativeArchiveBuilder {
-    os_file_path: PathBuf,
+    path: PathBuf,
+    /// Only [`None`] while a temporary file replaces the archive, since the
+    /// file has to be closed for that.
+    file: Option<File>,
+    /// Path of the file that is written to if it's not the archive itself. It
+    /// replaces the archive when saving.
+    temporary_path: Option<PathBuf>,
     file_table: FileTable,
-    data: Vec<u8>,
+    /// Regions of the data section that are not used by any file, sorted by
+    /// their offset.
+    free_regions: Vec<Range<u32>>,
+    /// Regions that were freed since the last save. The file table on disk
+    /// still references them, so they only become free after saving.
+    released_regions: Vec<Range<u32>>,
+    /// Size of the data section, which is everything between the header and
+    /// the file table.
+    data_size: u32,
+}
+
+/// Offsets and sizes inside of a GRF are 32 bit, so any archive or file that
+/// exceeds 4 GiB can't be represented.
+fn size_exceeded<T>(_error: T) -> io::Error {
+    io::Error::other("size exceeds the limits of the grf format")
+}
+
+fn compression_error(error: yazi::Error) -> io::Error {
+    io::Error::other(format!("failed to compress data: {error:?}"))
+}
+
+fn closed_error() -> io::Error {
+    io::Error::other("archive could not be reopened after saving")
 }
 
 impl NativeArchiveBuilder {
-    pub fn from_path(path: &Path) -> Self {
-        Self {
-            os_file_path: PathBuf::from(path),
-            file_table: FileTable::new(),
-            data: Vec::new(),
+    /// Opens the archive at the given path to add, replace or remove files.
+    /// If there is no archive yet, a new one is created when saving.
+    pub fn from_path(path: &Path) -> io::Result<Self> {
+        if !path.exists() {
+            let temporary_path = Self::temporary_path(path);
+            let file = Self::create_temporary_file(&temporary_path)?;
+
+            return Ok(Self {
+                path: path.to_owned(),
+                file: Some(file),
+                temporary_path: Some(temporary_path),
+                file_table: FileTable::new(),
+                free_regions: Vec::new(),
+                released_regions: Vec::new(),
+                data_size: 0,
+            });
+        }
+
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let file_size = file.metadata()?.len();
+
+        let mut file_header_buffer = vec![0u8; Header::size_in_bytes()];
+        file.read_exact(&mut file_header_buffer)?;
+        let file_header = Header::from_bytes(&mut ByteStream::<()>::without_metadata(&file_header_buffer)).map_err(conversion_error)?;
+
+        if file_header.version != 0x200 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "only grf version 0x200 can be modified in place",
+            ));
         }
+
+        let _ = file.seek(SeekFrom::Current(file_header.file_table_offset as i64))?;
+        let file_table = NativeArchive::load_file_table(&mut file, file_header.get_file_count().map_err(conversion_error)?)?;
+
+        // The current file table has to stay intact until a new one is saved, so
+        // new data is only appended after it.
+        let data_size = u32::try_from(file_size - Header::size_in_bytes() as u64).map_err(size_exceeded)?;
+        let free_regions = Self::find_free_regions(&file_table, file_header.file_table_offset);
+
+        let mut builder = Self {
+            path: path.to_owned(),
+            file: Some(file),
+            temporary_path: None,
+            file_table,
+            free_regions,
+            released_regions: Vec::new(),
+            data_size,
+        };
+
+        builder.released_regions.push(file_header.file_table_offset..data_size);
+        Ok(builder)
+    }
+
+    fn temporary_path(path: &Path) -> PathBuf {
+        let mut file_name = OsString::from(path.as_os_str());
+        file_name.push(".tmp");
+        PathBuf::from(file_name)
+    }
+
+    fn create_temporary_file(path: &Path) -> io::Result<File> {
+        OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)
+    }
+
+    fn find_free_regions(file_table: &FileTable, data_size: u32) -> Vec<Range<u32>> {
+        let mut used_regions: Vec<Range<u32>> = file_table
+            .values()
+            .map(|row| row.offset..row.offset + row.compressed_size_aligned)
+            .collect();
+        used_regions.sort_by_key(|region| region.start);
+
+        let mut free_regions = Vec::new();
+        let mut cursor = 0;
+
+        for region in used_regions {
+            if region.start > cursor {
+                free_regions.push(cursor..region.start);
+            }
+
+            cursor = u32::max(cursor, region.end);
+        }
+
+        if data_size > cursor {
+            free_regions.push(cursor..data_size);
+        }
+
+        free_regions
+    }
+
+    /// Returns the number of bytes in the data section that are not used by
+    /// any file.
+    pub fn unused_space(&self) -> u32 {
+        self.free_regions
+            .iter()
+            .chain(self.released_regions.iter())
+            .map(|region| region.end - region.start)
+            .sum()
+    }
+
+    /// Whether more than half of the data section is unused, so that it's worth
+    /// to [`compact`](Self::compact) the archive.
+    pub fn should_compact(&self) -> bool {
+        self.unused_space() > self.data_size / 2
+    }
+
+    /// Finds a place for the given number of bytes, preferring the first free
+    /// region that is large enough over growing the data section.
+    fn allocate(&mut self, size: u32) -> io::Result<u32> {
+        if let Some(index) = self.free_regions.iter().position(|region| region.end - region.start >= size) {
+            let region = &mut self.free_regions[index];
+            let offset = region.start;
+            region.start += size;
+
+            if region.start == region.end {
+                self.free_regions.remove(index);
+            }
+
+            return Ok(offset);
+        }
+
+        let offset = self.data_size;
+        self.data_size = self.data_size.checked_add(size).ok_or_else(|| size_exceeded(()))?;
+        Ok(offset)
+    }
+
+    fn release(&mut self, row: FileTableRow) {
+        if row.compressed_size_aligned > 0 {
+            self.released_regions.push(row.offset..row.offset + row.compressed_size_aligned);
+        }
+    }
+
+    /// Merges the released regions into the free regions, combining
+    /// neighboring regions.
+    fn reclaim_released_regions(&mut self) {
+        self.free_regions.append(&mut self.released_regions);
+        self.free_regions.sort_by_key(|region| region.start);
+
+        let mut merged_regions: Vec<Range<u32>> = Vec::with_capacity(self.free_regions.len());
+
+        for region in self.free_regions.drain(..) {
+            match merged_regions.last_mut() {
+                Some(last) if last.end >= region.start => last.end = u32::max(last.end, region.end),
+                _ => merged_regions.push(region),
+            }
+        }
+
+        self.free_regions = merged_regions;
+    }
+
+    fn write_at(&mut self, offset: u32, data: &[u8]) -> io::Result<()> {
+        let position = offset as u64 + Header::size_in_bytes() as u64;
+        let file = self.file.as_mut().ok_or_else(closed_error)?;
+        file.seek(SeekFrom::Start(position))?;
+        file.write_all(data)
+    }
+
+    /// Copies all files to a new file without any unused space in between.
+    /// The new file replaces the archive on disk when calling
+    /// [`save`](Writable::save), so the archive stays valid if the process is
+    /// interrupted.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let file = self.file.as_mut().ok_or_else(closed_error)?;
+        let temporary_path = Self::temporary_path(&self.path);
+        let mut compacted_file = Self::create_temporary_file(&temporary_path)?;
+        compacted_file.seek(SeekFrom::Start(Header::size_in_bytes() as u64))?;
+
+        let mut rows: Vec<&mut FileTableRow> = self.file_table.values_mut().collect();
+        rows.sort_by_key(|row| row.offset);
+
+        let mut cursor = 0;
+
+        for row in rows {
+            file.seek(SeekFrom::Start(row.offset as u64 + Header::size_in_bytes() as u64))?;
+            io::copy(&mut (&*file).take(row.compressed_size_aligned as u64), &mut compacted_file)?;
+
+            row.offset = cursor;
+            cursor += row.compressed_size_aligned;
+        }
+
+        self.file = Some(compacted_file);
+        self.temporary_path = Some(temporary_path);
+        self.data_size = cursor;
+        self.free_regions.clear();
+        self.released_regions.clear();
+
+        Ok(())
     }
 }
 
 impl Writable for NativeArchiveBuilder {
-    fn add_file(&mut self, path: &str, asset: Vec<u8>) {
-        let compressed = compress(&asset, Format::Zlib, CompressionLevel::Default).unwrap();
+    fn add_file(&mut self, path: &str, asset: Vec<u8>) -> io::Result<()> {
+        let compressed = compress(&asset, Format::Zlib, CompressionLevel::Default).map_err(compression_error)?;
 
-        let compressed_size = compressed.len() as u32;
+        let compressed_size = u32::try_from(compressed.len()).map_err(size_exceeded)?;
         let compressed_size_aligned = compressed_size;
-        let uncompressed_size = asset.len() as u32;
-        let flags = 1;
-        let offset = self.data.len() as u32;
+        let uncompressed_size = u32::try_from(asset.len()).map_err(size_exceeded)?;
+        let flags = FileFlags::FILE;
+        let file_name = FileName::new(path, GAME_FILE_TEXT_ENCODING).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{path} can't be represented in {GAME_FILE_TEXT_ENCODING:?}"),
+            )
+        })?;
+
+        // The file table is keyed by lowercase paths, just like when loading it.
+        let key = path.to_lowercase();
+
+        if let Some(previous) = self.file_table.remove(&key) {
+            self.release(previous);
+        }
+
+        let offset = self.allocate(compressed_size_aligned)?;
 
         let file_information = FileTableRow {
-            file_name: String::from(path),
+            file_name,
             compressed_size,
             compressed_size_aligned,
             uncompressed_size,
@@ -46,35 +276,358 @@ impl Writable for NativeArchiveBuilder {
             offset,
         };
 
-        self.data.extend_from_slice(&compressed);
-        self.file_table.insert(String::from(path), file_information);
+        self.write_at(offset, &compressed)?;
+        self.file_table.insert(key, file_information);
+
+        Ok(())
     }
 
-    fn save(&self) {
-        let file_table_offset = self.data.len() as u32;
-        let reserved_files = 0;
-        let raw_file_count = self.file_table.len() as u32 + 7;
-        let version = 0x200;
-        let file_header = Header::new(file_table_offset, reserved_files, raw_file_count, version);
+    fn remove_file(&mut self, path: &str) -> io::Result<()> {
+        if let Some(previous) = self.file_table.remove(&path.to_lowercase()) {
+            self.release(previous);
+        }
 
-        let mut bytes = file_header.to_bytes().unwrap();
-        bytes.extend_from_slice(&self.data);
+        Ok(())
+    }
 
-        let mut file_table_data = Vec::new();
+    fn save(&mut self) -> io::Result<()> {
+        let mut file_table_writer = ByteWriter::<()>::without_metadata().with_text_encoding(GAME_FILE_TEXT_ENCODING);
 
         for file_information in self.file_table.values() {
-            file_table_data.extend(file_information.to_bytes().unwrap());
+            file_information.to_bytes(&mut file_table_writer).map_err(conversion_error)?;
         }
 
-        let compressed_file_information_data = compress(&file_table_data, Format::Zlib, CompressionLevel::Default).unwrap();
+        let file_table_data = file_table_writer.into_inner();
+
+        let compressed_file_information_data =
+            compress(&file_table_data, Format::Zlib, CompressionLevel::Default).map_err(compression_error)?;
         let file_table = AssetTable {
-            compressed_size: compressed_file_information_data.len() as u32,
-            uncompressed_size: file_table_data.len() as u32,
+            compressed_size: u32::try_from(compressed_file_information_data.len()).map_err(size_exceeded)?,
+            uncompressed_size: u32::try_from(file_table_data.len()).map_err(size_exceeded)?,
         };
 
-        bytes.extend(file_table.to_bytes().unwrap());
-        bytes.extend(compressed_file_information_data);
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        file_table.to_bytes(&mut byte_writer).map_err(conversion_error)?;
+        byte_writer.extend_from_slice(&compressed_file_information_data);
+        let file_table_bytes = byte_writer.into_inner();
+
+        let file_table_offset = self.data_size;
+        let file_table_end = u32::try_from(file_table_bytes.len())
+            .ok()
+            .and_then(|file_table_size| file_table_offset.checked_add(file_table_size))
+            .ok_or_else(|| size_exceeded(()))?;
+        let raw_file_count = u32::try_from(self.file_table.len() + Header::FILE_OFFSET).map_err(size_exceeded)?;
+
+        self.write_at(file_table_offset, &file_table_bytes)?;
+
+        let file = self.file.as_mut().ok_or_else(closed_error)?;
+        file.set_len(file_table_end as u64 + Header::size_in_bytes() as u64)?;
+
+        // The header is written last, so the previous file table stays valid
+        // until all new data is written. The data has to reach the disk before
+        // the header does, since the header might otherwise reference a file
+        // table that was never written.
+        file.sync_data()?;
+
+        let reserved_files = 0;
+        let version = 0x200;
+        let file_header = Header::new(file_table_offset, reserved_files, raw_file_count, version);
+
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        file_header.to_bytes(&mut byte_writer).map_err(conversion_error)?;
+
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&byte_writer.into_inner())?;
+        file.sync_all()?;
+
+        if let Some(temporary_path) = self.temporary_path.take() {
+            // Open files can't be replaced on every platform, so the temporary file is
+            // closed first and the archive is opened again afterwards.
+            self.file = None;
+            fs::rename(temporary_path, &self.path)?;
+            self.file = Some(OpenOptions::new().read(true).write(true).open(&self.path)?);
+        }
+
+        // New data is appended after the file table that is now on disk.
+        self.data_size = file_table_end;
+        self.reclaim_released_regions();
+        self.released_regions.push(file_table_offset..file_table_end);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod in_place {
+    use std::path::PathBuf;
+
+    use super::NativeArchiveBuilder;
+    use crate::loaders::archive::native::NativeArchive;
+    use crate::loaders::archive::{Archive, Writable};
+
+    fn archive_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("korangar-{}-{name}.grf", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// Content that zlib can't compress, so every file has the same size.
+    fn content(seed: u8) -> Vec<u8> {
+        let mut state = seed as u32 + 1;
+        (0..256)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    fn file_size(path: &PathBuf) -> u64 {
+        std::fs::metadata(path).unwrap().len()
+    }
+
+    #[test]
+    fn create_and_extend() {
+        let path = archive_path("extend");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\first.txt", b"first".to_vec()).unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\second.txt", b"second".to_vec()).unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.get_file_by_path("data\\first.txt"), Some(b"first".to_vec()));
+        assert_eq!(archive.get_file_by_path("data\\second.txt"), Some(b"second".to_vec()));
+    }
+
+    #[test]
+    fn reuse_free_space() {
+        let path = archive_path("reuse");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\first.txt", content(1)).unwrap();
+        builder.add_file("data\\second.txt", content(2)).unwrap();
+        builder.add_file("data\\third.txt", content(3)).unwrap();
+        builder.save().unwrap();
+
+        let removed_offset = NativeArchive::from_path(&path).file_table["data\\second.txt"].offset;
+
+        builder.remove_file("data\\second.txt").unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let size_before = file_size(&path);
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\fourth.txt", content(4)).unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        let size_after = file_size(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.file_table["data\\fourth.txt"].offset, removed_offset);
+        assert!(size_after < size_before + 256);
+        assert_eq!(archive.get_file_by_path("data\\second.txt"), None);
+        assert_eq!(archive.get_file_by_path("data\\first.txt"), Some(content(1)));
+        assert_eq!(archive.get_file_by_path("data\\third.txt"), Some(content(3)));
+        assert_eq!(archive.get_file_by_path("data\\fourth.txt"), Some(content(4)));
+    }
+
+    #[test]
+    fn replace_file() {
+        let path = archive_path("replace");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\file.txt", b"old content".to_vec()).unwrap();
+        builder.add_file("data\\other.txt", b"other content".to_vec()).unwrap();
+        builder.save().unwrap();
+        builder.add_file("data\\file.txt", b"new content".to_vec()).unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.file_table.len(), 2);
+        assert_eq!(archive.get_file_by_path("data\\file.txt"), Some(b"new content".to_vec()));
+        assert_eq!(archive.get_file_by_path("data\\other.txt"), Some(b"other content".to_vec()));
+    }
+
+    #[test]
+    fn replace_and_remove_mixed_case() {
+        let path = archive_path("mixed-case");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\File.txt", b"old content".to_vec()).unwrap();
+        builder.add_file("data\\Other.txt", b"other content".to_vec()).unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("DATA\\FILE.TXT", b"new content".to_vec()).unwrap();
+        builder.remove_file("Data\\OTHER.txt").unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.file_table.len(), 1);
+        assert_eq!(archive.get_file_by_path("data\\file.txt"), Some(b"new content".to_vec()));
+    }
+
+    #[test]
+    fn corrupt_file_table() {
+        let path = archive_path("corrupt");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\file.txt", content(1)).unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        // Overwrite the compressed file table, which is stored after the sizes at the
+        // end of the archive.
+        let mut data = std::fs::read(&path).unwrap();
+        let length = data.len();
+        data[length - 8..].fill(0xFF);
+        std::fs::write(&path, data).unwrap();
+
+        let result = NativeArchiveBuilder::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn only_create_when_saving() {
+        let path = archive_path("create");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\file.txt", b"content".to_vec()).unwrap();
+
+        assert!(!path.exists());
+
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.get_file_by_path("data\\file.txt"), Some(b"content".to_vec()));
+    }
+
+    #[test]
+    fn compact_without_modifying_archive() {
+        let path = archive_path("compact-unmodified");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        for index in 0..4 {
+            builder.add_file(&format!("data\\{index}.txt"), content(index)).unwrap();
+        }
+        builder.save().unwrap();
+        builder.remove_file("data\\0.txt").unwrap();
+
+        let archive_before = std::fs::read(&path).unwrap();
+        builder.compact().unwrap();
+        let archive_after = std::fs::read(&path).unwrap();
+
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive_before, archive_after);
+        assert_eq!(archive.file_table["data\\1.txt"].offset, 0);
+        assert_eq!(archive.get_file_by_path("data\\0.txt"), None);
+        assert_eq!(archive.get_file_by_path("data\\3.txt"), Some(content(3)));
+    }
+
+    #[test]
+    fn no_compaction_when_saving() {
+        let path = archive_path("no-compaction");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        for index in 0..4 {
+            builder.add_file(&format!("data\\{index}.txt"), content(index)).unwrap();
+        }
+        builder.save().unwrap();
+
+        let offset = NativeArchive::from_path(&path).file_table["data\\3.txt"].offset;
+
+        for index in 0..3 {
+            builder.remove_file(&format!("data\\{index}.txt")).unwrap();
+        }
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.file_table["data\\3.txt"].offset, offset);
+        assert_eq!(archive.get_file_by_path("data\\3.txt"), Some(content(3)));
+    }
+
+    #[test]
+    fn modify_after_compacting() {
+        let path = archive_path("modify-compacted");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        builder.add_file("data\\first.txt", content(1)).unwrap();
+        builder.add_file("data\\second.txt", content(2)).unwrap();
+        builder.save().unwrap();
+        builder.remove_file("data\\first.txt").unwrap();
+        builder.compact().unwrap();
+        builder.save().unwrap();
+
+        builder.add_file("data\\third.txt", content(3)).unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        let temporary_path_exists = NativeArchiveBuilder::temporary_path(&path).exists();
+        std::fs::remove_file(path).unwrap();
+
+        assert!(!temporary_path_exists);
+        assert_eq!(archive.get_file_by_path("data\\first.txt"), None);
+        assert_eq!(archive.get_file_by_path("data\\second.txt"), Some(content(2)));
+        assert_eq!(archive.get_file_by_path("data\\third.txt"), Some(content(3)));
+    }
+
+    #[test]
+    fn compact() {
+        let path = archive_path("compact");
+
+        let mut builder = NativeArchiveBuilder::from_path(&path).unwrap();
+        for index in 0..4 {
+            builder.add_file(&format!("data\\{index}.txt"), content(index)).unwrap();
+        }
+        builder.save().unwrap();
+        builder.remove_file("data\\1.txt").unwrap();
+        builder.save().unwrap();
+
+        let size_before = file_size(&path);
+        builder.compact().unwrap();
+        builder.save().unwrap();
+        drop(builder);
+
+        let archive = NativeArchive::from_path(&path);
+        let size_after = file_size(&path);
+        std::fs::remove_file(path).unwrap();
 
-        std::fs::write(&self.os_file_path, bytes).expect("unable to write file");
+        assert!(size_after < size_before);
+        assert_eq!(archive.file_table.len(), 3);
+        assert_eq!(archive.get_file_by_path("data\\0.txt"), Some(content(0)));
+        assert_eq!(archive.get_file_by_path("data\\2.txt"), Some(content(2)));
+        assert_eq!(archive.get_file_by_path("data\\3.txt"), Some(content(3)));
     }
 }
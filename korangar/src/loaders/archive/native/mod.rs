@@ -3,68 +3,110 @@ mod builder;
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::path::Path;
 use std::sync::Mutex;
 
 #[cfg(feature = "debug")]
 use korangar_debug::logging::{Colorize, Timer};
 use ragnarok_bytes::{ByteStream, FixedByteSize, FromBytes};
-use ragnarok_formats::archive::{AssetTable, FileTableRow, Header};
+use ragnarok_formats::archive::{decrypt_file, AssetTable, FileFlags, FileTableRow, Header, LegacyFileTableRow};
 use yazi::{decompress, Format};
 
 pub use self::builder::NativeArchiveBuilder;
 use crate::loaders::archive::Archive;
+use crate::loaders::GAME_FILE_TEXT_ENCODING;
 
 /// Represents a GRF file. GRF Files are an archive to store game assets.
 /// Each GRF contains a [`Header`] with metadata (number of files, size,
 /// etc.) and a table [`AssetTable`] with information about individual assets.
 type FileTable = HashMap<String, FileTableRow>;
 
+fn conversion_error<T>(_error: T) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "failed to convert grf structures")
+}
+
+fn decompression_error(error: yazi::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("failed to decompress data: {error:?}"))
+}
+
 pub struct NativeArchive {
     file_table: FileTable,
     file_handle: Mutex<File>,
 }
 
-impl Archive for NativeArchive {
-    fn from_path(path: &Path) -> Self {
-        #[cfg(feature = "debug")]
-        let timer = Timer::new_dynamic(format!("load game data from {}", path.display().magenta()));
-        let mut file = File::open(path).unwrap();
+impl NativeArchive {
+    fn load_file_table(file: &mut File, file_count: usize) -> io::Result<FileTable> {
+        let mut file_table_buffer = vec![0; AssetTable::size_in_bytes()];
 
-        let mut file_header_buffer = vec![0u8; Header::size_in_bytes()];
-        file.read_exact(&mut file_header_buffer).unwrap();
-        let file_header = Header::from_bytes(&mut ByteStream::<()>::without_metadata(&file_header_buffer)).unwrap();
+        file.read_exact(&mut file_table_buffer)?;
+        let file_table = AssetTable::from_bytes(&mut ByteStream::<()>::without_metadata(&file_table_buffer)).map_err(conversion_error)?;
 
-        assert_eq!(file_header.version, 0x200, "invalid grf version");
+        let mut compressed_file_table_buffer = vec![0u8; file_table.compressed_size as usize];
+        file.read_exact(&mut compressed_file_table_buffer)?;
+        let (decompressed, _checksum) = decompress(&compressed_file_table_buffer, Format::Zlib).map_err(decompression_error)?;
 
-        let _ = file.seek(SeekFrom::Current(file_header.file_table_offset as i64)).unwrap();
-        let mut file_table_buffer = vec![0; AssetTable::size_in_bytes()];
+        let mut file_table_byte_stream = ByteStream::<()>::without_metadata(&decompressed).with_text_encoding(GAME_FILE_TEXT_ENCODING);
+        let mut assets = HashMap::with_capacity(file_count);
 
-        file.read_exact(&mut file_table_buffer).unwrap();
-        let file_table = AssetTable::from_bytes(&mut ByteStream::<()>::without_metadata(&file_table_buffer)).unwrap();
+        for _index in 0..file_count {
+            let file_information = FileTableRow::from_bytes(&mut file_table_byte_stream).map_err(conversion_error)?;
+            let file_name = file_information.file_name.as_str().to_lowercase();
 
-        let mut compressed_file_table_buffer = vec![0u8; file_table.compressed_size as usize];
-        file.read_exact(&mut compressed_file_table_buffer).unwrap();
-        let (decompressed, _checksum) = decompress(&compressed_file_table_buffer, Format::Zlib).unwrap();
+            assets.insert(file_name, file_information);
+        }
+
+        Ok(assets)
+    }
 
-        let file_count = file_header.get_file_count();
+    /// Versions 0x102 and 0x103 store the file table uncompressed until the
+    /// end of the file.
+    fn load_legacy_file_table(file: &mut File, file_count: usize) -> FileTable {
+        let mut file_table_buffer = Vec::new();
+        file.read_to_end(&mut file_table_buffer).unwrap();
 
-        let mut file_table_byte_stream = ByteStream::<()>::without_metadata(&decompressed);
+        let mut file_table_byte_stream = ByteStream::<()>::without_metadata(&file_table_buffer).with_text_encoding(GAME_FILE_TEXT_ENCODING);
         let mut assets = HashMap::with_capacity(file_count);
 
         for _index in 0..file_count {
-            let file_information = FileTableRow::from_bytes(&mut file_table_byte_stream).unwrap();
-            let file_name = file_information.file_name.to_lowercase();
+            let legacy_file_information = LegacyFileTableRow::from_bytes(&mut file_table_byte_stream).unwrap();
+            let file_information = FileTableRow::from(legacy_file_information);
 
+            // Unlike newer versions, the file table also contains directories.
+            if !file_information.flags.contains(FileFlags::FILE) {
+                continue;
+            }
+
+            let file_name = file_information.file_name.as_str().to_lowercase();
             assets.insert(file_name, file_information);
         }
 
+        assets
+    }
+}
+
+impl Archive for NativeArchive {
+    fn from_path(path: &Path) -> Self {
+        #[cfg(feature = "debug")]
+        let timer = Timer::new_dynamic(format!("load game data from {}", path.display().magenta()));
+        let mut file = File::open(path).unwrap();
+
+        let mut file_header_buffer = vec![0u8; Header::size_in_bytes()];
+        file.read_exact(&mut file_header_buffer).unwrap();
+        let file_header = Header::from_bytes(&mut ByteStream::<()>::without_metadata(&file_header_buffer)).unwrap();
+
+        let _ = file.seek(SeekFrom::Current(file_header.file_table_offset as i64)).unwrap();
+        let file_count = file_header.get_file_count().unwrap();
+
+        let assets = match file_header.version {
+            0x200 => Self::load_file_table(&mut file, file_count).expect("failed to load grf file table"),
+            0x102 | 0x103 => Self::load_legacy_file_table(&mut file, file_count),
+            version => panic!("unsupported grf version {version:#x}"),
+        };
+
         #[cfg(feature = "debug")]
         timer.stop();
 
-        // TODO: only take 64..? bytes so that loaded game archives can be extended
-        // aswell
         Self {
             file_table: assets,
             file_handle: Mutex::new(file),
@@ -72,15 +114,8 @@ impl Archive for NativeArchive {
     }
 
     fn get_file_by_path(&self, asset_path: &str) -> Option<Vec<u8>> {
-        self.file_table.get(asset_path).and_then(|file_information| {
+        self.file_table.get(asset_path).map(|file_information| {
             let mut compressed_file_buffer = vec![0u8; file_information.compressed_size_aligned as usize];
-
-            // TODO: Figure out what the GRF_FLAG_MIXCRYPT flag actually means and load the
-            // file correctly
-            if file_information.flags > 1 {
-                return None;
-            }
-
             let position = file_information.offset as u64 + Header::size_in_bytes() as u64;
 
             {
@@ -93,10 +128,16 @@ impl Archive for NativeArchive {
                     .expect("Can't read archive content");
             }
 
+            decrypt_file(
+                &mut compressed_file_buffer,
+                file_information.flags,
+                file_information.compressed_size,
+            );
+
             let (uncompressed_file_buffer, _checksum) =
                 decompress(&compressed_file_buffer, Format::Zlib).expect("Can't decompress archive content");
 
-            Some(uncompressed_file_buffer)
+            uncompressed_file_buffer
         })
     }
 
@@ -104,7 +145,7 @@ impl Archive for NativeArchive {
         let files = self
             .file_table
             .iter()
-            .filter(|(file_name, row)| file_name.ends_with(".lub") && row.flags == 0x01)
+            .filter(|(file_name, row)| file_name.ends_with(".lub") && row.flags.contains(FileFlags::FILE))
             .map(|(file_name, _)| file_name.clone());
 
         lua_files.extend(files);
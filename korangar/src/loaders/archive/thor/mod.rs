@@ -0,0 +1,309 @@
+//! A THOR patch containing game assets that are added or removed.
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+
+#[cfg(feature = "debug")]
+use korangar_debug::logging::{Colorize, Timer};
+use ragnarok_bytes::{ByteStream, FixedByteSize, FromBytes};
+use ragnarok_formats::archive::thor::{
+    ThorFileData, ThorFileFlags, ThorFileTable, ThorFileTableRow, ThorHeader, ThorMergeMode, ThorMode, ThorSingleFileEntry,
+};
+use yazi::{decompress, Format};
+
+use super::{Archive, Writable};
+use crate::loaders::GAME_FILE_TEXT_ENCODING;
+
+/// Upper bound of the size of the header and the single file entry. Both
+/// contain at most one string of up to 255 bytes.
+const MAXIMUM_HEADER_SIZE: u64 = 1024;
+
+/// Represents a THOR patch. Files marked as removed hide the files of
+/// archives that were loaded before the patch.
+pub struct ThorArchive {
+    /// Name of the archive the patch should be merged into. An empty name
+    /// stands for the default archive. Patches that are not merged are
+    /// [`None`].
+    merge_target: Option<String>,
+    /// Files that are removed by the patch map to [`None`].
+    file_table: HashMap<String, Option<ThorFileData>>,
+    file_handle: Mutex<File>,
+}
+
+impl ThorArchive {
+    fn load_file_table(file: &mut File, file_count: usize) -> Vec<ThorFileTableRow> {
+        let mut file_table_buffer = vec![0; ThorFileTable::size_in_bytes()];
+        file.read_exact(&mut file_table_buffer).unwrap();
+        let file_table = ThorFileTable::from_bytes(&mut ByteStream::<()>::without_metadata(&file_table_buffer)).unwrap();
+
+        let mut compressed_file_table_buffer = vec![0u8; file_table.compressed_size as usize];
+        file.seek(SeekFrom::Start(file_table.offset as u64)).unwrap();
+        file.read_exact(&mut compressed_file_table_buffer).unwrap();
+        let (decompressed, _checksum) = decompress(&compressed_file_table_buffer, Format::Zlib).unwrap();
+
+        let mut file_table_byte_stream = ByteStream::<()>::without_metadata(&decompressed).with_text_encoding(GAME_FILE_TEXT_ENCODING);
+        let mut rows = Vec::with_capacity(file_count);
+
+        while !file_table_byte_stream.is_empty() {
+            rows.push(ThorFileTableRow::from_bytes(&mut file_table_byte_stream).unwrap());
+        }
+
+        rows
+    }
+
+    /// Name of the archive the patch should be merged into, if any.
+    pub fn merge_target(&self) -> Option<&str> {
+        self.merge_target.as_deref()
+    }
+
+    /// Applies the patch to a writable archive, adding and removing files.
+    pub fn apply(&self, writable: &mut dyn Writable) -> io::Result<()> {
+        for (file_name, data) in &self.file_table {
+            match data {
+                Some(_) => writable.add_file(file_name, self.get_file_by_path(file_name).unwrap())?,
+                None => writable.remove_file(file_name)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Archive for ThorArchive {
+    fn from_path(path: &Path) -> Self {
+        #[cfg(feature = "debug")]
+        let timer = Timer::new_dynamic(format!("load patch from {}", path.display().magenta()));
+        let mut file = File::open(path).unwrap();
+
+        let mut header_buffer = Vec::new();
+        (&mut file).take(MAXIMUM_HEADER_SIZE).read_to_end(&mut header_buffer).unwrap();
+
+        let mut header_byte_stream = ByteStream::<()>::without_metadata(&header_buffer).with_text_encoding(GAME_FILE_TEXT_ENCODING);
+        let header = ThorHeader::from_bytes(&mut header_byte_stream).unwrap();
+
+        let rows = match header.mode {
+            ThorMode::SingleFile => {
+                let entry = ThorSingleFileEntry::from_bytes(&mut header_byte_stream).unwrap();
+
+                vec![ThorFileTableRow {
+                    file_name: entry.file_name,
+                    flags: ThorFileFlags::empty(),
+                    data: Some(ThorFileData {
+                        offset: header_byte_stream.get_offset() as u32,
+                        compressed_size: entry.compressed_size,
+                        uncompressed_size: entry.uncompressed_size,
+                    }),
+                }]
+            }
+            ThorMode::MultipleFiles => {
+                file.seek(SeekFrom::Start(header_byte_stream.get_offset() as u64)).unwrap();
+                Self::load_file_table(&mut file, header.file_count as usize)
+            }
+        };
+
+        let file_table = rows
+            .into_iter()
+            .map(|row| (row.file_name.replace('/', "\\").to_lowercase(), row.data))
+            .collect();
+
+        #[cfg(feature = "debug")]
+        timer.stop();
+
+        let merge_target = (header.merge_mode == ThorMergeMode::Grf).then_some(header.target_grf_name.0);
+
+        Self {
+            merge_target,
+            file_table,
+            file_handle: Mutex::new(file),
+        }
+    }
+
+    fn get_file_by_path(&self, asset_path: &str) -> Option<Vec<u8>> {
+        self.file_table.get(asset_path)?.as_ref().map(|data| {
+            let mut compressed_file_buffer = vec![0u8; data.compressed_size as usize];
+
+            {
+                let mut file_handle = self.file_handle.lock().unwrap();
+                file_handle.seek(SeekFrom::Start(data.offset as u64)).unwrap();
+                file_handle
+                    .read_exact(&mut compressed_file_buffer)
+                    .expect("Can't read patch content");
+            }
+
+            let (uncompressed_file_buffer, _checksum) =
+                decompress(&compressed_file_buffer, Format::Zlib).expect("Can't decompress patch content");
+
+            uncompressed_file_buffer
+        })
+    }
+
+    fn is_file_removed(&self, asset_path: &str) -> bool {
+        self.file_table.get(asset_path).is_some_and(Option::is_none)
+    }
+
+    fn get_lua_files(&self, lua_files: &mut Vec<String>) {
+        let files = self
+            .file_table
+            .iter()
+            .filter(|(file_name, data)| file_name.ends_with(".lub") && data.is_some())
+            .map(|(file_name, _)| file_name.clone());
+
+        lua_files.extend(files);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod patch {
+    use std::collections::HashMap;
+    use std::io;
+    use std::path::PathBuf;
+
+    use ragnarok_bytes::{ByteWriter, ToBytes};
+    use ragnarok_formats::archive::thor::{
+        ThorFileData, ThorFileFlags, ThorFileTable, ThorFileTableRow, ThorHeader, ThorMergeMode, ThorMode, ThorSingleFileEntry,
+    };
+    use ragnarok_formats::signature::Signature;
+    use yazi::{compress, CompressionLevel, Format};
+
+    use super::ThorArchive;
+    use crate::loaders::archive::{Archive, Writable};
+
+    #[derive(Default)]
+    struct MemoryArchive {
+        files: HashMap<String, Vec<u8>>,
+    }
+
+    impl Writable for MemoryArchive {
+        fn add_file(&mut self, path: &str, asset: Vec<u8>) -> io::Result<()> {
+            self.files.insert(path.to_owned(), asset);
+            Ok(())
+        }
+
+        fn remove_file(&mut self, path: &str) -> io::Result<()> {
+            self.files.remove(path);
+            Ok(())
+        }
+    }
+
+    fn header(file_count: u32, mode: ThorMode, target_grf_name: &str) -> ThorHeader {
+        ThorHeader {
+            signature: Signature,
+            merge_mode: ThorMergeMode::Grf,
+            file_count,
+            mode,
+            target_grf_name: target_grf_name.into(),
+        }
+    }
+
+    fn write_patch(name: &str, bytes: Vec<u8>) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("korangar-{}-{name}.thor", std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    /// Writes a patch that adds and removes the given files and is merged into
+    /// the target archive.
+    pub(crate) fn write_multiple_files_patch(name: &str, target_grf_name: &str, added: &[(&str, &[u8])], removed: &[&str]) -> PathBuf {
+        let file_count = (added.len() + removed.len()) as u32;
+
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        header(file_count, ThorMode::MultipleFiles, target_grf_name)
+            .to_bytes(&mut byte_writer)
+            .unwrap();
+
+        // The file data is placed right after the file table information.
+        let mut data_offset = byte_writer.len() as u32 + 8;
+        let mut file_data = Vec::new();
+        let mut file_table_writer = ByteWriter::<()>::without_metadata();
+
+        for (file_name, content) in added {
+            let compressed = compress(content, Format::Zlib, CompressionLevel::Default).unwrap();
+
+            ThorFileTableRow {
+                file_name: (*file_name).into(),
+                flags: ThorFileFlags::empty(),
+                data: Some(ThorFileData {
+                    offset: data_offset,
+                    compressed_size: compressed.len() as u32,
+                    uncompressed_size: content.len() as u32,
+                }),
+            }
+            .to_bytes(&mut file_table_writer)
+            .unwrap();
+
+            data_offset += compressed.len() as u32;
+            file_data.extend_from_slice(&compressed);
+        }
+
+        for file_name in removed {
+            ThorFileTableRow {
+                file_name: (*file_name).into(),
+                flags: ThorFileFlags::REMOVED,
+                data: None,
+            }
+            .to_bytes(&mut file_table_writer)
+            .unwrap();
+        }
+
+        let compressed_file_table = compress(&file_table_writer.into_inner(), Format::Zlib, CompressionLevel::Default).unwrap();
+
+        ThorFileTable {
+            compressed_size: compressed_file_table.len() as u32,
+            offset: data_offset,
+        }
+        .to_bytes(&mut byte_writer)
+        .unwrap();
+        byte_writer.extend_from_slice(&file_data);
+        byte_writer.extend_from_slice(&compressed_file_table);
+
+        write_patch(name, byte_writer.into_inner())
+    }
+
+    #[test]
+    fn single_file() {
+        let content = b"single file content".to_vec();
+        let compressed = compress(&content, Format::Zlib, CompressionLevel::Default).unwrap();
+
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        header(1, ThorMode::SingleFile, "").to_bytes(&mut byte_writer).unwrap();
+        ThorSingleFileEntry {
+            _unknown: 0,
+            compressed_size: compressed.len() as u32,
+            uncompressed_size: content.len() as u32,
+            file_name: "data\\Single.txt".into(),
+        }
+        .to_bytes(&mut byte_writer)
+        .unwrap();
+        byte_writer.extend_from_slice(&compressed);
+
+        let path = write_patch("single", byte_writer.into_inner());
+        let archive = ThorArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.get_file_by_path("data\\single.txt"), Some(content));
+    }
+
+    #[test]
+    fn multiple_files() {
+        let content = b"added file content".to_vec();
+
+        let path = write_multiple_files_patch("multiple", "data.grf", &[("data\\added.txt", &content)], &["data\\removed.txt"]);
+        let archive = ThorArchive::from_path(&path);
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(archive.merge_target(), Some("data.grf"));
+        assert_eq!(archive.get_file_by_path("data\\added.txt"), Some(content.clone()));
+        assert_eq!(archive.get_file_by_path("data\\removed.txt"), None);
+        assert!(archive.is_file_removed("data\\removed.txt"));
+        assert!(!archive.is_file_removed("data\\added.txt"));
+
+        let mut memory_archive = MemoryArchive::default();
+        memory_archive.add_file("data\\removed.txt", Vec::new()).unwrap();
+        archive.apply(&mut memory_archive).unwrap();
+
+        assert_eq!(memory_archive.files.len(), 1);
+        assert_eq!(memory_archive.files.get("data\\added.txt"), Some(&content));
+    }
+}
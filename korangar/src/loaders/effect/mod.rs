@@ -6,14 +6,14 @@ use cgmath::Deg;
 use korangar_debug::logging::{print_debug, Colorize, Timer};
 use korangar_util::FileLoader;
 use ragnarok_bytes::{ByteStream, FromBytes};
-use ragnarok_formats::effect::EffectData;
+use ragnarok_formats::effect::{EffectData, EffectId};
 use ragnarok_formats::version::InternalVersion;
 use wgpu::BlendFactor;
 
 use super::error::LoadError;
 use super::TextureLoader;
 use crate::graphics::Color;
-use crate::loaders::GameFileLoader;
+use crate::loaders::{GameFileLoader, GAME_FILE_TEXT_ENCODING};
 use crate::world::{AnimationType, Effect, Frame, FrameType, Layer, MultiTexturePresent};
 
 pub struct EffectLoader {
@@ -37,7 +37,8 @@ impl EffectLoader {
             .game_file_loader
             .get(&format!("data\\texture\\effect\\{path}"))
             .map_err(LoadError::File)?;
-        let mut byte_stream: ByteStream<Option<InternalVersion>> = ByteStream::without_metadata(&bytes);
+        let mut byte_stream: ByteStream<Option<InternalVersion>> =
+            ByteStream::without_metadata(&bytes).with_text_encoding(GAME_FILE_TEXT_ENCODING);
 
         // TODO: Add fallback
         let effect_data = EffectData::from_bytes(&mut byte_stream).map_err(LoadError::Conversion)?;
@@ -120,7 +121,7 @@ impl EffectLoader {
                                     frame.offset,
                                     frame.uv,
                                     frame.xy,
-                                    frame.texture_index as usize,
+                                    frame.texture_index,
                                     animation_type,
                                     frame.delay,
                                     Deg(frame.angle / (1024.0 / 360.0)).into(),
@@ -157,6 +158,30 @@ impl EffectLoader {
     }
 }
 
+/// Returns the STR file used to display the given effect. Effects that are
+/// not backed by an STR file (such as particle effects) or that are not mapped
+/// yet return [`None`].
+///
+/// The file names are the STR entries of the effect table of roBrowser
+/// (`src/DB/Effects/EffectTable.js`), which mirrors the table of the official
+/// client.
+pub fn effect_file(effect_id: EffectId) -> Option<&'static str> {
+    match effect_id {
+        EffectId::Firewall => Some("firewall.str"),
+        EffectId::Torch => Some("torch_01.str"),
+        EffectId::Pneuma => Some("pneuma1.str"),
+        EffectId::Waterfall => Some("waterfall.str"),
+        EffectId::Waterfall90 => Some("waterfall_90.str"),
+        EffectId::WaterfallSmall => Some("waterfall_small.str"),
+        EffectId::WaterfallSmall90 => Some("waterfall_small_90.str"),
+        EffectId::WaterfallT2 => Some("waterfall_t2.str"),
+        EffectId::WaterfallT2_90 => Some("waterfall_t2_90.str"),
+        EffectId::WaterfallSmallT2 => Some("waterfall_small_t2.str"),
+        EffectId::WaterfallSmallT2_90 => Some("waterfall_small_t2_90.str"),
+        _ => None,
+    }
+}
+
 fn parse_blend_factor(value: i32, previous: Option<BlendFactor>, is_source: bool) -> BlendFactor {
     match value {
         0 => previous.unwrap(),
@@ -188,14 +213,15 @@ fn parse_blend_factor(value: i32, previous: Option<BlendFactor>, is_source: bool
 
 fn parse_animation_type(value: i32) -> AnimationType {
     match value {
-        0 => AnimationType::Type0,
-        1 => AnimationType::Type1,
-        2 => AnimationType::Type2,
-        3 => AnimationType::Type3,
+        0 => AnimationType::Static,
+        1 => AnimationType::Interpolated,
+        2 => AnimationType::PlayOnce,
+        3 => AnimationType::Repeat,
+        4 => AnimationType::RepeatReversed,
         _ => {
             #[cfg(feature = "debug")]
             print_debug!("[{}] unknown animation type found in frame data: {value}", "error".red());
-            AnimationType::Type1
+            AnimationType::Interpolated
         }
     }
 }
@@ -215,6 +241,7 @@ fn parse_frame_type(value: i32) -> FrameType {
 fn parse_mt_present(value: i32) -> MultiTexturePresent {
     match value {
         0 => MultiTexturePresent::None,
+        1 => MultiTexturePresent::Blended,
         _ => {
             #[cfg(feature = "debug")]
             print_debug!("[{}] unknown multi texture present found in frame data: {value}", "error".red());
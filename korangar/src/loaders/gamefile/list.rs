@@ -1,6 +1,7 @@
 #[cfg(feature = "debug")]
 use korangar_debug::logging::{print_debug, Colorize};
 use korangar_interface::elements::PrototypeElement;
+use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, PrototypeElement)]
@@ -40,3 +41,41 @@ impl GameArchiveList {
             })
     }
 }
+
+/// THOR patch that is applied to one of the game archives.
+#[derive(Serialize, Deserialize)]
+pub(super) struct GamePatch {
+    pub patch: String,
+    pub target: String,
+}
+
+/// Patches that are applied to the game archives the next time they are
+/// loaded. Applied patches are removed from the list, so every patch is only
+/// applied once.
+#[derive(Default, Serialize, Deserialize)]
+pub(super) struct GamePatchList {
+    pub patches: Vec<GamePatch>,
+}
+
+impl GamePatchList {
+    const FILE_NAME: &'static str = "client/game_patches.ron";
+
+    pub(super) fn load() -> Self {
+        #[cfg(feature = "debug")]
+        print_debug!("loading game patch list from {}", Self::FILE_NAME.magenta());
+
+        std::fs::read_to_string(Self::FILE_NAME)
+            .ok()
+            .and_then(|data| ron::from_str(&data).ok())
+            .map(|patches| Self { patches })
+            .unwrap_or_default()
+    }
+
+    pub(super) fn save(&self) {
+        #[cfg(feature = "debug")]
+        print_debug!("saving game patch list to {}", Self::FILE_NAME.magenta());
+
+        let data = ron::ser::to_string_pretty(&self.patches, PrettyConfig::new()).unwrap();
+        std::fs::write(Self::FILE_NAME, data).expect("unable to write file");
+    }
+}
@@ -4,16 +4,19 @@
 mod list;
 
 use core::panic;
+use std::io;
 use std::path::Path;
 use std::sync::RwLock;
 
 #[cfg(feature = "debug")]
 use korangar_debug::logging::{print_debug, Colorize, Timer};
 use korangar_util::{FileLoader, FileNotFoundError};
+use ragnarok_bytes::TextEncoding;
 
-use self::list::GameArchiveList;
+use self::list::{GameArchiveList, GamePatchList};
 use super::archive::folder::FolderArchive;
 use super::archive::native::{NativeArchive, NativeArchiveBuilder};
+use super::archive::thor::ThorArchive;
 use super::archive::{Archive, ArchiveType, Writable};
 
 #[cfg(feature = "patched_as_folder")]
@@ -27,13 +30,17 @@ pub const FALLBACK_TGA_FILE: &str = "data\\texture\\missing.tga";
 pub const FALLBACK_MODEL_FILE: &str = "data\\model\\missing.rsm";
 pub const FALLBACK_SPRITE_FILE: &str = "data\\sprite\\npc\\missing.spr";
 pub const FALLBACK_ACTIONS_FILE: &str = "data\\sprite\\npc\\missing.act";
+/// Encoding of file names inside of archives and of strings inside of game
+/// files.
+pub const GAME_FILE_TEXT_ENCODING: TextEncoding = TextEncoding::Windows949;
 
 /// Type implementing the game file loader.
 ///
-/// Currently, there are two types implementing
+/// Currently, there are three types implementing
 /// [`Archive`]:
-/// - [`NativeArchive`] - Retrieve assets from GRF files.
+/// - [`NativeArchive`] - Retrieve assets from GRF and GPF files.
 /// - [`FolderArchive`] - Retrieve assets from an OS folder.
+/// - [`ThorArchive`] - Retrieve assets from THOR patches.
 #[derive(Default)]
 pub struct GameFileLoader {
     archives: RwLock<Vec<Box<dyn Archive>>>,
@@ -47,6 +54,7 @@ impl FileLoader for GameFileLoader {
             .read()
             .unwrap()
             .iter()
+            .take_while(|archive| !archive.is_file_removed(&lowercase_path))
             .find_map(|archive| archive.get_file_by_path(&lowercase_path))
             .ok_or(FileNotFoundError::new(path.to_owned()));
 
@@ -84,11 +92,15 @@ impl GameFileLoader {
         if path.is_dir() || path.display().to_string().ends_with('/') {
             ArchiveType::Folder
         } else if let Some(extension) = path.extension()
-            && let Some("grf") = extension.to_str()
+            && let Some("grf" | "gpf") = extension.to_str()
         {
             ArchiveType::Native
+        } else if let Some(extension) = path.extension()
+            && let Some("thor") = extension.to_str()
+        {
+            ArchiveType::Thor
         } else {
-            panic!("Provided archive must be a directory or have a .grf extension")
+            panic!("Provided archive must be a directory or have a .grf, .gpf or .thor extension")
         }
     }
 
@@ -98,6 +110,7 @@ impl GameFileLoader {
         match GameFileLoader::get_archive_type_by_path(path) {
             ArchiveType::Folder => Box::new(FolderArchive::from_path(path)),
             ArchiveType::Native => Box::new(NativeArchive::from_path(path)),
+            ArchiveType::Thor => Box::new(ThorArchive::from_path(path)),
         }
     }
 
@@ -105,15 +118,105 @@ impl GameFileLoader {
         #[cfg(feature = "debug")]
         let timer = Timer::new("load game archives");
 
+        Self::apply_patches_from_settings();
+
         let game_archive_list = GameArchiveList::load();
+        self.load_archives(&game_archive_list.archives);
+
+        #[cfg(feature = "debug")]
+        timer.stop();
+    }
+
+    /// Applies the pending patches in list order. If a patch fails, it and all
+    /// patches after it stay in the list, since later patches might depend on
+    /// it.
+    fn apply_patches_from_settings() {
+        let mut game_patch_list = GamePatchList::load();
+
+        if game_patch_list.patches.is_empty() {
+            return;
+        }
+
+        let applied_count = game_patch_list
+            .patches
+            .iter()
+            .take_while(|game_patch| match Self::apply_patch(&game_patch.patch, &game_patch.target) {
+                Ok(()) => true,
+                Err(_error) => {
+                    #[cfg(feature = "debug")]
+                    print_debug!(
+                        "[{}] failed to apply patch {}: {}",
+                        "error".red(),
+                        game_patch.patch.magenta(),
+                        _error
+                    );
+
+                    false
+                }
+            })
+            .count();
+
+        game_patch_list.patches.drain(..applied_count);
+        game_patch_list.save();
+    }
 
-        game_archive_list.archives.iter().for_each(|path| {
+    fn load_archives(&self, archive_paths: &[String]) {
+        archive_paths.iter().for_each(|path| {
             let game_archive = Self::load_archive_from_path(path);
             self.add_archive(game_archive);
         });
+    }
+
+    /// Applies a THOR patch to the archive at the target path, adding and
+    /// removing files. Only patches from the patch list are applied while
+    /// loading the game archives, patches in the archive list are mounted on
+    /// top of the other archives instead. The patch itself is left untouched.
+    pub fn apply_patch(patch_path: &str, target_path: &str) -> io::Result<()> {
+        let target_path = Path::new(target_path);
+        let patch = ThorArchive::from_path(Path::new(patch_path));
+
+        // An empty target name stands for the default archive, which can be any of the
+        // archives, so only patches naming a different archive are rejected.
+        if let Some(target_name) = patch.merge_target()
+            && !target_name.is_empty()
+            && !target_path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .is_some_and(|file_name| file_name.eq_ignore_ascii_case(target_name))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("patch {patch_path} targets {target_name}"),
+            ));
+        }
 
         #[cfg(feature = "debug")]
-        timer.stop();
+        print_debug!("applying patch {} to {}", patch_path.magenta(), target_path.display().magenta());
+
+        Self::merge_patch(&patch, target_path)
+    }
+
+    fn merge_patch(patch: &ThorArchive, target_path: &Path) -> io::Result<()> {
+        match GameFileLoader::get_archive_type_by_path(target_path) {
+            ArchiveType::Folder => {
+                let mut target = FolderArchive::from_path(target_path);
+                patch.apply(&mut target)?;
+                target.save()
+            }
+            ArchiveType::Native => {
+                let mut target = NativeArchiveBuilder::from_path(target_path)?;
+                patch.apply(&mut target)?;
+
+                // Every patch leaves the space of replaced files behind, so the archive is
+                // compacted once most of it is unused.
+                if target.should_compact() {
+                    target.compact()?;
+                }
+
+                target.save()
+            }
+            ArchiveType::Thor => panic!("THOR patches can't be written"),
+        }
     }
 
     pub fn remove_patched_lua_files(&self) {
@@ -127,15 +230,21 @@ impl GameFileLoader {
     }
 
     pub fn load_patched_lua_files(&self) {
-        if !Path::new(LUA_GRF_FILE_NAME).exists() {
-            self.patch_lua_files();
+        // The patched files are only moved to their final path after all of them
+        // are written, so an existing archive is always complete.
+        if !Path::new(LUA_GRF_FILE_NAME).exists()
+            && let Err(_error) = self.patch_lua_files()
+        {
+            #[cfg(feature = "debug")]
+            print_debug!("[{}] failed to patch lua files: {}", "error".red(), _error);
+            return;
         }
 
         let lua_archive = Self::load_archive_from_path(LUA_GRF_FILE_NAME);
         self.add_archive(lua_archive);
     }
 
-    fn patch_lua_files(&self) {
+    fn patch_lua_files(&self) -> io::Result<()> {
         use lunify::{unify, Format, Settings};
 
         let mut lua_files = Vec::new();
@@ -146,9 +255,22 @@ impl GameFileLoader {
             .for_each(|archive| archive.get_lua_files(&mut lua_files));
 
         let path = Path::new(LUA_GRF_FILE_NAME);
+        // Native archives are only created once they are saved. Folders are written
+        // to a temporary folder instead that is renamed when all files are written.
+        let mut temporary_folder = None;
         let mut lua_archive: Box<dyn Writable> = match GameFileLoader::get_archive_type_by_path(path) {
-            ArchiveType::Folder => Box::new(FolderArchive::from_path(path)),
-            ArchiveType::Native => Box::new(NativeArchiveBuilder::from_path(path)),
+            ArchiveType::Folder => {
+                let folder_path = temporary_folder.insert(path.with_extension("tmp"));
+
+                if folder_path.exists() {
+                    std::fs::remove_dir_all(&folder_path)?;
+                }
+
+                std::fs::create_dir_all(&folder_path)?;
+                Box::new(FolderArchive::from_path(folder_path))
+            }
+            ArchiveType::Native => Box::new(NativeArchiveBuilder::from_path(path)?),
+            ArchiveType::Thor => panic!("THOR patches can't be written"),
         };
 
         let bytecode_format = Format::default();
@@ -180,7 +302,15 @@ impl GameFileLoader {
 
             // Try to unify all bytecode to Lua 5.1 and possibly 64 bit.
             match unify(&bytes, &bytecode_format, &settings) {
-                Ok(bytes) => lua_archive.add_file(&file_name, bytes),
+                Ok(bytes) => {
+                    if let Err(_error) = lua_archive.add_file(&file_name, bytes) {
+                        #[cfg(feature = "debug")]
+                        {
+                            print_debug!("[{}] failed to write {}: {}", "warning".yellow(), file_name.magenta(), _error);
+                            failed_count += 1;
+                        }
+                    }
+                }
                 // If the operation fails the file with this error, the Lua file is not actually a
                 // pre-compiled binary but rather a source file, so we can safely ignore it.
                 #[cfg(feature = "debug")]
@@ -202,6 +332,104 @@ impl GameFileLoader {
             failed_count.red(),
         );
 
-        lua_archive.save();
+        lua_archive.save()?;
+
+        if let Some(temporary_folder) = temporary_folder {
+            std::fs::rename(temporary_folder, path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod patch {
+    use korangar_util::FileLoader;
+
+    use super::GameFileLoader;
+    use crate::loaders::archive::thor::patch::write_multiple_files_patch;
+
+    fn create_folder(name: &str) -> std::path::PathBuf {
+        let folder_path = std::env::temp_dir().join(format!("korangar-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder_path);
+        std::fs::create_dir_all(folder_path.join("data")).unwrap();
+        std::fs::write(folder_path.join("data").join("removed.txt"), b"removed").unwrap();
+        std::fs::write(folder_path.join("data").join("kept.txt"), b"kept").unwrap();
+        std::fs::write(folder_path.join("data").join("replaced.txt"), b"original").unwrap();
+        folder_path
+    }
+
+    #[test]
+    fn mount_in_list_order() {
+        let folder_path = create_folder("mount");
+        let later_folder_path = create_folder("mount-later");
+        std::fs::write(later_folder_path.join("data").join("kept.txt"), b"later").unwrap();
+
+        let patch_path = write_multiple_files_patch(
+            "mount",
+            "",
+            &[("data\\added.txt", b"added"), ("data\\replaced.txt", b"replaced")],
+            &["data\\removed.txt"],
+        );
+
+        let game_file_loader = GameFileLoader::default();
+        game_file_loader.load_archives(&[
+            format!("{}/", folder_path.display()),
+            patch_path.display().to_string(),
+            format!("{}/", later_folder_path.display()),
+        ]);
+
+        // Mounting a patch must never modify any files on disk.
+        assert!(patch_path.exists());
+        assert_eq!(
+            std::fs::read(folder_path.join("data").join("replaced.txt")).unwrap(),
+            b"original"
+        );
+        assert!(folder_path.join("data").join("removed.txt").exists());
+
+        assert_eq!(game_file_loader.get("data\\added.txt").unwrap(), b"added");
+        assert_eq!(game_file_loader.get("data\\kept.txt").unwrap(), b"later");
+        // Archives listed after the patch take priority over it.
+        assert_eq!(game_file_loader.get("data\\replaced.txt").unwrap(), b"original");
+        assert_eq!(game_file_loader.get("data\\removed.txt").unwrap(), b"removed");
+
+        let game_file_loader = GameFileLoader::default();
+        game_file_loader.load_archives(&[format!("{}/", folder_path.display()), patch_path.display().to_string()]);
+
+        assert_eq!(game_file_loader.get("data\\replaced.txt").unwrap(), b"replaced");
+        assert!(game_file_loader.get("data\\removed.txt").is_err());
+
+        std::fs::remove_file(patch_path).unwrap();
+        std::fs::remove_dir_all(folder_path).unwrap();
+        std::fs::remove_dir_all(later_folder_path).unwrap();
+    }
+
+    #[test]
+    fn apply_to_folder() {
+        let folder_path = create_folder("apply");
+        let folder_name = folder_path.file_name().unwrap().to_str().unwrap();
+        let patch_path = write_multiple_files_patch("apply", folder_name, &[("data\\added.txt", b"added")], &["data\\removed.txt"]);
+
+        GameFileLoader::apply_patch(&patch_path.display().to_string(), &folder_path.display().to_string()).unwrap();
+
+        assert!(patch_path.exists());
+        assert!(!folder_path.join("data").join("removed.txt").exists());
+        assert_eq!(std::fs::read(folder_path.join("data").join("added.txt")).unwrap(), b"added");
+        assert_eq!(std::fs::read(folder_path.join("data").join("kept.txt")).unwrap(), b"kept");
+
+        std::fs::remove_file(patch_path).unwrap();
+        std::fs::remove_dir_all(folder_path).unwrap();
+    }
+
+    #[test]
+    fn apply_to_other_target() {
+        let folder_path = create_folder("apply-other");
+        let patch_path = write_multiple_files_patch("apply-other", "data.grf", &[("data\\added.txt", b"added")], &[]);
+
+        assert!(GameFileLoader::apply_patch(&patch_path.display().to_string(), &folder_path.display().to_string()).is_err());
+        assert!(!folder_path.join("data").join("added.txt").exists());
+
+        std::fs::remove_file(patch_path).unwrap();
+        std::fs::remove_dir_all(folder_path).unwrap();
     }
 }
@@ -0,0 +1,71 @@
+use cgmath::Vector2;
+use image::{Rgba, RgbaImage};
+use ragnarok_formats::map::{LightmapData, LIGHTMAP_SIZE};
+
+/// Layout of all lightmaps of a map packed into a single square image.
+pub struct LightmapGrid {
+    lightmap_count: usize,
+    columns: usize,
+    rows: usize,
+}
+
+impl LightmapGrid {
+    pub fn new(lightmap_count: usize) -> Self {
+        let columns = (lightmap_count as f32).sqrt().ceil().max(1.0) as usize;
+        let rows = lightmap_count.div_ceil(columns).max(1);
+
+        Self {
+            lightmap_count,
+            columns,
+            rows,
+        }
+    }
+
+    /// Generates the image containing all lightmaps. The color is stored in
+    /// the RGB channels and the shadow intensity in the alpha channel.
+    pub fn generate_image(&self, lightmaps: &[LightmapData]) -> RgbaImage {
+        let width = (self.columns * LIGHTMAP_SIZE) as u32;
+        let height = (self.rows * LIGHTMAP_SIZE) as u32;
+        let mut image = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+
+        for (index, lightmap) in lightmaps.iter().enumerate() {
+            let offset_x = (index % self.columns) * LIGHTMAP_SIZE;
+            let offset_y = (index / self.columns) * LIGHTMAP_SIZE;
+
+            for cell in 0..LIGHTMAP_SIZE * LIGHTMAP_SIZE {
+                let [red, green, blue] = lightmap.color_map[cell];
+                let x = offset_x + cell % LIGHTMAP_SIZE;
+                let y = offset_y + cell / LIGHTMAP_SIZE;
+
+                image.put_pixel(x as u32, y as u32, Rgba([red, green, blue, lightmap.shadow_map[cell]]));
+            }
+        }
+
+        image
+    }
+
+    /// Returns the normalized coordinates of the given lightmap in the order
+    /// upper left, upper right, lower right, lower left. The outermost cells
+    /// only exist to be blended with the neighboring tiles and are therefore
+    /// excluded.
+    pub fn coordinates(&self, lightmap_index: i16) -> Option<[Vector2<f32>; 4]> {
+        let index = usize::try_from(lightmap_index).ok().filter(|index| *index < self.lightmap_count)?;
+
+        let width = (self.columns * LIGHTMAP_SIZE) as f32;
+        let height = (self.rows * LIGHTMAP_SIZE) as f32;
+        let offset_x = ((index % self.columns) * LIGHTMAP_SIZE) as f32;
+        let offset_y = ((index / self.columns) * LIGHTMAP_SIZE) as f32;
+
+        let left = (offset_x + 1.0) / width;
+        let right = (offset_x + LIGHTMAP_SIZE as f32 - 1.0) / width;
+        let top = (offset_y + 1.0) / height;
+        let bottom = (offset_y + LIGHTMAP_SIZE as f32 - 1.0) / height;
+
+        Some([
+            Vector2::new(left, top),
+            Vector2::new(right, top),
+            Vector2::new(right, bottom),
+            Vector2::new(left, bottom),
+        ])
+    }
+}
@@ -1,3 +1,4 @@
+mod lightmap;
 mod vertices;
 
 use std::sync::Arc;
@@ -8,30 +9,29 @@ use derive_new::new;
 use hashbrown::HashMap;
 use korangar_audio::AudioEngine;
 #[cfg(feature = "debug")]
-use korangar_debug::logging::Timer;
+use korangar_debug::logging::{print_debug, Colorize, Timer};
 use korangar_util::collision::{KDTree, Sphere, AABB};
 use korangar_util::container::SimpleSlab;
 use korangar_util::texture_atlas::{AllocationId, AtlasAllocation};
 use korangar_util::FileLoader;
 use ragnarok_bytes::{ByteStream, FromBytes};
-use ragnarok_formats::map::{GatData, GroundData, GroundTile, MapData, MapResources};
+use ragnarok_formats::map::{GatData, GroundData, GroundTile, MapData, MapResources, Resource, WaterPlane, WaterSettings};
 use ragnarok_formats::version::InternalVersion;
 use wgpu::{BufferUsages, Device, Queue};
 
+use self::lightmap::LightmapGrid;
 pub use self::vertices::MAP_TILE_SIZE;
-use self::vertices::{generate_tile_vertices, ground_water_vertices};
+use self::vertices::{generate_tile_vertices, ground_water_vertices, WaterPlanes};
 use super::error::LoadError;
 use crate::graphics::{Buffer, ModelVertex, NativeModelVertex, Texture};
-use crate::loaders::{GameFileLoader, ModelLoader, TextureAtlasFactory, TextureLoader};
-use crate::world::{LightSourceKey, Model};
+use crate::loaders::{effect_file, EffectLoader, GameFileLoader, ModelLoader, TextureAtlasFactory, TextureLoader, GAME_FILE_TEXT_ENCODING};
+use crate::world::{LightSourceKey, Model, ObjectAnimationType};
 use crate::{EffectSourceExt, LightSourceExt, Map, Object, ObjectKey, SoundSourceExt};
 
 const MAP_OFFSET: f32 = 5.0;
 
 #[cfg(feature = "debug")]
 fn assert_byte_stream_empty<Meta>(mut byte_stream: ByteStream<Meta>, file_name: &str) {
-    use korangar_debug::logging::{print_debug, Colorize};
-
     if !byte_stream.is_empty() {
         print_debug!(
             "incomplete read on file {}; {} bytes remaining",
@@ -44,6 +44,7 @@ fn assert_byte_stream_empty<Meta>(mut byte_stream: ByteStream<Meta>, file_name:
 pub struct DeferredVertexGeneration {
     pub native_model_vertices: Vec<NativeModelVertex>,
     pub texture_allocation: Vec<AllocationId>,
+    pub lightmap_allocation: Option<AllocationId>,
 }
 
 #[derive(new)]
@@ -59,13 +60,14 @@ impl MapLoader {
         &mut self,
         resource_file: String,
         model_loader: &mut ModelLoader,
+        effect_loader: &mut EffectLoader,
         texture_loader: Arc<TextureLoader>,
         #[cfg(feature = "debug")] tile_texture_mapping: &[AtlasAllocation],
     ) -> Result<Map, LoadError> {
         #[cfg(feature = "debug")]
         let timer = Timer::new_dynamic(format!("load map from {}", &resource_file));
 
-        let mut texture_atlas_factory = TextureAtlasFactory::new(texture_loader, "map", true);
+        let mut texture_atlas_factory = TextureAtlasFactory::new(texture_loader.clone(), "map", true);
         let mut deferred_vertex_generation: Vec<DeferredVertexGeneration> = Vec::new();
 
         let map_file_name = format!("data\\{}.rsw", resource_file);
@@ -85,12 +87,28 @@ impl MapLoader {
         #[cfg(not(feature = "debug"))]
         let (_, tile_picker_vertices) = generate_tile_vertices(&mut gat_data);
 
-        let water_level = -map_data
-            .water_settings
+        // Starting with RSW version 2.6 the water settings are stored in the GND.
+        let water_planes = match (&map_data.water_settings, &ground_data.water_settings) {
+            (Some(water_settings), _) => WaterPlanes::uniform(WaterPlane::from(water_settings)),
+            (None, Some(ground_water_settings)) => {
+                map_data.water_settings = Some(WaterSettings::from(ground_water_settings));
+                WaterPlanes::from_ground_settings(ground_water_settings)
+            }
+            (None, None) => WaterPlanes::uniform(WaterPlane::from(&WaterSettings::default())),
+        };
+
+        let lightmap_grid = ground_data
+            .light_maps
+            .as_ref()
+            .filter(|lightmaps| !lightmaps.is_empty())
+            .map(|lightmaps| LightmapGrid::new(lightmaps.len()));
+        let ground_lightmap_allocation = ground_data
+            .light_maps
             .as_ref()
-            .and_then(|settings| settings.water_level)
-            .unwrap_or_default();
-        let (ground_native_vertices, water_vertices) = ground_water_vertices(&ground_data, water_level);
+            .zip(lightmap_grid.as_ref())
+            .map(|(lightmaps, grid)| texture_atlas_factory.register_image(grid.generate_image(lightmaps)));
+
+        let (ground_native_vertices, water_vertices) = ground_water_vertices(&ground_data, &water_planes, lightmap_grid.as_ref());
 
         let ground_vertex_offset = 0;
         let ground_vertex_count = ground_native_vertices.len();
@@ -105,6 +123,7 @@ impl MapLoader {
         deferred_vertex_generation.push(DeferredVertexGeneration {
             native_model_vertices: ground_native_vertices,
             texture_allocation: ground_texture_allocation,
+            lightmap_allocation: ground_lightmap_allocation,
         });
 
         let water_vertex_buffer = (!water_vertices.is_empty()).then(|| self.create_vertex_buffer(&resource_file, "water", &water_vertices));
@@ -117,15 +136,50 @@ impl MapLoader {
         let tile_picker_vertex_buffer =
             (!tile_picker_vertices.is_empty()).then(|| self.create_vertex_buffer(&resource_file, "tile picker", &tile_picker_vertices));
 
+        prepare_map_resources(&mut map_data.resources);
         apply_map_offset(&ground_data, &mut map_data.resources);
 
+        let effects = map_data
+            .resources
+            .effect_sources()
+            .filter_map(|effect_source| {
+                let effect_file = effect_source.effect().and_then(effect_file);
+
+                #[cfg(feature = "debug")]
+                if effect_file.is_none() {
+                    print_debug!(
+                        "[{}] no effect file for effect {} ({:?}) of effect source {}; not rendering it",
+                        "warning".yellow(),
+                        effect_source.effect_id,
+                        effect_source.effect(),
+                        effect_source.name.magenta()
+                    );
+                }
+
+                let effect_file = effect_file?;
+
+                match effect_loader.get(effect_file, &texture_loader) {
+                    Ok(effect) => Some((effect, effect_source.position)),
+                    Err(_error) => {
+                        #[cfg(feature = "debug")]
+                        print_debug!(
+                            "[{}] failed to load effect {}: {:?}",
+                            "error".red(),
+                            effect_file.magenta(),
+                            _error
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
         let mut model_cache = HashMap::<(String, bool), Arc<Model>>::new();
-        let mut objects = SimpleSlab::with_capacity(map_data.resources.objects.len() as u32);
+        let mut objects = SimpleSlab::with_capacity(map_data.resources.objects().count() as u32);
 
         let object_bounding_boxes: Vec<(ObjectKey, AABB)> = map_data
             .resources
-            .objects
-            .iter()
+            .objects()
             .map(|object_data| {
                 let array: [f32; 3] = object_data.transform.scale.into();
                 let reverse_order = array.into_iter().fold(1.0, |a, b| a * b).is_sign_negative();
@@ -151,6 +205,8 @@ impl MapLoader {
                     object_data.model_name.to_owned(),
                     model,
                     object_data.transform,
+                    parse_object_animation_type(object_data.animation_type),
+                    parse_object_animation_speed(object_data.animation_speed),
                 );
                 let bounding_box_matrix = object.get_bounding_box_matrix();
                 let bounding_box = AABB::from_transformation_matrix(bounding_box_matrix);
@@ -164,11 +220,11 @@ impl MapLoader {
         let (texture, vertex_buffer) =
             self.generate_vertex_buffer_and_atlas_texture(&resource_file, texture_atlas_factory, deferred_vertex_generation, vertex_offset);
 
-        let mut light_sources = SimpleSlab::with_capacity(map_data.resources.light_sources.len() as u32);
+        let mut light_sources = SimpleSlab::with_capacity(map_data.resources.light_sources().count() as u32);
         let light_source_spheres: Vec<(LightSourceKey, Sphere)> = map_data
             .resources
-            .light_sources
-            .drain(..)
+            .light_sources()
+            .cloned()
             .map(|light_source| {
                 let sphere = Sphere::new(light_source.position, light_source.range);
                 let key = light_sources.insert(light_source).expect("light sources slab is full");
@@ -191,9 +247,10 @@ impl MapLoader {
             texture,
             objects,
             light_sources,
-            map_data.resources.sound_sources,
+            map_data.resources.sound_sources().cloned().collect(),
+            effects,
             #[cfg(feature = "debug")]
-            map_data.resources.effect_sources,
+            map_data.resources.effect_sources().cloned().collect(),
             tile_picker_vertex_buffer.unwrap(),
             #[cfg(feature = "debug")]
             tile_vertex_buffer,
@@ -228,7 +285,10 @@ impl MapLoader {
                 .drain(..)
                 .map(|allocation_id| texture_atlas_factory.get_allocation(allocation_id).unwrap())
                 .collect();
-            let model_vertices = NativeModelVertex::to_vertices(deferred.native_model_vertices, &texture_mapping);
+            let lightmap_mapping = deferred
+                .lightmap_allocation
+                .map(|allocation_id| texture_atlas_factory.get_allocation(allocation_id).unwrap());
+            let model_vertices = NativeModelVertex::to_vertices(deferred.native_model_vertices, &texture_mapping, lightmap_mapping);
             vertices.extend(model_vertices);
         }
 
@@ -249,6 +309,59 @@ impl MapLoader {
     }
 }
 
+fn prepare_map_resources(resources: &mut MapResources) {
+    // Offset the objects slightly to avoid depth buffer fighting.
+    resources
+        .objects_mut()
+        .enumerate()
+        .for_each(|(index, object)| object.transform.position += Vector3::new(0.0, 0.0005, 0.0) * index as f32);
+
+    // Some light sources have color channels with values bigger than 1.0 (255), so
+    // we need to clamp them.
+    // TODO: Does this maybe have a special meaning?
+    resources
+        .light_sources_mut()
+        .for_each(|light_source| light_source.color.clamp_color_channels());
+
+    resources
+        .sound_sources_mut()
+        .filter(|sound_source| sound_source.cycle.is_none())
+        .for_each(|sound_source| sound_source.cycle = Some(4.0));
+
+    // Unlike objects, the sources are stored with a y-axis that points down.
+    for resource in &mut resources.resources {
+        match resource {
+            Resource::Object(_) => {}
+            Resource::LightSource(light_source) => light_source.position.y = -light_source.position.y,
+            Resource::SoundSource(sound_source) => sound_source.position.y = -sound_source.position.y,
+            Resource::EffectSource(effect_source) => effect_source.position.y = -effect_source.position.y,
+        }
+    }
+}
+
+/// Maps without an animation type predate it and animate all of their
+/// objects.
+fn parse_object_animation_type(value: Option<i32>) -> ObjectAnimationType {
+    match value {
+        None | Some(1) => ObjectAnimationType::Loop,
+        Some(0) => ObjectAnimationType::Static,
+        Some(_value) => {
+            #[cfg(feature = "debug")]
+            print_debug!("[{}] unknown object animation type: {_value}", "error".red());
+            ObjectAnimationType::Loop
+        }
+    }
+}
+
+/// Speeds that are negative or unreasonably high are treated as the default
+/// speed.
+fn parse_object_animation_speed(value: Option<f32>) -> f32 {
+    match value {
+        Some(speed) if (0.0..100.0).contains(&speed) => speed,
+        _ => 1.0,
+    }
+}
+
 fn apply_map_offset(ground_data: &GroundData, resources: &mut MapResources) {
     let offset = Vector3::new(
         ground_data.width as f32 * MAP_OFFSET,
@@ -256,24 +369,18 @@ fn apply_map_offset(ground_data: &GroundData, resources: &mut MapResources) {
         ground_data.height as f32 * MAP_OFFSET,
     );
 
-    resources.objects.iter_mut().for_each(|object| object.transform.position += offset);
-    resources
-        .sound_sources
-        .iter_mut()
-        .for_each(|sound_source| sound_source.offset(offset));
+    resources.objects_mut().for_each(|object| object.transform.position += offset);
+    resources.sound_sources_mut().for_each(|sound_source| sound_source.offset(offset));
+    resources.light_sources_mut().for_each(|light_source| light_source.offset(offset));
     resources
-        .light_sources
-        .iter_mut()
-        .for_each(|light_source| light_source.offset(offset));
-    resources
-        .effect_sources
-        .iter_mut()
+        .effect_sources_mut()
         .for_each(|effect_source| effect_source.offset(offset));
 }
 
 fn parse_generic_data<Data: FromBytes>(resource_file: &str, game_file_loader: &GameFileLoader) -> Result<Data, LoadError> {
     let bytes = game_file_loader.get(resource_file).map_err(LoadError::File)?;
-    let mut byte_stream: ByteStream<Option<InternalVersion>> = ByteStream::without_metadata(&bytes);
+    let mut byte_stream: ByteStream<Option<InternalVersion>> =
+        ByteStream::without_metadata(&bytes).with_text_encoding(GAME_FILE_TEXT_ENCODING);
 
     let data = Data::from_bytes(&mut byte_stream).map_err(LoadError::Conversion)?;
 
@@ -300,3 +407,26 @@ impl GroundTileExt for GroundTile {
         .unwrap()
     }
 }
+
+#[cfg(test)]
+mod object_animation {
+    use super::{parse_object_animation_speed, parse_object_animation_type};
+    use crate::world::ObjectAnimationType;
+
+    #[test]
+    fn animation_type() {
+        assert_eq!(parse_object_animation_type(None), ObjectAnimationType::Loop);
+        assert_eq!(parse_object_animation_type(Some(0)), ObjectAnimationType::Static);
+        assert_eq!(parse_object_animation_type(Some(1)), ObjectAnimationType::Loop);
+        assert_eq!(parse_object_animation_type(Some(7)), ObjectAnimationType::Loop);
+    }
+
+    #[test]
+    fn animation_speed() {
+        assert_eq!(parse_object_animation_speed(None), 1.0);
+        assert_eq!(parse_object_animation_speed(Some(0.5)), 0.5);
+        assert_eq!(parse_object_animation_speed(Some(-1.0)), 1.0);
+        assert_eq!(parse_object_animation_speed(Some(100.0)), 1.0);
+        assert_eq!(parse_object_animation_speed(Some(f32::NAN)), 1.0);
+    }
+}
@@ -1,8 +1,9 @@
 use cgmath::{Point3, Vector2};
 #[cfg(feature = "debug")]
 use korangar_util::texture_atlas::AtlasAllocation;
-use ragnarok_formats::map::{GatData, GroundData, GroundTile, SurfaceType};
+use ragnarok_formats::map::{GatData, GroundData, GroundTile, GroundWaterSettings, SurfaceType, TileType, WaterPlane, WaterSettings};
 
+use super::lightmap::LightmapGrid;
 use super::GroundTileExt;
 #[cfg(feature = "debug")]
 use crate::graphics::Color;
@@ -18,7 +19,47 @@ pub enum Heights {
     LowerRight,
 }
 
-pub fn ground_water_vertices(ground_data: &GroundData, water_level: f32) -> (Vec<NativeModelVertex>, Vec<WaterVertex>) {
+/// Water settings of a map. The water can be split into multiple planes, each
+/// covering an equally sized area of the map.
+pub struct WaterPlanes {
+    columns: usize,
+    rows: usize,
+    planes: Vec<WaterPlane>,
+}
+
+impl WaterPlanes {
+    pub fn uniform(plane: WaterPlane) -> Self {
+        Self {
+            columns: 1,
+            rows: 1,
+            planes: vec![plane],
+        }
+    }
+
+    pub fn from_ground_settings(settings: &GroundWaterSettings) -> Self {
+        let columns = settings.water_splits_width.max(0) as usize;
+        let rows = settings.water_splits_height.max(0) as usize;
+        let planes = settings.planes();
+
+        match columns * rows > 0 && planes.len() == columns * rows {
+            true => Self { columns, rows, planes },
+            false => Self::uniform(WaterPlane::from(&WaterSettings::from(settings))),
+        }
+    }
+
+    fn plane_at(&self, x: usize, y: usize, width: usize, height: usize) -> &WaterPlane {
+        let column = (x * self.columns / width.max(1)).min(self.columns - 1);
+        let row = (y * self.rows / height.max(1)).min(self.rows - 1);
+
+        &self.planes[column + row * self.columns]
+    }
+}
+
+pub fn ground_water_vertices(
+    ground_data: &GroundData,
+    water_planes: &WaterPlanes,
+    lightmap_grid: Option<&LightmapGrid>,
+) -> (Vec<NativeModelVertex>, Vec<WaterVertex>) {
     let mut native_ground_vertices = Vec::new();
     let mut water_vertices = Vec::new();
 
@@ -83,6 +124,12 @@ pub fn ground_water_vertices(ground_data: &GroundData, water_level: f32) -> (Vec
                     let third_texture_coordinates = Vector2::new(ground_surface.u[3], ground_surface.v[3]);
                     let fourth_texture_coordinates = Vector2::new(ground_surface.u[2], ground_surface.v[2]);
 
+                    let lightmap_coordinates = lightmap_grid.and_then(|grid| grid.coordinates(ground_surface.light_map_index));
+                    let first_lightmap_coordinates = lightmap_coordinates.map(|coordinates| coordinates[0]);
+                    let second_lightmap_coordinates = lightmap_coordinates.map(|coordinates| coordinates[1]);
+                    let third_lightmap_coordinates = lightmap_coordinates.map(|coordinates| coordinates[2]);
+                    let fourth_lightmap_coordinates = lightmap_coordinates.map(|coordinates| coordinates[3]);
+
                     let neightbor_color = |x_offset, y_offset| {
                         let Some(neighbor_tile) = ground_tiles.get(x + x_offset + (y + y_offset) * width) else {
                             return ground_surface.color.into();
@@ -101,58 +148,87 @@ pub fn ground_water_vertices(ground_data: &GroundData, water_level: f32) -> (Vec
                     let color_top_right = neightbor_color(1, 1);
                     let color_top = neightbor_color(0, 1);
 
-                    native_ground_vertices.push(NativeModelVertex::new(
-                        first_position,
-                        first_normal,
-                        first_texture_coordinates,
-                        ground_surface.texture_index as i32,
-                        ground_surface.color.into(),
-                        0.0,
-                    ));
-                    native_ground_vertices.push(NativeModelVertex::new(
-                        second_position,
-                        first_normal,
-                        second_texture_coordinates,
-                        ground_surface.texture_index as i32,
-                        color_right,
-                        0.0,
-                    ));
-                    native_ground_vertices.push(NativeModelVertex::new(
-                        third_position,
-                        first_normal,
-                        third_texture_coordinates,
-                        ground_surface.texture_index as i32,
-                        color_top_right,
-                        0.0,
-                    ));
-
-                    native_ground_vertices.push(NativeModelVertex::new(
-                        first_position,
-                        second_normal,
-                        first_texture_coordinates,
-                        ground_surface.texture_index as i32,
-                        ground_surface.color.into(),
-                        0.0,
-                    ));
-                    native_ground_vertices.push(NativeModelVertex::new(
-                        third_position,
-                        second_normal,
-                        third_texture_coordinates,
-                        ground_surface.texture_index as i32,
-                        color_top_right,
-                        0.0,
-                    ));
-                    native_ground_vertices.push(NativeModelVertex::new(
-                        fourth_position,
-                        second_normal,
-                        fourth_texture_coordinates,
-                        ground_surface.texture_index as i32,
-                        color_top,
-                        0.0,
-                    ));
+                    native_ground_vertices.push(
+                        NativeModelVertex::new(
+                            first_position,
+                            first_normal,
+                            first_texture_coordinates,
+                            ground_surface.texture_index as i32,
+                            ground_surface.color.into(),
+                            0.0,
+                        )
+                        .with_lightmap_coordinates(first_lightmap_coordinates),
+                    );
+                    native_ground_vertices.push(
+                        NativeModelVertex::new(
+                            second_position,
+                            first_normal,
+                            second_texture_coordinates,
+                            ground_surface.texture_index as i32,
+                            color_right,
+                            0.0,
+                        )
+                        .with_lightmap_coordinates(second_lightmap_coordinates),
+                    );
+                    native_ground_vertices.push(
+                        NativeModelVertex::new(
+                            third_position,
+                            first_normal,
+                            third_texture_coordinates,
+                            ground_surface.texture_index as i32,
+                            color_top_right,
+                            0.0,
+                        )
+                        .with_lightmap_coordinates(third_lightmap_coordinates),
+                    );
+
+                    native_ground_vertices.push(
+                        NativeModelVertex::new(
+                            first_position,
+                            second_normal,
+                            first_texture_coordinates,
+                            ground_surface.texture_index as i32,
+                            ground_surface.color.into(),
+                            0.0,
+                        )
+                        .with_lightmap_coordinates(first_lightmap_coordinates),
+                    );
+                    native_ground_vertices.push(
+                        NativeModelVertex::new(
+                            third_position,
+                            second_normal,
+                            third_texture_coordinates,
+                            ground_surface.texture_index as i32,
+                            color_top_right,
+                            0.0,
+                        )
+                        .with_lightmap_coordinates(third_lightmap_coordinates),
+                    );
+                    native_ground_vertices.push(
+                        NativeModelVertex::new(
+                            fourth_position,
+                            second_normal,
+                            fourth_texture_coordinates,
+                            ground_surface.texture_index as i32,
+                            color_top,
+                            0.0,
+                        )
+                        .with_lightmap_coordinates(fourth_lightmap_coordinates),
+                    );
                 }
             }
 
+            let water_plane = water_planes.plane_at(x, y, width, height);
+            let water_level = -water_plane.water_level;
+            let water_vertex = |position| {
+                WaterVertex::with_wave(
+                    position,
+                    water_plane.wave_height,
+                    water_plane.wave_speed,
+                    water_plane.wave_pitch,
+                )
+            };
+
             if -current_tile.get_lowest_point() < water_level {
                 let first_position = Point3::new(x as f32 * MAP_TILE_SIZE, water_level, y as f32 * MAP_TILE_SIZE);
                 let second_position = Point3::new(MAP_TILE_SIZE + x as f32 * MAP_TILE_SIZE, water_level, y as f32 * MAP_TILE_SIZE);
@@ -163,13 +239,13 @@ pub fn ground_water_vertices(ground_data: &GroundData, water_level: f32) -> (Vec
                 );
                 let fourth_position = Point3::new(x as f32 * MAP_TILE_SIZE, water_level, MAP_TILE_SIZE + y as f32 * MAP_TILE_SIZE);
 
-                water_vertices.push(WaterVertex::new(first_position));
-                water_vertices.push(WaterVertex::new(second_position));
-                water_vertices.push(WaterVertex::new(third_position));
+                water_vertices.push(water_vertex(first_position));
+                water_vertices.push(water_vertex(second_position));
+                water_vertices.push(water_vertex(third_position));
 
-                water_vertices.push(WaterVertex::new(first_position));
-                water_vertices.push(WaterVertex::new(third_position));
-                water_vertices.push(WaterVertex::new(fourth_position));
+                water_vertices.push(water_vertex(first_position));
+                water_vertices.push(water_vertex(third_position));
+                water_vertices.push(water_vertex(fourth_position));
             }
         }
     }
@@ -197,7 +273,7 @@ pub fn generate_tile_vertices(
             tile.lower_right_height = -tile.lower_right_height;
             count += 1;
 
-            if tile.flags.is_empty() {
+            if tile.tile_type == TileType::NonWalkable {
                 continue;
             }
 
@@ -219,7 +295,7 @@ pub fn generate_tile_vertices(
                 let first_normal = NativeModelVertex::calculate_normal(first_position, second_position, third_position);
                 let second_normal = NativeModelVertex::calculate_normal(fourth_position, first_position, third_position);
 
-                let tile_type_index = TryInto::<u8>::try_into(tile.flags).unwrap() as usize;
+                let tile_type_index = tile.tile_type as usize;
                 let atlas_allocation = tile_texture_mapping[tile_type_index];
 
                 let first_texture_coordinates = atlas_allocation.map_to_atlas(Vector2::new(0.0, 0.0));
@@ -233,6 +309,7 @@ pub fn generate_tile_vertices(
                     first_texture_coordinates,
                     Color::WHITE,
                     0.0,
+                    None,
                 ));
                 tile_vertices.push(ModelVertex::new(
                     second_position,
@@ -240,6 +317,7 @@ pub fn generate_tile_vertices(
                     second_texture_coordinates,
                     Color::WHITE,
                     0.0,
+                    None,
                 ));
                 tile_vertices.push(ModelVertex::new(
                     third_position,
@@ -247,6 +325,7 @@ pub fn generate_tile_vertices(
                     third_texture_coordinates,
                     Color::WHITE,
                     0.0,
+                    None,
                 ));
 
                 tile_vertices.push(ModelVertex::new(
@@ -255,6 +334,7 @@ pub fn generate_tile_vertices(
                     first_texture_coordinates,
                     Color::WHITE,
                     0.0,
+                    None,
                 ));
                 tile_vertices.push(ModelVertex::new(
                     third_position,
@@ -262,6 +342,7 @@ pub fn generate_tile_vertices(
                     third_texture_coordinates,
                     Color::WHITE,
                     0.0,
+                    None,
                 ));
                 tile_vertices.push(ModelVertex::new(
                     fourth_position,
@@ -269,6 +350,7 @@ pub fn generate_tile_vertices(
                     fourth_texture_coordinates,
                     Color::WHITE,
                     0.0,
+                    None,
                 ));
             }
 
@@ -15,7 +15,7 @@ mod texture;
 
 pub use self::action::*;
 pub use self::animation::*;
-pub use self::effect::EffectLoader;
+pub use self::effect::{effect_file, EffectLoader};
 pub use self::font::{FontLoader, FontSize, Scaling};
 pub use self::gamefile::*;
 pub use self::map::{MapLoader, MAP_TILE_SIZE};
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use cgmath::{Matrix4, Point3, Rad, SquareMatrix, Vector2, Vector3};
+use cgmath::{EuclideanSpace, Matrix3, Matrix4, Point3, Rad, SquareMatrix, Vector2, Vector3, Zero};
 use derive_new::new;
 #[cfg(feature = "debug")]
 use korangar_debug::logging::{print_debug, Colorize, Timer};
@@ -9,15 +9,15 @@ use korangar_util::math::multiply_matrix4_and_point3;
 use korangar_util::texture_atlas::AllocationId;
 use korangar_util::FileLoader;
 use ragnarok_bytes::{ByteStream, FromBytes};
-use ragnarok_formats::model::{ModelData, ModelString, NodeData};
+use ragnarok_formats::model::{ModelData, NodeData, PositionKeyframeData, RotationKeyframeData, ScaleKeyframeData, TextureKeyframeData};
 use ragnarok_formats::version::InternalVersion;
 
 use super::error::LoadError;
-use super::FALLBACK_MODEL_FILE;
+use super::{FALLBACK_MODEL_FILE, GAME_FILE_TEXT_ENCODING};
 use crate::graphics::{Color, NativeModelVertex};
 use crate::loaders::map::DeferredVertexGeneration;
 use crate::loaders::{GameFileLoader, TextureAtlasFactory};
-use crate::world::{Model, Node};
+use crate::world::{Model, Node, TextureAnimation};
 
 #[derive(new)]
 pub struct ModelLoader {
@@ -66,11 +66,13 @@ impl ModelLoader {
     fn make_vertices(node: &NodeData, main_matrix: &Matrix4<f32>, reverse_order: bool) -> Vec<NativeModelVertex> {
         let mut native_vertices = Vec::new();
 
-        let array: [f32; 3] = node.scale.into();
-        let reverse_node_order = array.into_iter().fold(1.0, |a, b| a * b).is_sign_negative();
+        if let Some(scale) = node.scale {
+            let array: [f32; 3] = scale.into();
+            let reverse_node_order = array.into_iter().fold(1.0, |a, b| a * b).is_sign_negative();
 
-        if reverse_node_order {
-            panic!("this can actually happen");
+            if reverse_node_order {
+                panic!("this can actually happen");
+            }
         }
 
         for face in &node.faces {
@@ -117,9 +119,13 @@ impl ModelLoader {
     fn calculate_matrices(node: &NodeData, parent_matrix: &Matrix4<f32>) -> (Matrix4<f32>, Matrix4<f32>, Matrix4<f32>) {
         let main = Matrix4::from_translation(node.translation1) * Matrix4::from(node.offset_matrix);
 
-        let scale_matrix = Matrix4::from_nonuniform_scale(node.scale.x, node.scale.y, node.scale.z);
-        let rotation_matrix = Matrix4::from_axis_angle(node.rotation_axis, Rad(node.rotation_angle));
-        let translation_matrix = Matrix4::from_translation(node.translation2);
+        let scale = node.scale.unwrap_or(Vector3::new(1.0, 1.0, 1.0));
+        let scale_matrix = Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z);
+        let rotation_matrix = Matrix4::from_axis_angle(
+            node.rotation_axis.unwrap_or(Vector3::unit_y()),
+            Rad(node.rotation_angle.unwrap_or_default()),
+        );
+        let translation_matrix = Matrix4::from_translation(node.translation2.unwrap_or(Vector3::zero()));
 
         let transform = match node.rotation_keyframe_count > 0 {
             true => translation_matrix * scale_matrix,
@@ -131,34 +137,285 @@ impl ModelLoader {
         (main, transform, box_transform)
     }
 
+    /// Starting from version 2.2, the offset matrix and the first translation
+    /// describe the rotation and position of a node in model space rather than
+    /// relative to its parent. The vertices themselves are already relative to
+    /// the node, so there is no main matrix to apply.
+    fn calculate_matrices_rsm2(
+        node: &NodeData,
+        parent_node: Option<&NodeData>,
+        parent_matrix: &Matrix4<f32>,
+    ) -> (Matrix4<f32>, Matrix4<f32>, Matrix4<f32>) {
+        let (rotation_matrix, translation) = Self::relative_placement_rsm2(node, parent_node);
+        let rotation_matrix = Matrix4::from(rotation_matrix);
+        let translation_matrix = Matrix4::from_translation(translation);
+
+        let transform = match node.rotation_keyframe_count > 0 {
+            true => translation_matrix,
+            false => translation_matrix * rotation_matrix,
+        };
+
+        let box_transform = parent_matrix * translation_matrix * rotation_matrix;
+
+        (Matrix4::identity(), transform, box_transform)
+    }
+
+    /// Rotation and translation of a node relative to its parent for models
+    /// starting from version 2.2.
+    fn relative_placement_rsm2(node: &NodeData, parent_node: Option<&NodeData>) -> (Matrix3<f32>, Vector3<f32>) {
+        match parent_node {
+            Some(parent_node) => {
+                let inverse_parent_rotation = parent_node.offset_matrix.invert().unwrap_or(Matrix3::identity());
+                let rotation_matrix = inverse_parent_rotation * node.offset_matrix;
+                let translation = inverse_parent_rotation * (node.translation1 - parent_node.translation1);
+
+                (rotation_matrix, translation)
+            }
+            None => (node.offset_matrix, node.translation1),
+        }
+    }
+
+    /// Position keyframes place a node in model space, just like its first
+    /// translation. We convert them to offsets from the resting position of
+    /// the node, in the space of its transform matrix, so they can be applied
+    /// on top of it. Only models starting from version 2.2 have position
+    /// keyframes, so the frames are always converted to milliseconds.
+    fn convert_position_keyframes(
+        node: &NodeData,
+        parent_node: Option<&NodeData>,
+        frames_per_second: Option<f32>,
+    ) -> Vec<PositionKeyframeData> {
+        let (rotation_matrix, translation) = Self::relative_placement_rsm2(node, parent_node);
+        let (inverse_parent_rotation, parent_translation) = match parent_node {
+            Some(parent_node) => (
+                parent_node.offset_matrix.invert().unwrap_or(Matrix3::identity()),
+                parent_node.translation1,
+            ),
+            None => (Matrix3::identity(), Vector3::zero()),
+        };
+
+        // The rotation is only part of the transform matrix if it is not animated.
+        let inverse_rotation = match node.rotation_keyframe_count > 0 {
+            true => Matrix3::identity(),
+            false => rotation_matrix.invert().unwrap_or(Matrix3::identity()),
+        };
+
+        node.position_keyframes
+            .iter()
+            .map(|keyframe| {
+                let position = inverse_parent_rotation * (keyframe.position.to_vec() - parent_translation);
+                let offset = inverse_rotation * (position - translation);
+
+                let frame = match frames_per_second {
+                    Some(frames_per_second) if frames_per_second > 0.0 => (keyframe.frame as f32 * 1000.0 / frames_per_second) as u32,
+                    _ => keyframe.frame,
+                };
+
+                PositionKeyframeData {
+                    frame,
+                    position: Point3::from_vec(offset),
+                    data: keyframe.data,
+                }
+            })
+            .collect()
+    }
+
+    /// Models starting from version 2.2 specify keyframes in frames rather
+    /// than in milliseconds, so we convert them to match older models.
+    fn convert_rotation_keyframes(node: &NodeData, frames_per_second: Option<f32>) -> Vec<RotationKeyframeData> {
+        match frames_per_second {
+            Some(frames_per_second) if frames_per_second > 0.0 => node
+                .rotation_keyframes
+                .iter()
+                .map(|keyframe| RotationKeyframeData {
+                    frame: (keyframe.frame as f32 * 1000.0 / frames_per_second) as u32,
+                    quaternions: keyframe.quaternions,
+                })
+                .collect(),
+            _ => node.rotation_keyframes.clone(),
+        }
+    }
+
+    /// Models starting from version 2.2 specify keyframes in frames rather
+    /// than in milliseconds, so we convert them to match older models.
+    fn convert_scale_keyframes(node: &NodeData, frames_per_second: Option<f32>) -> Vec<ScaleKeyframeData> {
+        match frames_per_second {
+            Some(frames_per_second) if frames_per_second > 0.0 => node
+                .scale_keyframes
+                .iter()
+                .map(|keyframe| ScaleKeyframeData {
+                    frame: (keyframe.frame as f32 * 1000.0 / frames_per_second) as u32,
+                    ..keyframe.clone()
+                })
+                .collect(),
+            _ => node.scale_keyframes.clone(),
+        }
+    }
+
+    /// Texture animations only exist starting from version 2.3, so the frames
+    /// are always converted to milliseconds.
+    fn convert_texture_keyframes(keyframes: &[TextureKeyframeData], frames_per_second: Option<f32>) -> Vec<TextureKeyframeData> {
+        match frames_per_second {
+            Some(frames_per_second) if frames_per_second > 0.0 => keyframes
+                .iter()
+                .map(|keyframe| TextureKeyframeData {
+                    frame: (keyframe.frame as f32 * 1000.0 / frames_per_second) as u32,
+                    offset: keyframe.offset,
+                })
+                .collect(),
+            _ => keyframes.to_vec(),
+        }
+    }
+
+    /// Moves the vertices of every texture with an animated offset to the end
+    /// of the node vertices and returns the animations of the offsets. The
+    /// vertices of the node start at the given vertex offset. The animation
+    /// types are the ones used by BrowEdit 3, where type 0 moves the
+    /// texture along U and type 1 along V. Scaling and rotating textures
+    /// (types 2 to 4) is not supported yet.
+    fn sort_texture_animations(
+        node: &NodeData,
+        node_texture_mapping: &[i32],
+        node_native_vertices: &mut Vec<NativeModelVertex>,
+        node_vertex_offset: usize,
+        frames_per_second: Option<f32>,
+    ) -> Vec<TextureAnimation> {
+        const TEXTURE_OFFSET_U: u32 = 0;
+        const TEXTURE_OFFSET_V: u32 = 1;
+
+        let mut animated_textures: Vec<(i32, Vec<TextureKeyframeData>, Vec<TextureKeyframeData>)> = Vec::new();
+
+        for texture_animations in &node.texture_animations {
+            let Some(&texture_index) = node_texture_mapping.get(texture_animations.texture_index as usize) else {
+                continue;
+            };
+
+            for animation in &texture_animations.animations {
+                let keyframes = Self::convert_texture_keyframes(&animation.keyframes, frames_per_second);
+
+                if keyframes.is_empty() || !matches!(animation.animation_type, TEXTURE_OFFSET_U | TEXTURE_OFFSET_V) {
+                    continue;
+                }
+
+                let position = match animated_textures.iter().position(|(index, ..)| *index == texture_index) {
+                    Some(position) => position,
+                    None => {
+                        animated_textures.push((texture_index, Vec::new(), Vec::new()));
+                        animated_textures.len() - 1
+                    }
+                };
+
+                match animation.animation_type {
+                    TEXTURE_OFFSET_U => animated_textures[position].1 = keyframes,
+                    _ => animated_textures[position].2 = keyframes,
+                }
+            }
+        }
+
+        if animated_textures.is_empty() {
+            return Vec::new();
+        }
+
+        // Every triangle has a single texture, so we sort whole triangles.
+        let mut triangles: Vec<[NativeModelVertex; 3]> = Vec::with_capacity(node_native_vertices.len() / 3);
+        let mut drain_iterator = node_native_vertices.drain(..);
+
+        while let Ok(triangle) = drain_iterator.next_chunk::<3>() {
+            triangles.push(triangle);
+        }
+
+        drop(drain_iterator);
+
+        let group = |triangle: &[NativeModelVertex; 3]| {
+            animated_textures
+                .iter()
+                .position(|(texture_index, ..)| *texture_index == triangle[0].texture_index)
+                .map_or(0, |position| position + 1)
+        };
+
+        // The sort is stable, so the draw order inside of each group doesn't change.
+        triangles.sort_by_key(group);
+
+        let mut vertex_counts = vec![0; animated_textures.len()];
+        triangles
+            .iter()
+            .filter_map(|triangle| group(triangle).checked_sub(1))
+            .for_each(|position| vertex_counts[position] += 3);
+
+        node_native_vertices.extend(triangles.into_iter().flatten());
+
+        let mut texture_vertex_offset = node_vertex_offset + node_native_vertices.len() - vertex_counts.iter().sum::<usize>();
+
+        animated_textures
+            .into_iter()
+            .zip(vertex_counts)
+            .map(|((_, u_keyframes, v_keyframes), vertex_count)| {
+                let texture_animation = TextureAnimation::new(texture_vertex_offset, vertex_count, u_keyframes, v_keyframes);
+                texture_vertex_offset += vertex_count;
+                texture_animation
+            })
+            .collect()
+    }
+
+    /// Maps the node texture indices to the model texture indices. Starting
+    /// from version 2.3 nodes reference their textures by name.
+    fn node_texture_mapping(node: &NodeData, texture_names: &[&str]) -> Vec<i32> {
+        match node.texture_name_count.is_some() {
+            true => node
+                .texture_names
+                .iter()
+                .map(|texture_name| {
+                    texture_names
+                        .iter()
+                        .position(|name| *name == texture_name.as_ref())
+                        .expect("texture name not registered") as i32
+                })
+                .collect(),
+            false => node.texture_indices.iter().map(|&index| index as i32).collect(),
+        }
+    }
+
     fn process_node_mesh(
         current_node: &NodeData,
+        parent_node: Option<&NodeData>,
         nodes: &[NodeData],
         vertex_offset: &mut usize,
         native_vertices: &mut Vec<NativeModelVertex>,
-        model_texture_mapping: &[i32],
+        texture_names: &[&str],
         parent_matrix: &Matrix4<f32>,
         main_bounding_box: &mut AABB,
-        root_node_name: &ModelString<40>,
+        frames_per_second: Option<f32>,
         reverse_order: bool,
     ) -> Node {
-        let (main_matrix, transform_matrix, box_transform_matrix) = Self::calculate_matrices(current_node, parent_matrix);
+        // Only models starting from version 2.2 have a frame rate.
+        let (main_matrix, transform_matrix, box_transform_matrix) = match frames_per_second.is_some() {
+            true => Self::calculate_matrices_rsm2(current_node, parent_node, parent_matrix),
+            false => Self::calculate_matrices(current_node, parent_matrix),
+        };
         let mut node_native_vertices = Self::make_vertices(current_node, &main_matrix, reverse_order);
 
         // Map the node texture index to the model texture index.
-        let node_texture_mapping: Vec<i32> = current_node
-            .texture_indices
-            .iter()
-            .map(|&index| model_texture_mapping[index as usize])
-            .collect();
+        let node_texture_mapping = Self::node_texture_mapping(current_node, texture_names);
         node_native_vertices
             .iter_mut()
             .for_each(|vertice| vertice.texture_index = node_texture_mapping[vertice.texture_index as usize]);
 
-        // Remember the vertex offset/count and gather node vertices.
+        // Remember the vertex offset/count and gather node vertices. The vertices of
+        // animated textures are not part of the node vertex count.
         let node_vertex_offset = *vertex_offset;
-        let node_vertex_count = node_native_vertices.len();
-        *vertex_offset += node_vertex_count;
+        let texture_animations = Self::sort_texture_animations(
+            current_node,
+            &node_texture_mapping,
+            &mut node_native_vertices,
+            node_vertex_offset,
+            frames_per_second,
+        );
+        let animated_vertex_count = texture_animations
+            .iter()
+            .map(|texture_animation| texture_animation.vertex_count)
+            .sum::<usize>();
+        let node_vertex_count = node_native_vertices.len() - animated_vertex_count;
+        *vertex_offset += node_native_vertices.len();
         native_vertices.extend(node_native_vertices);
 
         let box_matrix = box_transform_matrix * main_matrix;
@@ -177,13 +434,14 @@ impl ModelLoader {
             .map(|node| {
                 Self::process_node_mesh(
                     node,
+                    Some(current_node),
                     nodes,
                     vertex_offset,
                     native_vertices,
-                    model_texture_mapping,
+                    texture_names,
                     &box_transform_matrix,
                     main_bounding_box,
-                    root_node_name,
+                    frames_per_second,
                     reverse_order,
                 )
             })
@@ -194,7 +452,10 @@ impl ModelLoader {
             node_vertex_offset,
             node_vertex_count,
             child_nodes,
-            current_node.rotation_keyframes.clone(),
+            Self::convert_scale_keyframes(current_node, frames_per_second),
+            Self::convert_rotation_keyframes(current_node, frames_per_second),
+            Self::convert_position_keyframes(current_node, parent_node, frames_per_second),
+            texture_animations,
         )
     }
 
@@ -231,7 +492,8 @@ impl ModelLoader {
             .game_file_loader
             .get(&format!("data\\model\\{model_file}"))
             .map_err(LoadError::File)?;
-        let mut byte_stream: ByteStream<Option<InternalVersion>> = ByteStream::without_metadata(&bytes);
+        let mut byte_stream: ByteStream<Option<InternalVersion>> =
+            ByteStream::without_metadata(&bytes).with_text_encoding(GAME_FILE_TEXT_ENCODING);
 
         let model_data = match ModelData::from_bytes(&mut byte_stream) {
             Ok(model_data) => model_data,
@@ -246,51 +508,78 @@ impl ModelLoader {
             }
         };
 
-        // TODO: Temporary check until we support more versions.
         let version: InternalVersion = model_data.version.into();
-        if version.equals_or_above(2, 2) {
-            #[cfg(feature = "debug")]
-            {
-                print_debug!("Failed to load model because version {} is unsupported", version);
-                print_debug!("Replacing with fallback");
-            }
 
-            return self.load(texture_atlas_factory, vertex_offset, FALLBACK_MODEL_FILE, reverse_order);
-        }
+        // Starting from version 2.3 textures are stored on the nodes, so we collect
+        // them into a single list for the whole model.
+        let texture_names: Vec<&str> = match version.equals_or_above(2, 3) {
+            true => {
+                let mut texture_names = Vec::new();
+
+                for texture_name in model_data.nodes.iter().flat_map(|node| node.texture_names.iter()) {
+                    if !texture_names.contains(&texture_name.as_ref()) {
+                        texture_names.push(texture_name.as_ref());
+                    }
+                }
+
+                texture_names
+            }
+            false => model_data.texture_names.iter().map(|texture_name| texture_name.as_ref()).collect(),
+        };
 
-        let texture_allocation: Vec<AllocationId> = model_data
-            .texture_names
+        let texture_allocation: Vec<AllocationId> = texture_names
             .iter()
-            .map(|texture_name| texture_atlas_factory.register(texture_name.as_ref()))
+            .map(|texture_name| texture_atlas_factory.register(texture_name))
             .collect();
-        let texture_mapping: Vec<i32> = (0..model_data.texture_names.len()).map(|index| index as i32).collect();
 
-        let root_node_name = &model_data.root_node_name;
-        let root_node = model_data
-            .nodes
-            .iter()
-            .find(|node_data| &node_data.node_name == root_node_name)
-            .expect("failed to find main node");
+        let frames_per_second = match version.equals_or_above(2, 2) {
+            true => Some(model_data.frames_per_second.unwrap_or_default()),
+            false => None,
+        };
 
         let mut native_model_vertices = Vec::<NativeModelVertex>::new();
-
         let mut bounding_box = AABB::uninitialized();
-        let mut root_node = Self::process_node_mesh(
-            root_node,
-            &model_data.nodes,
-            vertex_offset,
-            &mut native_model_vertices,
-            &texture_mapping,
-            &Matrix4::identity(),
-            &mut bounding_box,
-            root_node_name,
-            reverse_order,
-        );
-        Self::calculate_transformation_matrix(&mut root_node, true, bounding_box, Matrix4::identity());
+
+        let mut root_nodes: Vec<Node> = model_data
+            .root_node_names()
+            .map(|root_node_name| {
+                let root_node = model_data
+                    .nodes
+                    .iter()
+                    .find(|node_data| &node_data.node_name == root_node_name)
+                    .expect("failed to find main node");
+
+                Self::process_node_mesh(
+                    root_node,
+                    None,
+                    &model_data.nodes,
+                    vertex_offset,
+                    &mut native_model_vertices,
+                    &texture_names,
+                    &Matrix4::identity(),
+                    &mut bounding_box,
+                    frames_per_second,
+                    reverse_order,
+                )
+            })
+            .collect();
+
+        // Models before version 2.2 are centered around their bounding box, newer
+        // models are already placed relative to their origin.
+        let center_model = version.smaller(2, 2);
+        root_nodes
+            .iter_mut()
+            .for_each(|root_node| Self::calculate_transformation_matrix(root_node, center_model, bounding_box, Matrix4::identity()));
+
+        let animation_length = match frames_per_second {
+            Some(frames_per_second) if frames_per_second > 0.0 => (model_data.animation_length as f32 * 1000.0 / frames_per_second) as u32,
+            _ => model_data.animation_length,
+        };
 
         let model = Model::new(
-            root_node,
+            root_nodes,
             bounding_box,
+            animation_length,
             #[cfg(feature = "debug")]
             model_data,
         );
@@ -298,6 +587,7 @@ impl ModelLoader {
         let deferred = DeferredVertexGeneration {
             native_model_vertices,
             texture_allocation,
+            lightmap_allocation: None,
         };
 
         #[cfg(feature = "debug")]
@@ -1,145 +1,139 @@
+mod tables;
+
+use std::collections::HashMap;
 use std::sync::Arc;
 
+#[cfg(feature = "debug")]
+use korangar_debug::logging::{print_debug, Colorize};
 use korangar_networking::{InventoryItem, NoMetadata, ShopItem};
 use korangar_util::FileLoader;
 use mlua::Lua;
-use ragnarok_packets::ItemId;
+use ragnarok_packets::{ItemId, SkillId};
 
+pub use self::tables::{ItemInfo, SkillInfo};
 use super::TextureLoader;
 use crate::graphics::Texture;
 use crate::loaders::GameFileLoader;
 
+const DATA_INFO_PATH: &str = "data\\luafiles514\\lua files\\datainfo";
+const SKILL_INFO_PATH: &str = "data\\luafiles514\\lua files\\skillinfoz";
+
+/// Lua files that every client has.
+const REQUIRED_FILES: &[(&str, &str)] = &[
+    (DATA_INFO_PATH, "jobidentity.lub"),
+    (DATA_INFO_PATH, "iteminfo.lub"),
+    (DATA_INFO_PATH, "npcidentity.lub"),
+];
+
+/// Lua files that only some clients have. The skill files have to be executed
+/// in this order, since the later ones use the constants of `skillid.lub`.
+const OPTIONAL_FILES: &[(&str, &str)] = &[
+    (DATA_INFO_PATH, "jobname.lub"),
+    (SKILL_INFO_PATH, "skillid.lub"),
+    (SKILL_INFO_PATH, "skillinfolist.lub"),
+    (SKILL_INFO_PATH, "skilldescript.lub"),
+];
+
+/// Sprite that is used for jobs that are not part of the job tables.
+const FALLBACK_JOB_NAME: &str = "1_f_maria";
+/// Name that is used for items that are not part of the item table.
+const FALLBACK_ITEM_NAME: &str = "NOTFOUND";
+/// Icon that is used for items that are not part of the item table.
+const FALLBACK_ITEM_RESOURCE: &str = "사과";
+
 #[derive(Debug, Clone)]
 pub struct ResourceMetadata {
     pub texture: Arc<Texture>,
     pub name: String,
+    pub description: Vec<String>,
 }
 
+/// Executes the Lua files of the client once and extracts the tables into
+/// typed lookups. The Lua state is dropped afterwards.
 pub struct ScriptLoader {
-    state: Lua,
+    job_names: HashMap<usize, String>,
+    items: HashMap<ItemId, ItemInfo>,
+    skills: HashMap<SkillId, SkillInfo>,
 }
 
 impl ScriptLoader {
     pub fn new(game_file_loader: &GameFileLoader) -> mlua::Result<Self> {
         let state = Lua::new();
 
-        let data = game_file_loader
-            .get("data\\luafiles514\\lua files\\datainfo\\jobidentity.lub")
-            .unwrap();
-        state.load(&data).exec()?;
-
-        let data = game_file_loader
-            .get("data\\luafiles514\\lua files\\datainfo\\iteminfo.lub")
-            .unwrap();
-        state.load(&data).exec()?;
-
-        let data = game_file_loader
-            .get("data\\luafiles514\\lua files\\datainfo\\npcidentity.lub")
-            .unwrap();
-        state.load(&data).exec()?;
-
-        let job_id_function = r#"
-function get_job_name_from_id(id)
-  for k,v in pairs(JTtbl) do
-    if v==id then
-
-        if string.sub(k, 1, 5) == "JT_G_" then
-            return string.sub(k, 6)
-        end
-
-        if string.sub(k, 1, 6) == "JT_C1_" then
-            return string.sub(k, 7)
-        end
-
-        if string.sub(k, 1, 6) == "JT_C2_" then
-            return string.sub(k, 7)
-        end
-
-        if string.sub(k, 1, 6) == "JT_C3_" then
-            return string.sub(k, 7)
-        end
-
-        if string.sub(k, 1, 6) == "JT_C4_" then
-            return string.sub(k, 7)
-        end
-
-        if string.sub(k, 1, 6) == "JT_C5_" then
-            return string.sub(k, 7)
-        end
-
-        return string.sub(k, 4)
-    end
-  end
-
-  for k,v in pairs(jobtbl) do
-    if v==id then
-        if string.sub(k, 1, 5) == "JT_G_" then
-            return string.sub(k, 6)
-        end
-        return string.sub(k, 4)
-    end
-  end
-
-  return "1_f_maria"
-  --return nil
-end
-"#;
-
-        state.load(job_id_function).exec()?;
-
-        Ok(Self { state })
+        for (directory, file_name) in REQUIRED_FILES {
+            let data = game_file_loader.get(&format!("{directory}\\{file_name}")).unwrap();
+            state.load(&data).exec()?;
+        }
+
+        for (directory, file_name) in OPTIONAL_FILES {
+            let Ok(data) = game_file_loader.get(&format!("{directory}\\{file_name}")) else {
+                #[cfg(feature = "debug")]
+                print_debug!("[{}] optional lua file {} not found", "warning".yellow(), file_name.magenta());
+                continue;
+            };
+
+            // Some clients ship broken or incompatible versions of these files, so a
+            // failure only means that the tables they define will be missing.
+            if let Err(_error) = state.load(&data).exec() {
+                #[cfg(feature = "debug")]
+                print_debug!(
+                    "[{}] failed to execute optional lua file {}: {}",
+                    "warning".yellow(),
+                    file_name.magenta(),
+                    _error
+                );
+            }
+        }
+
+        Ok(Self {
+            job_names: tables::extract_job_names(&state)?,
+            items: tables::extract_items(&state)?,
+            skills: tables::extract_skills(&state)?,
+        })
     }
 
-    // TODO: move this to a different class that utilizes the script loader
-    pub fn get_job_name_from_id(&self, job_id: usize) -> String {
-        use mlua::prelude::*;
-        use mlua::Function;
-
-        let globals = self.state.globals();
+    pub fn get_job_name_from_id(&self, job_id: usize) -> &str {
+        self.job_names.get(&job_id).map_or(FALLBACK_JOB_NAME, String::as_str)
+    }
 
-        let print: Function = globals.get("get_job_name_from_id").unwrap();
-        print
-            .call::<LuaString>(job_id)
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .replace("CHONCHON", "chocho") // TODO: find a way to do this properly
+    pub fn get_item(&self, item_id: ItemId) -> Option<&ItemInfo> {
+        self.items.get(&item_id)
     }
 
-    // TODO: move this to a different class that utilizes the script loader
-    fn get_item_name_from_id(&self, item_id: ItemId, is_identified: bool) -> String {
-        use mlua::prelude::*;
-
-        let globals = self.state.globals();
-        let lookup_name = match is_identified {
-            true => "identifiedDisplayName",
-            false => "unidentifiedDisplayName",
-        };
-
-        globals
-            .get::<LuaTable>("tbl")
-            .unwrap()
-            .get::<LuaTable>(item_id.0)
-            .map(|table| table.get::<LuaString>(lookup_name).unwrap().to_str().unwrap().to_owned())
-            .unwrap_or_else(|_| "NOTFOUND".to_owned())
+    pub fn get_skill(&self, skill_id: SkillId) -> Option<&SkillInfo> {
+        self.skills.get(&skill_id)
     }
 
-    // TODO: move this to a different class that utilizes the script loader
-    fn get_item_resource_from_id(&self, item_id: ItemId, is_identified: bool) -> String {
-        use mlua::prelude::*;
-
-        let globals = self.state.globals();
-        let lookup_name = match is_identified {
-            true => "identifiedResourceName",
-            false => "undidentifiedResourceName",
-        };
-
-        globals
-            .get::<LuaTable>("tbl")
-            .unwrap()
-            .get::<LuaTable>(item_id.0)
-            .map(|table| table.get::<LuaString>(lookup_name).unwrap().to_str().unwrap().to_owned())
-            .unwrap_or_else(|_| "»ç°ú".to_owned())
+    fn item_resource_metadata(&self, texture_loader: &TextureLoader, item_id: ItemId, is_identified: bool) -> ResourceMetadata {
+        let item_info = self.get_item(item_id);
+        let display = item_info.map(|item_info| item_info.display(is_identified));
+
+        let mut name = display
+            .map(|display| display.name.clone())
+            .unwrap_or_else(|| FALLBACK_ITEM_NAME.to_owned());
+
+        // Slots are only revealed once the item is identified.
+        if let Some(item_info) = item_info
+            && is_identified
+            && item_info.slot_count > 0
+        {
+            name = format!("{name} [{}]", item_info.slot_count);
+        }
+
+        let resource_name = display
+            .map(|display| display.resource_name.as_str())
+            .filter(|resource_name| !resource_name.is_empty())
+            .unwrap_or(FALLBACK_ITEM_RESOURCE);
+        let full_path = format!("유저인터페이스\\item\\{resource_name}.bmp");
+        let texture = texture_loader.get(&full_path).unwrap();
+
+        let description = display.map(|display| display.description.clone()).unwrap_or_default();
+
+        ResourceMetadata {
+            texture,
+            name,
+            description,
+        }
     }
 
     pub fn load_inventory_item_metadata(
@@ -147,25 +141,13 @@ end
         texture_loader: &TextureLoader,
         item: InventoryItem<NoMetadata>,
     ) -> InventoryItem<ResourceMetadata> {
-        let is_identified = item.is_identifed();
-
-        let resource_name = self.get_item_resource_from_id(item.item_id, is_identified);
-        let full_path = format!("À¯ÀúÀÎÅÍÆäÀÌ½º\\item\\{resource_name}.bmp");
-        let texture = texture_loader.get(&full_path).unwrap();
-        let name = self.get_item_name_from_id(item.item_id, is_identified);
-
-        let metadata = ResourceMetadata { texture, name };
+        let metadata = self.item_resource_metadata(texture_loader, item.item_id, item.is_identifed());
 
         InventoryItem { metadata, ..item }
     }
 
     pub fn load_market_item_metadata(&self, texture_loader: &TextureLoader, item: ShopItem<NoMetadata>) -> ShopItem<ResourceMetadata> {
-        let resource_name = self.get_item_resource_from_id(item.item_id, true);
-        let full_path = format!("À¯ÀúÀÎÅÍÆäÀÌ½º\\item\\{resource_name}.bmp");
-        let texture = texture_loader.get(&full_path).unwrap();
-        let name = self.get_item_name_from_id(item.item_id, true);
-
-        let metadata = ResourceMetadata { texture, name };
+        let metadata = self.item_resource_metadata(texture_loader, item.item_id, true);
 
         ShopItem { metadata, ..item }
     }
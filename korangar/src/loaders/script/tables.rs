@@ -0,0 +1,333 @@
+//! Typed versions of the Lua tables of the client. They are extracted once
+//! after executing the Lua files, so that lookups don't need to call into Lua.
+use std::collections::HashMap;
+
+#[cfg(feature = "debug")]
+use korangar_debug::logging::{print_debug, Colorize};
+use mlua::{Lua, Table, Value};
+use ragnarok_packets::{ItemId, SkillId};
+
+use crate::loaders::GAME_FILE_TEXT_ENCODING;
+
+/// Monsters whose sprite name doesn't match their job name. Only needed if
+/// the client doesn't have a `jobname.lub`.
+const SPRITE_NAME_OVERRIDES: &[(&str, &str)] = &[("CHONCHON", "chocho")];
+
+/// Name, icon and description of an item.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemDisplay {
+    pub name: String,
+    pub resource_name: String,
+    pub description: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemInfo {
+    pub identified: ItemDisplay,
+    pub unidentified: ItemDisplay,
+    pub slot_count: usize,
+    /// Id of the sprite that is shown when the item is equipped, for example
+    /// the sprite of a weapon.
+    // Equipment sprites are not rendered yet, so nothing reads this outside of
+    // the tests.
+    #[allow(dead_code)]
+    pub class_id: usize,
+}
+
+impl ItemInfo {
+    pub fn display(&self, is_identified: bool) -> &ItemDisplay {
+        match is_identified {
+            true => &self.identified,
+            false => &self.unidentified,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SkillInfo {
+    /// Internal name of the skill, like `NV_BASIC`.
+    pub resource_name: String,
+    pub name: String,
+    pub maximum_level: usize,
+    pub description: Vec<String>,
+}
+
+fn decode(value: &mlua::String) -> String {
+    GAME_FILE_TEXT_ENCODING.decode(&value.as_bytes()).into_owned()
+}
+
+/// Missing fields are treated as empty strings.
+fn string_field(table: &Table, key: impl mlua::IntoLua) -> mlua::Result<String> {
+    Ok(table.get::<Option<mlua::String>>(key)?.as_ref().map(decode).unwrap_or_default())
+}
+
+fn string_list(table: &Table) -> mlua::Result<Vec<String>> {
+    table
+        .sequence_values::<mlua::String>()
+        .map(|value| value.map(|value| decode(&value)))
+        .collect()
+}
+
+fn string_list_field(table: &Table, key: &str) -> mlua::Result<Vec<String>> {
+    match table.get::<Option<Table>>(key)? {
+        Some(table) => string_list(&table),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn integer_field(table: &Table, key: &str) -> mlua::Result<usize> {
+    Ok(table.get::<Option<usize>>(key)?.unwrap_or_default())
+}
+
+/// Calls the function for every entry of an optional global table. Entries
+/// that can't be converted or that the function fails on are skipped, so that
+/// a single malformed entry doesn't discard the entire table.
+fn for_each_global_entry<K: mlua::FromLua, V: mlua::FromLua>(
+    lua: &Lua,
+    table_name: &str,
+    mut function: impl FnMut(K, V) -> mlua::Result<()>,
+) -> mlua::Result<()> {
+    let Some(table) = lua.globals().get::<Option<Table>>(table_name)? else {
+        return Ok(());
+    };
+
+    // Conversion errors would end the iteration over typed pairs, so the keys
+    // and values are converted separately.
+    for pair in table.pairs::<Value, Value>() {
+        let (key, value) = pair?;
+        let result = K::from_lua(key, lua).and_then(|key| Ok((key, V::from_lua(value, lua)?)));
+
+        if let Err(_error) = result.and_then(|(key, value)| function(key, value)) {
+            #[cfg(feature = "debug")]
+            print_debug!(
+                "[{}] skipping malformed entry in {}: {}",
+                "warning".yellow(),
+                table_name.magenta(),
+                _error
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Strips the prefix of a job constant, so that only the name of the sprite
+/// remains. Player jobs in `JTtbl` use some additional prefixes.
+fn strip_job_prefix(constant: &str, is_player_job: bool) -> &str {
+    let player_prefixes: &[&str] = match is_player_job {
+        true => &["JT_C1_", "JT_C2_", "JT_C3_", "JT_C4_", "JT_C5_"],
+        false => &[],
+    };
+
+    ["JT_G_"]
+        .iter()
+        .chain(player_prefixes)
+        .find_map(|prefix| constant.strip_prefix(prefix))
+        .or_else(|| constant.get(3..))
+        .unwrap_or(constant)
+}
+
+/// Extracts the sprite names of all jobs. Names from the `JobNameTable` take
+/// precedence over the names derived from the job constants.
+pub(super) fn extract_job_names(lua: &Lua) -> mlua::Result<HashMap<usize, String>> {
+    let mut job_names = HashMap::new();
+
+    for_each_global_entry(lua, "JobNameTable", |job_id: usize, name: mlua::String| {
+        job_names.insert(job_id, decode(&name));
+        Ok(())
+    })?;
+
+    for (table_name, is_player_job) in [("JTtbl", true), ("jobtbl", false)] {
+        for_each_global_entry(lua, table_name, |constant: mlua::String, job_id: usize| {
+            let constant = decode(&constant);
+            let name = strip_job_prefix(&constant, is_player_job);
+            let name = SPRITE_NAME_OVERRIDES
+                .iter()
+                .find(|(job_name, _)| *job_name == name)
+                .map_or(name, |(_, sprite_name)| sprite_name);
+
+            job_names.entry(job_id).or_insert_with(|| name.to_owned());
+            Ok(())
+        })?;
+    }
+
+    Ok(job_names)
+}
+
+pub(super) fn extract_items(lua: &Lua) -> mlua::Result<HashMap<ItemId, ItemInfo>> {
+    let mut items = HashMap::new();
+
+    for_each_global_entry(lua, "tbl", |item_id: u32, table: Table| {
+        let item_info = ItemInfo {
+            identified: ItemDisplay {
+                name: string_field(&table, "identifiedDisplayName")?,
+                resource_name: string_field(&table, "identifiedResourceName")?,
+                description: string_list_field(&table, "identifiedDescriptionName")?,
+            },
+            unidentified: ItemDisplay {
+                name: string_field(&table, "unidentifiedDisplayName")?,
+                resource_name: string_field(&table, "unidentifiedResourceName")?,
+                description: string_list_field(&table, "unidentifiedDescriptionName")?,
+            },
+            slot_count: integer_field(&table, "slotCount")?,
+            class_id: integer_field(&table, "ClassNum")?,
+        };
+
+        items.insert(ItemId(item_id), item_info);
+        Ok(())
+    })?;
+
+    Ok(items)
+}
+
+pub(super) fn extract_skills(lua: &Lua) -> mlua::Result<HashMap<SkillId, SkillInfo>> {
+    let mut skills = HashMap::new();
+
+    for_each_global_entry(lua, "SKILL_INFO_LIST", |skill_id: u16, table: Table| {
+        let skill_info = SkillInfo {
+            resource_name: string_field(&table, 1)?,
+            name: string_field(&table, "SkillName")?,
+            maximum_level: integer_field(&table, "MaxLv")?,
+            description: Vec::new(),
+        };
+
+        skills.insert(SkillId(skill_id), skill_info);
+        Ok(())
+    })?;
+
+    for_each_global_entry(lua, "SKILL_DESCRIPT", |skill_id: u16, value: Value| {
+        if let (Some(skill_info), Value::Table(table)) = (skills.get_mut(&SkillId(skill_id)), value) {
+            skill_info.description = string_list(&table)?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(skills)
+}
+
+#[cfg(test)]
+mod extraction {
+    use mlua::Lua;
+    use ragnarok_packets::{ItemId, SkillId};
+
+    use super::{extract_items, extract_job_names, extract_skills};
+
+    fn lua(source: &str) -> Lua {
+        let lua = Lua::new();
+        lua.load(source).exec().unwrap();
+        lua
+    }
+
+    #[test]
+    fn job_names() {
+        let lua = lua(r#"
+            JTtbl = { JT_NOVICE = 0, JT_G_MASTER = 1, JT_C1_SWORDMAN = 2 }
+            jobtbl = { JT_PORING = 1002, JT_CHONCHON = 1011, JT_4_F_KAFRA1 = 112, JT_NOVICE = 0 }
+        "#);
+        let job_names = extract_job_names(&lua).unwrap();
+
+        assert_eq!(job_names[&0], "NOVICE");
+        assert_eq!(job_names[&1], "MASTER");
+        assert_eq!(job_names[&2], "SWORDMAN");
+        assert_eq!(job_names[&1002], "PORING");
+        assert_eq!(job_names[&1011], "chocho");
+        assert_eq!(job_names[&112], "4_F_KAFRA1");
+    }
+
+    #[test]
+    fn job_name_table() {
+        let lua = lua(r#"
+            jobtbl = { JT_CHONCHON = 1011, JT_PORING = 1002 }
+            JobNameTable = { [jobtbl.JT_CHONCHON] = "CHOCHO" }
+        "#);
+        let job_names = extract_job_names(&lua).unwrap();
+
+        assert_eq!(job_names[&1011], "CHOCHO");
+        assert_eq!(job_names[&1002], "PORING");
+    }
+
+    #[test]
+    fn items() {
+        let lua = lua(r#"
+            tbl = {
+                [501] = {
+                    unidentifiedDisplayName = "Red Potion",
+                    unidentifiedResourceName = "»¡°£Æ÷¼Ç",
+                    unidentifiedDescriptionName = { "A potion." },
+                    identifiedDisplayName = "Red Potion",
+                    identifiedResourceName = "»¡°£Æ÷¼Ç",
+                    identifiedDescriptionName = { "Restores ^000088 45 HP^000000.", "Weight: 7" },
+                    slotCount = 0,
+                    ClassNum = 0
+                },
+                [1201] = { identifiedDisplayName = "Knife", slotCount = 3, ClassNum = 1 }
+            }
+        "#);
+        let items = extract_items(&lua).unwrap();
+
+        let potion = &items[&ItemId(501)];
+        assert_eq!(potion.display(true).name, "Red Potion");
+        assert_eq!(potion.display(true).description.len(), 2);
+        assert_eq!(potion.display(false).description, ["A potion."]);
+
+        let knife = &items[&ItemId(1201)];
+        assert_eq!(knife.slot_count, 3);
+        assert_eq!(knife.class_id, 1);
+        assert_eq!(knife.unidentified.name, "");
+    }
+
+    #[test]
+    fn skills() {
+        let lua = lua(r#"
+            SKID = { NV_BASIC = 1, SM_SWORD = 2 }
+            SKILL_INFO_LIST = {
+                [SKID.NV_BASIC] = { "NV_BASIC", SkillName = "Basic Skill", MaxLv = 9 },
+                [SKID.SM_SWORD] = { "SM_SWORD", SkillName = "Sword Mastery", MaxLv = 10 }
+            }
+            SKILL_DESCRIPT = {
+                [SKID.NV_BASIC] = { "Basic Skill", "Max Lv: 9" },
+                [99] = { "Unknown skill" }
+            }
+        "#);
+        let skills = extract_skills(&lua).unwrap();
+
+        assert_eq!(skills.len(), 2);
+        assert_eq!(skills[&SkillId(1)].resource_name, "NV_BASIC");
+        assert_eq!(skills[&SkillId(1)].maximum_level, 9);
+        assert_eq!(skills[&SkillId(1)].description, ["Basic Skill", "Max Lv: 9"]);
+        assert_eq!(skills[&SkillId(2)].name, "Sword Mastery");
+        assert!(skills[&SkillId(2)].description.is_empty());
+    }
+
+    #[test]
+    fn malformed_entries() {
+        let lua = lua(r#"
+            tbl = {
+                [501] = { identifiedDisplayName = "Red Potion", slotCount = 0 },
+                [502] = { identifiedDisplayName = "Orange Potion", slotCount = "none" },
+                [503] = "Yellow Potion"
+            }
+            SKILL_INFO_LIST = {
+                [1] = { "NV_BASIC", SkillName = "Basic Skill", MaxLv = 9 },
+                [2] = { "SM_SWORD", SkillName = "Sword Mastery", MaxLv = {} }
+            }
+        "#);
+        let items = extract_items(&lua).unwrap();
+        let skills = extract_skills(&lua).unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[&ItemId(501)].identified.name, "Red Potion");
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[&SkillId(1)].maximum_level, 9);
+    }
+
+    #[test]
+    fn missing_tables() {
+        let lua = Lua::new();
+
+        assert!(extract_job_names(&lua).unwrap().is_empty());
+        assert!(extract_items(&lua).unwrap().is_empty());
+        assert!(extract_skills(&lua).unwrap().is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use std::hash::{DefaultHasher, Hash, Hasher};
 
+use ragnarok_bytes::TextEncoding;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer};
 
@@ -112,9 +113,15 @@ pub struct Service {
     #[serde(default, alias = "aid")]
     pub game_master_accounts: Vec<GameMasterAccount>,
 
-    /// Define each loading screen in the path `/data/texture/À¯ÀúÀÎÅÍÆäÀÌ½º/`
+    /// Define each loading screen in the path `/data/texture/유저인터페이스/`
     #[serde(default, alias = "loading")]
     pub loading_images: Option<Vec<LoadingImage>>,
+
+    /// Packet version (`PACKETVER`) of the server, e.g. `20211103`. This is
+    /// not part of the official format and defaults to the packet version
+    /// that rAthena uses.
+    #[serde(default, alias = "packetversion")]
+    pub packet_version: Option<u32>,
 }
 
 impl Service {
@@ -226,6 +233,33 @@ impl ServiceType {
             _ => ServiceType::Unknown,
         }
     }
+
+    /// Encoding of the text that servers of this service type send and
+    /// receive.
+    pub fn text_encoding(&self) -> TextEncoding {
+        match self {
+            ServiceType::Korea => TextEncoding::Windows949,
+            ServiceType::Japan => TextEncoding::ShiftJis,
+            ServiceType::China => TextEncoding::Gbk,
+            ServiceType::Taiwan => TextEncoding::Big5,
+            ServiceType::Thai => TextEncoding::Windows874,
+            ServiceType::Russia => TextEncoding::Windows1251,
+            ServiceType::Vietnam => TextEncoding::Windows1258,
+            ServiceType::America
+            | ServiceType::Indonesia
+            | ServiceType::Philippine
+            | ServiceType::Malaysia
+            | ServiceType::Singapore
+            | ServiceType::Germany
+            | ServiceType::India
+            | ServiceType::Brazil
+            | ServiceType::Australia
+            | ServiceType::Chile
+            | ServiceType::France
+            | ServiceType::Uae => TextEncoding::Windows1252,
+            ServiceType::Unknown => TextEncoding::Latin1,
+        }
+    }
 }
 
 fn language_type_from_index<'de, D>(deserializer: D) -> Result<Option<ServiceType>, D::Error>
@@ -3,6 +3,7 @@ mod client_info;
 #[cfg(feature = "debug")]
 use korangar_debug::logging::Timer;
 use korangar_util::FileLoader;
+use ragnarok_bytes::TextEncoding;
 use serde::{Deserialize, Serialize};
 use serde_xml_rs::de::Deserializer;
 use xml::reader::{EventReader, ParserConfig};
@@ -13,6 +14,16 @@ use super::GameFileLoader;
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct ServiceId(pub usize);
 
+/// Get the label of the encoding from the XML declaration, e.g. `euc-kr` for
+/// `<?xml version="1.0" encoding="euc-kr" ?>`.
+fn declared_encoding(source: &str) -> Option<&str> {
+    let declaration = &source[..source.find("?>")?];
+    let start = declaration.find("encoding=")? + "encoding=".len() + 1;
+    let end = start + declaration.get(start..)?.find(['"', '\''])?;
+
+    Some(&declaration[start..end])
+}
+
 pub fn load_client_info(game_file_loader: &GameFileLoader) -> ClientInfo {
     #[cfg(feature = "debug")]
     let timer = Timer::new("read clientinfo");
@@ -22,11 +33,17 @@ pub fn load_client_info(game_file_loader: &GameFileLoader) -> ClientInfo {
         .or_else(|_| game_file_loader.get("data\\clientinfo.xml"))
         .expect("failed to find clientinfo");
 
-    let source = String::from_utf8(clientinfo).unwrap();
-
-    // TODO: Make it work with euc-kr, since it panics
-    // with error: "Unsupported encoding: euc-kr"
-    let replaced_source = source.replace("euc-kr", "utf8");
+    // The XML parser only supports UTF-8, so we decode the file ourselves and
+    // update the declared encoding to match.
+    let declaration = TextEncoding::Latin1.decode(&clientinfo);
+    let declared_encoding = declared_encoding(&declaration);
+    let text_encoding = declared_encoding.and_then(TextEncoding::from_label).unwrap_or(TextEncoding::Utf8);
+    let source = text_encoding.decode(&clientinfo);
+
+    let replaced_source = match declared_encoding {
+        Some(label) => source.replacen(label, "utf-8", 1),
+        None => source.into_owned(),
+    };
 
     let config = ParserConfig::new().trim_whitespace(true);
     let event_reader = EventReader::new_with_config(replaced_source.as_bytes(), config);
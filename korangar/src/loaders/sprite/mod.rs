@@ -7,11 +7,12 @@ use korangar_debug::logging::{print_debug, Colorize, Timer};
 use korangar_interface::elements::PrototypeElement;
 use korangar_util::FileLoader;
 use ragnarok_bytes::{ByteStream, FromBytes};
-use ragnarok_formats::sprite::{PaletteColor, RgbaImageData, SpriteData};
+use ragnarok_formats::imf::ImfData;
+use ragnarok_formats::sprite::{Palette, PaletteColor, RgbaImageData, SpriteData};
 use ragnarok_formats::version::InternalVersion;
 use wgpu::{Device, Extent3d, Queue, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages};
 
-use super::FALLBACK_SPRITE_FILE;
+use super::{FALLBACK_SPRITE_FILE, GAME_FILE_TEXT_ENCODING};
 use crate::graphics::Texture;
 use crate::loaders::error::LoadError;
 use crate::loaders::GameFileLoader;
@@ -31,11 +32,28 @@ pub struct SpriteLoader {
     queue: Arc<Queue>,
     game_file_loader: Arc<GameFileLoader>,
     #[new(default)]
-    cache: HashMap<String, Arc<Sprite>>,
+    cache: HashMap<(String, Option<String>), Arc<Sprite>>,
+    #[new(default)]
+    imf_cache: HashMap<String, Option<Arc<ImfData>>>,
 }
 
 impl SpriteLoader {
-    fn load(&mut self, path: &str) -> Result<Arc<Sprite>, LoadError> {
+    fn load_palette(&self, palette_path: &str) -> Option<Palette> {
+        let bytes = self.game_file_loader.get(&format!("data\\palette\\{palette_path}")).ok()?;
+        let mut byte_stream: ByteStream = ByteStream::without_metadata(&bytes);
+
+        match Palette::from_bytes(&mut byte_stream) {
+            Ok(palette) => Some(palette),
+            Err(_error) => {
+                #[cfg(feature = "debug")]
+                print_debug!("Failed to load palette: {:?}", _error);
+
+                None
+            }
+        }
+    }
+
+    fn load(&mut self, path: &str, palette_path: Option<&str>) -> Result<Arc<Sprite>, LoadError> {
         #[cfg(feature = "debug")]
         let timer = Timer::new_dynamic(format!("load sprite from {}", path.magenta()));
 
@@ -43,7 +61,8 @@ impl SpriteLoader {
             .game_file_loader
             .get(&format!("data\\sprite\\{path}"))
             .map_err(LoadError::File)?;
-        let mut byte_stream: ByteStream<Option<InternalVersion>> = ByteStream::without_metadata(&bytes);
+        let mut byte_stream: ByteStream<Option<InternalVersion>> =
+            ByteStream::without_metadata(&bytes).with_text_encoding(GAME_FILE_TEXT_ENCODING);
 
         let sprite_data = match SpriteData::from_bytes(&mut byte_stream) {
             Ok(sprite_data) => sprite_data,
@@ -61,32 +80,19 @@ impl SpriteLoader {
         #[cfg(feature = "debug")]
         let cloned_sprite_data = sprite_data.clone();
 
-        let palette = sprite_data.palette.unwrap(); // unwrap_or_default() as soon as i know what
+        // Dyes replace the palette of the sprite. If the palette can't be loaded we
+        // fall back to the palette of the sprite.
+        let palette = palette_path
+            .and_then(|palette_path| self.load_palette(palette_path))
+            .unwrap_or_else(|| sprite_data.palette.unwrap()); // unwrap_or_default() as soon as i know what
 
         let rgba_images: Vec<RgbaImageData> = sprite_data
             .rgba_image_data
             .iter()
-            .map(|image_data| {
-                // Revert the rows, the image is flipped upside down
-                // Convert the pixel from ABGR format to RGBA format
-                let width = image_data.width;
-                let data = image_data
-                    .data
-                    .chunks_exact(4 * width as usize)
-                    .rev()
-                    .flat_map(|pixels| {
-                        pixels
-                            .chunks_exact(4)
-                            .flat_map(|pixel| [pixel[3], pixel[2], pixel[1], pixel[0]])
-                            .collect::<Vec<u8>>()
-                    })
-                    .collect();
-
-                RgbaImageData {
-                    width: image_data.width,
-                    height: image_data.height,
-                    data,
-                }
+            .map(|image_data| RgbaImageData {
+                width: image_data.width,
+                height: image_data.height,
+                data: image_data.to_rgba(),
             })
             .collect();
 
@@ -149,7 +155,8 @@ impl SpriteLoader {
             #[cfg(feature = "debug")]
             sprite_data: cloned_sprite_data,
         });
-        self.cache.insert(path.to_string(), sprite.clone());
+        self.cache
+            .insert((path.to_string(), palette_path.map(str::to_string)), sprite.clone());
 
         #[cfg(feature = "debug")]
         timer.stop();
@@ -158,9 +165,34 @@ impl SpriteLoader {
     }
 
     pub fn get(&mut self, path: &str) -> Result<Arc<Sprite>, LoadError> {
-        match self.cache.get(path) {
+        self.get_with_palette(path, None)
+    }
+
+    /// Get a sprite with its palette replaced by the `.pal` file at
+    /// `palette_path`, relative to the palette directory.
+    pub fn get_with_palette(&mut self, path: &str, palette_path: Option<&str>) -> Result<Arc<Sprite>, LoadError> {
+        match self.cache.get(&(path.to_string(), palette_path.map(str::to_string))) {
             Some(sprite) => Ok(sprite.clone()),
-            None => self.load(path),
+            None => self.load(path, palette_path),
+        }
+    }
+
+    /// Get the layer priorities of the IMF file at `path`, relative to the
+    /// IMF directory. Most sprites don't have an IMF file, in which case
+    /// [`None`] is returned.
+    pub fn get_imf(&mut self, path: &str) -> Option<Arc<ImfData>> {
+        if let Some(imf_data) = self.imf_cache.get(path) {
+            return imf_data.clone();
         }
+
+        let imf_data = self
+            .game_file_loader
+            .get(&format!("data\\imf\\{path}.imf"))
+            .ok()
+            .and_then(|bytes| ImfData::from_bytes(&mut ByteStream::<()>::without_metadata(&bytes)).ok())
+            .map(Arc::new);
+
+        self.imf_cache.insert(path.to_string(), imf_data.clone());
+        imf_data
     }
 }
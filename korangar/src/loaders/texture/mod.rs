@@ -168,6 +168,12 @@ impl TextureAtlasFactory {
         allocation_id
     }
 
+    /// Registers an image that was generated at runtime. Will return an
+    /// allocation ID which can later be used to get the actual allocation.
+    pub fn register_image(&mut self, image: RgbaImage) -> AllocationId {
+        self.texture_atlas.register_image(image)
+    }
+
     pub fn get_allocation(&self, allocation_id: AllocationId) -> Option<AtlasAllocation> {
         self.texture_atlas.get_allocation(allocation_id)
     }
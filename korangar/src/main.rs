@@ -58,8 +58,8 @@ use korangar_interface::state::{
 };
 use korangar_interface::Interface;
 use korangar_networking::{
-    DisconnectReason, HotkeyState, LoginServerLoginData, MessageColor, NetworkEvent, NetworkEventBuffer, NetworkingSystem, SellItem,
-    ShopItem,
+    DisconnectReason, HotkeyState, LoginServerLoginData, MessageColor, NetworkEvent, NetworkEventBuffer, NetworkingSystem, PartyMember,
+    SellItem, SendPacketError, ShopItem,
 };
 #[cfg(feature = "debug")]
 use korangar_util::texture_atlas::AtlasAllocation;
@@ -67,7 +67,7 @@ use korangar_util::texture_atlas::AtlasAllocation;
 use ragnarok_packets::handler::NoPacketCallback;
 use ragnarok_packets::{
     BuyShopItemsResult, CharacterId, CharacterInformation, CharacterServerInformation, DisappearanceReason, Friend, HotbarSlot,
-    SellItemsResult, SkillId, SkillType, TilePosition, UnitId, WorldPosition,
+    PacketVersion, SellItemsResult, SkillId, SkillType, TilePosition, UnitId, WorldPosition,
 };
 use renderer::InterfaceRenderer;
 #[cfg(feature = "debug")]
@@ -103,7 +103,7 @@ const CLIENT_NAME: &str = "Korangar";
 const ROLLING_CUTTER_ID: SkillId = SkillId(2036);
 const DEFAULT_MAP: &str = "geffen";
 const DEFAULT_BACKGROUND_MUSIC: Option<&str> = Some("bgm\\01.mp3");
-const MAIN_MENU_CLICK_SOUND_EFFECT: &str = "¹öÆ°¼Ò¸®.wav";
+const MAIN_MENU_CLICK_SOUND_EFFECT: &str = "버튼소리.wav";
 // TODO: The number of point lights that can cast shadows should be configurable
 // through the graphics settings. For now I just chose an arbitrary smaller
 // number that should be playable on most devices.
@@ -214,6 +214,7 @@ struct Client {
     network_event_buffer: NetworkEventBuffer,
     client_info: ClientInfo,
     friend_list: PlainTrackedState<Vec<(Friend, LinkedElement)>>,
+    party_members: PlainTrackedState<Vec<(PartyMember, LinkedElement)>>,
     saved_login_data: Option<LoginServerLoginData>,
     saved_character_server: Option<CharacterServerInformation>,
     saved_characters: PlainTrackedState<Vec<CharacterInformation>>,
@@ -341,7 +342,7 @@ impl Client {
             let mut map_loader = MapLoader::new(device.clone(), queue.clone(), game_file_loader.clone(), audio_engine.clone());
             let mut sprite_loader = SpriteLoader::new(device.clone(), queue.clone(), game_file_loader.clone());
             let mut action_loader = ActionLoader::new(game_file_loader.clone());
-            let effect_loader = EffectLoader::new(game_file_loader.clone());
+            let mut effect_loader = EffectLoader::new(game_file_loader.clone());
             let animation_loader = AnimationLoader::new();
 
             let script_loader = ScriptLoader::new(&game_file_loader).unwrap_or_else(|_| {
@@ -454,6 +455,7 @@ impl Client {
             let (networking_system, network_event_buffer) = NetworkingSystem::spawn_with_callback(packet_history_callback.clone());
 
             let friend_list: PlainTrackedState<Vec<(Friend, LinkedElement)>> = PlainTrackedState::default();
+            let party_members: PlainTrackedState<Vec<(PartyMember, LinkedElement)>> = PlainTrackedState::default();
             let saved_login_data: Option<LoginServerLoginData> = None;
             let saved_character_server: Option<CharacterServerInformation> = None;
             let saved_characters: PlainTrackedState<Vec<CharacterInformation>> = PlainTrackedState::default();
@@ -473,7 +475,7 @@ impl Client {
         time_phase!("create resources", {
             let particle_holder = ParticleHolder::default();
             let point_light_manager = PointLightManager::new();
-            let effect_holder = EffectHolder::default();
+            let mut effect_holder = EffectHolder::default();
             let entities = Vec::<Entity>::new();
             let player_inventory = Inventory::default();
             let player_skill_tree = SkillTree::default();
@@ -522,6 +524,7 @@ impl Client {
                 .load(
                     DEFAULT_MAP.to_string(),
                     &mut model_loader,
+                    &mut effect_loader,
                     texture_loader.clone(),
                     #[cfg(feature = "debug")]
                     &tile_texture_mapping,
@@ -529,6 +532,7 @@ impl Client {
                 .expect("failed to load initial map");
 
             map.set_ambient_sound_sources(&audio_engine);
+            map.set_effects(&mut effect_holder);
             audio_engine.play_background_music_track(DEFAULT_BACKGROUND_MUSIC);
         });
 
@@ -600,6 +604,7 @@ impl Client {
             network_event_buffer,
             client_info,
             friend_list,
+            party_members,
             saved_login_data,
             saved_character_server,
             saved_characters,
@@ -758,8 +763,17 @@ impl Client {
                         print_debug!("Disconnection from the character server with error");
 
                         let socket_address = self.saved_login_server_address.unwrap();
-                        self.networking_system
-                            .connect_to_login_server(socket_address, &self.saved_username, &self.saved_password);
+                        if self
+                            .networking_system
+                            .connect_to_login_server(socket_address, &self.saved_username, &self.saved_password)
+                            .is_err()
+                        {
+                            self.interface.open_window(
+                                &self.application,
+                                &mut self.focus_state,
+                                &ErrorWindow::new("Failed to serialize the login data for this server".to_owned()),
+                            );
+                        }
                     }
                 }
                 NetworkEvent::CharacterServerConnected { normal_slot_count } => {
@@ -779,7 +793,13 @@ impl Client {
 
                         let login_data = self.saved_login_data.as_ref().unwrap();
                         let server = self.saved_character_server.clone().unwrap();
-                        self.networking_system.connect_to_character_server(login_data, server);
+                        if self.networking_system.connect_to_character_server(login_data, server).is_err() {
+                            self.interface.open_window(
+                                &self.application,
+                                &mut self.focus_state,
+                                &ErrorWindow::new("Failed to serialize the login data for this server".to_owned()),
+                            );
+                        }
                     }
                 }
                 NetworkEvent::MapServerDisconnected { reason } => {
@@ -791,12 +811,19 @@ impl Client {
 
                     let login_data = self.saved_login_data.as_ref().unwrap();
                     let server = self.saved_character_server.clone().unwrap();
-                    self.networking_system.connect_to_character_server(login_data, server);
+                    if self.networking_system.connect_to_character_server(login_data, server).is_err() {
+                        self.interface.open_window(
+                            &self.application,
+                            &mut self.focus_state,
+                            &ErrorWindow::new("Failed to serialize the login data for this server".to_owned()),
+                        );
+                    }
 
                     self.entities.clear();
                     self.particle_holder.clear();
                     self.effect_holder.clear();
                     self.point_light_manager.clear();
+                    self.party_members.mutate(|party_members| party_members.clear());
                     self.audio_engine.play_background_music_track(None);
 
                     self.map = self
@@ -804,6 +831,7 @@ impl Client {
                         .load(
                             DEFAULT_MAP.to_string(),
                             &mut self.model_loader,
+                            &mut self.effect_loader,
                             self.texture_loader.clone(),
                             #[cfg(feature = "debug")]
                             &self.tile_texture_mapping,
@@ -811,6 +839,7 @@ impl Client {
                         .expect("failed to load initial map");
 
                     self.map.set_ambient_sound_sources(&self.audio_engine);
+                    self.map.set_effects(&mut self.effect_holder);
                     self.audio_engine.play_background_music_track(DEFAULT_BACKGROUND_MUSIC);
 
                     self.interface.close_all_windows_except(&mut self.focus_state);
@@ -874,7 +903,13 @@ impl Client {
 
                     let saved_login_data = self.saved_login_data.as_ref().unwrap();
                     self.networking_system.disconnect_from_character_server();
-                    self.networking_system.connect_to_map_server(saved_login_data, login_data);
+                    if self.networking_system.connect_to_map_server(saved_login_data, login_data).is_err() {
+                        self.interface.open_window(
+                            &self.application,
+                            &mut self.focus_state,
+                            &ErrorWindow::new("Failed to serialize the login data for this server".to_owned()),
+                        );
+                    }
 
                     let character_information = self
                         .saved_characters
@@ -889,6 +924,7 @@ impl Client {
                         .load(
                             map_name,
                             &mut self.model_loader,
+                            &mut self.effect_loader,
                             self.texture_loader.clone(),
                             #[cfg(feature = "debug")]
                             &self.tile_texture_mapping,
@@ -896,6 +932,8 @@ impl Client {
                         .unwrap();
 
                     self.map.set_ambient_sound_sources(&self.audio_engine);
+                    self.effect_holder.clear();
+                    self.map.set_effects(&mut self.effect_holder);
                     self.audio_engine
                         .play_background_music_track(self.map.background_music_track_name());
 
@@ -1032,6 +1070,7 @@ impl Client {
                         .load(
                             map_name,
                             &mut self.model_loader,
+                            &mut self.effect_loader,
                             self.texture_loader.clone(),
                             #[cfg(feature = "debug")]
                             &self.tile_texture_mapping,
@@ -1048,6 +1087,7 @@ impl Client {
 
                     self.particle_holder.clear();
                     self.effect_holder.clear();
+                    self.map.set_effects(&mut self.effect_holder);
                     self.point_light_manager.clear();
                     let _ = self.networking_system.map_loaded();
 
@@ -1138,8 +1178,12 @@ impl Client {
                     self.player_inventory.remove_item(index, amount);
                 }
                 NetworkEvent::SkillTree(skill_information) => {
-                    self.player_skill_tree
-                        .fill(&mut self.sprite_loader, &mut self.action_loader, skill_information);
+                    self.player_skill_tree.fill(
+                        &mut self.sprite_loader,
+                        &mut self.action_loader,
+                        &self.script_loader,
+                        skill_information,
+                    );
                 }
                 NetworkEvent::UpdateEquippedPosition { index, equipped_position } => {
                     self.player_inventory.update_equipped_position(index, equipped_position);
@@ -1323,15 +1367,14 @@ impl Client {
                                     .find(|inventory_item| inventory_item.index == item.inventory_index)
                                     .expect("item not in inventory");
 
-                                let name = inventory_item.metadata.name.clone();
-                                let texture = inventory_item.metadata.texture.clone();
+                                let metadata = inventory_item.metadata.clone();
                                 let quantity = match &inventory_item.details {
                                     korangar_networking::InventoryItemDetails::Regular { amount, .. } => *amount,
                                     korangar_networking::InventoryItemDetails::Equippable { .. } => 1,
                                 };
 
                                 SellItem {
-                                    metadata: (ResourceMetadata { name, texture }, quantity),
+                                    metadata: (metadata, quantity),
                                     inventory_index: item.inventory_index,
                                     price: item.price,
                                     overcharge_price: item.overcharge_price,
@@ -1364,6 +1407,90 @@ impl Client {
                         });
                     }
                 },
+                NetworkEvent::PartyInvitation { party_id, party_name } => self.interface.open_window(
+                    &self.application,
+                    &mut self.focus_state,
+                    &PartyInvitationWindow::new(party_id, party_name),
+                ),
+                NetworkEvent::SetParty { members, .. } => {
+                    self.party_members.mutate(|party_members| {
+                        *party_members = members.into_iter().map(|member| (member, LinkedElement::new())).collect();
+                    });
+                }
+                NetworkEvent::UpdatePartyMember { mut member } => {
+                    self.party_members.mutate(|party_members| {
+                        let existing_member = party_members
+                            .iter_mut()
+                            .find(|(party_member, _)| party_member.account_id == member.account_id);
+
+                        // The element of an updated member is replaced, so the new state is displayed.
+                        match existing_member {
+                            Some(existing_member) => {
+                                // The update doesn't contain the health of the member, so we keep the
+                                // last known value.
+                                member.health = member.health.or(existing_member.0.health);
+                                *existing_member = (member, LinkedElement::new());
+                            }
+                            None => party_members.push((member, LinkedElement::new())),
+                        }
+                    });
+                }
+                NetworkEvent::PartyMemberLeft { account_id } => {
+                    let player_left = self
+                        .saved_login_data
+                        .as_ref()
+                        .is_some_and(|login_data| login_data.account_id == account_id);
+
+                    match player_left {
+                        true => self.party_members.mutate(|party_members| party_members.clear()),
+                        false => self.party_members.retain(|(party_member, _)| party_member.account_id != account_id),
+                    }
+                }
+                NetworkEvent::PartyMemberHealth {
+                    account_id,
+                    health_points,
+                    maximum_health_points,
+                } => {
+                    let entity = self.entities.iter_mut().find(|entity| entity.get_entity_id().0 == account_id.0);
+
+                    if let Some(entity) = entity {
+                        entity.update_health(health_points, maximum_health_points);
+                    }
+
+                    self.party_members.mutate(|party_members| {
+                        party_members
+                            .iter_mut()
+                            .filter(|(party_member, _)| party_member.account_id == account_id)
+                            .for_each(|(party_member, linked_element)| {
+                                party_member.health = Some((health_points, maximum_health_points));
+                                *linked_element = LinkedElement::new();
+                            });
+                    });
+                }
+                // NOTE: There is no minimap yet, so the position is only displayed in the
+                // party window. Markers on the minimap are left for when it exists.
+                NetworkEvent::PartyMemberPosition { account_id, position } => {
+                    self.party_members.mutate(|party_members| {
+                        party_members
+                            .iter_mut()
+                            .filter(|(party_member, _)| party_member.account_id == account_id)
+                            .for_each(|(party_member, linked_element)| {
+                                party_member.position = Some(position);
+                                *linked_element = LinkedElement::new();
+                            });
+                    });
+                }
+                NetworkEvent::PartyLeaderChanged { old_leader, new_leader } => {
+                    self.party_members.mutate(|party_members| {
+                        party_members
+                            .iter_mut()
+                            .filter(|(party_member, _)| party_member.account_id == old_leader || party_member.account_id == new_leader)
+                            .for_each(|(party_member, linked_element)| {
+                                party_member.is_leader = party_member.account_id == new_leader;
+                                *linked_element = LinkedElement::new();
+                            });
+                    });
+                }
             }
         }
 
@@ -1393,11 +1520,30 @@ impl Client {
                         .next()
                         .expect("ill formatted service IP");
 
+                    let text_encoding = service
+                        .language_type
+                        .as_ref()
+                        .unwrap_or(&self.client_info.service_type)
+                        .text_encoding();
+                    let packet_version = service.packet_version.map(PacketVersion).unwrap_or_default();
+
                     self.saved_login_server_address = Some(socket_address);
                     self.saved_username = username.clone();
                     self.saved_password = password.clone();
 
-                    self.networking_system.connect_to_login_server(socket_address, username, password);
+                    self.networking_system.set_text_encoding(text_encoding);
+                    self.networking_system.set_packet_version(packet_version);
+                    if self
+                        .networking_system
+                        .connect_to_login_server(socket_address, username, password)
+                        .is_err()
+                    {
+                        self.interface.open_window(
+                            &self.application,
+                            &mut self.focus_state,
+                            &ErrorWindow::new("Failed to serialize the login data for this server".to_owned()),
+                        );
+                    }
                 }
                 UserEvent::SelectServer(server) => {
                     self.saved_character_server = Some(server.clone());
@@ -1408,7 +1554,13 @@ impl Client {
                     // server before it logged in to the login server, so it's fine to
                     // unwrap here.
                     let login_data = self.saved_login_data.as_ref().unwrap();
-                    self.networking_system.connect_to_character_server(login_data, server);
+                    if self.networking_system.connect_to_character_server(login_data, server).is_err() {
+                        self.interface.open_window(
+                            &self.application,
+                            &mut self.focus_state,
+                            &ErrorWindow::new("Failed to serialize the login data for this server".to_owned()),
+                        );
+                    }
                 }
                 UserEvent::Respawn => {
                     let _ = self.networking_system.respawn();
@@ -1479,6 +1631,13 @@ impl Client {
                         &FriendsWindow::new(self.friend_list.new_remote()),
                     );
                 }
+                UserEvent::OpenPartyWindow => {
+                    self.interface.open_window(
+                        &self.application,
+                        &mut self.focus_state,
+                        &PartyWindow::new(self.party_members.new_remote()),
+                    );
+                }
                 UserEvent::ToggleShowInterface => self.show_interface = !self.show_interface,
                 UserEvent::SetThemeFile { theme_file, theme_kind } => self.application.set_theme_file(theme_file, theme_kind),
                 UserEvent::SaveTheme { theme_kind } => self.application.save_theme(theme_kind),
@@ -1537,7 +1696,13 @@ impl Client {
                     let _ = self.networking_system.warp_to_map(map_name, position);
                 }
                 UserEvent::SendMessage(message) => {
-                    let _ = self.networking_system.send_chat_message(&self.saved_player_name, &message);
+                    // Like in the official client, messages starting with `%` are sent to the
+                    // party.
+                    let result = match message.strip_prefix('%') {
+                        Some(party_message) => self.networking_system.send_party_message(&self.saved_player_name, party_message),
+                        None => self.networking_system.send_chat_message(&self.saved_player_name, &message),
+                    };
+                    self.report_send_error(result);
                     // TODO: maybe find a better solution for unfocusing the message box if
                     // this becomes problematic
                     self.focus_state.remove_focus();
@@ -1642,7 +1807,8 @@ impl Client {
                         #[cfg(feature = "debug")]
                         print_debug!("[{}] friend name {} is too long", "error".red(), name.magenta());
                     } else {
-                        let _ = self.networking_system.add_friend(name);
+                        let result = self.networking_system.add_friend(name);
+                        self.report_send_error(result);
                     }
                 }
                 UserEvent::RemoveFriend { account_id, character_id } => {
@@ -1658,6 +1824,43 @@ impl Client {
                     self.interface
                         .close_window_with_class(&mut self.focus_state, FriendRequestWindow::WINDOW_CLASS);
                 }
+                UserEvent::CreateParty(name) => {
+                    if name.len() > 24 {
+                        #[cfg(feature = "debug")]
+                        print_debug!("[{}] party name {} is too long", "error".red(), name.magenta());
+                    } else {
+                        let result = self.networking_system.create_party(name);
+                        self.report_send_error(result);
+                    }
+                }
+                UserEvent::InviteToParty(name) => {
+                    if name.len() > 24 {
+                        #[cfg(feature = "debug")]
+                        print_debug!("[{}] character name {} is too long", "error".red(), name.magenta());
+                    } else {
+                        let result = self.networking_system.invite_to_party(name);
+                        self.report_send_error(result);
+                    }
+                }
+                UserEvent::LeaveParty => {
+                    let _ = self.networking_system.leave_party();
+                }
+                UserEvent::ExpelPartyMember { account_id, name } => {
+                    let _ = self.networking_system.expel_party_member(account_id, name);
+                }
+                UserEvent::ChangePartyLeader { account_id } => {
+                    let _ = self.networking_system.change_party_leader(account_id);
+                }
+                UserEvent::RejectPartyInvitation { party_id } => {
+                    let _ = self.networking_system.reject_party_invitation(party_id);
+                    self.interface
+                        .close_window_with_class(&mut self.focus_state, PartyInvitationWindow::WINDOW_CLASS);
+                }
+                UserEvent::AcceptPartyInvitation { party_id } => {
+                    let _ = self.networking_system.accept_party_invitation(party_id);
+                    self.interface
+                        .close_window_with_class(&mut self.focus_state, PartyInvitationWindow::WINDOW_CLASS);
+                }
                 UserEvent::BuyItems { items } => {
                     let _ = self.networking_system.purchase_items(items);
                 }
@@ -1761,9 +1964,15 @@ impl Client {
         #[cfg(feature = "debug")]
         let update_entities_measurement = Profiler::start_measurement("update entities");
 
-        self.entities
-            .iter_mut()
-            .for_each(|entity| entity.update(&self.map, delta_time as f32, client_tick));
+        self.entities.iter_mut().for_each(|entity| {
+            entity.update(
+                &self.audio_engine,
+                &self.map,
+                &self.player_camera,
+                delta_time as f32,
+                client_tick,
+            )
+        });
 
         #[cfg(feature = "debug")]
         update_entities_measurement.stop();
@@ -2104,6 +2313,29 @@ impl Client {
                 }
             }
 
+            {
+                #[cfg(feature = "debug")]
+                profile_block!("render party status");
+
+                let party_members = self.party_members.get();
+
+                entities
+                    .iter()
+                    .filter(|entity| {
+                        party_members
+                            .iter()
+                            .any(|(party_member, _)| party_member.account_id.0 == entity.get_entity_id().0)
+                    })
+                    .for_each(|entity| {
+                        entity.render_party_status(
+                            &self.middle_interface_renderer,
+                            current_camera,
+                            self.application.get_game_theme(),
+                            screen_size,
+                        )
+                    });
+            }
+
             if !entities.is_empty() {
                 #[cfg(feature = "debug")]
                 profile_block!("render player status");
@@ -2259,6 +2491,17 @@ impl Client {
             self.interface.schedule_render();
         }
     }
+
+    /// Packets containing user provided text can fail to serialize if the text
+    /// can't be represented in the text encoding of the server.
+    fn report_send_error(&mut self, result: Result<(), SendPacketError>) {
+        if let Err(SendPacketError::FailedToSerialize(_)) = result {
+            self.chat_messages.push(ChatMessage {
+                text: "The message contains characters that can't be sent to this server".to_owned(),
+                color: MessageColor::Error,
+            });
+        }
+    }
 }
 
 impl ApplicationHandler for Client {
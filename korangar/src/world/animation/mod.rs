@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use cgmath::{Array, Matrix4, Point3, Vector2, Zero};
@@ -66,7 +67,70 @@ impl Default for AnimationFramePart {
     }
 }
 
+/// Frames that were entered while the animation advanced from the previous
+/// to the current frame time. If the animation advanced by more than one
+/// loop, every frame is only entered once.
+fn entered_frames(previous_frame_time: Option<u32>, frame_time: u32, frame_count: usize) -> RangeInclusive<u32> {
+    let first = match previous_frame_time {
+        Some(previous_frame_time) => previous_frame_time + 1,
+        None => 0,
+    };
+    let first = first.max((frame_time + 1).saturating_sub(frame_count as u32));
+
+    first..=frame_time
+}
+
 impl AnimationData {
+    fn frame_time(&self, animation_state: &AnimationState, action_index: usize, time: u32) -> u32 {
+        let delay = self.delays[action_index % self.delays.len()];
+        let animation = &self.animations[action_index % self.animations.len()];
+
+        let factor = animation_state
+            .factor
+            .map(|factor| delay * (factor / 5.0))
+            .unwrap_or_else(|| delay * 50.0);
+
+        // TODO: Work out how to avoid losing digits when casting time to an f32. When
+        //       fixed remove set_start_time in MouseCursor.
+        animation_state
+            .duration
+            .map(|duration| time * animation.frames.len() as u32 / duration)
+            .unwrap_or_else(|| (time as f32 / factor) as u32)
+    }
+
+    /// Get the events of all frames that were shown since the previous update
+    /// of the animation state, for example footstep or attack sounds.
+    pub fn frame_events(&self, animation_state: &AnimationState, camera_direction: usize, head_direction: usize) -> Vec<&str> {
+        let direction = (camera_direction + head_direction) % 8;
+        let action_index = animation_state.action * 8 + direction;
+        let frame_count = self.animations[action_index % self.animations.len()].frames.len();
+
+        let previous_frame_time = animation_state
+            .previous_time
+            .map(|previous_time| self.frame_time(animation_state, action_index, previous_time));
+        let frame_time = self.frame_time(animation_state, action_index, animation_state.time);
+
+        entered_frames(previous_frame_time, frame_time, frame_count)
+            .flat_map(|frame_time| {
+                let motion_index = frame_time as usize % frame_count;
+                let mut events = Vec::new();
+
+                // The layers of an entity (like body and head) usually carry the same
+                // events, which should only trigger once per frame.
+                self.animation_pair
+                    .iter()
+                    .filter_map(|pair| pair.actions.motion_event(action_index, motion_index))
+                    .for_each(|event| {
+                        if !events.contains(&event) {
+                            events.push(event);
+                        }
+                    });
+
+                events
+            })
+            .collect()
+    }
+
     pub fn render(
         &self,
         instructions: &mut Vec<EntityInstruction>,
@@ -79,21 +143,9 @@ impl AnimationData {
         let camera_direction = camera.camera_direction();
         let direction = (camera_direction + head_direction) % 8;
         let aa = animation_state.action * 8 + direction;
-        let delay = self.delays[aa % self.delays.len()];
         let animation = &self.animations[aa % self.animations.len()];
+        let frame_time = self.frame_time(animation_state, aa, animation_state.time);
 
-        let factor = animation_state
-            .factor
-            .map(|factor| delay * (factor / 5.0))
-            .unwrap_or_else(|| delay * 50.0);
-
-        let frame_time = animation_state
-            .duration
-            .map(|duration| animation_state.time * animation.frames.len() as u32 / duration)
-            .unwrap_or_else(|| (animation_state.time as f32 / factor) as u32);
-
-        // TODO: Work out how to avoid losing digits when casting time to an f32. When
-        //       fixed remove set_start_time in MouseCursor.
         let time = frame_time as usize % animation.frames.len();
         let mut frame = &animation.frames[time];
 
@@ -144,3 +196,31 @@ impl AnimationData {
         }
     }
 }
+
+#[cfg(test)]
+mod frame_events {
+    use super::entered_frames;
+
+    #[test]
+    fn restarted() {
+        assert_eq!(entered_frames(None, 0, 4), 0..=0);
+        assert_eq!(entered_frames(None, 2, 4), 0..=2);
+    }
+
+    #[test]
+    fn advanced() {
+        assert_eq!(entered_frames(Some(1), 3, 4), 2..=3);
+        assert_eq!(entered_frames(Some(3), 5, 4), 4..=5);
+    }
+
+    #[test]
+    fn not_advanced() {
+        assert!(entered_frames(Some(2), 2, 4).is_empty());
+    }
+
+    #[test]
+    fn multiple_loops() {
+        assert_eq!(entered_frames(Some(0), 10, 4), 7..=10);
+        assert_eq!(entered_frames(None, 10, 4), 7..=10);
+    }
+}
@@ -1,5 +1,3 @@
-mod lookup;
-
 use std::sync::Arc;
 
 use cgmath::{Point3, Rad, Vector2, Vector3};
@@ -68,34 +66,56 @@ impl Effect {
                 continue;
             };
 
-            if frame.texture_index > layer.textures.len() {
+            let texture_index = frame.texture_index.floor();
+
+            let Some(texture) = layer.textures.get(texture_index as usize) else {
                 continue;
-            }
+            };
 
-            renderer.render_effect(
-                camera,
-                position,
-                layer.textures[frame.texture_index].clone(),
-                [
-                    Vector2::new(frame.xy[0], frame.xy[4]),
-                    Vector2::new(frame.xy[1], frame.xy[5]),
-                    Vector2::new(frame.xy[3], frame.xy[7]),
-                    Vector2::new(frame.xy[2], frame.xy[6]),
-                ],
-                [
-                    Vector2::new(frame.uv[0] + frame.uv[2], frame.uv[3] + frame.uv[1]),
-                    Vector2::new(frame.uv[0] + frame.uv[2], frame.uv[1]),
-                    Vector2::new(frame.uv[0], frame.uv[1]),
-                    Vector2::new(frame.uv[0], frame.uv[3] + frame.uv[1]),
-                ],
-                frame.offset,
-                frame.angle,
-                frame.color,
-                frame.source_blend_factor,
-                frame.destination_blend_factor,
-            );
+            match frame.mt_present {
+                MultiTexturePresent::None => Self::render_frame(renderer, camera, position, &frame, texture.clone(), frame.color),
+                MultiTexturePresent::Blended => {
+                    let blend = frame.texture_index - texture_index;
+                    let next_texture = &layer.textures[(texture_index as usize + 1) % layer.textures.len()];
+
+                    Self::render_frame(renderer, camera, position, &frame, texture.clone(), frame.color * (1.0 - blend));
+                    Self::render_frame(renderer, camera, position, &frame, next_texture.clone(), frame.color * blend);
+                }
+            }
         }
     }
+
+    fn render_frame(
+        renderer: &mut EffectRenderer,
+        camera: &dyn Camera,
+        position: Point3<f32>,
+        frame: &Frame,
+        texture: Arc<Texture>,
+        color: Color,
+    ) {
+        renderer.render_effect(
+            camera,
+            position,
+            texture,
+            [
+                Vector2::new(frame.xy[0], frame.xy[4]),
+                Vector2::new(frame.xy[1], frame.xy[5]),
+                Vector2::new(frame.xy[3], frame.xy[7]),
+                Vector2::new(frame.xy[2], frame.xy[6]),
+            ],
+            [
+                Vector2::new(frame.uv[0] + frame.uv[2], frame.uv[3] + frame.uv[1]),
+                Vector2::new(frame.uv[0] + frame.uv[2], frame.uv[1]),
+                Vector2::new(frame.uv[0], frame.uv[1]),
+                Vector2::new(frame.uv[0], frame.uv[3] + frame.uv[1]),
+            ],
+            frame.offset,
+            frame.angle,
+            color,
+            frame.source_blend_factor,
+            frame.destination_blend_factor,
+        );
+    }
 }
 
 #[derive(new)]
@@ -107,57 +127,61 @@ pub struct Layer {
 
 impl Layer {
     fn interpolate_frame(&self, frame_timer: &FrameTimer) -> Option<Frame> {
-        if let Some(frame_index) = self.indices[frame_timer.current_frame] {
-            if let Some(next_frame) = self.frames.get(frame_index + 2) {
-                Some(Self::interpolate(
-                    &self.frames[frame_index],
-                    next_frame,
-                    frame_timer.current_frame,
-                ))
-            } else {
-                Some(self.frames[frame_index].clone())
+        let frame_index = self.indices[frame_timer.current_frame]?;
+        let first = &self.frames[frame_index];
+
+        match self.frames.get(frame_index + 2) {
+            Some(next_frame) => Some(Self::interpolate(
+                first,
+                next_frame,
+                frame_timer.current_frame,
+                self.textures.len(),
+            )),
+            None => {
+                let elapsed = frame_timer.current_frame as f32 - first.frame_index as f32;
+
+                Some(Frame {
+                    texture_index: Self::animate_texture(first, None, elapsed, self.textures.len()),
+                    ..first.clone()
+                })
             }
-        } else {
-            None
         }
     }
 
-    fn interpolate(first: &Frame, second: &Frame, frame_index: usize) -> Frame {
-        let time = 1.0 / (second.frame_index - first.frame_index) as f32 * (frame_index - first.frame_index) as f32;
-        let sub_mult = 1.0;
+    fn interpolate(first: &Frame, second: &Frame, frame_index: usize, texture_count: usize) -> Frame {
+        if second.frame_index == first.frame_index {
+            return first.clone();
+        }
 
-        // TODO: angle bias
-        let angle = Self::ease_interpolate(first.angle.0, second.angle.0, time, 0.0, sub_mult);
-        let color = (second.color - first.color) * time + first.color * sub_mult;
+        let elapsed = frame_index as f32 - first.frame_index as f32;
+        let time = elapsed / (second.frame_index as f32 - first.frame_index as f32);
+
+        let offset = first.offset + (second.offset - first.offset) * time;
+        let angle = first.angle + (second.angle - first.angle) * time;
+        let color = (second.color - first.color) * time + first.color;
 
         let uv = (0..8)
-            .map(|index| (second.uv[index] - first.uv[index]) * time + first.uv[index] * sub_mult)
+            .map(|index| (second.uv[index] - first.uv[index]) * time + first.uv[index])
             .next_chunk()
             .unwrap();
 
-        // TODO: scale bias
         let xy = (0..8)
-            .map(|index| Self::ease_interpolate(first.xy[index], second.xy[index], time, 0.0, sub_mult))
+            .map(|index| (second.xy[index] - first.xy[index]) * time + first.xy[index])
             .next_chunk()
             .unwrap();
 
-        // TODO: additional logic for animation type 2 and 3
-        let texture_index = first.texture_index;
-
-        // TODO: bezier curves
-        let offset_x = (second.offset.x - first.offset.x) * time + first.offset.x * sub_mult;
-        let offset_y = (second.offset.y - first.offset.y) * time + first.offset.y * sub_mult;
+        let texture_index = Self::animate_texture(first, Some((second, time)), elapsed, texture_count);
 
         Frame {
             frame_index,
             frame_type: first.frame_type,
-            offset: Vector2::new(offset_x, offset_y),
+            offset,
             uv,
             xy,
             texture_index,
             animation_type: first.animation_type,
             delay: first.delay,
-            angle: Rad(angle),
+            angle,
             color,
             source_blend_factor: first.source_blend_factor,
             destination_blend_factor: first.destination_blend_factor,
@@ -165,13 +189,22 @@ impl Layer {
         }
     }
 
-    fn ease_interpolate(start_value: f32, end_value: f32, time: f32, bias: f32, sub_multiplier: f32) -> f32 {
-        if bias > 0.0 {
-            (end_value - start_value) * time.powf(1.0 + bias / 5.0) + start_value * sub_multiplier
-        } else if bias < 0.0 {
-            (end_value - start_value) * (1.0 - (1.0 - time).powf(-bias / 5.0 + 1.0)) + start_value * sub_multiplier
-        } else {
-            (end_value - start_value) * time + start_value * sub_multiplier
+    /// Get the texture index of a frame `elapsed` key frames after `first`.
+    /// `next` is the following key frame and the interpolation time, if
+    /// there is one.
+    fn animate_texture(first: &Frame, next: Option<(&Frame, f32)>, elapsed: f32, texture_count: usize) -> f32 {
+        let texture_count = texture_count as f32;
+        let advanced = first.delay * elapsed;
+
+        match first.animation_type {
+            AnimationType::Static => first.texture_index,
+            AnimationType::Interpolated => match next {
+                Some((second, time)) => first.texture_index + (second.texture_index - first.texture_index) * time,
+                None => first.texture_index,
+            },
+            AnimationType::PlayOnce => (first.texture_index + advanced).min(texture_count - 1.0),
+            AnimationType::Repeat => (first.texture_index + advanced).rem_euclid(texture_count),
+            AnimationType::RepeatReversed => (first.texture_index - advanced).rem_euclid(texture_count),
         }
     }
 }
@@ -183,8 +216,10 @@ pub struct Frame {
     offset: Vector2<f32>,
     uv: [f32; 8],
     xy: [f32; 8],
-    texture_index: usize,
+    texture_index: f32,
     animation_type: AnimationType,
+    /// Number of textures to advance per key frame for the
+    /// [`AnimationType`]s that play through the textures.
     delay: f32,
     angle: Rad<f32>,
     color: Color,
@@ -193,12 +228,19 @@ pub struct Frame {
     mt_present: MultiTexturePresent,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AnimationType {
-    Type0,
-    Type1,
-    Type2,
-    Type3,
+    /// The texture stays the same.
+    Static,
+    /// The texture index is interpolated between the key frames.
+    Interpolated,
+    /// Plays through the textures once and stops at the last one.
+    PlayOnce,
+    /// Plays through the textures and starts over after the last one.
+    Repeat,
+    /// Plays through the textures backwards and starts over after the first
+    /// one.
+    RepeatReversed,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -207,9 +249,13 @@ pub enum FrameType {
     Morphing,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MultiTexturePresent {
+    /// Only the current texture is rendered.
     None,
+    /// The current texture is faded into the next one, based on the
+    /// fractional part of the texture index.
+    Blended,
 }
 
 pub struct FrameTimer {
@@ -349,6 +395,45 @@ impl EffectBase for EffectWithLight {
     }
 }
 
+/// An effect placed on the map by an effect source. It repeats indefinitely
+/// and does not emit any light, since the map has its own light sources.
+pub struct MapEffect {
+    effect: Arc<Effect>,
+    frame_timer: FrameTimer,
+    position: Point3<f32>,
+    gets_deleted: bool,
+}
+
+impl MapEffect {
+    pub fn new(effect: Arc<Effect>, position: Point3<f32>) -> Self {
+        let frame_timer = effect.new_frame_timer();
+
+        Self {
+            effect,
+            frame_timer,
+            position,
+            gets_deleted: false,
+        }
+    }
+}
+
+impl EffectBase for MapEffect {
+    fn update(&mut self, _entities: &[crate::world::Entity], delta_time: f32) -> bool {
+        self.frame_timer.update(delta_time);
+        !self.gets_deleted
+    }
+
+    fn mark_for_deletion(&mut self) {
+        self.gets_deleted = true;
+    }
+
+    fn register_point_lights(&self, _point_light_manager: &mut PointLightManager, _camera: &dyn Camera) {}
+
+    fn render(&self, renderer: &mut EffectRenderer, camera: &dyn Camera) {
+        self.effect.render(renderer, camera, &self.frame_timer, self.position);
+    }
+}
+
 #[derive(Default)]
 pub struct EffectHolder {
     effects: Vec<(Box<dyn EffectBase + Send + Sync>, Option<EntityId>)>,
@@ -388,3 +473,141 @@ impl EffectHolder {
         self.effects.iter().for_each(|(effect, _)| effect.render(renderer, camera));
     }
 }
+
+#[cfg(test)]
+mod interpolation {
+    use cgmath::{Rad, Vector2};
+    use wgpu::BlendFactor;
+
+    use super::{AnimationType, Frame, FrameType, Layer, MultiTexturePresent};
+    use crate::graphics::Color;
+
+    fn key_frame(frame_index: usize) -> Frame {
+        Frame::new(
+            frame_index,
+            FrameType::Basic,
+            Vector2::new(0.0, 0.0),
+            [0.0; 8],
+            [0.0; 8],
+            0.0,
+            AnimationType::Static,
+            0.0,
+            Rad(0.0),
+            Color::BLACK,
+            BlendFactor::SrcAlpha,
+            BlendFactor::One,
+            MultiTexturePresent::None,
+        )
+    }
+
+    fn key_frames() -> (Frame, Frame) {
+        let first = key_frame(10);
+        let mut second = key_frame(20);
+        second.offset = Vector2::new(8.0, -4.0);
+        second.xy = [16.0; 8];
+        second.uv = [1.0; 8];
+        second.angle = Rad(2.0);
+        second.color = Color::rgba(1.0, 0.5, 0.25, 1.0);
+        (first, second)
+    }
+
+    #[test]
+    fn key_frame_values() {
+        let (first, second) = key_frames();
+
+        let start = Layer::interpolate(&first, &second, 10, 1);
+        let end = Layer::interpolate(&first, &second, 20, 1);
+
+        assert_eq!(start.offset, first.offset);
+        assert_eq!(start.xy, first.xy);
+        assert_eq!(start.angle, first.angle);
+        assert_eq!(start.color, first.color);
+        assert_eq!(end.offset, second.offset);
+        assert_eq!(end.xy, second.xy);
+        assert_eq!(end.uv, second.uv);
+        assert_eq!(end.angle, second.angle);
+        assert_eq!(end.color, second.color);
+    }
+
+    #[test]
+    fn linear() {
+        let (first, second) = key_frames();
+        let frame = Layer::interpolate(&first, &second, 15, 1);
+
+        assert_eq!(frame.frame_index, 15);
+        assert_eq!(frame.offset, Vector2::new(4.0, -2.0));
+        assert_eq!(frame.xy, [8.0; 8]);
+        assert_eq!(frame.uv, [0.5; 8]);
+        assert_eq!(frame.angle, Rad(1.0));
+        assert_eq!(frame.color, Color::rgba(0.5, 0.25, 0.125, 1.0));
+    }
+
+    #[test]
+    fn same_key_frame_index() {
+        let (first, mut second) = key_frames();
+        second.frame_index = first.frame_index;
+
+        let frame = Layer::interpolate(&first, &second, 10, 1);
+
+        assert_eq!(frame.offset, first.offset);
+        assert_eq!(frame.xy, first.xy);
+        assert_eq!(frame.uv, first.uv);
+        assert_eq!(frame.angle, first.angle);
+        assert_eq!(frame.color, first.color);
+    }
+
+    #[test]
+    fn static_texture() {
+        let (mut first, mut second) = key_frames();
+        first.texture_index = 1.0;
+        second.texture_index = 3.0;
+
+        assert_eq!(Layer::interpolate(&first, &second, 15, 4).texture_index, 1.0);
+    }
+
+    #[test]
+    fn interpolated_texture() {
+        let (mut first, mut second) = key_frames();
+        first.animation_type = AnimationType::Interpolated;
+        first.texture_index = 1.0;
+        second.texture_index = 3.0;
+
+        assert_eq!(Layer::interpolate(&first, &second, 15, 4).texture_index, 2.0);
+        assert_eq!(Layer::animate_texture(&first, None, 5.0, 4), 1.0);
+    }
+
+    #[test]
+    fn play_once_texture() {
+        let (mut first, second) = key_frames();
+        first.animation_type = AnimationType::PlayOnce;
+        first.delay = 0.5;
+
+        assert_eq!(Layer::interpolate(&first, &second, 14, 4).texture_index, 2.0);
+        assert_eq!(Layer::interpolate(&first, &second, 19, 4).texture_index, 3.0);
+        assert_eq!(Layer::animate_texture(&first, None, 20.0, 4), 3.0);
+    }
+
+    #[test]
+    fn repeat_texture() {
+        let (mut first, second) = key_frames();
+        first.animation_type = AnimationType::Repeat;
+        first.texture_index = 2.0;
+        first.delay = 1.0;
+
+        assert_eq!(Layer::interpolate(&first, &second, 11, 4).texture_index, 3.0);
+        assert_eq!(Layer::interpolate(&first, &second, 12, 4).texture_index, 0.0);
+        assert_eq!(Layer::interpolate(&first, &second, 17, 4).texture_index, 1.0);
+    }
+
+    #[test]
+    fn repeat_reversed_texture() {
+        let (mut first, second) = key_frames();
+        first.animation_type = AnimationType::RepeatReversed;
+        first.texture_index = 1.0;
+        first.delay = 1.0;
+
+        assert_eq!(Layer::interpolate(&first, &second, 11, 4).texture_index, 0.0);
+        assert_eq!(Layer::interpolate(&first, &second, 12, 4).texture_index, 3.0);
+        assert_eq!(Layer::interpolate(&first, &second, 17, 4).texture_index, 2.0);
+    }
+}
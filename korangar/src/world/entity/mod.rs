@@ -3,22 +3,24 @@ use std::sync::Arc;
 
 use cgmath::{EuclideanSpace, Point3, Vector2, VectorSpace};
 use derive_new::new;
+use korangar_audio::AudioEngine;
 use korangar_interface::elements::PrototypeElement;
 use korangar_interface::windows::{PrototypeWindow, Window};
 use korangar_networking::EntityData;
 #[cfg(feature = "debug")]
 use korangar_util::texture_atlas::AtlasAllocation;
-use ragnarok_formats::map::TileFlags;
 use ragnarok_packets::{AccountId, CharacterInformation, ClientTick, EntityId, Sex, StatusType, WorldPosition};
 #[cfg(feature = "debug")]
 use wgpu::{BufferUsages, Device, Queue};
 
-use crate::graphics::{Camera, EntityInstruction};
+use crate::graphics::{Camera, Color, EntityInstruction};
 use crate::interface::application::InterfaceSettings;
 use crate::interface::layout::{ScreenPosition, ScreenSize};
 use crate::interface::theme::GameTheme;
 use crate::interface::windows::WindowCache;
-use crate::loaders::{ActionLoader, AnimationLoader, AnimationState, ScriptLoader, SpriteLoader};
+use crate::loaders::{
+    ActionLoader, AnimationLoader, AnimationState, EntityFiles, EntityPartFiles, GameFileLoader, ScriptLoader, SpriteLoader,
+};
 use crate::renderer::GameInterfaceRenderer;
 #[cfg(feature = "debug")]
 use crate::renderer::MarkerRenderer;
@@ -28,6 +30,9 @@ use crate::world::{AnimationData, Map};
 #[cfg(feature = "debug")]
 use crate::{Buffer, ModelVertex};
 
+/// Distance at which the sounds of animation events can no longer be heard.
+const SOUND_EVENT_RANGE: f32 = 100.0;
+
 pub enum ResourceState<T> {
     Available(T),
     Unavailable,
@@ -72,6 +77,9 @@ pub struct Common {
     pub movement_speed: usize,
     pub head_direction: usize,
     pub sex: Sex,
+    pub head: usize,
+    pub head_palette: usize,
+    pub body_palette: usize,
 
     #[hidden_element]
     pub entity_type: EntityType,
@@ -89,130 +97,130 @@ pub struct Common {
 #[allow(clippy::invisible_characters)]
 fn get_sprite_path_for_player_job(job_id: usize) -> &'static str {
     match job_id {
-        0 => "ÃÊº¸ÀÚ",             // NOVICE
-        1 => "°Ë»Ç",               // SWORDMAN
-        2 => "À§Àúµå",             // MAGICIAN
-        3 => "±Ã¼Ö",               // ARCHER
-        4 => "¼ºÁ÷ÀÚ",             // ACOLYTE
-        5 => "»ÓÀÎ",               // MERCHANT
-        6 => "µµµÏ",               // THIEF
-        7 => "±â»ç",               // KNIGHT
-        8 => "¼ºÅõ»ç",             // PRIEST
-        9 => "¸¶¹Ý»Ç",             // WIZARD
-        10 => "Á¦Ã¶°ø",            // BLACKSMITH
-        11 => "ÇåÅÍ",              // HUNTER
-        12 => "¾î¼¼½Å",            // ASSASSIN
-        13 => "¿£´ë¿î",            // CHICKEN
-        14 => "Å©·ç¼¼ÀÌ´õ",        // CRUSADER
-        15 => "¸ùÅ©",              // MONK
-        16 => "¼¼ÀÌÁö",            // SAGE
-        17 => "·Î±×",              // ROGUE
-        18 => "¿¬±Ý¼ú»ç",          // ALCHEMIST
-        19 => "¹Ùµå",              // BARD
-        20 => "¹«Èñ",              // DANCER
-        23 => "½´ÆÛ³ëºñ½º",        // SUPERNOVICE
-        24 => "°Ç³Ê",              // GUNSLINGER
-        25 => "´ÑÀÚ",              // NINJA
-        4001 => "ÃÊº¸ÀÚ",          // NOVICE_H
-        4002 => "°Ë»Ç",            // SWORDMAN_H
-        4003 => "À§Àúµå",          // MAGICIAN_H
-        4004 => "±Ã¼Ö",            // ARCHER_H
-        4005 => "¼ºÁ÷ÀÚ",          // ACOLYTE_H
-        4006 => "»ÓÀÎ",            // MERCHANT_H
-        4007 => "µµµÏ",            // THIEF_H
-        4008 => "·Îµå³ªÀÌÆ®",      // KNIGHT_H
-        4009 => "ÇÏÀÌÇÁ¸®",        // PRIEST_H
-        4010 => "ÇÏÀÌÀ§Àúµå",      // WIZARD_H
-        4011 => "È­ÀÌÆ®½º¹Ì½º",     // BLACKSMITH_H
-        4012 => "½º³ªÀÌÆÛ",        // HUNTER_H
-        4013 => "¾î½Ø½ÅÅ©·Î½º",    // ASSASSIN_H
-        4014 => "¿£´ë¿î",          // CHICKEN_H
-        4015 => "Å©·ç¼¼ÀÌ´õ",      // CRUSADER_H
-        4016 => "¸ùÅ©",            // MONK_H
-        4017 => "¼¼ÀÌÁö",          // SAGE_H
-        4018 => "·Î±×",            // ROGUE_H
-        4019 => "¿¬±Ý¼ú»ç",        // ALCHEMIST_H
-        4020 => "¹Ùµå",            // BARD_H
-        4021 => "¹«Èñ",            // DANCER_H
-        4023 => "½´ÆÛ³ëºñ½º",      // NOVICE_B
-        4024 => "°Ë»Ç",            // SWORDMAN_B
-        4025 => "À§Àúµå",          // MAGICIAN_B
-        4026 => "±Ã¼Ö",            // ARCHER_B
-        4027 => "¼ºÁ÷ÀÚ",          // ACOLYTE_B
-        4028 => "»ÓÀÎ",            // MERCHANT_B
-        4029 => "µµµÏ",            // THIEF_B
-        4030 => "±â»ç",            // KNIGHT_B
-        4031 => "¼ºÅõ»ç",          // PRIEST_B
-        4032 => "¸¶¹Ý»Ç",          // WIZARD_B
-        4033 => "Á¦Ã¶°ø",          // BLACKSMITH_B
-        4034 => "ÇåÅÍ",            // HUNTER_B
-        4035 => "¾î¼¼½Å",          // ASSASSIN_B
-        4037 => "Å©·ç¼¼ÀÌ´õ",      // CRUSADER_B
-        4038 => "¸ùÅ©",            // MONK_B
-        4039 => "¼¼ÀÌÁö",          // SAGE_B
-        4040 => "·Î±×",            // ROGUE_B
-        4041 => "¿¬±Ý¼ú»ç",        // ALCHEMIST_B
-        4042 => "¹Ùµå",            // BARD_B
-        4043 => "¹«Èñ",            // DANCER_B
-        4045 => "½´ÆÛ³ëºñ½º",      // SUPERNOVICE_B
-        4054 => "·é³ªÀÌÆ®",        // RUNE_KNIGHT
-        4055 => "¿ö·Ï",            // WARLOCK
-        4056 => "·¹ÀÎÁ®",          // RANGER
-        4057 => "¾ÆÅ©ºñ¼ó",        // ARCH_BISHOP
-        4058 => "¹ÌÄÉ´Ð",          // MECHANIC
-        4059 => "±æ·ÎÆ¾Å©·Î½º",    // GUILLOTINE_CROSS
-        4066 => "°¡µå",            // ROYAL_GUARD
-        4067 => "¼Ò¼­·¯",           // SORCERER
-        4068 => "¹Î½ºÆ®·²",        // MINSTREL
-        4069 => "¿ø´õ·¯",          // WANDERER
-        4070 => "½´¶ó",            // SURA
-        4071 => "Á¦³×¸¯",          // GENETIC
-        4072 => "½¦µµ¿ìÃ¼ÀÌ¼­",     // SHADOW_CHASER
-        4060 => "·é³ªÀÌÆ®",        // RUNE_KNIGHT_H
-        4061 => "¿ö·Ï",            // WARLOCK_H
-        4062 => "·¹ÀÎÁ®",          // RANGER_H
-        4063 => "¾ÆÅ©ºñ¼ó",        // ARCH_BISHOP_H
-        4064 => "¹ÌÄÉ´Ð",          // MECHANIC_H
-        4065 => "±æ·ÎÆ¾Å©·Î½º",    // GUILLOTINE_CROSS_H
-        4073 => "°¡µÅ",            // ROYAL_GUARD_H
-        4074 => "¼Ò¼­·¯",           // SORCERER_H
-        4075 => "¹Î½ºÆ®·²",        // MINSTREL_H
-        4076 => "¿ø´õ·¯",          // WANDERER_H
-        4077 => "½´¶ó",            // SURA_H
-        4078 => "Á¦³×¸¯",          // GENETIC_H
-        4079 => "½¦µµ¿ìÃ¼ÀÌ¼­",     // SHADOW_CHASER_H
-        4096 => "·é³ªÀÌÆ®",        // RUNE_KNIGHT_B
-        4097 => "¿ö·Ï",            // WARLOCK_B
-        4098 => "·¹ÀÎÁ®",          // RANGER_B
-        4099 => "¾ÆÅ©ºñ¼ó",        // ARCHBISHOP_B
-        4100 => "¹ÌÄÉ´Ð",          // MECHANIC_B
-        4101 => "±æ·ÎÆ¾Å©·Î½º",    // GUILLOTINE_CROSS_B
-        4102 => "°¡µå",            // ROYAL_GUARD_B
-        4103 => "¼Ò¼­·¯",           // SORCERER_B
-        4104 => "¹Î½ºÆ®·²",        // MINSTREL_B
-        4105 => "¿ø´õ·¯",          // WANDERER_B
-        4106 => "½´¶ó",            // SURA_B
-        4107 => "Á¦³×¸¯",          // GENETIC_B
-        4108 => "½¦µµ¿ìÃ¼ÀÌ¼­",     // SHADOW_CHASER_B
-        4046 => "ÅÂ±Ç¼Ò³â",        // TAEKWON
-        4047 => "±Ç¼º",            // STAR
-        4049 => "¼Ò¿ï¸µÄ¿",        // LINKER
-        4190 => "½´ÆÛ³ëºñ½º",      // SUPERNOVICE2
+        0 => "초보자",             // NOVICE
+        1 => "검사",               // SWORDMAN
+        2 => "위저드",             // MAGICIAN
+        3 => "궁수",               // ARCHER
+        4 => "성직자",             // ACOLYTE
+        5 => "상인",               // MERCHANT
+        6 => "도둑",               // THIEF
+        7 => "기사",               // KNIGHT
+        8 => "성투사",             // PRIEST
+        9 => "마법사",             // WIZARD
+        10 => "제철공",            // BLACKSMITH
+        11 => "헌터",              // HUNTER
+        12 => "어세신",            // ASSASSIN
+        13 => "엔대운",            // CHICKEN
+        14 => "크루세이더",        // CRUSADER
+        15 => "몽크",              // MONK
+        16 => "세이지",            // SAGE
+        17 => "로그",              // ROGUE
+        18 => "연금술사",          // ALCHEMIST
+        19 => "바드",              // BARD
+        20 => "무희",              // DANCER
+        23 => "슈퍼노비스",        // SUPERNOVICE
+        24 => "건너",              // GUNSLINGER
+        25 => "닌자",              // NINJA
+        4001 => "초보자",          // NOVICE_H
+        4002 => "검사",            // SWORDMAN_H
+        4003 => "위저드",          // MAGICIAN_H
+        4004 => "궁수",            // ARCHER_H
+        4005 => "성직자",          // ACOLYTE_H
+        4006 => "상인",            // MERCHANT_H
+        4007 => "도둑",            // THIEF_H
+        4008 => "로드나이트",      // KNIGHT_H
+        4009 => "하이프리",        // PRIEST_H
+        4010 => "하이위저드",      // WIZARD_H
+        4011 => "화이트스미스",    // BLACKSMITH_H
+        4012 => "스나이퍼",        // HUNTER_H
+        4013 => "어쌔신크로스",    // ASSASSIN_H
+        4014 => "엔대운",          // CHICKEN_H
+        4015 => "크루세이더",      // CRUSADER_H
+        4016 => "몽크",            // MONK_H
+        4017 => "세이지",          // SAGE_H
+        4018 => "로그",            // ROGUE_H
+        4019 => "연금술사",        // ALCHEMIST_H
+        4020 => "바드",            // BARD_H
+        4021 => "무희",            // DANCER_H
+        4023 => "슈퍼노비스",      // NOVICE_B
+        4024 => "검사",            // SWORDMAN_B
+        4025 => "위저드",          // MAGICIAN_B
+        4026 => "궁수",            // ARCHER_B
+        4027 => "성직자",          // ACOLYTE_B
+        4028 => "상인",            // MERCHANT_B
+        4029 => "도둑",            // THIEF_B
+        4030 => "기사",            // KNIGHT_B
+        4031 => "성투사",          // PRIEST_B
+        4032 => "마법사",          // WIZARD_B
+        4033 => "제철공",          // BLACKSMITH_B
+        4034 => "헌터",            // HUNTER_B
+        4035 => "어세신",          // ASSASSIN_B
+        4037 => "크루세이더",      // CRUSADER_B
+        4038 => "몽크",            // MONK_B
+        4039 => "세이지",          // SAGE_B
+        4040 => "로그",            // ROGUE_B
+        4041 => "연금술사",        // ALCHEMIST_B
+        4042 => "바드",            // BARD_B
+        4043 => "무희",            // DANCER_B
+        4045 => "슈퍼노비스",      // SUPERNOVICE_B
+        4054 => "룬나이트",        // RUNE_KNIGHT
+        4055 => "워록",            // WARLOCK
+        4056 => "레인져",          // RANGER
+        4057 => "아크비숍",        // ARCH_BISHOP
+        4058 => "미케닉",          // MECHANIC
+        4059 => "길로틴크로스",    // GUILLOTINE_CROSS
+        4066 => "가드",            // ROYAL_GUARD
+        4067 => "소서러",          // SORCERER
+        4068 => "민스트럴",        // MINSTREL
+        4069 => "원더러",          // WANDERER
+        4070 => "슈라",            // SURA
+        4071 => "제네릭",          // GENETIC
+        4072 => "쉐도우체이서",    // SHADOW_CHASER
+        4060 => "룬나이트",        // RUNE_KNIGHT_H
+        4061 => "워록",            // WARLOCK_H
+        4062 => "레인져",          // RANGER_H
+        4063 => "아크비숍",        // ARCH_BISHOP_H
+        4064 => "미케닉",          // MECHANIC_H
+        4065 => "길로틴크로스",    // GUILLOTINE_CROSS_H
+        4073 => "가드",            // ROYAL_GUARD_H
+        4074 => "소서러",          // SORCERER_H
+        4075 => "민스트럴",        // MINSTREL_H
+        4076 => "원더러",          // WANDERER_H
+        4077 => "슈라",            // SURA_H
+        4078 => "제네릭",          // GENETIC_H
+        4079 => "쉐도우체이서",    // SHADOW_CHASER_H
+        4096 => "룬나이트",        // RUNE_KNIGHT_B
+        4097 => "워록",            // WARLOCK_B
+        4098 => "레인져",          // RANGER_B
+        4099 => "아크비숍",        // ARCHBISHOP_B
+        4100 => "미케닉",          // MECHANIC_B
+        4101 => "길로틴크로스",    // GUILLOTINE_CROSS_B
+        4102 => "가드",            // ROYAL_GUARD_B
+        4103 => "소서러",          // SORCERER_B
+        4104 => "민스트럴",        // MINSTREL_B
+        4105 => "원더러",          // WANDERER_B
+        4106 => "슈라",            // SURA_B
+        4107 => "제네릭",          // GENETIC_B
+        4108 => "쉐도우체이서",    // SHADOW_CHASER_B
+        4046 => "태권소년",        // TAEKWON
+        4047 => "권성",            // STAR
+        4049 => "소울링커",        // LINKER
+        4190 => "슈퍼노비스",      // SUPERNOVICE2
         4211 => "KAGEROU",         // KAGEROU
         4212 => "OBORO",           // OBORO
         4215 => "REBELLION",       // REBELLION
-        4222 => "´ÑÀÚ",            // NINJA_B
+        4222 => "닌자",            // NINJA_B
         4223 => "KAGEROU",         // KAGEROU_B
         4224 => "OBORO",           // OBORO_B
-        4225 => "ÅÂ±Ç¼Ò³â",        // TAEKWON_B
-        4226 => "±Ç¼º",            // STAR_B
-        4227 => "¼Ò¿ï¸µÄ¿",        // LINKER_B
-        4228 => "°Ç³Ê",            // GUNSLINGER_B
+        4225 => "태권소년",        // TAEKWON_B
+        4226 => "권성",            // STAR_B
+        4227 => "소울링커",        // LINKER_B
+        4228 => "건너",            // GUNSLINGER_B
         4229 => "REBELLION",       // REBELLION_B
-        4239 => "¼ºÁ¦",            // STAR EMPEROR
-        4240 => "¼Ò¿ï¸®ÆÛ",        // SOUL REAPER
-        4241 => "¼ºÁ¦",            // STAR_EMPEROR_B
-        4242 => "¼Ò¿ï¸®ÆÛ",        // SOUL_REAPER_B
+        4239 => "성제",            // STAR EMPEROR
+        4240 => "소울리퍼",        // SOUL REAPER
+        4241 => "성제",            // STAR_EMPEROR_B
+        4242 => "소울리퍼",        // SOUL_REAPER_B
         4252 => "DRAGON_KNIGHT",   // DRAGON KNIGHT
         4253 => "MEISTER",         // MEISTER
         4254 => "SHADOW_CROSS",    // SHADOW CROSS
@@ -232,19 +240,27 @@ fn get_sprite_path_for_player_job(job_id: usize) -> &'static str {
         4305 => "SHIRANUI",        // SHIRANUI
         4306 => "NIGHT_WATCH",     // NIGHT WATCH
         4307 => "HYPER_NOVICE",    // HYPER NOVICE
-        _ => "ÃÊº¸ÀÚ",             // NOVICE
+        _ => "초보자",             // NOVICE
     }
 }
 
-fn get_entity_part_files(script_loader: &ScriptLoader, entity_type: EntityType, job_id: usize, sex: Sex) -> Vec<String> {
+fn get_entity_files(
+    script_loader: &ScriptLoader,
+    entity_type: EntityType,
+    job_id: usize,
+    sex: Sex,
+    head: usize,
+    head_palette: usize,
+    body_palette: usize,
+) -> EntityFiles {
     let sex_sprite_path = match sex == Sex::Female {
-        true => "¿©",
-        false => "³²",
+        true => "여",
+        false => "남",
     };
 
     fn player_body_path(sex_sprite_path: &str, job_id: usize) -> String {
         format!(
-            "ÀÎ°£Á·\\¸öÅë\\{}\\{}_{}",
+            "인간족\\몸통\\{}\\{}_{}",
             sex_sprite_path,
             get_sprite_path_for_player_job(job_id),
             sex_sprite_path
@@ -252,14 +268,49 @@ fn get_entity_part_files(script_loader: &ScriptLoader, entity_type: EntityType,
     }
 
     fn player_head_path(sex_sprite_path: &str, head_id: usize) -> String {
-        format!("ÀÎ°£Á·\\¸Ó¸®Åë\\{}\\{}_{}", sex_sprite_path, head_id, sex_sprite_path)
+        format!("인간족\\머리통\\{}\\{}_{}", sex_sprite_path, head_id, sex_sprite_path)
+    }
+
+    // A palette of 0 means that the palette of the sprite is used.
+    fn player_body_palette_path(sex_sprite_path: &str, job_id: usize, body_palette: usize) -> Option<String> {
+        (body_palette != 0).then(|| {
+            format!(
+                "몸\\{}_{}_{}.pal",
+                get_sprite_path_for_player_job(job_id),
+                sex_sprite_path,
+                body_palette
+            )
+        })
+    }
+
+    fn player_head_palette_path(sex_sprite_path: &str, head_id: usize, head_palette: usize) -> Option<String> {
+        (head_palette != 0).then(|| format!("머리\\머리{}_{}_{}.pal", head_id, sex_sprite_path, head_palette))
+    }
+
+    fn single_part(path: String) -> EntityFiles {
+        EntityFiles {
+            parts: vec![EntityPartFiles { path, palette_path: None }],
+            imf_path: None,
+        }
     }
 
     match entity_type {
-        EntityType::Player => vec![player_body_path(sex_sprite_path, job_id), player_head_path(sex_sprite_path, 32)],
-        EntityType::Npc => vec![format!("npc\\{}", script_loader.get_job_name_from_id(job_id))],
-        EntityType::Monster => vec![format!("¸ó½ºÅÍ\\{}", script_loader.get_job_name_from_id(job_id))],
-        EntityType::Warp | EntityType::Hidden => vec![format!("npc\\{}", script_loader.get_job_name_from_id(job_id))], // TODO: change
+        EntityType::Player => EntityFiles {
+            parts: vec![
+                EntityPartFiles {
+                    path: player_body_path(sex_sprite_path, job_id),
+                    palette_path: player_body_palette_path(sex_sprite_path, job_id, body_palette),
+                },
+                EntityPartFiles {
+                    path: player_head_path(sex_sprite_path, head),
+                    palette_path: player_head_palette_path(sex_sprite_path, head, head_palette),
+                },
+            ],
+            imf_path: Some(format!("{}_{}", get_sprite_path_for_player_job(job_id), sex_sprite_path)),
+        },
+        EntityType::Npc => single_part(format!("npc\\{}", script_loader.get_job_name_from_id(job_id))),
+        EntityType::Monster => single_part(format!("몬스터\\{}", script_loader.get_job_name_from_id(job_id))),
+        EntityType::Warp | EntityType::Hidden => single_part(format!("npc\\{}", script_loader.get_job_name_from_id(job_id))), /* TODO: change */
     }
 }
 
@@ -284,6 +335,9 @@ impl Common {
         let health_points = entity_data.health_points as usize;
         let maximum_health_points = entity_data.maximum_health_points as usize;
         let sex = entity_data.sex;
+        let head = entity_data.head as usize;
+        let head_palette = entity_data.head_palette as usize;
+        let body_palette = entity_data.body_palette as usize;
 
         let active_movement = None;
 
@@ -297,9 +351,9 @@ impl Common {
             _ => EntityType::Npc,
         };
 
-        let entity_part_files = get_entity_part_files(script_loader, entity_type, job_id, sex);
+        let entity_files = get_entity_files(script_loader, entity_type, job_id, sex, head, head_palette, body_palette);
         let animation_data = animation_loader
-            .get(sprite_loader, action_loader, entity_type, &entity_part_files)
+            .get(sprite_loader, action_loader, entity_type, &entity_files)
             .unwrap();
         let details = ResourceState::Unavailable;
         let animation_state = AnimationState::new(client_tick);
@@ -311,6 +365,9 @@ impl Common {
             job_id,
             head_direction,
             sex,
+            head,
+            head_palette,
+            body_palette,
             active_movement,
             entity_type,
             movement_speed,
@@ -337,9 +394,17 @@ impl Common {
         script_loader: &ScriptLoader,
         animation_loader: &mut AnimationLoader,
     ) {
-        let entity_part_files = get_entity_part_files(script_loader, self.entity_type, self.job_id, self.sex);
+        let entity_files = get_entity_files(
+            script_loader,
+            self.entity_type,
+            self.job_id,
+            self.sex,
+            self.head,
+            self.head_palette,
+            self.body_palette,
+        );
         self.animation_data = animation_loader
-            .get(sprite_loader, action_loader, self.entity_type, &entity_part_files)
+            .get(sprite_loader, action_loader, self.entity_type, &entity_files)
             .unwrap();
     }
 
@@ -350,7 +415,14 @@ impl Common {
         self.animation_state.idle(client_tick);
     }
 
-    pub fn update(&mut self, map: &Map, _delta_time: f32, client_tick: ClientTick) {
+    pub fn update(
+        &mut self,
+        audio_engine: &AudioEngine<GameFileLoader>,
+        map: &Map,
+        camera: &dyn Camera,
+        _delta_time: f32,
+        client_tick: ClientTick,
+    ) {
         if let Some(active_movement) = self.active_movement.take() {
             let last_step = active_movement.steps.last().unwrap();
 
@@ -399,6 +471,19 @@ impl Common {
         }
 
         self.animation_state.update(client_tick);
+
+        // Events that are not sounds (like the "atk" marker of attack animations) are
+        // not handled yet.
+        let sound_events = self
+            .animation_data
+            .frame_events(&self.animation_state, camera.camera_direction(), self.head_direction)
+            .into_iter()
+            .filter(|event| event.to_lowercase().ends_with(".wav"));
+
+        for sound_event in sound_events {
+            let sound_effect_key = audio_engine.load(sound_event);
+            audio_engine.play_spatial_sound_effect(sound_effect_key, self.position, SOUND_EVENT_RANGE);
+        }
     }
 
     pub fn move_from_to(&mut self, map: &Map, from: Vector2<usize>, to: Vector2<usize>, starting_timestamp: ClientTick) {
@@ -430,39 +515,39 @@ impl Common {
 
                 if map.x_in_bounds(x + 1)
                     && map.y_in_bounds(y + 1)
-                    && map.get_tile(Vector2::new(x + 1, y)).flags.contains(TileFlags::WALKABLE)
-                    && map.get_tile(Vector2::new(x, y + 1)).flags.contains(TileFlags::WALKABLE)
+                    && map.get_tile(Vector2::new(x + 1, y)).tile_type.is_walkable()
+                    && map.get_tile(Vector2::new(x, y + 1)).tile_type.is_walkable()
                 {
                     successors.push(Pos(x + 1, y + 1));
                 }
 
                 if x > 0
                     && map.y_in_bounds(y + 1)
-                    && map.get_tile(Vector2::new(x - 1, y)).flags.contains(TileFlags::WALKABLE)
-                    && map.get_tile(Vector2::new(x, y + 1)).flags.contains(TileFlags::WALKABLE)
+                    && map.get_tile(Vector2::new(x - 1, y)).tile_type.is_walkable()
+                    && map.get_tile(Vector2::new(x, y + 1)).tile_type.is_walkable()
                 {
                     successors.push(Pos(x - 1, y + 1));
                 }
 
                 if map.x_in_bounds(x + 1)
                     && y > 0
-                    && map.get_tile(Vector2::new(x + 1, y)).flags.contains(TileFlags::WALKABLE)
-                    && map.get_tile(Vector2::new(x, y - 1)).flags.contains(TileFlags::WALKABLE)
+                    && map.get_tile(Vector2::new(x + 1, y)).tile_type.is_walkable()
+                    && map.get_tile(Vector2::new(x, y - 1)).tile_type.is_walkable()
                 {
                     successors.push(Pos(x + 1, y - 1));
                 }
 
                 if x > 0
                     && y > 0
-                    && map.get_tile(Vector2::new(x - 1, y)).flags.contains(TileFlags::WALKABLE)
-                    && map.get_tile(Vector2::new(x, y - 1)).flags.contains(TileFlags::WALKABLE)
+                    && map.get_tile(Vector2::new(x - 1, y)).tile_type.is_walkable()
+                    && map.get_tile(Vector2::new(x, y - 1)).tile_type.is_walkable()
                 {
                     successors.push(Pos(x - 1, y - 1));
                 }
 
                 let successors = successors
                     .drain(..)
-                    .filter(|Pos(x, y)| map.get_tile(Vector2::new(*x, *y)).flags.contains(TileFlags::WALKABLE))
+                    .filter(|Pos(x, y)| map.get_tile(Vector2::new(*x, *y)).tile_type.is_walkable())
                     .collect::<Vec<Pos>>();
 
                 successors
@@ -630,7 +715,7 @@ impl Common {
 
     #[cfg(feature = "debug")]
     pub fn generate_pathing_mesh(&mut self, device: &Device, queue: &Queue, map: &Map, pathing_mapping: &[AtlasAllocation]) {
-        use crate::{Color, NativeModelVertex, MAP_TILE_SIZE};
+        use crate::{NativeModelVertex, MAP_TILE_SIZE};
 
         const HALF_TILE_SIZE: f32 = MAP_TILE_SIZE / 2.0;
         const PATHING_MESH_OFFSET: f32 = 0.95;
@@ -724,7 +809,7 @@ impl Common {
             ));
         }
 
-        let pathing_vertices = NativeModelVertex::to_vertices(native_pathing_vertices, pathing_mapping);
+        let pathing_vertices = NativeModelVertex::to_vertices(native_pathing_vertices, pathing_mapping, None);
 
         if let Some(steps_vertex_buffer) = &active_movement.pathing_vertex_buffer {
             steps_vertex_buffer.write_exact(queue, pathing_vertices.as_slice());
@@ -935,6 +1020,26 @@ impl Npc {
             return;
         }
 
+        self.render_health_bar(renderer, camera, theme, window_size, theme.status_bar.enemy_health_color.get());
+    }
+
+    /// Health bar of another player that is in the same party.
+    pub fn render_party_status(&self, renderer: &GameInterfaceRenderer, camera: &dyn Camera, theme: &GameTheme, window_size: ScreenSize) {
+        if self.common.entity_type != EntityType::Player {
+            return;
+        }
+
+        self.render_health_bar(renderer, camera, theme, window_size, theme.status_bar.party_health_color.get());
+    }
+
+    fn render_health_bar(
+        &self,
+        renderer: &GameInterfaceRenderer,
+        camera: &dyn Camera,
+        theme: &GameTheme,
+        window_size: ScreenSize,
+        health_color: Color,
+    ) {
         let (view_matrix, projection_matrix) = camera.view_projection_matrices();
         let clip_space_position = (projection_matrix * view_matrix) * self.common.position.to_homogeneous();
         let screen_position = camera.clip_to_screen_space(clip_space_position);
@@ -960,7 +1065,7 @@ impl Npc {
                 width: bar_width,
                 height: theme.status_bar.enemy_health_height.get(),
             },
-            theme.status_bar.enemy_health_color.get(),
+            health_color,
             self.common.maximum_health_points as f32,
             self.common.health_points as f32,
         );
@@ -1057,8 +1162,15 @@ impl Entity {
         common.maximum_health_points = maximum_health_points;
     }
 
-    pub fn update(&mut self, map: &Map, delta_time: f32, client_tick: ClientTick) {
-        self.get_common_mut().update(map, delta_time, client_tick);
+    pub fn update(
+        &mut self,
+        audio_engine: &AudioEngine<GameFileLoader>,
+        map: &Map,
+        camera: &dyn Camera,
+        delta_time: f32,
+        client_tick: ClientTick,
+    ) {
+        self.get_common_mut().update(audio_engine, map, camera, delta_time, client_tick);
     }
 
     pub fn move_from_to(&mut self, map: &Map, from: Vector2<usize>, to: Vector2<usize>, starting_timestamp: ClientTick) {
@@ -1100,6 +1212,12 @@ impl Entity {
             Self::Npc(npc) => npc.render_status(renderer, camera, theme, window_size),
         }
     }
+
+    pub fn render_party_status(&self, renderer: &GameInterfaceRenderer, camera: &dyn Camera, theme: &GameTheme, window_size: ScreenSize) {
+        if let Self::Npc(npc) = self {
+            npc.render_party_status(renderer, camera, theme, window_size);
+        }
+    }
 }
 
 impl PrototypeWindow<InterfaceSettings> for Entity {
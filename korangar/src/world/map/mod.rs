@@ -2,7 +2,7 @@
 use std::collections::HashSet;
 use std::sync::Arc;
 
-use cgmath::{Array, Matrix4, Point3, SquareMatrix, Vector2, Vector3};
+use cgmath::{Array, Matrix4, Point3, SquareMatrix, Vector2, Vector3, Zero};
 use derive_new::new;
 use korangar_audio::AudioEngine;
 #[cfg(feature = "debug")]
@@ -16,12 +16,12 @@ use option_ext::OptionExt;
 use ragnarok_formats::map::EffectSource;
 #[cfg(feature = "debug")]
 use ragnarok_formats::map::MapData;
-use ragnarok_formats::map::{LightSettings, LightSource, SoundSource, Tile, TileFlags, WaterSettings};
+use ragnarok_formats::map::{LightSettings, LightSource, SoundSource, Tile, WaterSettings};
 #[cfg(feature = "debug")]
 use ragnarok_formats::transform::Transform;
 use ragnarok_packets::ClientTick;
 
-use super::{Entity, Object, PointLightId, PointLightManager, ResourceSet, ResourceSetBuffer};
+use super::{Effect, EffectHolder, Entity, MapEffect, Object, PointLightId, PointLightManager, ResourceSet, ResourceSetBuffer};
 #[cfg(feature = "debug")]
 use super::{LightSourceExt, Model, PointLightSet};
 #[cfg(feature = "debug")]
@@ -125,6 +125,7 @@ pub struct Map {
     objects: SimpleSlab<ObjectKey, Object>,
     light_sources: SimpleSlab<LightSourceKey, LightSource>,
     sound_sources: Vec<SoundSource>,
+    effects: Vec<(Arc<Effect>, Point3<f32>)>,
     #[cfg(feature = "debug")]
     effect_sources: Vec<EffectSource>,
     tile_picker_vertex_buffer: Buffer<TileVertex>,
@@ -195,6 +196,12 @@ impl Map {
         audio_engine.prepare_ambient_sound_world();
     }
 
+    pub fn set_effects(&self, effect_holder: &mut EffectHolder) {
+        for (effect, position) in self.effects.iter() {
+            effect_holder.add_effect(Box::new(MapEffect::new(effect.clone(), *position)));
+        }
+    }
+
     // We want to make sure that the object set also captures the lifetime of the
     // map, so we never have a stale object set.
     #[cfg_attr(feature = "debug", korangar_debug::profile)]
@@ -255,6 +262,7 @@ impl Map {
             model_matrix: Matrix4::identity(),
             vertex_offset: self.ground_vertex_offset,
             vertex_count: self.ground_vertex_count,
+            texture_offset: Vector2::zero(),
         });
     }
 
@@ -317,7 +325,7 @@ impl Map {
 
         let tile = self.get_tile(position);
 
-        if tile.flags.contains(TileFlags::WALKABLE) {
+        if tile.tile_type.is_walkable() {
             let base_x = position.x as f32 * HALF_TILE_SIZE;
             let base_y = position.y as f32 * HALF_TILE_SIZE;
 
@@ -417,6 +425,7 @@ impl Map {
             model_matrix: Matrix4::identity(),
             vertex_offset: 0,
             vertex_count,
+            texture_offset: Vector2::zero(),
         });
 
         model_batches.push(ModelBatch {
@@ -445,6 +454,7 @@ impl Map {
                     model_matrix: Matrix4::identity(),
                     vertex_offset: 0,
                     vertex_count,
+                    texture_offset: Vector2::zero(),
                 });
 
                 model_batches.push(ModelBatch {
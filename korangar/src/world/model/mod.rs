@@ -9,9 +9,8 @@ use korangar_util::collision::AABB;
 #[cfg(feature = "debug")]
 use ragnarok_formats::model::ModelData;
 use ragnarok_formats::transform::Transform;
-use ragnarok_packets::ClientTick;
 
-pub use self::node::Node;
+pub use self::node::{Node, TextureAnimation};
 #[cfg(feature = "debug")]
 use crate::graphics::Color;
 #[cfg(feature = "debug")]
@@ -20,15 +19,19 @@ use crate::graphics::ModelInstruction;
 
 #[derive(PrototypeElement, new)]
 pub struct Model {
-    pub root_node: Node,
+    pub root_nodes: Vec<Node>,
     pub bounding_box: AABB,
+    /// Length of the animation in milliseconds.
+    pub animation_length: u32,
     #[cfg(feature = "debug")]
     pub model_data: ModelData,
 }
 
 impl Model {
-    pub fn render_geometry(&self, instructions: &mut Vec<ModelInstruction>, transform: &Transform, client_tick: ClientTick) {
-        self.root_node.render_geometry(instructions, transform, client_tick);
+    pub fn render_geometry(&self, instructions: &mut Vec<ModelInstruction>, transform: &Transform, animation_time: u32) {
+        self.root_nodes
+            .iter()
+            .for_each(|root_node| root_node.render_geometry(instructions, transform, animation_time));
     }
 
     #[cfg(feature = "debug")]
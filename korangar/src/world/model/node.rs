@@ -1,50 +1,122 @@
-use cgmath::{EuclideanSpace, Matrix4, SquareMatrix};
+use cgmath::{EuclideanSpace, Matrix4, SquareMatrix, Vector2, VectorSpace, Zero};
 use derive_new::new;
 use korangar_interface::elements::PrototypeElement;
-use ragnarok_formats::model::RotationKeyframeData;
+use ragnarok_formats::model::{PositionKeyframeData, RotationKeyframeData, ScaleKeyframeData, TextureKeyframeData};
 use ragnarok_formats::transform::Transform;
-use ragnarok_packets::ClientTick;
 
 use crate::graphics::ModelInstruction;
 
+/// Vertices of a node that use a texture with an animated offset. They are
+/// stored after the other vertices of the node.
+#[derive(PrototypeElement, new)]
+pub struct TextureAnimation {
+    pub vertex_offset: usize,
+    pub vertex_count: usize,
+    /// Offset of the texture along the U axis, relative to its size.
+    pub u_keyframes: Vec<TextureKeyframeData>,
+    /// Offset of the texture along the V axis, relative to its size.
+    pub v_keyframes: Vec<TextureKeyframeData>,
+}
+
+impl TextureAnimation {
+    fn texture_offset(&self, animation_time: u32) -> Vector2<f32> {
+        let interpolate = |keyframes: &[TextureKeyframeData]| match keyframes.is_empty() {
+            true => 0.0,
+            false => {
+                let (last_step, next_step, animation_elapsed) = surrounding_keyframes(keyframes, |keyframe| keyframe.frame, animation_time);
+                last_step.offset + (next_step.offset - last_step.offset) * animation_elapsed
+            }
+        };
+
+        Vector2::new(interpolate(&self.u_keyframes), interpolate(&self.v_keyframes))
+    }
+}
+
 #[derive(PrototypeElement, new)]
 pub struct Node {
     #[hidden_element]
     pub transform_matrix: Matrix4<f32>,
+    /// Vertices of the node that don't use an animated texture.
     pub vertex_offset: usize,
     pub vertex_count: usize,
     pub child_nodes: Vec<Node>,
+    pub scale_keyframes: Vec<ScaleKeyframeData>,
     pub rotation_keyframes: Vec<RotationKeyframeData>,
+    /// The positions are offsets from the resting position of the node.
+    pub position_keyframes: Vec<PositionKeyframeData>,
+    pub texture_animations: Vec<TextureAnimation>,
 }
 
-impl Node {
-    fn animaton_matrix(&self, client_tick: ClientTick) -> Matrix4<f32> {
-        let last_step = self.rotation_keyframes.last().unwrap();
-        let animation_tick = client_tick.0 % last_step.frame;
+/// Get the keyframes before and after the animation time and how far the
+/// animation progressed between them. The animation loops after the last
+/// keyframe.
+fn surrounding_keyframes<T>(keyframes: &[T], frame: impl Fn(&T) -> u32, animation_time: u32) -> (&T, &T, f32) {
+    let last_frame = frame(keyframes.last().unwrap());
 
-        let mut last_keyframe_index = 0;
-        while self.rotation_keyframes[last_keyframe_index + 1].frame < animation_tick {
-            last_keyframe_index += 1;
-        }
-
-        let last_step = &self.rotation_keyframes[last_keyframe_index];
-        let next_step = &self.rotation_keyframes[(last_keyframe_index + 1) % self.rotation_keyframes.len()];
+    if last_frame == 0 {
+        return (&keyframes[0], &keyframes[0], 0.0);
+    }
 
-        let total = next_step.frame - last_step.frame;
-        let offset = animation_tick - last_step.frame;
+    let animation_time = animation_time % last_frame;
 
-        let animation_elapsed = (1.0 / total as f32) * offset as f32;
-        let current_rotation = last_step.quaternions.nlerp(next_step.quaternions, animation_elapsed);
+    // Since the animation time is smaller than the last frame, there is always a
+    // next keyframe.
+    let next_index = keyframes.iter().position(|keyframe| frame(keyframe) > animation_time).unwrap();
 
-        current_rotation.into()
+    if next_index == 0 {
+        return (&keyframes[0], &keyframes[0], 0.0);
     }
 
-    pub fn world_matrix(&self, transform: &Transform, client_tick: ClientTick) -> Matrix4<f32> {
-        let animation_rotation_matrix = match self.rotation_keyframes.is_empty() {
+    let last_step = &keyframes[next_index - 1];
+    let next_step = &keyframes[next_index];
+
+    let total = frame(next_step) - frame(last_step);
+    let offset = animation_time - frame(last_step);
+
+    (last_step, next_step, offset as f32 / total as f32)
+}
+
+impl Node {
+    fn animation_matrix(&self, animation_time: u32) -> Matrix4<f32> {
+        let translation_matrix = match self.position_keyframes.is_empty() {
             true => Matrix4::identity(),
-            false => self.animaton_matrix(client_tick),
+            false => {
+                let (last_step, next_step, animation_elapsed) =
+                    surrounding_keyframes(&self.position_keyframes, |keyframe| keyframe.frame, animation_time);
+                let current_position = last_step.position.to_vec().lerp(next_step.position.to_vec(), animation_elapsed);
+
+                Matrix4::from_translation(current_position)
+            }
         };
 
+        let rotation_matrix = match self.rotation_keyframes.is_empty() {
+            true => Matrix4::identity(),
+            false => {
+                let (last_step, next_step, animation_elapsed) =
+                    surrounding_keyframes(&self.rotation_keyframes, |keyframe| keyframe.frame, animation_time);
+                let current_rotation = last_step.quaternions.nlerp(next_step.quaternions, animation_elapsed);
+
+                current_rotation.into()
+            }
+        };
+
+        let scale_matrix = match self.scale_keyframes.is_empty() {
+            true => Matrix4::identity(),
+            false => {
+                let (last_step, next_step, animation_elapsed) =
+                    surrounding_keyframes(&self.scale_keyframes, |keyframe| keyframe.frame, animation_time);
+                let current_scale = last_step.scale.lerp(next_step.scale, animation_elapsed);
+
+                Matrix4::from_nonuniform_scale(current_scale.x, current_scale.y, current_scale.z)
+            }
+        };
+
+        translation_matrix * rotation_matrix * scale_matrix
+    }
+
+    /// The animation time is the time in milliseconds since the animation of
+    /// the object started, already scaled by its animation speed.
+    pub fn world_matrix(&self, transform: &Transform, animation_time: u32) -> Matrix4<f32> {
         let rotation_matrix = Matrix4::from_angle_z(-transform.rotation.z)
             * Matrix4::from_angle_x(-transform.rotation.x)
             * Matrix4::from_angle_y(transform.rotation.y);
@@ -53,18 +125,197 @@ impl Node {
             * rotation_matrix
             * Matrix4::from_nonuniform_scale(transform.scale.x, -transform.scale.y, transform.scale.z)
             * self.transform_matrix
-            * animation_rotation_matrix
+            * self.animation_matrix(animation_time)
     }
 
-    pub fn render_geometry(&self, instructions: &mut Vec<ModelInstruction>, transform: &Transform, client_tick: ClientTick) {
+    pub fn render_geometry(&self, instructions: &mut Vec<ModelInstruction>, transform: &Transform, animation_time: u32) {
+        let model_matrix = self.world_matrix(transform, animation_time);
+
         instructions.push(ModelInstruction {
-            model_matrix: self.world_matrix(transform, client_tick),
+            model_matrix,
             vertex_offset: self.vertex_offset,
             vertex_count: self.vertex_count,
+            texture_offset: Vector2::zero(),
         });
 
+        instructions.extend(self.texture_animations.iter().map(|texture_animation| ModelInstruction {
+            model_matrix,
+            vertex_offset: texture_animation.vertex_offset,
+            vertex_count: texture_animation.vertex_count,
+            texture_offset: texture_animation.texture_offset(animation_time),
+        }));
+
         self.child_nodes
             .iter()
-            .for_each(|node| node.render_geometry(instructions, transform, client_tick));
+            .for_each(|node| node.render_geometry(instructions, transform, animation_time));
+    }
+}
+
+#[cfg(test)]
+mod interpolation {
+    use cgmath::{InnerSpace, Matrix4, Point3, Quaternion, Rad, Rotation3, SquareMatrix, Vector2, Vector3, Vector4};
+    use ragnarok_formats::model::{PositionKeyframeData, RotationKeyframeData, ScaleKeyframeData, TextureKeyframeData};
+
+    use super::{surrounding_keyframes, Node, TextureAnimation};
+
+    fn position_keyframe(frame: u32, x: f32) -> PositionKeyframeData {
+        PositionKeyframeData {
+            frame,
+            position: Point3::new(x, 0.0, 0.0),
+            data: 0,
+        }
+    }
+
+    fn scale_keyframe(frame: u32, scale: f32) -> ScaleKeyframeData {
+        ScaleKeyframeData {
+            frame,
+            scale: Vector3::new(scale, scale, scale),
+            data: 0.0,
+        }
+    }
+
+    fn texture_keyframe(frame: u32, offset: f32) -> TextureKeyframeData {
+        TextureKeyframeData { frame, offset }
+    }
+
+    fn node(rotation_keyframes: Vec<RotationKeyframeData>, position_keyframes: Vec<PositionKeyframeData>) -> Node {
+        Node::new(
+            Matrix4::identity(),
+            0,
+            0,
+            Vec::new(),
+            Vec::new(),
+            rotation_keyframes,
+            position_keyframes,
+            Vec::new(),
+        )
+    }
+
+    fn scaled_node(scale_keyframes: Vec<ScaleKeyframeData>, position_keyframes: Vec<PositionKeyframeData>) -> Node {
+        Node::new(
+            Matrix4::identity(),
+            0,
+            0,
+            Vec::new(),
+            scale_keyframes,
+            Vec::new(),
+            position_keyframes,
+            Vec::new(),
+        )
+    }
+
+    fn assert_translation(matrix: Matrix4<f32>, expected: Vector3<f32>) {
+        let translation = matrix.w.truncate();
+        assert!((translation - expected).magnitude2() < 1e-6, "{translation:?} != {expected:?}");
+    }
+
+    #[test]
+    fn surrounding() {
+        let keyframes = [0u32, 100, 300];
+
+        assert_eq!(surrounding_keyframes(&keyframes, |frame| *frame, 0), (&0, &100, 0.0));
+        assert_eq!(surrounding_keyframes(&keyframes, |frame| *frame, 50), (&0, &100, 0.5));
+        assert_eq!(surrounding_keyframes(&keyframes, |frame| *frame, 200), (&100, &300, 0.5));
+    }
+
+    #[test]
+    fn surrounding_loops() {
+        let keyframes = [0u32, 100, 300];
+
+        assert_eq!(surrounding_keyframes(&keyframes, |frame| *frame, 300), (&0, &100, 0.0));
+        assert_eq!(surrounding_keyframes(&keyframes, |frame| *frame, 350), (&0, &100, 0.5));
+    }
+
+    #[test]
+    fn surrounding_before_first() {
+        let keyframes = [100u32, 200];
+
+        assert_eq!(surrounding_keyframes(&keyframes, |frame| *frame, 50), (&100, &100, 0.0));
+        assert_eq!(surrounding_keyframes(&keyframes, |frame| *frame, 150), (&100, &200, 0.5));
+    }
+
+    #[test]
+    fn surrounding_single() {
+        assert_eq!(surrounding_keyframes(&[0u32], |frame| *frame, 50), (&0, &0, 0.0));
+        assert_eq!(surrounding_keyframes(&[100u32], |frame| *frame, 50), (&100, &100, 0.0));
+    }
+
+    #[test]
+    fn static_node() {
+        assert_eq!(node(Vec::new(), Vec::new()).animation_matrix(1234), Matrix4::identity());
+    }
+
+    #[test]
+    fn position() {
+        let node = node(Vec::new(), vec![position_keyframe(0, 0.0), position_keyframe(1000, 10.0)]);
+
+        assert_translation(node.animation_matrix(0), Vector3::new(0.0, 0.0, 0.0));
+        assert_translation(node.animation_matrix(250), Vector3::new(2.5, 0.0, 0.0));
+        assert_translation(node.animation_matrix(1250), Vector3::new(2.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn position_and_rotation() {
+        let rotation_keyframes = vec![
+            RotationKeyframeData {
+                frame: 0,
+                quaternions: Quaternion::from_angle_y(Rad(0.0)),
+            },
+            RotationKeyframeData {
+                frame: 1000,
+                quaternions: Quaternion::from_angle_y(Rad(std::f32::consts::PI)),
+            },
+        ];
+        let node = node(rotation_keyframes, vec![
+            position_keyframe(0, 0.0),
+            position_keyframe(1000, 10.0),
+        ]);
+        let matrix = node.animation_matrix(500);
+
+        // The translation is applied after the rotation, so it is not rotated itself.
+        assert_translation(matrix, Vector3::new(5.0, 0.0, 0.0));
+
+        let rotated = matrix * Vector4::new(1.0, 0.0, 0.0, 0.0);
+        assert!(rotated.x.abs() < 1e-5 && (rotated.z.abs() - 1.0).abs() < 1e-5, "{rotated:?}");
+    }
+
+    #[test]
+    fn scale() {
+        let node = scaled_node(vec![scale_keyframe(0, 1.0), scale_keyframe(1000, 3.0)], Vec::new());
+
+        assert_eq!(node.animation_matrix(0), Matrix4::identity());
+        assert_eq!(node.animation_matrix(500), Matrix4::from_scale(2.0));
+        assert_eq!(node.animation_matrix(1500), Matrix4::from_scale(2.0));
+    }
+
+    #[test]
+    fn position_and_scale() {
+        let node = scaled_node(vec![scale_keyframe(0, 2.0), scale_keyframe(1000, 2.0)], vec![
+            position_keyframe(0, 4.0),
+            position_keyframe(1000, 4.0),
+        ]);
+        let matrix = node.animation_matrix(500);
+
+        // The translation is applied after the scale, so it is not scaled itself.
+        assert_translation(matrix, Vector3::new(4.0, 0.0, 0.0));
+        assert_eq!(matrix * Vector4::new(1.0, 0.0, 0.0, 0.0), Vector4::new(2.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn texture_offset() {
+        let texture_animation = TextureAnimation::new(0, 3, vec![texture_keyframe(0, 0.0), texture_keyframe(1000, 1.0)], vec![
+            texture_keyframe(0, 0.5),
+        ]);
+
+        assert_eq!(texture_animation.texture_offset(0), Vector2::new(0.0, 0.5));
+        assert_eq!(texture_animation.texture_offset(250), Vector2::new(0.25, 0.5));
+        assert_eq!(texture_animation.texture_offset(1250), Vector2::new(0.25, 0.5));
+    }
+
+    #[test]
+    fn static_texture() {
+        let texture_animation = TextureAnimation::new(0, 3, Vec::new(), Vec::new());
+
+        assert_eq!(texture_animation.texture_offset(1234), Vector2::new(0.0, 0.0));
     }
 }
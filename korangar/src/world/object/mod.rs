@@ -20,17 +20,51 @@ use crate::renderer::MarkerRenderer;
 #[cfg(feature = "debug")]
 use crate::Camera;
 
+/// How the keyframes of the model of an object are played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectAnimationType {
+    /// The model stays in the pose of its first keyframes.
+    Static,
+    /// The keyframes are played in a loop.
+    Loop,
+}
+
 #[derive(PrototypeElement, PrototypeWindow, new)]
 pub struct Object {
     pub name: Option<String>,
     pub model_name: String,
     pub model: Arc<Model>,
     pub transform: Transform,
+    #[hidden_element]
+    pub animation_type: ObjectAnimationType,
+    pub animation_speed: f32,
+}
+
+/// Time in milliseconds into an animation of the given length that loops
+/// since the client started, scaled by the animation speed. Animations
+/// without a length wrap around like a `u32` instead.
+fn looped_animation_time(client_tick: ClientTick, animation_speed: f32, animation_length: u32) -> u32 {
+    let animation_length = match animation_length {
+        0 => u32::MAX as f64 + 1.0,
+        animation_length => animation_length as f64,
+    };
+
+    (client_tick.0 as f64 * animation_speed as f64).rem_euclid(animation_length) as u32
 }
 
 impl Object {
+    /// Time in milliseconds that the model of the object has been animated
+    /// for.
+    fn animation_time(&self, client_tick: ClientTick) -> u32 {
+        match self.animation_type {
+            ObjectAnimationType::Static => 0,
+            ObjectAnimationType::Loop => looped_animation_time(client_tick, self.animation_speed, self.model.animation_length),
+        }
+    }
+
     pub fn render_geometry(&self, instructions: &mut Vec<ModelInstruction>, client_tick: ClientTick) {
-        self.model.render_geometry(instructions, &self.transform, client_tick);
+        self.model
+            .render_geometry(instructions, &self.transform, self.animation_time(client_tick));
     }
 
     pub fn get_bounding_box_matrix(&self) -> Matrix4<f32> {
@@ -53,3 +87,34 @@ impl Object {
         renderer.render_marker(camera, marker_identifier, self.transform.position, hovered);
     }
 }
+
+#[cfg(test)]
+mod animation {
+    use ragnarok_packets::ClientTick;
+
+    use super::looped_animation_time;
+
+    #[test]
+    fn loops_at_the_animation_length() {
+        assert_eq!(looped_animation_time(ClientTick(2500), 1.0, 1000), 500);
+        assert_eq!(looped_animation_time(ClientTick(2500), 2.0, 1000), 0);
+        assert_eq!(looped_animation_time(ClientTick(2500), 0.5, 1000), 250);
+    }
+
+    #[test]
+    fn keeps_looping_past_u32_max() {
+        let client_tick = ClientTick(u32::MAX);
+        let expected = ((u32::MAX as u64 * 4) % 3000) as u32;
+
+        assert_eq!(looped_animation_time(client_tick, 4.0, 3000), expected);
+        assert_ne!(
+            looped_animation_time(client_tick, 4.0, 3000),
+            looped_animation_time(client_tick, 8.0, 3000)
+        );
+    }
+
+    #[test]
+    fn wraps_without_animation_length() {
+        assert_eq!(looped_animation_time(ClientTick(u32::MAX), 2.0, 0), u32::MAX - 1);
+    }
+}
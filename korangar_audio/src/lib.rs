@@ -47,11 +47,21 @@ struct BackgroundMusicTrack {
     handle: StreamingSoundHandle<FromFileError>,
 }
 
+/// Where a sound effect is played once it finished loading.
+enum PlaybackDestination {
+    /// Played without any spatialization.
+    SoundEffect,
+    /// Played by the emitter of an ambient sound.
+    Ambient(AmbientKey),
+    /// Played by a short-lived emitter at the given position.
+    Spatial { position: Point3<f32>, range: f32 },
+}
+
 struct QueuedSoundEffect {
     /// The key of the sound that should be played.
     sound_effect_key: SoundEffectKey,
-    /// The optional key to the ambient sound emitter.
-    ambient: Option<AmbientKey>,
+    /// Where the sound effect should be played.
+    destination: PlaybackDestination,
     /// The time this playback was queued.
     queued_time: Instant,
 }
@@ -123,7 +133,9 @@ struct EngineContext<F> {
     queued_sound_effect: Vec<QueuedSoundEffect>,
     scene: SpatialSceneHandle,
     scratchpad: Vec<AmbientKey>,
+    sound_effect_listener: ListenerHandle,
     sound_effect_paths: GenerationalSlab<SoundEffectKey, String>,
+    sound_effect_scene: SpatialSceneHandle,
     sound_effect_track: TrackHandle,
 }
 
@@ -143,6 +155,11 @@ impl<F: FileLoader> AudioEngine<F> {
         let mut scene = manager
             .add_spatial_scene(SpatialSceneSettings::default())
             .expect("Can't create spatial scene");
+        // Spatial sound effects use their own scene, so that they are mixed into the
+        // sound effect track and ambient sounds are not heard twice.
+        let mut sound_effect_scene = manager
+            .add_spatial_scene(SpatialSceneSettings::default())
+            .expect("Can't create sound effect spatial scene");
         let background_music_track = manager
             .add_sub_track(TrackBuilder::new())
             .expect("Can't create background music track");
@@ -153,6 +170,11 @@ impl<F: FileLoader> AudioEngine<F> {
         let ambient_listener = scene
             .add_listener(position, orientation, ListenerSettings { track: ambient_track.id() })
             .expect("Can't create ambient listener");
+        let sound_effect_listener = sound_effect_scene
+            .add_listener(position, orientation, ListenerSettings {
+                track: sound_effect_track.id(),
+            })
+            .expect("Can't create sound effect listener");
         let loading_sound_effect = HashSet::new();
         let cache = ResourceCache::new(
             NonZeroU32::new(MAX_CACHE_COUNT).unwrap(),
@@ -188,7 +210,9 @@ impl<F: FileLoader> AudioEngine<F> {
             queued_sound_effect: Vec::default(),
             scene,
             scratchpad: Vec::default(),
+            sound_effect_listener,
             sound_effect_paths: GenerationalSlab::default(),
+            sound_effect_scene,
             sound_effect_track,
         });
 
@@ -274,6 +298,15 @@ impl<F: FileLoader> AudioEngine<F> {
         self.engine_context.lock().unwrap().play_sound_effect(sound_effect_key)
     }
 
+    /// Plays a sound effect at a position in the world. The sound fades out
+    /// linearly until it can't be heard beyond the given range.
+    pub fn play_spatial_sound_effect(&self, sound_effect_key: SoundEffectKey, position: Point3<f32>, range: f32) {
+        self.engine_context
+            .lock()
+            .unwrap()
+            .play_spatial_sound_effect(sound_effect_key, position, range)
+    }
+
     /// Sets the listener of the ambient sound. This is normally the camera's
     /// position and orientation. This should update each frame.
     pub fn set_ambient_listener(&self, position: Point3<f32>, view_direction: Vector3<f32>, look_up: Vector3<f32>) {
@@ -410,7 +443,28 @@ impl<F: FileLoader> EngineContext<F> {
             &self.sound_effect_paths,
             &mut self.queued_sound_effect,
             sound_effect_key,
-            None,
+            PlaybackDestination::SoundEffect,
+        );
+    }
+
+    fn play_spatial_sound_effect(&mut self, sound_effect_key: SoundEffectKey, position: Point3<f32>, range: f32) {
+        if let Some(data) = self
+            .cache
+            .get(sound_effect_key)
+            .map(|cached_sound_effect| cached_sound_effect.0.clone())
+        {
+            self.cache.touch(sound_effect_key);
+            play_at_position(&mut self.manager, &mut self.sound_effect_scene, data, position, range);
+            return;
+        }
+
+        queue_sound_effect_playback(
+            self.game_file_loader.clone(),
+            self.async_response_sender.clone(),
+            &self.sound_effect_paths,
+            &mut self.queued_sound_effect,
+            sound_effect_key,
+            PlaybackDestination::Spatial { position, range },
         );
     }
 
@@ -484,7 +538,7 @@ impl<F: FileLoader> EngineContext<F> {
                     &self.sound_effect_paths,
                     &mut self.queued_sound_effect,
                     sound_effect_key,
-                    Some(ambient_key),
+                    PlaybackDestination::Ambient(ambient_key),
                 );
             }
 
@@ -523,6 +577,8 @@ impl<F: FileLoader> EngineContext<F> {
             };
             self.ambient_listener.set_position(position, tween);
             self.ambient_listener.set_orientation(orientation, tween);
+            self.sound_effect_listener.set_position(position, tween);
+            self.sound_effect_listener.set_orientation(orientation, tween);
         }
     }
 
@@ -644,14 +700,17 @@ impl<F: FileLoader> EngineContext<F> {
                 return true;
             };
 
-            match queued.ambient {
-                None => {
+            match queued.destination {
+                PlaybackDestination::SoundEffect => {
                     if let Err(_error) = self.manager.play(data.output_destination(&self.sound_effect_track)) {
                         #[cfg(feature = "debug")]
                         print_debug!("[{}] can't play sound effect: {:?}", "error".red(), _error);
                     }
                 }
-                Some(ambient_key) => {
+                PlaybackDestination::Spatial { position, range } => {
+                    play_at_position(&mut self.manager, &mut self.sound_effect_scene, data, position, range);
+                }
+                PlaybackDestination::Ambient(ambient_key) => {
                     if let Some(emitter_handle) = self.active_emitters.get(&ambient_key)
                         && let Some(sound_config) = self.ambient_sound.get(ambient_key)
                     {
@@ -744,13 +803,43 @@ fn adjust_ambient_sound(mut data: StaticSoundData, emitter_handle: &EmitterHandl
     data.output_destination(emitter_handle)
 }
 
+/// Plays a sound through a new emitter at the given position. The emitter is
+/// removed from the scene once the sound finished playing.
+fn play_at_position(manager: &mut AudioManager, scene: &mut SpatialSceneHandle, data: StaticSoundData, position: Point3<f32>, range: f32) {
+    // Kira uses a RH coordinate system, so we need to convert our LH vectors.
+    let position = Vector3::new(position.x, position.y, -position.z);
+    let emitter_settings = EmitterSettings {
+        distances: EmitterDistances {
+            min_distance: 5.0,
+            max_distance: range,
+        },
+        attenuation_function: Some(Easing::Linear),
+        enable_spatialization: true,
+        persist_until_sounds_finish: true,
+    };
+
+    let emitter_handle = match scene.add_emitter(position, emitter_settings) {
+        Ok(emitter_handle) => emitter_handle,
+        Err(_error) => {
+            #[cfg(feature = "debug")]
+            print_debug!("[{}] can't add sound effect emitter: {:?}", "error".red(), _error);
+            return;
+        }
+    };
+
+    if let Err(_error) = manager.play(data.output_destination(&emitter_handle)) {
+        #[cfg(feature = "debug")]
+        print_debug!("[{}] can't play spatial sound effect: {:?}", "error".red(), _error);
+    }
+}
+
 fn queue_sound_effect_playback(
     game_file_loader: Arc<impl FileLoader>,
     async_response_sender: Sender<AsyncLoadResult>,
     sound_effect_paths: &GenerationalSlab<SoundEffectKey, String>,
     queued_sound_effect: &mut Vec<QueuedSoundEffect>,
     sound_effect_key: SoundEffectKey,
-    ambient: Option<AmbientKey>,
+    destination: PlaybackDestination,
 ) -> bool {
     let Some(path) = sound_effect_paths.get(sound_effect_key).cloned() else {
         // This case could happen, if the sound effect was queued for deletion.
@@ -759,7 +848,7 @@ fn queue_sound_effect_playback(
 
     queued_sound_effect.push(QueuedSoundEffect {
         sound_effect_key,
-        ambient,
+        destination,
         queued_time: Instant::now(),
     });
 
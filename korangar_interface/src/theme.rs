@@ -142,6 +142,7 @@ where
     fn server_color(&self) -> App::Color;
     fn error_color(&self) -> App::Color;
     fn information_color(&self) -> App::Color;
+    fn party_color(&self) -> App::Color;
 }
 
 pub trait CursorTheme<App>
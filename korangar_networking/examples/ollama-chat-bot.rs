@@ -78,7 +78,9 @@ async fn main() {
     let mut message_history = MessageHistory { hash_map: HashMap::new() };
 
     // Kick of the bot by connecting to the login server.
-    networking_system.connect_to_login_server(SOCKET_ADDR, USERNAME.to_owned(), PASSWORD.to_owned());
+    networking_system
+        .connect_to_login_server(SOCKET_ADDR, USERNAME.to_owned(), PASSWORD.to_owned())
+        .expect("Failed to serialize login data");
 
     loop {
         networking_system.get_events(&mut network_event_buffer);
@@ -92,7 +94,9 @@ async fn main() {
                     println!("[{}] Successfully connected to login server", "Setup".green());
 
                     networking_system.disconnect_from_login_server();
-                    networking_system.connect_to_character_server(&login_data, character_servers[0].clone());
+                    networking_system
+                        .connect_to_character_server(&login_data, character_servers[0].clone())
+                        .expect("Failed to serialize login data");
 
                     saved_login_data = Some(login_data);
                 }
@@ -142,7 +146,9 @@ async fn main() {
                     let login_login_data = saved_login_data.as_ref().unwrap();
 
                     networking_system.disconnect_from_character_server();
-                    networking_system.connect_to_map_server(login_login_data, login_data);
+                    networking_system
+                        .connect_to_map_server(login_login_data, login_data)
+                        .expect("Failed to serialize login data");
 
                     networking_system.map_loaded().expect("Map server disconnected");
                 }
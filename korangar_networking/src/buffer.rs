@@ -0,0 +1,150 @@
+/// Number of bytes that are read from a connection at once.
+const READ_SIZE: usize = 8192;
+
+/// Default for the largest packet that is reassembled. The length of variable
+/// length packets is stored in two bytes, so no valid packet is bigger.
+pub const DEFAULT_MAXIMUM_PACKET_SIZE: usize = u16::MAX as usize;
+
+/// Buffer for the bytes received from a server. A packet can be split across
+/// any number of reads, so the bytes of an incomplete packet are kept until
+/// the rest of it arrives.
+pub struct ReceiveBuffer {
+    data: Vec<u8>,
+    length: usize,
+    maximum_packet_size: usize,
+    /// Bytes of a packet that is too big that still have to be skipped.
+    skipped_length: usize,
+}
+
+impl ReceiveBuffer {
+    pub fn new(maximum_packet_size: usize) -> Self {
+        Self {
+            data: Vec::new(),
+            length: 0,
+            maximum_packet_size,
+            skipped_length: 0,
+        }
+    }
+
+    /// Space for the next read. Received bytes only become part of the buffer
+    /// once they are committed, so a read that is cancelled doesn't leave
+    /// anything behind.
+    pub fn spare(&mut self) -> &mut [u8] {
+        let required_size = self.length + READ_SIZE;
+
+        if self.data.len() < required_size {
+            self.data.resize(required_size, 0);
+        }
+
+        &mut self.data[self.length..]
+    }
+
+    pub fn commit(&mut self, received_bytes: usize) {
+        self.length += received_bytes;
+    }
+
+    pub fn filled(&self) -> &[u8] {
+        &self.data[..self.length]
+    }
+
+    /// Remove `count` processed bytes from the start of the buffer. The
+    /// remaining bytes are the start of an incomplete packet.
+    pub fn consume(&mut self, count: usize) {
+        self.data.copy_within(count..self.length, 0);
+        self.length -= count;
+    }
+
+    /// Skip the incomplete packet at the start of the buffer if it is bigger
+    /// than the maximum packet size, instead of reassembling it. Its bytes
+    /// are removed as they arrive, so the packets after it are still read
+    /// correctly. Returns whether the packet is skipped.
+    pub fn skip_if_too_big(&mut self, packet_length: usize) -> bool {
+        if packet_length <= self.maximum_packet_size {
+            return false;
+        }
+
+        self.skipped_length = packet_length;
+        true
+    }
+
+    pub fn is_skipping(&self) -> bool {
+        self.skipped_length > 0
+    }
+
+    /// Remove the bytes of the skipped packet that arrived so far from the
+    /// start of the buffer.
+    pub fn take_skipped(&mut self) -> Vec<u8> {
+        let count = self.skipped_length.min(self.length);
+        let bytes = self.data[..count].to_vec();
+
+        self.consume(count);
+        self.skipped_length -= count;
+
+        bytes
+    }
+
+    pub fn clear(&mut self) {
+        self.length = 0;
+        self.skipped_length = 0;
+    }
+}
+
+#[cfg(test)]
+mod receive_buffer {
+    use super::{ReceiveBuffer, READ_SIZE};
+
+    fn receive(receive_buffer: &mut ReceiveBuffer, bytes: &[u8]) {
+        receive_buffer.spare()[..bytes.len()].copy_from_slice(bytes);
+        receive_buffer.commit(bytes.len());
+    }
+
+    #[test]
+    fn grow() {
+        let mut receive_buffer = ReceiveBuffer::new(usize::MAX);
+
+        for index in 0..3 {
+            assert!(receive_buffer.spare().len() >= READ_SIZE);
+            receive(&mut receive_buffer, &[index; READ_SIZE]);
+        }
+
+        assert_eq!(receive_buffer.filled().len(), 3 * READ_SIZE);
+        assert_eq!(receive_buffer.filled()[2 * READ_SIZE], 2);
+    }
+
+    #[test]
+    fn uncommitted_bytes() {
+        let mut receive_buffer = ReceiveBuffer::new(usize::MAX);
+
+        receive(&mut receive_buffer, &[1, 2]);
+        receive_buffer.spare()[0] = 3;
+
+        assert_eq!(receive_buffer.filled(), [1, 2]);
+    }
+
+    #[test]
+    fn consume() {
+        let mut receive_buffer = ReceiveBuffer::new(usize::MAX);
+
+        receive(&mut receive_buffer, &[1, 2, 3, 4]);
+        receive_buffer.consume(3);
+        receive(&mut receive_buffer, &[5]);
+
+        assert_eq!(receive_buffer.filled(), [4, 5]);
+    }
+
+    #[test]
+    fn packet_too_big() {
+        let mut receive_buffer = ReceiveBuffer::new(4);
+
+        receive(&mut receive_buffer, &[1, 2, 3]);
+        assert!(!receive_buffer.skip_if_too_big(4));
+        assert!(receive_buffer.skip_if_too_big(6));
+        assert_eq!(receive_buffer.take_skipped(), [1, 2, 3]);
+        assert!(receive_buffer.is_skipping());
+
+        receive(&mut receive_buffer, &[4, 5, 6, 7, 8]);
+        assert_eq!(receive_buffer.take_skipped(), [4, 5, 6]);
+        assert!(!receive_buffer.is_skipping());
+        assert_eq!(receive_buffer.filled(), [7, 8]);
+    }
+}
@@ -11,6 +11,9 @@ pub struct EntityData {
     pub maximum_health_points: i32,
     pub head_direction: usize,
     pub sex: Sex,
+    pub head: u16,
+    pub head_palette: u16,
+    pub body_palette: u16,
 }
 
 impl EntityData {
@@ -25,6 +28,9 @@ impl EntityData {
             maximum_health_points: character_information.maximum_health_points as i32,
             head_direction: 0, // TODO: get correct rotation
             sex: character_information.sex,
+            head: character_information.head as u16,
+            head_palette: character_information.head_palette as u16,
+            body_palette: character_information.body_palette as u16,
         }
     }
 }
@@ -41,6 +47,9 @@ impl From<EntityAppearedPacket> for EntityData {
             maximum_health_points: packet.maximum_health_points,
             head_direction: packet.head_direction as usize,
             sex: packet.sex,
+            head: packet.head,
+            head_palette: packet.head_palette,
+            body_palette: packet.body_palette,
         }
     }
 }
@@ -57,6 +66,9 @@ impl From<EntityAppeared2Packet> for EntityData {
             maximum_health_points: packet.maximum_health_points,
             head_direction: packet.head_direction as usize,
             sex: packet.sex,
+            head: packet.head,
+            head_palette: packet.head_palette,
+            body_palette: packet.body_palette,
         }
     }
 }
@@ -75,6 +87,9 @@ impl From<MovingEntityAppearedPacket> for EntityData {
             maximum_health_points: packet.maximum_health_points,
             head_direction: packet.head_direction as usize,
             sex: packet.sex,
+            head: packet.head,
+            head_palette: packet.head_palette,
+            body_palette: packet.body_palette,
         }
     }
 }
@@ -3,7 +3,7 @@ use ragnarok_packets::*;
 use crate::hotkey::HotkeyState;
 use crate::items::ShopItem;
 use crate::{
-    CharacterServerLoginData, EntityData, InventoryItem, LoginServerLoginData, MessageColor, NoMetadata,
+    CharacterServerLoginData, EntityData, InventoryItem, LoginServerLoginData, MessageColor, NoMetadata, PartyMember,
     UnifiedCharacterSelectionFailedReason, UnifiedLoginFailedReason,
 };
 
@@ -161,6 +161,37 @@ pub enum NetworkEvent {
         index: InventoryIndex,
         amount: u16,
     },
+    PartyInvitation {
+        party_id: PartyId,
+        party_name: String,
+    },
+    /// The player joined a party or logged in while being in one.
+    SetParty {
+        name: String,
+        members: Vec<PartyMember>,
+    },
+    /// A member joined the party or their map or online state changed.
+    UpdatePartyMember {
+        member: PartyMember,
+    },
+    /// A member left the party. If this is the player, the party should be
+    /// cleared.
+    PartyMemberLeft {
+        account_id: AccountId,
+    },
+    PartyMemberHealth {
+        account_id: AccountId,
+        health_points: usize,
+        maximum_health_points: usize,
+    },
+    PartyMemberPosition {
+        account_id: AccountId,
+        position: TilePosition,
+    },
+    PartyLeaderChanged {
+        old_leader: AccountId,
+        new_leader: AccountId,
+    },
 }
 
 /// New-type so we can implement some `From` traits. This will help when
@@ -1,8 +1,10 @@
+mod buffer;
 mod entity;
 mod event;
 mod hotkey;
 mod items;
 mod message;
+mod party;
 mod server;
 
 use std::cell::RefCell;
@@ -10,11 +12,12 @@ use std::net::{IpAddr, SocketAddr};
 use std::rc::Rc;
 use std::time::Duration;
 
+use buffer::{ReceiveBuffer, DEFAULT_MAXIMUM_PACKET_SIZE};
 use event::{
     CharacterServerDisconnectedEvent, DisconnectedEvent, LoginServerDisconnectedEvent, MapServerDisconnectedEvent, NetworkEventList,
     NoNetworkEvents,
 };
-use ragnarok_bytes::{ByteStream, FromBytes};
+use ragnarok_bytes::{ByteStream, ByteWriter, ConversionResult, FromBytes, TextEncoding};
 use ragnarok_packets::handler::{DuplicateHandlerError, HandlerResult, NoPacketCallback, PacketCallback, PacketHandler};
 use ragnarok_packets::*;
 use server::{ServerConnectCommand, ServerConnection};
@@ -29,11 +32,18 @@ pub use self::event::{DisconnectReason, NetworkEvent};
 pub use self::hotkey::HotkeyState;
 pub use self::items::{InventoryItem, InventoryItemDetails, ItemQuantity, NoMetadata, SellItem, ShopItem};
 pub use self::message::MessageColor;
+pub use self::party::PartyMember;
 pub use self::server::{
-    CharacterServerLoginData, LoginServerLoginData, NotConnectedError, UnifiedCharacterSelectionFailedReason, UnifiedLoginFailedReason,
+    CharacterServerLoginData, LoginServerLoginData, SendPacketError, UnifiedCharacterSelectionFailedReason, UnifiedLoginFailedReason,
 };
 use crate::server::NetworkTaskError;
 
+fn serialize_packet(packet: &impl Packet, text_encoding: TextEncoding, packet_version: PacketVersion) -> ConversionResult<Vec<u8>> {
+    let mut byte_writer = ByteWriter::with_metadata(packet_version).with_text_encoding(text_encoding);
+    packet.packet_to_bytes(&mut byte_writer)?;
+    Ok(byte_writer.into_inner())
+}
+
 /// Buffer for networking events. This struct exists to reduce heap allocations
 /// and is purely an optimization.
 pub struct NetworkEventBuffer(Vec<NetworkEvent>);
@@ -50,6 +60,9 @@ pub struct NetworkingSystem<Callback> {
     character_server_connection: ServerConnection,
     map_server_connection: ServerConnection,
     packet_callback: Callback,
+    text_encoding: TextEncoding,
+    packet_version: PacketVersion,
+    maximum_packet_size: usize,
 }
 
 impl NetworkingSystem<NoPacketCallback> {
@@ -71,6 +84,9 @@ where
             character_server_connection: ServerConnection::Disconnected,
             map_server_connection: ServerConnection::Disconnected,
             packet_callback,
+            text_encoding: TextEncoding::default(),
+            packet_version: PacketVersion::default(),
+            maximum_packet_size: DEFAULT_MAXIMUM_PACKET_SIZE,
         };
         let event_buffer = NetworkEventBuffer(Vec::new());
 
@@ -103,18 +119,24 @@ where
                             address,
                             action_receiver,
                             event_sender,
+                            text_encoding,
+                            packet_version,
+                            maximum_packet_size,
                         } => {
                             if let Some(handle) = login_server_task_handle.take() {
                                 // TODO: Maybe add a timeout here? Maybe handle Result?
                                 let _ = handle.await.unwrap();
                             }
 
-                            let packet_handler = Self::create_login_server_packet_handler(packet_callback.clone()).unwrap();
+                            let packet_handler = Self::create_login_server_packet_handler(packet_callback.clone(), packet_version).unwrap();
                             let handle = local_set.spawn_local(Self::handle_server_connection(
                                 address,
                                 action_receiver,
                                 event_sender,
                                 packet_handler,
+                                text_encoding,
+                                packet_version,
+                                maximum_packet_size,
                                 LoginServerKeepalivePacket::new,
                                 Duration::from_secs(58),
                                 false,
@@ -126,18 +148,25 @@ where
                             address,
                             action_receiver,
                             event_sender,
+                            text_encoding,
+                            packet_version,
+                            maximum_packet_size,
                         } => {
                             if let Some(handle) = character_server_task_handle.take() {
                                 // TODO: Maybe add a timeout here? Maybe handle Result?
                                 let _ = handle.await.unwrap();
                             }
 
-                            let packet_handler = Self::create_character_server_packet_handler(packet_callback.clone()).unwrap();
+                            let packet_handler =
+                                Self::create_character_server_packet_handler(packet_callback.clone(), packet_version).unwrap();
                             let handle = local_set.spawn_local(Self::handle_server_connection(
                                 address,
                                 action_receiver,
                                 event_sender,
                                 packet_handler,
+                                text_encoding,
+                                packet_version,
+                                maximum_packet_size,
                                 CharacterServerKeepalivePacket::new,
                                 Duration::from_secs(10),
                                 true,
@@ -149,18 +178,24 @@ where
                             address,
                             action_receiver,
                             event_sender,
+                            text_encoding,
+                            packet_version,
+                            maximum_packet_size,
                         } => {
                             if let Some(handle) = map_server_task_handle.take() {
                                 // TODO: Maybe add a timeout here? Maybe handle Result?
                                 let _ = handle.await.unwrap();
                             }
 
-                            let packet_handler = Self::create_map_server_packet_handler(packet_callback.clone()).unwrap();
+                            let packet_handler = Self::create_map_server_packet_handler(packet_callback.clone(), packet_version).unwrap();
                             let handle = local_set.spawn_local(Self::handle_server_connection(
                                 address,
                                 action_receiver,
                                 event_sender,
                                 packet_handler,
+                                text_encoding,
+                                packet_version,
+                                maximum_packet_size,
                                 // Always passing 100 seems to work fine for now, but it might cause
                                 // issues when connecting to something other than rAthena.
                                 || RequestServerTickPacket::new(ClientTick(100)),
@@ -213,17 +248,40 @@ where
         };
     }
 
+    /// Set the [`TextEncoding`] of the server. It only applies to connections
+    /// that are established afterwards.
+    pub fn set_text_encoding(&mut self, text_encoding: TextEncoding) {
+        self.text_encoding = text_encoding;
+    }
+
+    /// Set the [`PacketVersion`] of the server. It only applies to connections
+    /// that are established afterwards.
+    pub fn set_packet_version(&mut self, packet_version: PacketVersion) {
+        self.packet_version = packet_version;
+    }
+
+    /// Set the size of the biggest packet that is accepted from the server,
+    /// in bytes. Incomplete packets that exceed it are discarded. It only
+    /// applies to connections that are established afterwards.
+    pub fn set_maximum_packet_size(&mut self, maximum_packet_size: usize) {
+        self.maximum_packet_size = maximum_packet_size;
+    }
+
     pub fn get_events(&mut self, events: &mut NetworkEventBuffer) {
         Self::handle_connection::<LoginServerDisconnectedEvent>(&mut self.login_server_connection, events);
         Self::handle_connection::<CharacterServerDisconnectedEvent>(&mut self.character_server_connection, events);
         Self::handle_connection::<MapServerDisconnectedEvent>(&mut self.map_server_connection, events);
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn handle_server_connection<PingPacket>(
         address: SocketAddr,
         mut action_receiver: UnboundedReceiver<Vec<u8>>,
         event_sender: UnboundedSender<NetworkEvent>,
-        mut packet_handler: PacketHandler<NetworkEventList, (), Callback>,
+        mut packet_handler: PacketHandler<NetworkEventList, PacketVersion, Callback>,
+        text_encoding: TextEncoding,
+        packet_version: PacketVersion,
+        maximum_packet_size: usize,
         ping_factory: impl Fn() -> PingPacket,
         ping_frequency: Duration,
         // After logging in to the character server, it sends the account id without any packet.
@@ -237,8 +295,7 @@ where
     {
         let mut stream = TcpStream::connect(address).await.map_err(|_| NetworkTaskError::FailedToConnect)?;
         let mut interval = tokio::time::interval(ping_frequency);
-        let mut buffer = [0u8; 8192];
-        let mut cut_off_buffer_base = 0;
+        let mut receive_buffer = ReceiveBuffer::new(maximum_packet_size);
         let mut events = Vec::new();
 
         loop {
@@ -253,7 +310,7 @@ where
                     stream.write_all(&action).await.map_err(|_| NetworkTaskError::ConnectionClosed)?;
                 }
                 // Receive some packets from the server.
-                received_bytes = stream.read(&mut buffer[cut_off_buffer_base..]) => {
+                received_bytes = stream.read(receive_buffer.spare()) => {
                     let Ok(received_bytes) = received_bytes else {
                         // Channel was closed by the main thread.
                         break Err(NetworkTaskError::ConnectionClosed);
@@ -265,47 +322,16 @@ where
                         break Err(NetworkTaskError::ConnectionClosed);
                     }
 
-                    let data = &buffer[..cut_off_buffer_base + received_bytes];
-                    let mut byte_stream = ByteStream::without_metadata(data);
-
+                    receive_buffer.commit(received_bytes);
 
-                    if read_account_id {
-                        let account_id = AccountId::from_bytes(&mut byte_stream).unwrap();
-                        events.push(NetworkEvent::AccountId(account_id));
-                        read_account_id = false;
-                    }
-
-                    while !byte_stream.is_empty() {
-                        match packet_handler.process_one(&mut byte_stream) {
-                            HandlerResult::Ok(packet_events) => events.extend(packet_events.0.into_iter()),
-                            HandlerResult::PacketCutOff => {
-                                let packet_start = byte_stream.get_offset();
-                                let packet_end = cut_off_buffer_base + received_bytes;
-
-                                if packet_start == 0 {
-                                    // If the packet_start is 0, that means the packet is allegidly bigger than the MTU of a TCP packet.
-                                    // We limit the size of a packet to the MTU, to avoid getting stuck on packets that are parsed incorrectly.
-                                    // TODO: Call the packet callback?
-                                    cut_off_buffer_base = 0;
-                                    break;
-                                }
-
-                                buffer.copy_within(packet_start..packet_end, 0);
-                                cut_off_buffer_base = packet_end - packet_start;
-
-                                break;
-                            },
-                            // The packet callback can take care of handling these properly.
-                            HandlerResult::UnhandledPacket => {
-                                cut_off_buffer_base = 0;
-                                break
-                            },
-                            HandlerResult::InternalError(..) => {
-                                cut_off_buffer_base = 0;
-                                break
-                            },
-                        }
-                    }
+                    Self::process_received_bytes(
+                        &mut packet_handler,
+                        &mut receive_buffer,
+                        text_encoding,
+                        packet_version,
+                        &mut read_account_id,
+                        &mut events,
+                    );
 
                     for event in events.drain(..) {
                         event_sender.send(event).map_err(|_| NetworkTaskError::ConnectionClosed)?;
@@ -313,18 +339,90 @@ where
                 }
                 // Send a keep-alive packet to the server.
                 _ = interval.tick() => {
-                    let packet_bytes = ping_factory().packet_to_bytes().unwrap();
+                    let packet_bytes =
+                        serialize_packet(&ping_factory(), text_encoding, packet_version).map_err(|_| NetworkTaskError::FailedToSerialize)?;
                     stream.write_all(&packet_bytes).await.map_err(|_| NetworkTaskError::ConnectionClosed)?;
                 }
             }
         }
     }
 
-    pub fn connect_to_login_server(&mut self, address: SocketAddr, username: impl Into<String>, password: impl Into<String>) {
+    /// Process all complete packets in the receive buffer. The start of an
+    /// incomplete packet is kept until the rest of it arrives.
+    fn process_received_bytes(
+        packet_handler: &mut PacketHandler<NetworkEventList, PacketVersion, Callback>,
+        receive_buffer: &mut ReceiveBuffer,
+        text_encoding: TextEncoding,
+        packet_version: PacketVersion,
+        read_account_id: &mut bool,
+        events: &mut Vec<NetworkEvent>,
+    ) {
+        // Drop the rest of a packet that is too big to be reassembled.
+        if receive_buffer.is_skipping() {
+            packet_handler.packet_callback().unknown_packet(receive_buffer.take_skipped());
+
+            if receive_buffer.is_skipping() {
+                return;
+            }
+        }
+
+        let mut byte_stream = ByteStream::with_metadata(receive_buffer.filled(), packet_version).with_text_encoding(text_encoding);
+
+        if *read_account_id {
+            let Ok(account_id) = AccountId::from_bytes(&mut byte_stream) else {
+                // The account id is cut off, so we wait for the rest of it.
+                return;
+            };
+
+            events.push(NetworkEvent::AccountId(account_id));
+            *read_account_id = false;
+        }
+
+        let (processed_bytes, cut_off_length) = loop {
+            if byte_stream.is_empty() {
+                break (byte_stream.get_offset(), None);
+            }
+
+            match packet_handler.process_one(&mut byte_stream) {
+                HandlerResult::Ok(packet_events) => events.extend(packet_events.0),
+                // Skipped packets are reported to the packet callback, so we can just
+                // continue with the next one.
+                HandlerResult::SkippedPacket => {}
+                HandlerResult::PacketCutOff => {
+                    let offset = byte_stream.get_offset();
+                    break (offset, packet_handler.packet_length(&receive_buffer.filled()[offset..]));
+                }
+                // The packet callback can take care of handling these properly. Since we
+                // don't know where the next packet starts, the rest of the data is discarded.
+                HandlerResult::UnhandledPacket | HandlerResult::InternalError(..) => {
+                    receive_buffer.clear();
+                    return;
+                }
+            }
+        };
+
+        receive_buffer.consume(processed_bytes);
+
+        if cut_off_length.is_some_and(|packet_length| receive_buffer.skip_if_too_big(packet_length)) {
+            packet_handler.packet_callback().unknown_packet(receive_buffer.take_skipped());
+        }
+    }
+
+    /// Fails if the username or password can't be represented in the text
+    /// encoding of the server.
+    pub fn connect_to_login_server(
+        &mut self,
+        address: SocketAddr,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> ConversionResult<()> {
         if !matches!(self.login_server_connection, ServerConnection::Disconnected) {
-            return;
+            return Ok(());
         }
 
+        let login_packet = LoginServerLoginPacket::new(username.into(), password.into());
+        let packet_bytes = serialize_packet(&login_packet, self.text_encoding, self.packet_version)?;
+
         let (action_sender, action_receiver) = tokio::sync::mpsc::unbounded_channel();
         let (event_sender, event_receiver) = tokio::sync::mpsc::unbounded_channel();
 
@@ -333,28 +431,41 @@ where
                 address,
                 action_receiver,
                 event_sender,
+                text_encoding: self.text_encoding,
+                packet_version: self.packet_version,
+                maximum_packet_size: self.maximum_packet_size,
             })
             .expect("network thread dropped");
 
-        let login_packet = LoginServerLoginPacket::new(username.into(), password.into());
-
         self.packet_callback.outgoing_packet(&login_packet);
 
-        action_sender
-            .send(login_packet.packet_to_bytes().unwrap())
-            .expect("action receiver instantly dropped");
+        action_sender.send(packet_bytes).expect("action receiver instantly dropped");
 
         self.login_server_connection = ServerConnection::Connected {
             action_sender,
             event_receiver,
         };
+
+        Ok(())
     }
 
-    pub fn connect_to_character_server(&mut self, login_data: &LoginServerLoginData, server: CharacterServerInformation) {
+    pub fn connect_to_character_server(
+        &mut self,
+        login_data: &LoginServerLoginData,
+        server: CharacterServerInformation,
+    ) -> ConversionResult<()> {
         if !matches!(self.character_server_connection, ServerConnection::Disconnected) {
-            return;
+            return Ok(());
         }
 
+        let login_packet = CharacterServerLoginPacket::new(
+            login_data.account_id,
+            login_data.login_id1,
+            login_data.login_id2,
+            login_data.sex,
+        );
+        let packet_bytes = serialize_packet(&login_packet, self.text_encoding, self.packet_version)?;
+
         let (action_sender, action_receiver) = tokio::sync::mpsc::unbounded_channel();
         let (event_sender, event_receiver) = tokio::sync::mpsc::unbounded_channel();
 
@@ -365,37 +476,44 @@ where
                 address,
                 action_receiver,
                 event_sender,
+                text_encoding: self.text_encoding,
+                packet_version: self.packet_version,
+                maximum_packet_size: self.maximum_packet_size,
             })
             .expect("network thread dropped");
 
-        let login_packet = CharacterServerLoginPacket::new(
-            login_data.account_id,
-            login_data.login_id1,
-            login_data.login_id2,
-            login_data.sex,
-        );
-
         self.packet_callback.outgoing_packet(&login_packet);
 
-        action_sender
-            .send(login_packet.packet_to_bytes().unwrap())
-            .expect("action receiver instantly dropped");
+        action_sender.send(packet_bytes).expect("action receiver instantly dropped");
 
         self.character_server_connection = ServerConnection::Connected {
             action_sender,
             event_receiver,
         };
+
+        Ok(())
     }
 
     pub fn connect_to_map_server(
         &mut self,
         login_server_login_data: &LoginServerLoginData,
         character_server_login_data: CharacterServerLoginData,
-    ) {
+    ) -> ConversionResult<()> {
         if !matches!(self.map_server_connection, ServerConnection::Disconnected) {
-            return;
+            return Ok(());
         }
 
+        let login_packet = MapServerLoginPacket::new(
+            login_server_login_data.account_id,
+            character_server_login_data.character_id,
+            login_server_login_data.login_id1,
+            // Always passing 100 seems to work fine for now, but it might cause
+            // issues when connecting to something other than rAthena.
+            ClientTick(100),
+            login_server_login_data.sex,
+        );
+        let packet_bytes = serialize_packet(&login_packet, self.text_encoding, self.packet_version)?;
+
         let (action_sender, action_receiver) = tokio::sync::mpsc::unbounded_channel();
         let (event_sender, event_receiver) = tokio::sync::mpsc::unbounded_channel();
 
@@ -406,29 +524,22 @@ where
                 address,
                 action_receiver,
                 event_sender,
+                text_encoding: self.text_encoding,
+                packet_version: self.packet_version,
+                maximum_packet_size: self.maximum_packet_size,
             })
             .expect("network thread dropped");
 
-        let login_packet = MapServerLoginPacket::new(
-            login_server_login_data.account_id,
-            character_server_login_data.character_id,
-            login_server_login_data.login_id1,
-            // Always passing 100 seems to work fine for now, but it might cause
-            // issues when connecting to something other than rAthena.
-            ClientTick(100),
-            login_server_login_data.sex,
-        );
-
         self.packet_callback.outgoing_packet(&login_packet);
 
-        action_sender
-            .send(login_packet.packet_to_bytes().unwrap())
-            .expect("action receiver instantly dropped");
+        action_sender.send(packet_bytes).expect("action receiver instantly dropped");
 
         self.map_server_connection = ServerConnection::Connected {
             action_sender,
             event_receiver,
         };
+
+        Ok(())
     }
 
     pub fn disconnect_from_login_server(&mut self) {
@@ -443,46 +554,54 @@ where
         self.map_server_connection = ServerConnection::ClosingManually;
     }
 
-    pub fn send_login_server_packet(&mut self, packet: &impl LoginServerPacket) -> Result<(), NotConnectedError> {
+    pub fn send_login_server_packet(&mut self, packet: &impl LoginServerPacket) -> Result<(), SendPacketError> {
         match &mut self.login_server_connection {
             ServerConnection::Connected { action_sender, .. } => {
                 self.packet_callback.outgoing_packet(packet);
 
-                // FIX: Don't unwrap.
-                action_sender.send(packet.packet_to_bytes().unwrap()).map_err(|_| NotConnectedError)
+                let packet_bytes =
+                    serialize_packet(packet, self.text_encoding, self.packet_version).map_err(SendPacketError::FailedToSerialize)?;
+
+                action_sender.send(packet_bytes).map_err(|_| SendPacketError::NotConnected)
             }
-            _ => Err(NotConnectedError),
+            _ => Err(SendPacketError::NotConnected),
         }
     }
 
-    pub fn send_character_server_packet(&mut self, packet: &impl CharacterServerPacket) -> Result<(), NotConnectedError> {
+    pub fn send_character_server_packet(&mut self, packet: &impl CharacterServerPacket) -> Result<(), SendPacketError> {
         match &mut self.character_server_connection {
             ServerConnection::Connected { action_sender, .. } => {
                 self.packet_callback.outgoing_packet(packet);
 
-                // FIX: Don't unwrap.
-                action_sender.send(packet.packet_to_bytes().unwrap()).map_err(|_| NotConnectedError)
+                let packet_bytes =
+                    serialize_packet(packet, self.text_encoding, self.packet_version).map_err(SendPacketError::FailedToSerialize)?;
+
+                action_sender.send(packet_bytes).map_err(|_| SendPacketError::NotConnected)
             }
-            _ => Err(NotConnectedError),
+            _ => Err(SendPacketError::NotConnected),
         }
     }
 
-    pub fn send_map_server_packet(&mut self, packet: &impl MapServerPacket) -> Result<(), NotConnectedError> {
+    pub fn send_map_server_packet(&mut self, packet: &impl MapServerPacket) -> Result<(), SendPacketError> {
         match &mut self.map_server_connection {
             ServerConnection::Connected { action_sender, .. } => {
                 self.packet_callback.outgoing_packet(packet);
 
-                // FIX: Don't unwrap.
-                action_sender.send(packet.packet_to_bytes().unwrap()).map_err(|_| NotConnectedError)
+                let packet_bytes =
+                    serialize_packet(packet, self.text_encoding, self.packet_version).map_err(SendPacketError::FailedToSerialize)?;
+
+                action_sender.send(packet_bytes).map_err(|_| SendPacketError::NotConnected)
             }
-            _ => Err(NotConnectedError),
+            _ => Err(SendPacketError::NotConnected),
         }
     }
 
     fn create_login_server_packet_handler(
         packet_callback: Callback,
-    ) -> Result<PacketHandler<NetworkEventList, (), Callback>, DuplicateHandlerError> {
-        let mut packet_handler = PacketHandler::<NetworkEventList, (), Callback>::with_callback(packet_callback);
+        packet_version: PacketVersion,
+    ) -> Result<PacketHandler<NetworkEventList, PacketVersion, Callback>, DuplicateHandlerError> {
+        let mut packet_handler =
+            PacketHandler::<NetworkEventList, PacketVersion, Callback>::with_packet_version(packet_callback, packet_version);
 
         packet_handler.register(|packet: LoginServerLoginSuccessPacket| NetworkEvent::LoginServerConnected {
             character_servers: packet.character_server_information,
@@ -529,8 +648,10 @@ where
 
     fn create_character_server_packet_handler(
         packet_callback: Callback,
-    ) -> Result<PacketHandler<NetworkEventList, (), Callback>, DuplicateHandlerError> {
-        let mut packet_handler = PacketHandler::<NetworkEventList, (), Callback>::with_callback(packet_callback);
+        packet_version: PacketVersion,
+    ) -> Result<PacketHandler<NetworkEventList, PacketVersion, Callback>, DuplicateHandlerError> {
+        let mut packet_handler =
+            PacketHandler::<NetworkEventList, PacketVersion, Callback>::with_packet_version(packet_callback, packet_version);
 
         packet_handler.register(|packet: LoginFailedPacket| {
             let reason = packet.reason;
@@ -612,8 +733,10 @@ where
 
     fn create_map_server_packet_handler(
         packet_callback: Callback,
-    ) -> Result<PacketHandler<NetworkEventList, (), Callback>, DuplicateHandlerError> {
-        let mut packet_handler = PacketHandler::<NetworkEventList, (), Callback>::with_callback(packet_callback);
+        packet_version: PacketVersion,
+    ) -> Result<PacketHandler<NetworkEventList, PacketVersion, Callback>, DuplicateHandlerError> {
+        let mut packet_handler =
+            PacketHandler::<NetworkEventList, PacketVersion, Callback>::with_packet_version(packet_callback, packet_version);
 
         // This is a bit of a workaround for the way that the inventory is
         // sent. There is a single packet to start the inventory list,
@@ -1042,7 +1165,111 @@ where
             account_id: packet.account_id,
             character_id: packet.character_id,
         })?;
-        packet_handler.register_noop::<PartyInvitePacket>()?;
+        packet_handler.register(|packet: PartyInvitePacket| NetworkEvent::PartyInvitation {
+            party_id: packet.party_id,
+            party_name: packet.party_name,
+        })?;
+        packet_handler.register(|packet: PartyCreatedPacket| {
+            let text = match packet.result {
+                PartyCreationResult::Success => "Party created.",
+                PartyCreationResult::NameAlreadyExists => "A party with that name already exists.",
+                PartyCreationResult::AlreadyInParty => "You are already in a party.",
+                PartyCreationResult::NotAllowedOnMap => "You can't create a party on this map.",
+            };
+
+            NetworkEvent::ChatMessage {
+                text: text.to_owned(),
+                color: MessageColor::Information,
+            }
+        })?;
+        packet_handler.register(|packet: PartyInvitationResultPacket| {
+            let text = match packet.result {
+                PartyInvitationResult::AlreadyInOtherParty => format!("{} is already in a party.", packet.name),
+                PartyInvitationResult::Rejected => format!("{} rejected the party invitation.", packet.name),
+                PartyInvitationResult::Accepted => format!("{} accepted the party invitation.", packet.name),
+                PartyInvitationResult::PartyFull => "The party is full.".to_owned(),
+                PartyInvitationResult::SameAccount => "A character of the same account is already in the party.".to_owned(),
+                PartyInvitationResult::InvitationsBlocked => format!("{} doesn't accept party invitations.", packet.name),
+                PartyInvitationResult::UnknownError => "The party invitation failed.".to_owned(),
+                PartyInvitationResult::NotOnline => format!("{} is not online.", packet.name),
+                PartyInvitationResult::InvalidMap => format!("{} is on a map that doesn't allow parties.", packet.name),
+                PartyInvitationResult::InvalidMapOwn => "You can't invite characters to a party on this map.".to_owned(),
+            };
+
+            NetworkEvent::ChatMessage {
+                text,
+                color: MessageColor::Information,
+            }
+        })?;
+        packet_handler.register(|packet: PartyInfoPacket| NetworkEvent::SetParty {
+            name: packet.party_name,
+            members: packet.members.into_iter().map(PartyMember::from).collect(),
+        })?;
+        packet_handler.register(|packet: PartyMemberAddedPacket| NetworkEvent::UpdatePartyMember { member: packet.into() })?;
+        packet_handler.register(|packet: PartyMemberLeftPacket| {
+            let text = match packet.reason {
+                PartyMemberLeftReason::Left => format!("{} left the party.", packet.name),
+                PartyMemberLeftReason::Expelled => format!("{} was expelled from the party.", packet.name),
+                PartyMemberLeftReason::LeavingNotAllowed => "You can't leave the party on this map.".to_owned(),
+                PartyMemberLeftReason::ExpellingNotAllowed => "You can't expel party members on this map.".to_owned(),
+            };
+
+            let mut events = vec![NetworkEvent::ChatMessage {
+                text,
+                color: MessageColor::Information,
+            }];
+
+            if matches!(packet.reason, PartyMemberLeftReason::Left | PartyMemberLeftReason::Expelled) {
+                events.push(NetworkEvent::PartyMemberLeft {
+                    account_id: packet.account_id,
+                });
+            }
+
+            events
+        })?;
+        packet_handler.register(|packet: PartyMemberHealthPacket| NetworkEvent::PartyMemberHealth {
+            account_id: packet.account_id,
+            health_points: packet.health_points as usize,
+            maximum_health_points: packet.maximum_health_points as usize,
+        })?;
+        packet_handler.register(|packet: PartyMemberPositionPacket| NetworkEvent::PartyMemberPosition {
+            account_id: packet.account_id,
+            position: packet.position,
+        })?;
+        packet_handler.register(|packet: PartyOptionsPacket| {
+            let experience = match packet.experience {
+                PartyExperienceShare::EachTake => "each take",
+                PartyExperienceShare::EvenShare => "even share",
+                PartyExperienceShare::EvenShareNotAllowed => "each take (the level difference is too big to share evenly)",
+            };
+            let mut text = format!("Party experience: {experience}.");
+
+            if let (Some(item_pickup), Some(item_division)) = (packet.item_pickup, packet.item_division) {
+                let item_share = |item_share| match item_share {
+                    PartyItemShare::EachTake => "each take",
+                    PartyItemShare::PartyShare => "party share",
+                };
+
+                text.push_str(&format!(
+                    " Item pickup: {}. Item division: {}.",
+                    item_share(item_pickup),
+                    item_share(item_division)
+                ));
+            }
+
+            NetworkEvent::ChatMessage {
+                text,
+                color: MessageColor::Information,
+            }
+        })?;
+        packet_handler.register(|packet: PartyLeaderChangedPacket| NetworkEvent::PartyLeaderChanged {
+            old_leader: packet.old_leader,
+            new_leader: packet.new_leader,
+        })?;
+        packet_handler.register(|packet: PartyChatMessagePacket| NetworkEvent::ChatMessage {
+            text: packet.message,
+            color: MessageColor::Party,
+        })?;
         packet_handler.register_noop::<StatusChangeSequencePacket>()?;
         packet_handler.register_noop::<ReputationPacket>()?;
         packet_handler.register_noop::<ClanInfoPacket>()?;
@@ -1075,73 +1302,73 @@ where
         Ok(packet_handler)
     }
 
-    pub fn request_character_list(&mut self) -> Result<(), NotConnectedError> {
+    pub fn request_character_list(&mut self) -> Result<(), SendPacketError> {
         self.send_character_server_packet(&RequestCharacterListPacket::default())
     }
 
-    pub fn select_character(&mut self, character_slot: usize) -> Result<(), NotConnectedError> {
+    pub fn select_character(&mut self, character_slot: usize) -> Result<(), SendPacketError> {
         self.send_character_server_packet(&SelectCharacterPacket::new(character_slot as u8))
     }
 
-    pub fn map_loaded(&mut self) -> Result<(), NotConnectedError> {
+    pub fn map_loaded(&mut self) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&MapLoadedPacket::default())
     }
 
-    pub fn respawn(&mut self) -> Result<(), NotConnectedError> {
+    pub fn respawn(&mut self) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RestartPacket::new(RestartType::Respawn))
     }
 
-    pub fn log_out(&mut self) -> Result<(), NotConnectedError> {
+    pub fn log_out(&mut self) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RestartPacket::new(RestartType::Disconnect))
     }
 
-    pub fn player_move(&mut self, position: WorldPosition) -> Result<(), NotConnectedError> {
+    pub fn player_move(&mut self, position: WorldPosition) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RequestPlayerMovePacket::new(position))
     }
 
-    pub fn warp_to_map(&mut self, map_name: String, position: TilePosition) -> Result<(), NotConnectedError> {
+    pub fn warp_to_map(&mut self, map_name: String, position: TilePosition) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RequestWarpToMapPacket::new(map_name, position))
     }
 
-    pub fn entity_details(&mut self, entity_id: EntityId) -> Result<(), NotConnectedError> {
+    pub fn entity_details(&mut self, entity_id: EntityId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RequestDetailsPacket::new(entity_id))
     }
 
-    pub fn player_attack(&mut self, entity_id: EntityId) -> Result<(), NotConnectedError> {
+    pub fn player_attack(&mut self, entity_id: EntityId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RequestActionPacket::new(entity_id, Action::Attack))
     }
 
-    pub fn send_chat_message(&mut self, player_name: &str, message: &str) -> Result<(), NotConnectedError> {
+    pub fn send_chat_message(&mut self, player_name: &str, message: &str) -> Result<(), SendPacketError> {
         let complete_message = format!("{} : {}", player_name, message);
 
         self.send_map_server_packet(&GlobalMessagePacket::new(complete_message))
     }
 
-    pub fn start_dialog(&mut self, npc_id: EntityId) -> Result<(), NotConnectedError> {
+    pub fn start_dialog(&mut self, npc_id: EntityId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&StartDialogPacket::new(npc_id))
     }
 
-    pub fn next_dialog(&mut self, npc_id: EntityId) -> Result<(), NotConnectedError> {
+    pub fn next_dialog(&mut self, npc_id: EntityId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&NextDialogPacket::new(npc_id))
     }
 
-    pub fn close_dialog(&mut self, npc_id: EntityId) -> Result<(), NotConnectedError> {
+    pub fn close_dialog(&mut self, npc_id: EntityId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&CloseDialogPacket::new(npc_id))
     }
 
-    pub fn choose_dialog_option(&mut self, npc_id: EntityId, option: i8) -> Result<(), NotConnectedError> {
+    pub fn choose_dialog_option(&mut self, npc_id: EntityId, option: i8) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&ChooseDialogOptionPacket::new(npc_id, option))
     }
 
-    pub fn request_item_equip(&mut self, item_index: InventoryIndex, equip_position: EquipPosition) -> Result<(), NotConnectedError> {
+    pub fn request_item_equip(&mut self, item_index: InventoryIndex, equip_position: EquipPosition) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RequestEquipItemPacket::new(item_index, equip_position))
     }
 
-    pub fn request_item_unequip(&mut self, item_index: InventoryIndex) -> Result<(), NotConnectedError> {
+    pub fn request_item_unequip(&mut self, item_index: InventoryIndex) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RequestUnequipItemPacket::new(item_index))
     }
 
-    pub fn cast_skill(&mut self, skill_id: SkillId, skill_level: SkillLevel, entity_id: EntityId) -> Result<(), NotConnectedError> {
+    pub fn cast_skill(&mut self, skill_id: SkillId, skill_level: SkillLevel, entity_id: EntityId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&UseSkillAtIdPacket::new(skill_level, skill_id, entity_id))
     }
 
@@ -1150,7 +1377,7 @@ where
         skill_id: SkillId,
         skill_level: SkillLevel,
         target_position: TilePosition,
-    ) -> Result<(), NotConnectedError> {
+    ) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&UseSkillOnGroundPacket::new(skill_level, skill_id, target_position))
     }
 
@@ -1159,23 +1386,23 @@ where
         skill_id: SkillId,
         skill_level: SkillLevel,
         entity_id: EntityId,
-    ) -> Result<(), NotConnectedError> {
+    ) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&StartUseSkillPacket::new(skill_id, skill_level, entity_id))
     }
 
-    pub fn stop_channeling_skill(&mut self, skill_id: SkillId) -> Result<(), NotConnectedError> {
+    pub fn stop_channeling_skill(&mut self, skill_id: SkillId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&EndUseSkillPacket::new(skill_id))
     }
 
-    pub fn add_friend(&mut self, name: String) -> Result<(), NotConnectedError> {
+    pub fn add_friend(&mut self, name: String) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&AddFriendPacket::new(name))
     }
 
-    pub fn remove_friend(&mut self, account_id: AccountId, character_id: CharacterId) -> Result<(), NotConnectedError> {
+    pub fn remove_friend(&mut self, account_id: AccountId, character_id: CharacterId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&RemoveFriendPacket::new(account_id, character_id))
     }
 
-    pub fn reject_friend_request(&mut self, account_id: AccountId, character_id: CharacterId) -> Result<(), NotConnectedError> {
+    pub fn reject_friend_request(&mut self, account_id: AccountId, character_id: CharacterId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&FriendRequestResponsePacket::new(
             account_id,
             character_id,
@@ -1183,7 +1410,7 @@ where
         ))
     }
 
-    pub fn accept_friend_request(&mut self, account_id: AccountId, character_id: CharacterId) -> Result<(), NotConnectedError> {
+    pub fn accept_friend_request(&mut self, account_id: AccountId, character_id: CharacterId) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&FriendRequestResponsePacket::new(
             account_id,
             character_id,
@@ -1191,7 +1418,54 @@ where
         ))
     }
 
-    pub fn create_character(&mut self, slot: usize, name: String) -> Result<(), NotConnectedError> {
+    pub fn create_party(&mut self, name: String) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&CreatePartyPacket::new(
+            name,
+            PartyItemShare::EachTake,
+            PartyItemShare::EachTake,
+        ))
+    }
+
+    pub fn invite_to_party(&mut self, name: String) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&InviteToPartyPacket::new(name))
+    }
+
+    pub fn reject_party_invitation(&mut self, party_id: PartyId) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&PartyInvitationResponsePacket::new(party_id, PartyInvitationResponse::Reject))
+    }
+
+    pub fn accept_party_invitation(&mut self, party_id: PartyId) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&PartyInvitationResponsePacket::new(party_id, PartyInvitationResponse::Accept))
+    }
+
+    pub fn leave_party(&mut self) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&LeavePartyPacket::new())
+    }
+
+    pub fn expel_party_member(&mut self, account_id: AccountId, name: String) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&ExpelPartyMemberPacket::new(account_id, name))
+    }
+
+    pub fn change_party_leader(&mut self, account_id: AccountId) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&ChangePartyLeaderPacket::new(account_id))
+    }
+
+    pub fn change_party_options(
+        &mut self,
+        experience: PartyExperienceShare,
+        item_pickup: PartyItemShare,
+        item_division: PartyItemShare,
+    ) -> Result<(), SendPacketError> {
+        self.send_map_server_packet(&ChangePartyOptionsPacket::new(experience, item_pickup, item_division))
+    }
+
+    pub fn send_party_message(&mut self, player_name: &str, message: &str) -> Result<(), SendPacketError> {
+        let complete_message = format!("{} : {}", player_name, message);
+
+        self.send_map_server_packet(&PartyMessagePacket::new(complete_message))
+    }
+
+    pub fn create_character(&mut self, slot: usize, name: String) -> Result<(), SendPacketError> {
         let hair_color = 0;
         let hair_style = 0;
         let start_job = 0;
@@ -1202,25 +1476,25 @@ where
         ))
     }
 
-    pub fn delete_character(&mut self, character_id: CharacterId) -> Result<(), NotConnectedError> {
+    pub fn delete_character(&mut self, character_id: CharacterId) -> Result<(), SendPacketError> {
         let email = "a@a.com".to_string();
 
         self.send_character_server_packet(&DeleteCharacterPacket::new(character_id, email))
     }
 
-    pub fn switch_character_slot(&mut self, origin_slot: usize, destination_slot: usize) -> Result<(), NotConnectedError> {
+    pub fn switch_character_slot(&mut self, origin_slot: usize, destination_slot: usize) -> Result<(), SendPacketError> {
         self.send_character_server_packet(&SwitchCharacterSlotPacket::new(origin_slot as u16, destination_slot as u16))
     }
 
-    pub fn set_hotkey_data(&mut self, tab: HotbarTab, index: HotbarSlot, hotkey_data: HotkeyData) -> Result<(), NotConnectedError> {
+    pub fn set_hotkey_data(&mut self, tab: HotbarTab, index: HotbarSlot, hotkey_data: HotkeyData) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&SetHotkeyData2Packet::new(tab, index, hotkey_data))
     }
 
-    pub fn select_buy_or_sell(&mut self, shop_id: ShopId, buy_or_sell: BuyOrSellOption) -> Result<(), NotConnectedError> {
+    pub fn select_buy_or_sell(&mut self, shop_id: ShopId, buy_or_sell: BuyOrSellOption) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&SelectBuyOrSellPacket::new(shop_id, buy_or_sell))
     }
 
-    pub fn purchase_items(&mut self, items: Vec<ShopItem<u32>>) -> Result<(), NotConnectedError> {
+    pub fn purchase_items(&mut self, items: Vec<ShopItem<u32>>) -> Result<(), SendPacketError> {
         let item_information = items
             .into_iter()
             .map(|item| BuyShopItemInformation {
@@ -1232,11 +1506,11 @@ where
         self.send_map_server_packet(&BuyShopItemsPacket::new(item_information))
     }
 
-    pub fn close_shop(&mut self) -> Result<(), NotConnectedError> {
+    pub fn close_shop(&mut self) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&CloseShopPacket::new())
     }
 
-    pub fn sell_items(&mut self, items: Vec<SoldItemInformation>) -> Result<(), NotConnectedError> {
+    pub fn sell_items(&mut self, items: Vec<SoldItemInformation>) -> Result<(), SendPacketError> {
         self.send_map_server_packet(&SellItemsPacket { items })
     }
 }
@@ -1244,24 +1518,144 @@ where
 #[cfg(test)]
 mod packet_handlers {
     use ragnarok_packets::handler::NoPacketCallback;
+    use ragnarok_packets::PacketVersion;
 
     use crate::NetworkingSystem;
 
     #[test]
     fn login_server() {
-        let result = NetworkingSystem::create_login_server_packet_handler(NoPacketCallback);
+        let result = NetworkingSystem::create_login_server_packet_handler(NoPacketCallback, PacketVersion::DEFAULT);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn login_server_before_2017() {
+        let result = NetworkingSystem::create_login_server_packet_handler(NoPacketCallback, PacketVersion(20170314));
         assert!(result.is_ok());
     }
 
     #[test]
     fn character_server() {
-        let result = NetworkingSystem::create_character_server_packet_handler(NoPacketCallback);
+        let result = NetworkingSystem::create_character_server_packet_handler(NoPacketCallback, PacketVersion::DEFAULT);
         assert!(result.is_ok());
     }
 
     #[test]
     fn map_server() {
-        let result = NetworkingSystem::create_map_server_packet_handler(NoPacketCallback);
+        let result = NetworkingSystem::create_map_server_packet_handler(NoPacketCallback, PacketVersion::DEFAULT);
         assert!(result.is_ok());
     }
 }
+
+#[cfg(test)]
+mod reassembly {
+    use ragnarok_bytes::TextEncoding;
+    use ragnarok_packets::handler::NoPacketCallback;
+    use ragnarok_packets::{AccountId, PacketVersion, ServerMessagePacket};
+
+    use crate::buffer::{ReceiveBuffer, DEFAULT_MAXIMUM_PACKET_SIZE};
+    use crate::{serialize_packet, NetworkEvent, NetworkingSystem};
+
+    fn messages() -> Vec<String> {
+        // The second message doesn't fit into a single read.
+        vec!["first".to_owned(), "x".repeat(9000), "last".to_owned()]
+    }
+
+    fn message_bytes() -> Vec<u8> {
+        messages()
+            .into_iter()
+            .flat_map(|message| serialize_packet(&ServerMessagePacket::new(message), TextEncoding::Utf8, PacketVersion::DEFAULT).unwrap())
+            .collect()
+    }
+
+    fn receive<'a>(chunks: impl IntoIterator<Item = &'a [u8]>, maximum_packet_size: usize, mut read_account_id: bool) -> Vec<NetworkEvent> {
+        let mut packet_handler =
+            NetworkingSystem::<NoPacketCallback>::create_map_server_packet_handler(NoPacketCallback, PacketVersion::DEFAULT).unwrap();
+        let mut receive_buffer = ReceiveBuffer::new(maximum_packet_size);
+        let mut events = Vec::new();
+
+        // Like a read from the connection, a chunk might not fit into the spare space
+        // at once.
+        for mut chunk in chunks {
+            while !chunk.is_empty() {
+                let spare = receive_buffer.spare();
+                let received_bytes = chunk.len().min(spare.len());
+
+                spare[..received_bytes].copy_from_slice(&chunk[..received_bytes]);
+                receive_buffer.commit(received_bytes);
+                chunk = &chunk[received_bytes..];
+
+                NetworkingSystem::process_received_bytes(
+                    &mut packet_handler,
+                    &mut receive_buffer,
+                    TextEncoding::Utf8,
+                    PacketVersion::DEFAULT,
+                    &mut read_account_id,
+                    &mut events,
+                );
+            }
+        }
+
+        assert!(receive_buffer.filled().is_empty());
+
+        events
+    }
+
+    fn texts(events: Vec<NetworkEvent>) -> Vec<String> {
+        events
+            .into_iter()
+            .map(|event| match event {
+                NetworkEvent::ChatMessage { text, .. } => text,
+                event => panic!("unexpected event {event:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_at_every_byte() {
+        let bytes = message_bytes();
+
+        for split in 0..=bytes.len() {
+            let (first, second) = bytes.split_at(split);
+            let events = receive([first, second], DEFAULT_MAXIMUM_PACKET_SIZE, false);
+
+            assert_eq!(texts(events), messages(), "split at {split}");
+        }
+    }
+
+    #[test]
+    fn byte_by_byte() {
+        let bytes = message_bytes();
+        let events = receive(bytes.chunks(1), DEFAULT_MAXIMUM_PACKET_SIZE, false);
+
+        assert_eq!(texts(events), messages());
+    }
+
+    #[test]
+    fn account_id_split_at_every_byte() {
+        let mut bytes = vec![7, 0, 0, 0];
+        bytes.extend(message_bytes());
+
+        for split in 0..=8 {
+            let (first, second) = bytes.split_at(split);
+            let mut events = receive([first, second], DEFAULT_MAXIMUM_PACKET_SIZE, true);
+
+            assert!(
+                matches!(events.remove(0), NetworkEvent::AccountId(AccountId(7))),
+                "split at {split}"
+            );
+            assert_eq!(texts(events), messages(), "split at {split}");
+        }
+    }
+
+    #[test]
+    fn packet_too_big() {
+        let bytes = message_bytes();
+
+        let events = receive([bytes.as_slice()], 1024, false);
+        assert_eq!(texts(events), ["first", "last"]);
+
+        let events = receive(bytes.chunks(100), 1024, false);
+        assert_eq!(texts(events), ["first", "last"]);
+    }
+}
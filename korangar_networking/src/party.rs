@@ -0,0 +1,44 @@
+use ragnarok_packets::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartyMember {
+    pub account_id: AccountId,
+    pub name: String,
+    pub map_name: String,
+    pub is_leader: bool,
+    pub is_online: bool,
+    /// Tile position of the member on their map. [`None`] until the server
+    /// sends a position.
+    pub position: Option<TilePosition>,
+    /// Current and maximum health points of the member. [`None`] until the
+    /// server sends an update.
+    pub health: Option<(usize, usize)>,
+}
+
+impl From<PartyMemberInformation> for PartyMember {
+    fn from(information: PartyMemberInformation) -> Self {
+        Self {
+            account_id: information.account_id,
+            name: information.name,
+            map_name: information.map_name,
+            is_leader: information.leader == 0,
+            is_online: information.state == OnlineState::Online,
+            position: None,
+            health: None,
+        }
+    }
+}
+
+impl From<PartyMemberAddedPacket> for PartyMember {
+    fn from(packet: PartyMemberAddedPacket) -> Self {
+        Self {
+            account_id: packet.account_id,
+            name: packet.name,
+            map_name: packet.map_name,
+            is_leader: packet.leader == 0,
+            is_online: packet.state == OnlineState::Online,
+            position: Some(packet.position),
+            health: None,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::net::{IpAddr, SocketAddr};
 
-use ragnarok_packets::{AccountId, CharacterId, Sex};
+use ragnarok_bytes::{ConversionError, TextEncoding};
+use ragnarok_packets::{AccountId, CharacterId, PacketVersion, Sex};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 use crate::event::NetworkEvent;
@@ -47,16 +48,25 @@ pub(crate) enum ServerConnectCommand {
         address: SocketAddr,
         action_receiver: UnboundedReceiver<Vec<u8>>,
         event_sender: UnboundedSender<NetworkEvent>,
+        text_encoding: TextEncoding,
+        packet_version: PacketVersion,
+        maximum_packet_size: usize,
     },
     Character {
         address: SocketAddr,
         action_receiver: UnboundedReceiver<Vec<u8>>,
         event_sender: UnboundedSender<NetworkEvent>,
+        text_encoding: TextEncoding,
+        packet_version: PacketVersion,
+        maximum_packet_size: usize,
     },
     Map {
         address: SocketAddr,
         action_receiver: UnboundedReceiver<Vec<u8>>,
         event_sender: UnboundedSender<NetworkEvent>,
+        text_encoding: TextEncoding,
+        packet_version: PacketVersion,
+        maximum_packet_size: usize,
     },
 }
 
@@ -64,10 +74,16 @@ pub(crate) enum ServerConnectCommand {
 pub(crate) enum NetworkTaskError {
     FailedToConnect,
     ConnectionClosed,
+    FailedToSerialize,
 }
 
 #[derive(Debug)]
-pub struct NotConnectedError;
+pub enum SendPacketError {
+    NotConnected,
+    /// The packet could not be converted to bytes. This happens if it contains
+    /// text that can't be represented in the text encoding of the server.
+    FailedToSerialize(Box<ConversionError>),
+}
 
 pub(crate) enum ServerConnection {
     Connected {
@@ -0,0 +1,154 @@
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, BIG5, EUC_KR, GBK, SHIFT_JIS, UTF_8, WINDOWS_1251, WINDOWS_1252, WINDOWS_1258, WINDOWS_874};
+
+/// Character set used to convert strings from and to bytes. It is carried by
+/// [`ByteStream`](crate::ByteStream) and [`ByteWriter`](crate::ByteWriter)
+/// next to the metadata.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// Every byte maps to the character with the same code point. This never
+    /// fails and preserves all bytes, so it is used if nothing else is known.
+    #[default]
+    Latin1,
+    /// Western European languages (CP1252).
+    Windows1252,
+    /// Cyrillic languages (CP1251).
+    Windows1251,
+    /// Vietnamese (CP1258).
+    Windows1258,
+    /// Thai (CP874).
+    Windows874,
+    /// Korean (CP949). This is a superset of EUC-KR and used for the file
+    /// names inside of game archives.
+    Windows949,
+    /// Japanese (CP932).
+    ShiftJis,
+    /// Simplified Chinese (CP936).
+    Gbk,
+    /// Traditional Chinese (CP950).
+    Big5,
+    Utf8,
+}
+
+impl TextEncoding {
+    fn encoding(self) -> Option<&'static Encoding> {
+        match self {
+            TextEncoding::Latin1 => None,
+            TextEncoding::Windows1252 => Some(WINDOWS_1252),
+            TextEncoding::Windows1251 => Some(WINDOWS_1251),
+            TextEncoding::Windows1258 => Some(WINDOWS_1258),
+            TextEncoding::Windows874 => Some(WINDOWS_874),
+            TextEncoding::Windows949 => Some(EUC_KR),
+            TextEncoding::ShiftJis => Some(SHIFT_JIS),
+            TextEncoding::Gbk => Some(GBK),
+            TextEncoding::Big5 => Some(BIG5),
+            TextEncoding::Utf8 => Some(UTF_8),
+        }
+    }
+
+    /// Get the encoding for a label like `euc-kr` or `windows-1252`, as it
+    /// is used in XML and HTML documents.
+    pub fn from_label(label: &str) -> Option<Self> {
+        let encoding = Encoding::for_label(label.as_bytes())?;
+
+        [
+            TextEncoding::Windows1252,
+            TextEncoding::Windows1251,
+            TextEncoding::Windows1258,
+            TextEncoding::Windows874,
+            TextEncoding::Windows949,
+            TextEncoding::ShiftJis,
+            TextEncoding::Gbk,
+            TextEncoding::Big5,
+            TextEncoding::Utf8,
+        ]
+        .into_iter()
+        .find(|text_encoding| text_encoding.encoding() == Some(encoding))
+    }
+
+    /// Decodes the given bytes. Invalid sequences are replaced with
+    /// `U+FFFD`.
+    pub fn decode(self, bytes: &[u8]) -> Cow<'_, str> {
+        match self.encoding() {
+            Some(encoding) => encoding.decode_without_bom_handling(bytes).0,
+            None => Cow::Owned(bytes.iter().map(|byte| *byte as char).collect()),
+        }
+    }
+
+    /// Encodes the given string. Returns [`None`] if any character can't be
+    /// represented in this encoding.
+    pub fn encode(self, string: &str) -> Option<Cow<'_, [u8]>> {
+        match self.encoding() {
+            Some(encoding) => {
+                let (bytes, _, had_errors) = encoding.encode(string);
+                (!had_errors).then_some(bytes)
+            }
+            None => string
+                .chars()
+                .map(|character| u8::try_from(character).ok())
+                .collect::<Option<Vec<u8>>>()
+                .map(Cow::Owned),
+        }
+    }
+}
+
+#[cfg(test)]
+mod text {
+    use super::TextEncoding;
+
+    #[test]
+    fn latin1() {
+        let bytes = [0xBE, 0xC6, 0xC0, 0xCC, 0xC5, 0xDB];
+        let string = TextEncoding::Latin1.decode(&bytes);
+
+        assert_eq!(string, "¾ÆÀÌÅÛ");
+        assert_eq!(TextEncoding::Latin1.encode(&string).unwrap(), bytes.as_slice());
+        assert!(TextEncoding::Latin1.encode("아이템").is_none());
+    }
+
+    #[test]
+    fn windows949() {
+        let bytes = [0xBE, 0xC6, 0xC0, 0xCC, 0xC5, 0xDB];
+        let string = TextEncoding::Windows949.decode(&bytes);
+
+        assert_eq!(string, "아이템");
+        assert_eq!(TextEncoding::Windows949.encode(&string).unwrap(), bytes.as_slice());
+    }
+
+    #[test]
+    fn windows1252() {
+        let bytes = [0x80, 0x20, 0xE9];
+        let string = TextEncoding::Windows1252.decode(&bytes);
+
+        assert_eq!(string, "€ é");
+        assert_eq!(TextEncoding::Windows1252.encode(&string).unwrap(), bytes.as_slice());
+        assert!(TextEncoding::Windows1252.encode("아이템").is_none());
+    }
+
+    #[test]
+    fn windows1251() {
+        let bytes = [0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2];
+        let string = TextEncoding::Windows1251.decode(&bytes);
+
+        assert_eq!(string, "Привет");
+        assert_eq!(TextEncoding::Windows1251.encode(&string).unwrap(), bytes.as_slice());
+    }
+
+    #[test]
+    fn from_label() {
+        assert_eq!(TextEncoding::from_label("euc-kr"), Some(TextEncoding::Windows949));
+        assert_eq!(TextEncoding::from_label("UTF-8"), Some(TextEncoding::Utf8));
+        assert_eq!(TextEncoding::from_label("windows-1251"), Some(TextEncoding::Windows1251));
+        assert_eq!(TextEncoding::from_label("unknown"), None);
+    }
+
+    #[test]
+    fn utf8() {
+        let string = TextEncoding::Utf8.decode("아이템".as_bytes());
+
+        assert_eq!(string, "아이템");
+        assert_eq!(TextEncoding::Utf8.encode(&string).unwrap(), "아이템".as_bytes());
+        assert_eq!(TextEncoding::Utf8.decode(&[0xFF]), "\u{FFFD}");
+    }
+}
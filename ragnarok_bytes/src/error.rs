@@ -6,10 +6,38 @@ pub enum ConversionErrorType {
     Specific { message: String },
 }
 
+use crate::{ByteSource, ByteStream};
+
+/// Number of bytes before and after the failing offset that are kept for
+/// diagnostics.
+const BYTE_WINDOW_RADIUS: usize = 16;
+/// Number of bytes per line when printing the byte window.
+const BYTES_PER_LINE: usize = 16;
+
+/// Part of the path to the field that failed to parse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldPathSegment {
+    Field(&'static str),
+    Index(usize),
+}
+
+/// Bytes surrounding the offset at which parsing failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteWindow {
+    /// Offset of the first byte of the window in the parsed data.
+    pub start: usize,
+    pub bytes: Vec<u8>,
+}
+
 #[derive(Clone)]
 pub struct ConversionError {
     error_type: ConversionErrorType,
     stack: Vec<&'static str>,
+    root_type: Option<&'static str>,
+    field_path: Vec<FieldPathSegment>,
+    offset: Option<usize>,
+    byte_window: Option<ByteWindow>,
+    version: Option<String>,
 }
 
 impl ConversionError {
@@ -17,6 +45,11 @@ impl ConversionError {
         Box::new(Self {
             error_type,
             stack: Vec::new(),
+            root_type: None,
+            field_path: Vec::new(),
+            offset: None,
+            byte_window: None,
+            version: None,
         })
     }
 
@@ -30,9 +63,97 @@ impl ConversionError {
         matches!(self.error_type, ConversionErrorType::ByteStreamTooShort { .. })
     }
 
+    /// Offset of the innermost field that failed to parse.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Path to the innermost field that failed to parse, for example
+    /// `MapData.resources.objects[12].transform`. Only fields of derived
+    /// types are part of the path.
+    pub fn field_path(&self) -> Option<String> {
+        let root_type = self.root_type?;
+        let root_type = root_type.split('<').next().unwrap_or(root_type);
+        let mut field_path = root_type.rsplit("::").next().unwrap_or(root_type).to_owned();
+
+        for segment in &self.field_path {
+            match segment {
+                FieldPathSegment::Field(name) => field_path.push_str(&format!(".{name}")),
+                FieldPathSegment::Index(index) => field_path.push_str(&format!("[{index}]")),
+            }
+        }
+
+        Some(field_path)
+    }
+
+    /// Version of the data at the point of failure, if the parsed type is
+    /// versioned.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn byte_window(&self) -> Option<&ByteWindow> {
+        self.byte_window.as_ref()
+    }
+
     fn add_to_stack(&mut self, type_name: &'static str) {
         self.stack.insert(0, type_name);
     }
+
+    fn add_field<Caller, Meta>(&mut self, byte_stream: &ByteStream<Meta, impl ByteSource>, field_offset: usize, field_name: &'static str) {
+        // Only the innermost field knows the exact location of the failure.
+        if self.offset.is_none() {
+            let (start, bytes) = byte_stream.window(field_offset, BYTE_WINDOW_RADIUS);
+
+            self.offset = Some(field_offset);
+            self.byte_window = Some(ByteWindow {
+                start,
+                bytes: bytes.to_vec(),
+            });
+        }
+
+        self.root_type = Some(std::any::type_name::<Caller>());
+        self.field_path.insert(0, FieldPathSegment::Field(field_name));
+    }
+
+    fn add_index(&mut self, index: usize) {
+        self.field_path.insert(0, FieldPathSegment::Index(index));
+    }
+
+    fn set_version(&mut self, version: String) {
+        self.version.get_or_insert(version);
+    }
+
+    fn fmt_diagnostics(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(field_path) = self.field_path() {
+            write!(formatter, "\n  field: {}", field_path)?;
+        }
+
+        if let Some(offset) = self.offset {
+            write!(formatter, "\n  offset: {:#X}", offset)?;
+        }
+
+        if let Some(version) = &self.version {
+            write!(formatter, "\n  version: {}", version)?;
+        }
+
+        if let Some(ByteWindow { start, bytes }) = &self.byte_window {
+            for (line_index, line) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+                let line_start = start + line_index * BYTES_PER_LINE;
+                write!(formatter, "\n  {:08X}:", line_start)?;
+
+                for (byte_index, byte) in line.iter().enumerate() {
+                    // Mark the byte at which the failing field starts.
+                    match Some(line_start + byte_index) == self.offset {
+                        true => write!(formatter, "[{:02X}]", byte)?,
+                        false => write!(formatter, " {:02X} ", byte)?,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl std::fmt::Debug for ConversionError {
@@ -58,7 +179,9 @@ impl std::fmt::Debug for ConversionError {
                 )
             }
             ConversionErrorType::Specific { message } => write!(formatter, "{} in {}", message, stack),
-        }
+        }?;
+
+        self.fmt_diagnostics(formatter)
     }
 }
 
@@ -69,6 +192,16 @@ pub type ConversionResult<T> = Result<T, Box<ConversionError>>;
 pub trait ConversionResultExt {
     /// Add a type name to the stack trace.
     fn trace<Caller>(self) -> Self;
+
+    /// Add a field of the caller to the field path. The first field that is
+    /// added also records the offset and the surrounding bytes.
+    fn field<Caller, Meta>(self, byte_stream: &ByteStream<Meta, impl ByteSource>, field_offset: usize, field_name: &'static str) -> Self;
+
+    /// Add the index of an element to the field path.
+    fn index(self, index: usize) -> Self;
+
+    /// Record the version of the data, unless a version was already recorded.
+    fn version(self, version: Option<impl std::fmt::Display>) -> Self;
 }
 
 impl<T> ConversionResultExt for ConversionResult<T> {
@@ -78,6 +211,29 @@ impl<T> ConversionResultExt for ConversionResult<T> {
             error
         })
     }
+
+    fn field<Caller, Meta>(self, byte_stream: &ByteStream<Meta, impl ByteSource>, field_offset: usize, field_name: &'static str) -> Self {
+        self.map_err(|mut error| {
+            error.add_field::<Caller, Meta>(byte_stream, field_offset, field_name);
+            error
+        })
+    }
+
+    fn index(self, index: usize) -> Self {
+        self.map_err(|mut error| {
+            error.add_index(index);
+            error
+        })
+    }
+
+    fn version(self, version: Option<impl std::fmt::Display>) -> Self {
+        self.map_err(|mut error| {
+            if let Some(version) = version {
+                error.set_version(version.to_string());
+            }
+            error
+        })
+    }
 }
 
 #[cfg(test)]
@@ -168,3 +324,68 @@ mod type_check {
         assert!(error.is_byte_stream_too_short());
     }
 }
+
+#[cfg(test)]
+mod diagnostics {
+    use super::{ConversionError, ConversionResult, ConversionResultExt, FieldPathSegment};
+    use crate::ByteStream;
+
+    struct Outer {}
+
+    struct Inner {}
+
+    #[test]
+    fn field_path() {
+        let data = [0; 64];
+        let byte_stream = ByteStream::<()>::without_metadata(&data);
+
+        let result: ConversionResult<()> = Err(ConversionError::from_message("test"));
+        let error = result
+            .field::<Inner, _>(&byte_stream, 40, "transform")
+            .index(12)
+            .field::<Outer, _>(&byte_stream, 20, "objects")
+            .unwrap_err();
+
+        assert_eq!(error.field_path, vec![
+            FieldPathSegment::Field("objects"),
+            FieldPathSegment::Index(12),
+            FieldPathSegment::Field("transform")
+        ]);
+        assert_eq!(error.field_path().unwrap(), "Outer.objects[12].transform");
+    }
+
+    #[test]
+    fn innermost_offset() {
+        let data: Vec<u8> = (0..64).collect();
+        let byte_stream = ByteStream::<()>::without_metadata(&data);
+
+        let result: ConversionResult<()> = Err(ConversionError::from_message("test"));
+        let error = result
+            .field::<Inner, _>(&byte_stream, 60, "inner")
+            .field::<Outer, _>(&byte_stream, 8, "outer")
+            .unwrap_err();
+
+        assert_eq!(error.offset(), Some(60));
+        assert_eq!(error.byte_window().unwrap().start, 44);
+        assert_eq!(error.byte_window().unwrap().bytes, data[44..]);
+        assert!(format!("{:?}", error).contains("[3C]"));
+    }
+
+    #[test]
+    fn first_version() {
+        let result: ConversionResult<()> = Err(ConversionError::from_message("test"));
+        let error = result.version(Some("2.1")).version(None::<&str>).version(Some("1.0")).unwrap_err();
+
+        assert_eq!(error.version(), Some("2.1"));
+    }
+
+    #[test]
+    fn no_diagnostics() {
+        let error = ConversionError::from_message("test");
+
+        assert!(error.field_path().is_none());
+        assert!(error.offset().is_none());
+        assert!(error.version().is_none());
+        assert_eq!(format!("{:?}", error), "test in ");
+    }
+}
@@ -1,22 +1,60 @@
 #[cfg(feature = "cgmath")]
 use cgmath::{Matrix3, Point3, Quaternion, Vector2, Vector3, Vector4};
 
-use crate::{ByteStream, ConversionResult, ConversionResultExt, FromBytes};
+use super::from_bytes_chunked;
+use crate::{ByteSource, ByteStream, ConversionResult, ConversionResultExt, FromBytes};
+
+/// Read `count` little endian numbers in bulk.
+fn from_le_bytes_repeated<T, Meta, const SIZE: usize>(
+    byte_stream: &mut ByteStream<Meta, impl ByteSource>,
+    count: usize,
+    convert: fn([u8; SIZE]) -> T,
+) -> ConversionResult<Vec<T>> {
+    from_bytes_chunked::<Vec<T>, T, Meta>(byte_stream, count, SIZE, |bytes| {
+        Ok(convert(bytes.try_into().expect("chunks have the size of the number")))
+    })
+}
+
+/// Read `count` groups of `SIZE` values in bulk. The values of all groups are
+/// read as a single sequence, so element indices in errors refer to the
+/// position in that sequence.
+fn from_bytes_grouped<T, G, Meta, const SIZE: usize>(
+    byte_stream: &mut ByteStream<Meta, impl ByteSource>,
+    count: usize,
+    group: fn([T; SIZE]) -> G,
+) -> ConversionResult<Vec<G>>
+where
+    T: FromBytes,
+{
+    let mut values = T::from_bytes_repeated(byte_stream, count.saturating_mul(SIZE))?.into_iter();
+
+    Ok((0..count)
+        .map(|_| group(std::array::from_fn(|_| values.next().expect("all values were read"))))
+        .collect())
+}
 
 impl FromBytes for u8 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         byte_stream.byte::<Self>()
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for u16 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(Self::from_le_bytes([byte_stream.byte::<Self>()?, byte_stream.byte::<Self>()?]))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for u32 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(Self::from_le_bytes([
             byte_stream.byte::<Self>()?,
             byte_stream.byte::<Self>()?,
@@ -24,10 +62,14 @@ impl FromBytes for u32 {
             byte_stream.byte::<Self>()?,
         ]))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for u64 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(Self::from_le_bytes([
             byte_stream.byte::<Self>()?,
             byte_stream.byte::<Self>()?,
@@ -39,22 +81,34 @@ impl FromBytes for u64 {
             byte_stream.byte::<Self>()?,
         ]))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for i8 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(byte_stream.byte::<Self>()? as i8)
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for i16 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(Self::from_le_bytes([byte_stream.byte::<Self>()?, byte_stream.byte::<Self>()?]))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for i32 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(Self::from_le_bytes([
             byte_stream.byte::<Self>()?,
             byte_stream.byte::<Self>()?,
@@ -62,10 +116,14 @@ impl FromBytes for i32 {
             byte_stream.byte::<Self>()?,
         ]))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for i64 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(Self::from_le_bytes([
             byte_stream.byte::<Self>()?,
             byte_stream.byte::<Self>()?,
@@ -77,10 +135,14 @@ impl FromBytes for i64 {
             byte_stream.byte::<Self>()?,
         ]))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl FromBytes for f32 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         Ok(Self::from_le_bytes([
             byte_stream.byte::<Self>()?,
             byte_stream.byte::<Self>()?,
@@ -88,10 +150,14 @@ impl FromBytes for f32 {
             byte_stream.byte::<Self>()?,
         ]))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_le_bytes_repeated(byte_stream, count, Self::from_le_bytes)
+    }
 }
 
 impl<T: FromBytes, const SIZE: usize> FromBytes for [T; SIZE] {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         use std::mem::MaybeUninit;
 
         let mut data: [MaybeUninit<T>; SIZE] = unsafe { MaybeUninit::uninit().assume_init() };
@@ -112,25 +178,29 @@ impl<T: FromBytes, const SIZE: usize> FromBytes for [T; SIZE] {
 
         Ok(result)
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_bytes_grouped(byte_stream, count, |array| array)
+    }
 }
 
 impl FromBytes for String {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        let mut value = String::new();
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
+        let mut bytes = Vec::new();
 
         loop {
             match byte_stream.byte::<Self>()? {
                 0 => break,
-                byte => value.push(byte as char),
+                byte => bytes.push(byte),
             }
         }
 
-        Ok(value)
+        Ok(byte_stream.get_text_encoding().decode(&bytes).into_owned())
     }
 }
 
 impl<T: FromBytes> FromBytes for Vec<T> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let mut vector = Vec::new();
 
         while !byte_stream.is_empty() {
@@ -144,28 +214,36 @@ impl<T: FromBytes> FromBytes for Vec<T> {
 
 #[cfg(feature = "cgmath")]
 impl<T: FromBytes> FromBytes for Vector2<T> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let first = T::from_bytes(byte_stream).trace::<Self>()?;
         let second = T::from_bytes(byte_stream).trace::<Self>()?;
 
         Ok(Vector2::new(first, second))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_bytes_grouped(byte_stream, count, |[first, second]| Vector2::new(first, second))
+    }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: FromBytes> FromBytes for Vector3<T> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let first = T::from_bytes(byte_stream).trace::<Self>()?;
         let second = T::from_bytes(byte_stream).trace::<Self>()?;
         let third = T::from_bytes(byte_stream).trace::<Self>()?;
 
         Ok(Vector3::new(first, second, third))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_bytes_grouped(byte_stream, count, |[first, second, third]| Vector3::new(first, second, third))
+    }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: FromBytes> FromBytes for Vector4<T> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let first = T::from_bytes(byte_stream).trace::<Self>()?;
         let second = T::from_bytes(byte_stream).trace::<Self>()?;
         let third = T::from_bytes(byte_stream).trace::<Self>()?;
@@ -173,22 +251,32 @@ impl<T: FromBytes> FromBytes for Vector4<T> {
 
         Ok(Vector4::new(first, second, third, fourth))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_bytes_grouped(byte_stream, count, |[first, second, third, fourth]| {
+            Vector4::new(first, second, third, fourth)
+        })
+    }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: FromBytes> FromBytes for Point3<T> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let first = T::from_bytes(byte_stream).trace::<Self>()?;
         let second = T::from_bytes(byte_stream).trace::<Self>()?;
         let third = T::from_bytes(byte_stream).trace::<Self>()?;
 
         Ok(Point3::new(first, second, third))
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        from_bytes_grouped(byte_stream, count, |[first, second, third]| Point3::new(first, second, third))
+    }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: FromBytes> FromBytes for Quaternion<T> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let first = T::from_bytes(byte_stream).trace::<Self>()?;
         let second = T::from_bytes(byte_stream).trace::<Self>()?;
         let third = T::from_bytes(byte_stream).trace::<Self>()?;
@@ -200,7 +288,7 @@ impl<T: FromBytes> FromBytes for Quaternion<T> {
 
 #[cfg(feature = "cgmath")]
 impl<T: FromBytes> FromBytes for Matrix3<T> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let c0r0 = T::from_bytes(byte_stream).trace::<Self>()?;
         let c0r1 = T::from_bytes(byte_stream).trace::<Self>()?;
         let c0r2 = T::from_bytes(byte_stream).trace::<Self>()?;
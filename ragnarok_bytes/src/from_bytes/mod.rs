@@ -1,30 +1,134 @@
-use crate::{ByteStream, ConversionResult};
+use bytemuck::CheckedBitPattern;
+
+use crate::{ByteSource, ByteStream, ConversionError, ConversionResult, ConversionResultExt};
 
 mod implement;
 
+/// Maximum number of bytes that are requested at once when reading values in
+/// bulk. Counts are usually read from the data, so requesting all bytes up
+/// front would allow a bogus count to allocate a huge buffer in
+/// [`ReaderSource`](crate::ReaderSource).
+const BULK_CHUNK_SIZE: usize = u16::MAX as usize + 1;
+
 /// Trait to deserialize from a [`ByteStream`].
 pub trait FromBytes {
     /// Takes bytes from a [`ByteStream`] and deserializes them into a type `T`.
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self>
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self>
     where
         Self: Sized;
+
+    /// Takes bytes from a [`ByteStream`] and deserializes them into `count`
+    /// consecutive values of type `T`. Errors are annotated with the index of
+    /// the element that failed to parse.
+    ///
+    /// Types that are stored exactly like they are laid out in memory can
+    /// override this to read all values at once, see [`from_bytes_bulk`].
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>>
+    where
+        Self: Sized,
+    {
+        from_bytes_each(byte_stream, count)
+    }
 }
 
 /// Extension trait for [`FromBytes`].
 pub trait FromBytesExt: FromBytes {
     /// Takes a fixed number of bytes from the [`ByteStream`] and tries to
     /// deserialize them into a type `T`.
-    fn from_n_bytes<Meta>(byte_stream: &mut ByteStream<Meta>, size: usize) -> ConversionResult<Self>
+    fn from_n_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, size: usize) -> ConversionResult<Self>
+    where
+        Self: Sized;
+}
+
+/// Trait to deserialize types whose variant is selected by a discriminant that
+/// was read before, for example from a field of the surrounding struct.
+pub trait FromBytesWithDiscriminant {
+    /// Takes bytes from a [`ByteStream`] and deserializes them into the variant
+    /// selected by `discriminant`.
+    fn from_bytes_with_discriminant<Meta>(
+        byte_stream: &mut ByteStream<Meta, impl ByteSource>,
+        discriminant: usize,
+    ) -> ConversionResult<Self>
     where
         Self: Sized;
 }
 
+/// Creates a vector for `count` elements that are read from the data. The
+/// count is usually read from the data as well, so it can't be trusted and the
+/// capacity is capped. Every element takes at least one byte, so the vector
+/// only grows past this if the data is actually there.
+pub fn vector_for_untrusted_count<T>(count: usize) -> Vec<T> {
+    Vec::with_capacity(usize::min(count, u16::MAX as usize))
+}
+
+/// Read `count` values one after another.
+fn from_bytes_each<T, Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<T>>
+where
+    T: FromBytes,
+{
+    let mut vector = vector_for_untrusted_count(count);
+
+    for index in 0..count {
+        let element = T::from_bytes(byte_stream).index(index)?;
+        vector.push(element);
+    }
+
+    Ok(vector)
+}
+
+/// Read `count` values of `size` bytes each by decoding the bytes of the
+/// [`ByteStream`] in chunks.
+pub(crate) fn from_bytes_chunked<Caller, T, Meta>(
+    byte_stream: &mut ByteStream<Meta, impl ByteSource>,
+    count: usize,
+    size: usize,
+    decode: impl Fn(&[u8]) -> ConversionResult<T>,
+) -> ConversionResult<Vec<T>> {
+    let chunk_count = usize::max(BULK_CHUNK_SIZE / size, 1);
+    let mut vector = Vec::new();
+
+    while vector.len() < count {
+        let element_count = usize::min(count - vector.len(), chunk_count);
+        let bytes = byte_stream.slice::<Caller>(element_count * size)?;
+
+        vector.reserve_exact(element_count);
+
+        for element_bytes in bytes.chunks_exact(size) {
+            let element = decode(element_bytes).index(vector.len())?;
+            vector.push(element);
+        }
+    }
+
+    Ok(vector)
+}
+
+/// Bulk implementation of [`FromBytes::from_bytes_repeated`] for types that
+/// are serialized exactly like their in-memory representation (`#[repr(C)]`
+/// without padding, little endian). Values are copied straight out of the
+/// [`ByteStream`] and only their bit patterns are validated.
+///
+/// The `ByteConvertable` derive uses this for structs marked with `#[bulk]`.
+pub fn from_bytes_bulk<T, Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<T>>
+where
+    T: FromBytes + CheckedBitPattern,
+{
+    // The in-memory representation only matches on little endian targets.
+    if cfg!(target_endian = "big") {
+        return from_bytes_each(byte_stream, count);
+    }
+
+    from_bytes_chunked::<Vec<T>, T, Meta>(byte_stream, count, std::mem::size_of::<T>(), |bytes| {
+        bytemuck::checked::try_pod_read_unaligned(bytes)
+            .map_err(|_| ConversionError::from_message(format!("invalid value for {}", std::any::type_name::<T>())))
+    })
+}
+
 impl<T> FromBytesExt for T
 where
     T: FromBytes,
 {
     #[allow(clippy::uninit_assumed_init)]
-    fn from_n_bytes<Meta>(byte_stream: &mut ByteStream<Meta>, size: usize) -> ConversionResult<Self>
+    fn from_n_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, size: usize) -> ConversionResult<Self>
     where
         Self: Sized,
     {
@@ -48,7 +152,7 @@ mod from_n_bytes {
     const TEST_BYTE_SIZE: usize = 4;
 
     impl FromBytes for Test {
-        fn from_bytes<Meta>(byte_stream: &mut crate::ByteStream<Meta>) -> crate::ConversionResult<Self>
+        fn from_bytes<Meta>(byte_stream: &mut crate::ByteStream<Meta, impl crate::ByteSource>) -> crate::ConversionResult<Self>
         where
             Self: Sized,
         {
@@ -98,3 +202,76 @@ mod from_n_bytes {
         assert_eq!(byte_stream.remaining_bytes().len(), TEST_BYTE_SIZE / 2);
     }
 }
+
+#[cfg(test)]
+mod from_bytes_repeated {
+    use super::{from_bytes_bulk, FromBytes, BULK_CHUNK_SIZE};
+    use crate::{ByteStream, ReaderByteStream};
+
+    const TEST_BYTES: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn one_by_one<T: FromBytes>(bytes: &[u8], count: usize) -> Vec<T> {
+        let mut byte_stream = ByteStream::<()>::without_metadata(bytes);
+        (0..count).map(|_| T::from_bytes(&mut byte_stream).unwrap()).collect()
+    }
+
+    #[test]
+    fn numbers() {
+        let mut byte_stream = ByteStream::<()>::without_metadata(TEST_BYTES);
+
+        assert_eq!(
+            u16::from_bytes_repeated(&mut byte_stream, 3).unwrap(),
+            one_by_one::<u16>(TEST_BYTES, 3)
+        );
+        assert_eq!(byte_stream.remaining_bytes(), [7, 8]);
+    }
+
+    #[test]
+    fn arrays() {
+        let mut byte_stream = ByteStream::<()>::without_metadata(TEST_BYTES);
+
+        assert_eq!(
+            <[i16; 2]>::from_bytes_repeated(&mut byte_stream, 2).unwrap(),
+            one_by_one::<[i16; 2]>(TEST_BYTES, 2)
+        );
+        assert!(byte_stream.is_empty());
+    }
+
+    #[test]
+    fn bulk() {
+        let mut byte_stream = ByteStream::<()>::without_metadata(TEST_BYTES);
+
+        assert_eq!(
+            from_bytes_bulk::<u32, _>(&mut byte_stream, 2).unwrap(),
+            one_by_one::<u32>(TEST_BYTES, 2)
+        );
+        assert!(byte_stream.is_empty());
+    }
+
+    #[test]
+    fn data_missing() {
+        let mut byte_stream = ByteStream::<()>::without_metadata(TEST_BYTES);
+
+        assert!(u32::from_bytes_repeated(&mut byte_stream, 3).is_err());
+    }
+
+    #[test]
+    fn multiple_chunks() {
+        let data: Vec<u8> = (0..BULK_CHUNK_SIZE * 2 + 6).map(|index| index as u8).collect();
+        let count = data.len() / 2;
+        let mut byte_stream = ReaderByteStream::<_>::from_reader(data.as_slice());
+
+        assert_eq!(
+            u16::from_bytes_repeated(&mut byte_stream, count).unwrap(),
+            one_by_one::<u16>(&data, count)
+        );
+        assert!(byte_stream.is_empty());
+    }
+
+    #[test]
+    fn bogus_count() {
+        let mut byte_stream = ReaderByteStream::<_>::from_reader(TEST_BYTES);
+
+        assert!(u64::from_bytes_repeated(&mut byte_stream, usize::MAX / 8).is_err());
+    }
+}
@@ -1,29 +1,37 @@
 #![cfg_attr(test, feature(assert_matches))]
 
+mod encoding;
 mod error;
 mod fixed;
 mod from_bytes;
+mod source;
 mod stream;
+#[cfg(feature = "testing")]
+pub mod testing;
 mod to_bytes;
+mod writer;
 
 #[cfg(feature = "derive")]
 pub use ragnarok_procedural::{ByteConvertable, FixedByteSize, FromBytes, ToBytes};
 
-pub use self::error::{ConversionError, ConversionErrorType, ConversionResult, ConversionResultExt};
+pub use self::encoding::TextEncoding;
+pub use self::error::{ByteWindow, ConversionError, ConversionErrorType, ConversionResult, ConversionResultExt, FieldPathSegment};
 pub use self::fixed::{FixedByteSize, FixedByteSizeCollection};
-pub use self::from_bytes::{FromBytes, FromBytesExt};
-pub use self::stream::ByteStream;
-pub use self::to_bytes::{ToBytes, ToBytesExt};
+pub use self::from_bytes::{from_bytes_bulk, vector_for_untrusted_count, FromBytes, FromBytesExt, FromBytesWithDiscriminant};
+pub use self::source::{ByteSource, ReaderSource};
+pub use self::stream::{ByteStream, ReaderByteStream};
+pub use self::to_bytes::{ToBytes, ToBytesExt, ToBytesWithDiscriminant};
+pub use self::writer::ByteWriter;
 
 #[cfg(test)]
 mod conversion {
-    use crate::{ByteStream, FromBytes, ToBytes};
+    use crate::{ByteStream, ByteWriter, FromBytes, TextEncoding, ToBytes, ToBytesExt};
 
     fn encode_decode<T: FromBytes + ToBytes>(input: &[u8]) {
         let mut byte_stream = ByteStream::<()>::without_metadata(input);
 
         let data = T::from_bytes(&mut byte_stream).unwrap();
-        let output = data.to_bytes().unwrap();
+        let output = data.to_bytes_vec::<()>().unwrap();
 
         assert_eq!(input, output.as_slice());
     }
@@ -83,6 +91,22 @@ mod conversion {
         encode_decode::<String>(b"testing\0");
     }
 
+    #[test]
+    pub fn string_with_text_encoding() {
+        let input = [0xBE, 0xC6, 0xC0, 0xCC, 0xC5, 0xDB, 0];
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input).with_text_encoding(TextEncoding::Windows949);
+
+        let data = String::from_bytes(&mut byte_stream).unwrap();
+        assert_eq!(data, "아이템");
+
+        let mut byte_writer = ByteWriter::<()>::without_metadata().with_text_encoding(TextEncoding::Windows949);
+        data.to_bytes(&mut byte_writer).unwrap();
+        assert_eq!(byte_writer.into_inner(), input);
+
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        assert!(data.to_bytes(&mut byte_writer).is_err());
+    }
+
     #[test]
     pub fn vector() {
         encode_decode::<Vec<u8>>(&[1, 2, 3, 4]);
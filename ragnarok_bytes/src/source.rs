@@ -0,0 +1,187 @@
+use std::io::{ErrorKind, Read};
+use std::ops::Range;
+
+use crate::{ConversionError, ConversionResult};
+
+/// Minimum number of bytes requested from a reader at once.
+const MINIMUM_READ_SIZE: usize = 4096;
+/// Maximum number of bytes requested from a reader at once. Bigger fills are
+/// split into multiple reads, so the buffer only grows with the data that the
+/// reader actually returns and not with the requested size.
+const MAXIMUM_READ_SIZE: usize = 64 * 1024;
+
+/// Storage that a [`ByteStream`](crate::ByteStream) reads its bytes from.
+/// Offsets are always relative to the start of the source, even if the source
+/// already discarded some of its bytes.
+pub trait ByteSource {
+    /// Make sure that all bytes before `end` are buffered. Returns `false` if
+    /// the source ends before `end`.
+    fn fill(&mut self, end: usize) -> ConversionResult<bool>;
+
+    /// Get a range of buffered bytes. The range has to be filled before.
+    fn bytes(&self, range: Range<usize>) -> &[u8];
+
+    /// Get the range of bytes that is currently buffered.
+    fn buffered_range(&self) -> Range<usize>;
+
+    /// Allow the source to discard all bytes before `offset`.
+    fn release(&mut self, offset: usize);
+}
+
+impl ByteSource for &[u8] {
+    fn fill(&mut self, end: usize) -> ConversionResult<bool> {
+        Ok(end <= self.len())
+    }
+
+    fn bytes(&self, range: Range<usize>) -> &[u8] {
+        &self[range]
+    }
+
+    fn buffered_range(&self) -> Range<usize> {
+        0..self.len()
+    }
+
+    fn release(&mut self, _offset: usize) {}
+}
+
+/// A [`ByteSource`] that buffers the bytes of an [`std::io::Read`]. Bytes
+/// that were released are discarded once they make up at least half of the
+/// buffer, so the buffer only grows with the size of the biggest read.
+pub struct ReaderSource<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    buffer_start: usize,
+    is_finished: bool,
+}
+
+impl<R> ReaderSource<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            is_finished: false,
+        }
+    }
+
+    pub fn into_reader(self) -> R {
+        self.reader
+    }
+
+    fn buffer_end(&self) -> usize {
+        self.buffer_start + self.buffer.len()
+    }
+}
+
+impl<R: Read> ByteSource for ReaderSource<R> {
+    fn fill(&mut self, end: usize) -> ConversionResult<bool> {
+        while self.buffer_end() < end && !self.is_finished {
+            let old_length = self.buffer.len();
+            let read_size = (end - self.buffer_end()).clamp(MINIMUM_READ_SIZE, MAXIMUM_READ_SIZE);
+            self.buffer.resize(old_length + read_size, 0);
+
+            let result = self.reader.read(&mut self.buffer[old_length..]);
+            let read_count = match result {
+                Ok(read_count) => read_count,
+                Err(ref error) if error.kind() == ErrorKind::Interrupted => 0,
+                Err(error) => {
+                    self.buffer.truncate(old_length);
+                    return Err(ConversionError::from_message(format!("failed to read from source: {error}")));
+                }
+            };
+
+            self.buffer.truncate(old_length + read_count);
+            self.is_finished = read_count == 0 && result.is_ok();
+        }
+
+        Ok(self.buffer_end() >= end)
+    }
+
+    fn bytes(&self, range: Range<usize>) -> &[u8] {
+        &self.buffer[range.start - self.buffer_start..range.end - self.buffer_start]
+    }
+
+    fn buffered_range(&self) -> Range<usize> {
+        self.buffer_start..self.buffer_end()
+    }
+
+    fn release(&mut self, offset: usize) {
+        let releasable = offset.saturating_sub(self.buffer_start).min(self.buffer.len());
+
+        // Only discard big chunks, to avoid moving the buffer on every read.
+        if releasable >= MINIMUM_READ_SIZE && releasable * 2 >= self.buffer.len() {
+            self.buffer.drain(..releasable);
+            self.buffer_start += releasable;
+        }
+    }
+}
+
+#[cfg(test)]
+mod reader_source {
+    use std::io::Read;
+
+    use super::{ByteSource, ReaderSource, MAXIMUM_READ_SIZE, MINIMUM_READ_SIZE};
+
+    /// Reader that returns at most one byte per read.
+    struct SingleByteReader<'a>(&'a [u8]);
+
+    impl Read for SingleByteReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            self.0.take(1).read(buffer).inspect(|read_count| self.0 = &self.0[*read_count..])
+        }
+    }
+
+    #[test]
+    fn fill() {
+        let data = [1, 2, 3, 4];
+        let mut source = ReaderSource::new(SingleByteReader(&data));
+
+        assert!(source.fill(3).unwrap());
+        assert_eq!(source.bytes(1..3), &[2, 3]);
+        assert!(source.fill(4).unwrap());
+        assert!(!source.fill(5).unwrap());
+        assert_eq!(source.buffered_range(), 0..4);
+    }
+
+    #[test]
+    fn fill_beyond_end() {
+        let data = [1, 2, 3, 4];
+        let mut source = ReaderSource::new(data.as_slice());
+
+        assert!(!source.fill(usize::MAX / 2).unwrap());
+        assert_eq!(source.buffered_range(), 0..4);
+        assert!(source.buffer.capacity() <= MAXIMUM_READ_SIZE * 2);
+    }
+
+    #[test]
+    fn fill_in_steps() {
+        let data = vec![7; MAXIMUM_READ_SIZE * 3];
+        let mut source = ReaderSource::new(data.as_slice());
+
+        assert!(source.fill(MAXIMUM_READ_SIZE * 2 + 1).unwrap());
+        assert!(source.buffered_range().end > MAXIMUM_READ_SIZE * 2);
+        assert_eq!(source.bytes(MAXIMUM_READ_SIZE * 2..MAXIMUM_READ_SIZE * 2 + 1), &[7]);
+    }
+
+    #[test]
+    fn release() {
+        let data: Vec<u8> = (0..MINIMUM_READ_SIZE * 3).map(|index| index as u8).collect();
+        let mut source = ReaderSource::new(data.as_slice());
+
+        assert!(source.fill(MINIMUM_READ_SIZE * 2).unwrap());
+
+        // Small releases keep the buffer.
+        source.release(10);
+        assert_eq!(source.buffered_range().start, 0);
+
+        source.release(MINIMUM_READ_SIZE + 10);
+        assert_eq!(source.buffered_range().start, MINIMUM_READ_SIZE + 10);
+        assert_eq!(
+            source.bytes(MINIMUM_READ_SIZE + 10..MINIMUM_READ_SIZE + 12),
+            &data[MINIMUM_READ_SIZE + 10..][..2]
+        );
+
+        assert!(source.fill(MINIMUM_READ_SIZE * 3).unwrap());
+        assert_eq!(source.buffered_range().end, MINIMUM_READ_SIZE * 3);
+    }
+}
@@ -1,6 +1,8 @@
 use std::any::TypeId;
+use std::io::Read;
+use std::marker::PhantomData;
 
-use crate::{ConversionError, ConversionErrorType, ConversionResult};
+use crate::{ByteSource, ConversionError, ConversionErrorType, ConversionResult, ReaderSource, TextEncoding};
 
 /// Saved state of a [`ByteStream`] that can be restored.
 #[derive(Debug, PartialEq, Eq)]
@@ -15,30 +17,49 @@ pub(crate) struct TemporaryLimit {
     old_limit: usize,
 }
 
-/// A stream of bytes that iterates over borrowed data. It can produce single
+/// Number of bytes before the current offset that a [`ByteSource`] has to
+/// keep, so that save points and error diagnostics can refer to them.
+const RETAINED_BYTES: usize = u16::MAX as usize;
+
+/// A stream of bytes that iterates over a [`ByteSource`]. It can produce single
 /// bytes or slices of memory and carries metadata about the read operation (for
 /// example a version).
 ///
+/// By default the stream reads from borrowed data. Streams created with
+/// [`from_reader`](ByteStream::from_reader) read from an [`std::io::Read`]
+/// with a bounded buffer instead, so the data doesn't need to be loaded into
+/// memory up front.
+///
 /// The stream is intended for reading data without lookahead.
 ///
 /// The state of the stream can be saved at any time with
 /// [`create_save_point`](ByteStream::create_save_point), and restored with
-/// [`restore_save_point`](ByteStream::restore_save_point).
+/// [`restore_save_point`](ByteStream::restore_save_point). Streams reading
+/// from a reader only keep the last 64 KiB, so restoring a save point fails
+/// after reading past that.
+///
+/// Strings are decoded with the [`TextEncoding`] of the stream, which can be
+/// set with [`with_text_encoding`](ByteStream::with_text_encoding).
 ///
 /// NOTE: The save point *does not* restore the previous state of the metadata.
 /// It should therefore be avoided to modify the metadata while reading of
 /// composite structures data that might fail, for example multi-field structs
 /// that implement [`FromBytes`](crate::from_bytes::FromBytes).
-pub struct ByteStream<'a, Meta = ()>
+pub struct ByteStream<'a, Meta = (), Source = &'a [u8]>
 where
     Meta: 'static,
 {
-    data: &'a [u8],
+    source: Source,
     offset: usize,
     limit: usize,
     metadata: Meta,
+    text_encoding: TextEncoding,
+    _lifetime: PhantomData<&'a [u8]>,
 }
 
+/// A [`ByteStream`] that reads from an [`std::io::Read`].
+pub type ReaderByteStream<R, Meta = ()> = ByteStream<'static, Meta, ReaderSource<R>>;
+
 impl<'a, Meta> ByteStream<'a, Meta>
 where
     Meta: Default + 'static,
@@ -56,15 +77,57 @@ where
     /// Create a new [`ByteStream`] with specific metadata.
     pub fn with_metadata(data: &'a [u8], metadata: Meta) -> Self {
         let limit = data.len();
+        Self::from_source(data, limit, metadata)
+    }
+}
 
+impl<R, Meta> ReaderByteStream<R, Meta>
+where
+    R: Read,
+    Meta: 'static,
+{
+    /// Create a new [`ByteStream`] that reads from a reader, with default
+    /// metadata.
+    pub fn from_reader(reader: R) -> Self
+    where
+        Meta: Default,
+    {
+        Self::from_reader_with_metadata(reader, Default::default())
+    }
+
+    /// Create a new [`ByteStream`] that reads from a reader, with specific
+    /// metadata.
+    pub fn from_reader_with_metadata(reader: R, metadata: Meta) -> Self {
+        Self::from_source(ReaderSource::new(reader), usize::MAX, metadata)
+    }
+}
+
+impl<Meta, Source> ByteStream<'_, Meta, Source>
+where
+    Meta: 'static,
+    Source: ByteSource,
+{
+    fn from_source(source: Source, limit: usize, metadata: Meta) -> Self {
         Self {
-            data,
+            source,
             offset: 0,
             limit,
             metadata,
+            text_encoding: TextEncoding::default(),
+            _lifetime: PhantomData,
         }
     }
 
+    /// Set the [`TextEncoding`] used to decode strings.
+    pub fn with_text_encoding(mut self, text_encoding: TextEncoding) -> Self {
+        self.text_encoding = text_encoding;
+        self
+    }
+
+    pub fn get_text_encoding(&self) -> TextEncoding {
+        self.text_encoding
+    }
+
     pub fn get_offset(&self) -> usize {
         self.offset
     }
@@ -84,13 +147,21 @@ where
     // while reading.
     //
     // E.g: `Reusable` or `Rollback` trait.
-    pub fn restore_save_point(&mut self, save_point: SavePoint) {
+    pub fn restore_save_point(&mut self, save_point: SavePoint) -> ConversionResult<()> {
+        if save_point.offset < self.source.buffered_range().start {
+            return Err(ConversionError::from_message(
+                "the bytes of the save point were already discarded",
+            ));
+        }
+
         self.offset = save_point.offset;
         self.limit = save_point.limit;
+
+        Ok(())
     }
 
     pub(crate) fn install_limit<Caller>(&mut self, size: usize) -> ConversionResult<TemporaryLimit> {
-        let frame_limit = self.offset + size;
+        let frame_limit = self.offset.saturating_add(size);
         let old_limit = self.limit;
 
         if frame_limit > old_limit {
@@ -109,8 +180,10 @@ where
         self.limit = limits.old_limit;
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.offset >= self.limit
+    /// Check if there are no more bytes to read. Errors of the source are
+    /// not reported here but by the next read.
+    pub fn is_empty(&mut self) -> bool {
+        self.offset >= self.limit || matches!(self.source.fill(self.offset + 1), Ok(false))
     }
 
     pub fn get_metadata<Caller, As>(&self) -> ConversionResult<&As>
@@ -141,8 +214,13 @@ where
         self.metadata
     }
 
-    fn check_upper_bound<Caller>(offset: usize, length: usize) -> ConversionResult<()> {
-        match offset < length {
+    /// Make sure that the next `count` bytes can be read.
+    fn request<Caller>(&mut self, count: usize) -> ConversionResult<()> {
+        let end = self.offset + count;
+
+        self.source.release(self.offset.saturating_sub(RETAINED_BYTES));
+
+        match end <= self.limit && self.source.fill(end)? {
             true => Ok(()),
             false => Err(ConversionError::from_error_type(ConversionErrorType::ByteStreamTooShort {
                 type_name: std::any::type_name::<Caller>(),
@@ -151,25 +229,41 @@ where
     }
 
     pub fn byte<Caller>(&mut self) -> ConversionResult<u8> {
-        Self::check_upper_bound::<Caller>(self.offset, self.limit)?;
+        self.request::<Caller>(1)?;
 
-        let byte = self.data[self.offset];
+        let byte = self.source.bytes(self.offset..self.offset + 1)[0];
         self.offset += 1;
         Ok(byte)
     }
 
     pub fn slice<Caller>(&mut self, count: usize) -> ConversionResult<&[u8]> {
-        Self::check_upper_bound::<Caller>(self.offset + count, self.limit + 1)?;
+        self.request::<Caller>(count)?;
 
         let start_index = self.offset;
         self.offset += count;
 
-        Ok(&self.data[start_index..self.offset])
+        Ok(self.source.bytes(start_index..self.offset))
+    }
+
+    /// Get the buffered bytes within `radius` of an offset, together with the
+    /// offset of the first byte. Used to give context to errors.
+    pub(crate) fn window(&self, offset: usize, radius: usize) -> (usize, &[u8]) {
+        let buffered_range = self.source.buffered_range();
+        let start = offset.saturating_sub(radius).clamp(buffered_range.start, buffered_range.end);
+        let end = offset.saturating_add(radius).clamp(start, buffered_range.end);
+
+        (start, self.source.bytes(start..end))
     }
 
     pub fn remaining_bytes(&mut self) -> Vec<u8> {
-        let data = self.data[self.offset..self.limit].to_vec();
-        self.offset = self.limit;
+        let mut data = Vec::new();
+
+        while !self.is_empty() {
+            let end = usize::min(self.source.buffered_range().end, self.limit);
+            data.extend_from_slice(self.source.bytes(self.offset..end));
+            self.offset = end;
+        }
+
         data
     }
 }
@@ -195,7 +289,7 @@ mod save_point {
         assert_eq!(byte_stream.offset, TEST_BYTE_SIZE / 2);
         assert_eq!(byte_stream.limit, TEST_BYTE_SIZE / 2);
 
-        byte_stream.restore_save_point(save_point);
+        byte_stream.restore_save_point(save_point).unwrap();
 
         assert_eq!(byte_stream.offset, 0);
         assert_eq!(byte_stream.limit, TEST_BYTE_SIZE);
@@ -346,3 +440,81 @@ mod remaining_bytes {
         assert!(byte_stream.remaining_bytes().is_empty());
     }
 }
+
+#[cfg(test)]
+mod reader {
+    use std::assert_matches::assert_matches;
+
+    use crate::{ByteSource, FromBytes, ReaderByteStream};
+
+    const TEST_BYTES: &[u8] = &[1, 2, 3, 4, 5, 6];
+
+    #[test]
+    fn read() {
+        let mut byte_stream = ReaderByteStream::<_>::from_reader(TEST_BYTES);
+
+        assert_matches!(byte_stream.byte::<()>(), Ok(1));
+        assert_matches!(byte_stream.slice::<()>(2), Ok(&[2, 3]));
+        assert_eq!(u16::from_bytes(&mut byte_stream).unwrap(), 0x0504);
+        assert!(!byte_stream.is_empty());
+        assert!(byte_stream.slice::<()>(2).is_err());
+        assert_eq!(byte_stream.remaining_bytes(), [6]);
+        assert!(byte_stream.is_empty());
+    }
+
+    #[test]
+    fn save_point() {
+        let mut byte_stream = ReaderByteStream::<_>::from_reader(TEST_BYTES);
+
+        assert!(byte_stream.byte::<()>().is_ok());
+        let save_point = byte_stream.create_save_point();
+        assert!(byte_stream.slice::<()>(4).is_ok());
+        byte_stream.restore_save_point(save_point).unwrap();
+
+        assert_eq!(byte_stream.get_offset(), 1);
+        assert_eq!(byte_stream.remaining_bytes(), &TEST_BYTES[1..]);
+    }
+
+    #[test]
+    fn discarded_save_point() {
+        let data = vec![0; u16::MAX as usize * 4];
+        let mut byte_stream = ReaderByteStream::<_>::from_reader(data.as_slice());
+
+        let save_point = byte_stream.create_save_point();
+        for _ in &data {
+            byte_stream.byte::<()>().unwrap();
+        }
+
+        assert!(byte_stream.restore_save_point(save_point).is_err());
+        assert_eq!(byte_stream.get_offset(), data.len());
+    }
+
+    #[test]
+    fn bounded_buffer() {
+        let data: Vec<u8> = (0..u16::MAX as usize * 4).map(|index| index as u8).collect();
+        let mut byte_stream = ReaderByteStream::<_>::from_reader(data.as_slice());
+
+        for &expected in &data {
+            assert_eq!(byte_stream.byte::<()>().unwrap(), expected);
+        }
+
+        assert!(byte_stream.is_empty());
+        assert!(byte_stream.source.buffered_range().len() < data.len() / 2);
+    }
+
+    #[test]
+    fn reader_error() {
+        struct FailingReader;
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, _buffer: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("test"))
+            }
+        }
+
+        let mut byte_stream = ReaderByteStream::<_>::from_reader(FailingReader);
+
+        assert!(!byte_stream.is_empty());
+        assert!(byte_stream.byte::<()>().is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Property tests for [`FromBytes`] and [`ToBytes`] implementations.
+//!
+//! Values are generated by decoding arbitrary bytes, so they always respect
+//! the invariants of the type (lengths, counts and version restricted
+//! fields). The `ByteConvertable` derive uses this to generate a round trip
+//! test for every type.
+use std::any::type_name;
+use std::cell::Cell;
+use std::fmt::Debug;
+
+pub use proptest;
+use proptest::collection::vec;
+use proptest::prelude::{any, Just, Strategy};
+use proptest::prop_oneof;
+use proptest::test_runner::{TestCaseError, TestRunner};
+
+use crate::{ByteStream, ByteWriter, FromBytes, ToBytes};
+
+/// Maximum number of arbitrary bytes that a value is decoded from.
+pub const MAXIMUM_INPUT_SIZE: usize = 4096;
+
+/// Arbitrary input for [`check_round_trip`]. The share of zero bytes varies
+/// between inputs, so counts, lengths and discriminants are small often
+/// enough for the input to decode.
+pub fn arbitrary_bytes() -> impl Strategy<Value = Vec<u8>> {
+    (0u32..64).prop_flat_map(|zero_weight| vec(prop_oneof![zero_weight => Just(0), 1 => any::<u8>()], 0..MAXIMUM_INPUT_SIZE))
+}
+
+fn encode<T, Meta>(value: &T, metadata: Meta) -> Result<Vec<u8>, TestCaseError>
+where
+    T: ToBytes,
+    Meta: 'static,
+{
+    let mut byte_writer = ByteWriter::with_metadata(metadata);
+
+    value
+        .to_bytes(&mut byte_writer)
+        .map_err(|error| TestCaseError::fail(format!("failed to encode decoded value: {error:?}")))?;
+
+    Ok(byte_writer.into_inner())
+}
+
+/// Decodes `bytes` and checks that the value survives a round trip. Encoding
+/// may normalize the value (for example padding of strings), but encoding
+/// the decoded encoding again must produce the exact same bytes.
+///
+/// Bytes that don't decode are fine, as long as decoding doesn't panic.
+/// Returns whether the bytes decoded.
+pub fn check_round_trip<T, Meta>(bytes: &[u8], metadata: Meta) -> Result<bool, TestCaseError>
+where
+    T: FromBytes + ToBytes,
+    Meta: Clone + Debug + 'static,
+{
+    let mut byte_stream = ByteStream::with_metadata(bytes, metadata.clone());
+
+    let Ok(value) = T::from_bytes(&mut byte_stream) else {
+        return Ok(false);
+    };
+
+    let encoded = encode(&value, metadata.clone())?;
+    let mut byte_stream = ByteStream::with_metadata(encoded.as_slice(), metadata.clone());
+
+    let decoded = T::from_bytes(&mut byte_stream)
+        .map_err(|error| TestCaseError::fail(format!("failed to decode {encoded:?} with {metadata:?}: {error:?}")))?;
+
+    if !byte_stream.is_empty() {
+        return Err(TestCaseError::fail(format!(
+            "decoding {encoded:?} with {metadata:?} left {} bytes",
+            byte_stream.remaining_bytes().len()
+        )));
+    }
+
+    let encoded_again = encode(&decoded, metadata.clone())?;
+
+    match encoded == encoded_again {
+        true => Ok(true),
+        false => Err(TestCaseError::fail(format!(
+            "encoding is not stable with {metadata:?}: {encoded:?} became {encoded_again:?}"
+        ))),
+    }
+}
+
+/// Runs [`check_round_trip`] for arbitrary bytes starting with a `prefix` (for
+/// example the signature of a file format or a valid discriminant) and
+/// arbitrary metadata. Fails if none of the inputs decode, since nothing would
+/// be tested in that case.
+pub fn test_round_trip<T, Meta>(prefix: impl Strategy<Value = Vec<u8>>, metadata: impl Strategy<Value = Meta>)
+where
+    T: FromBytes + ToBytes,
+    Meta: Clone + Debug + 'static,
+{
+    let mut test_runner = TestRunner::default();
+    let decoded_count = Cell::new(0);
+
+    let result = test_runner.run(&(prefix, arbitrary_bytes(), metadata), |(prefix, bytes, metadata)| {
+        let input = [prefix, bytes].concat();
+
+        if check_round_trip::<T, _>(&input, metadata)? {
+            decoded_count.set(decoded_count.get() + 1);
+        }
+
+        Ok(())
+    });
+
+    if let Err(error) = result {
+        panic!("round trip of {} failed: {error}", type_name::<T>());
+    }
+
+    assert!(decoded_count.get() > 0, "no input decoded to {}", type_name::<T>());
+}
+
+#[cfg(test)]
+mod round_trip {
+    use proptest::prelude::Just;
+
+    use super::test_round_trip;
+    use crate::{ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, FromBytes, ToBytes};
+
+    struct Undecodable;
+
+    impl FromBytes for Undecodable {
+        fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
+            let _ = byte_stream;
+            Err(ConversionError::from_message("undecodable"))
+        }
+    }
+
+    impl ToBytes for Undecodable {
+        fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+            let _ = byte_writer;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn primitives() {
+        test_round_trip::<u8, _>(Just(Vec::new()), Just(()));
+        test_round_trip::<i16, _>(Just(Vec::new()), Just(()));
+        test_round_trip::<u32, _>(Just(Vec::new()), Just(()));
+        test_round_trip::<f32, _>(Just(Vec::new()), Just(()));
+        test_round_trip::<[u16; 3], _>(Just(Vec::new()), Just(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "no input decoded")]
+    fn nothing_decoded() {
+        test_round_trip::<Undecodable, _>(Just(Vec::new()), Just(()));
+    }
+}
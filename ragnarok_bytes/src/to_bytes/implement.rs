@@ -1,164 +1,167 @@
 #[cfg(feature = "cgmath")]
 use cgmath::{Matrix3, Point3, Quaternion, Vector2, Vector3, Vector4};
 
-use crate::{ConversionResult, ConversionResultExt, ToBytes};
+use crate::{ByteWriter, ConversionError, ConversionResult, ConversionResultExt, ToBytes};
 
 impl ToBytes for u8 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(vec![*self])
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.push(*self);
+        Ok(())
     }
 }
 
 impl ToBytes for u16 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.to_le_bytes().to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.to_le_bytes());
+        Ok(())
     }
 }
 
 impl ToBytes for u32 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.to_le_bytes().to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.to_le_bytes());
+        Ok(())
     }
 }
 
 impl ToBytes for u64 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.to_le_bytes().to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.to_le_bytes());
+        Ok(())
     }
 }
 
 impl ToBytes for i8 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(vec![*self as u8])
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.push(*self as u8);
+        Ok(())
     }
 }
 
 impl ToBytes for i16 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.to_le_bytes().to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.to_le_bytes());
+        Ok(())
     }
 }
 
 impl ToBytes for i32 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.to_le_bytes().to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.to_le_bytes());
+        Ok(())
     }
 }
 
 impl ToBytes for i64 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.to_le_bytes().to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.to_le_bytes());
+        Ok(())
     }
 }
 
 impl ToBytes for f32 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.to_ne_bytes().to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.to_ne_bytes());
+        Ok(())
     }
 }
 
 impl<T: ToBytes, const SIZE: usize> ToBytes for [T; SIZE] {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = Vec::new();
-
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
         for item in self.iter() {
-            let item = item.to_bytes().trace::<Self>()?;
-            bytes.extend(item);
+            item.to_bytes(byte_writer).trace::<Self>()?;
         }
 
-        Ok(bytes)
+        Ok(())
     }
 }
 
 impl ToBytes for String {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(self.bytes().chain(std::iter::once(0)).collect())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        let text_encoding = byte_writer.get_text_encoding();
+        let bytes = text_encoding
+            .encode(self)
+            .ok_or_else(|| ConversionError::from_message(format!("{self:?} can't be represented in {text_encoding:?}")))?;
+
+        byte_writer.extend_from_slice(&bytes);
+        byte_writer.push(0);
+        Ok(())
     }
 }
 
 impl<T: ToBytes> ToBytes for Vec<T> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = Vec::new();
-
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
         for item in self.iter() {
-            let item = item.to_bytes().trace::<Self>()?;
-            bytes.extend(item);
+            item.to_bytes(byte_writer).trace::<Self>()?;
         }
 
-        Ok(bytes)
+        Ok(())
     }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: ToBytes> ToBytes for Vector2<T> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = self.x.to_bytes().trace::<Self>()?;
-        bytes.append(&mut self.y.to_bytes().trace::<Self>()?);
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.x.to_bytes(byte_writer).trace::<Self>()?;
+        self.y.to_bytes(byte_writer).trace::<Self>()?;
 
-        Ok(bytes)
+        Ok(())
     }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: ToBytes> ToBytes for Vector3<T> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = self.x.to_bytes().trace::<Self>()?;
-        bytes.append(&mut self.y.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.z.to_bytes().trace::<Self>()?);
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.x.to_bytes(byte_writer).trace::<Self>()?;
+        self.y.to_bytes(byte_writer).trace::<Self>()?;
+        self.z.to_bytes(byte_writer).trace::<Self>()?;
 
-        Ok(bytes)
+        Ok(())
     }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: ToBytes> ToBytes for Vector4<T> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = self.x.to_bytes().trace::<Self>()?;
-        bytes.append(&mut self.y.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.z.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.w.to_bytes().trace::<Self>()?);
-        Ok(bytes)
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.x.to_bytes(byte_writer).trace::<Self>()?;
+        self.y.to_bytes(byte_writer).trace::<Self>()?;
+        self.z.to_bytes(byte_writer).trace::<Self>()?;
+        self.w.to_bytes(byte_writer).trace::<Self>()?;
+
+        Ok(())
     }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: ToBytes> ToBytes for Point3<T> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = self.x.to_bytes().trace::<Self>()?;
-        bytes.append(&mut self.y.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.z.to_bytes().trace::<Self>()?);
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.x.to_bytes(byte_writer).trace::<Self>()?;
+        self.y.to_bytes(byte_writer).trace::<Self>()?;
+        self.z.to_bytes(byte_writer).trace::<Self>()?;
 
-        Ok(bytes)
+        Ok(())
     }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: ToBytes> ToBytes for Quaternion<T> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = self.v.x.to_bytes().trace::<Self>()?;
-        bytes.append(&mut self.v.y.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.v.z.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.s.to_bytes().trace::<Self>()?);
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.v.x.to_bytes(byte_writer).trace::<Self>()?;
+        self.v.y.to_bytes(byte_writer).trace::<Self>()?;
+        self.v.z.to_bytes(byte_writer).trace::<Self>()?;
+        self.s.to_bytes(byte_writer).trace::<Self>()?;
 
-        Ok(bytes)
+        Ok(())
     }
 }
 
 #[cfg(feature = "cgmath")]
 impl<T: ToBytes> ToBytes for Matrix3<T> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = self.x.x.to_bytes().trace::<Self>()?;
-        bytes.append(&mut self.x.y.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.x.z.to_bytes().trace::<Self>()?);
-
-        bytes.append(&mut self.y.x.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.y.y.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.y.z.to_bytes().trace::<Self>()?);
-
-        bytes.append(&mut self.z.x.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.z.y.to_bytes().trace::<Self>()?);
-        bytes.append(&mut self.z.z.to_bytes().trace::<Self>()?);
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.x.to_bytes(byte_writer).trace::<Self>()?;
+        self.y.to_bytes(byte_writer).trace::<Self>()?;
+        self.z.to_bytes(byte_writer).trace::<Self>()?;
 
-        Ok(bytes)
+        Ok(())
     }
 }
@@ -1,80 +1,117 @@
-use crate::{ConversionError, ConversionErrorType, ConversionResult};
+use crate::{ByteWriter, ConversionError, ConversionErrorType, ConversionResult};
 
 mod implement;
 
-/// Trait to serialize into bytes.
+/// Trait to serialize into a [`ByteWriter`].
 pub trait ToBytes {
-    /// Converts self to a [`Vec`] of bytes.
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>>;
+    /// Serializes self and appends the bytes to a [`ByteWriter`].
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()>;
 }
 
 /// Extension trait for [`ToBytes`].
 pub trait ToBytesExt: ToBytes {
-    /// Converts self to a [`Vec`] of bytes and pads it with zeros to match the
+    /// Serializes self and pads the written bytes with zeros to match the
     /// size of `size`.
-    fn to_n_bytes(&self, size: usize) -> ConversionResult<Vec<u8>>
+    fn to_n_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>, size: usize) -> ConversionResult<()>
     where
         Self: Sized;
+
+    /// Serializes self into a [`Vec`] of bytes, starting with default
+    /// metadata.
+    fn to_bytes_vec<Meta>(&self) -> ConversionResult<Vec<u8>>
+    where
+        Self: Sized,
+        Meta: Default + 'static;
+}
+
+/// Trait to serialize types whose discriminant is stored separately, the
+/// counterpart of
+/// [`FromBytesWithDiscriminant`](crate::FromBytesWithDiscriminant).
+pub trait ToBytesWithDiscriminant {
+    /// The discriminant of the variant of self.
+    fn discriminant(&self) -> usize;
+
+    /// Serializes self without the discriminant and appends the bytes to a
+    /// [`ByteWriter`].
+    fn to_bytes_without_discriminant<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()>;
 }
 
 impl<T> ToBytesExt for T
 where
     T: ToBytes,
 {
-    fn to_n_bytes(&self, size: usize) -> ConversionResult<Vec<u8>>
+    fn to_n_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>, size: usize) -> ConversionResult<()>
     where
         Self: Sized,
     {
-        let mut data = T::to_bytes(self)?;
+        let start_length = byte_writer.len();
+
+        T::to_bytes(self, byte_writer)?;
 
-        if data.len() > size {
+        let written = byte_writer.len() - start_length;
+
+        if written > size {
             return Err(ConversionError::from_error_type(ConversionErrorType::DataTooBig {
                 type_name: std::any::type_name::<T>(),
             }));
         }
 
-        data.resize(size, 0);
-        Ok(data)
+        byte_writer.extend_from_slice(&vec![0; size - written]);
+        Ok(())
+    }
+
+    fn to_bytes_vec<Meta>(&self) -> ConversionResult<Vec<u8>>
+    where
+        Self: Sized,
+        Meta: Default + 'static,
+    {
+        let mut byte_writer = ByteWriter::<Meta>::without_metadata();
+        T::to_bytes(self, &mut byte_writer)?;
+        Ok(byte_writer.into_inner())
     }
 }
 
 #[cfg(test)]
 mod to_n_bytes {
     use super::ToBytes;
-    use crate::ToBytesExt;
+    use crate::{ByteWriter, ToBytesExt};
 
     struct Test;
 
     const TEST_BYTE_SIZE: usize = 4;
 
     impl ToBytes for Test {
-        fn to_bytes(&self) -> crate::ConversionResult<Vec<u8>> {
-            Ok(vec![9; TEST_BYTE_SIZE])
+        fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> crate::ConversionResult<()> {
+            byte_writer.extend_from_slice(&[9; TEST_BYTE_SIZE]);
+            Ok(())
         }
     }
 
     #[test]
     fn data_saturated() {
-        let result = Test.to_n_bytes(TEST_BYTE_SIZE);
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        let result = Test.to_n_bytes(&mut byte_writer, TEST_BYTE_SIZE);
 
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), vec![9; TEST_BYTE_SIZE]);
+        assert_eq!(byte_writer.into_inner(), vec![9; TEST_BYTE_SIZE]);
     }
 
     #[test]
     fn data_smaller() {
-        let result = Test.to_n_bytes(TEST_BYTE_SIZE * 2);
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        let result = Test.to_n_bytes(&mut byte_writer, TEST_BYTE_SIZE * 2);
 
         assert!(result.is_ok());
 
-        let data = result.unwrap();
+        let data = byte_writer.into_inner();
         assert_eq!(&data[..TEST_BYTE_SIZE], vec![9; TEST_BYTE_SIZE]);
         assert_eq!(&data[TEST_BYTE_SIZE..], vec![0; TEST_BYTE_SIZE]);
     }
 
     #[test]
     fn data_bigger() {
-        let result = Test.to_n_bytes(TEST_BYTE_SIZE / 2);
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        let result = Test.to_n_bytes(&mut byte_writer, TEST_BYTE_SIZE / 2);
 
         assert!(result.is_err());
     }
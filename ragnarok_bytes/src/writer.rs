@@ -0,0 +1,135 @@
+use std::any::TypeId;
+
+use crate::{ConversionError, ConversionErrorType, ConversionResult, TextEncoding};
+
+/// A growable buffer that bytes are written into. It carries metadata about
+/// the write operation (for example a version) and the [`TextEncoding`] of
+/// strings, the same way a [`ByteStream`](crate::ByteStream) does for reading.
+pub struct ByteWriter<Meta = ()>
+where
+    Meta: 'static,
+{
+    data: Vec<u8>,
+    metadata: Meta,
+    text_encoding: TextEncoding,
+}
+
+impl<Meta> ByteWriter<Meta>
+where
+    Meta: Default + 'static,
+{
+    /// Create a new [`ByteWriter`] with default metadata.
+    pub fn without_metadata() -> Self {
+        Self::with_metadata(Default::default())
+    }
+}
+
+impl<Meta> ByteWriter<Meta>
+where
+    Meta: 'static,
+{
+    /// Create a new [`ByteWriter`] with specific metadata.
+    pub fn with_metadata(metadata: Meta) -> Self {
+        Self {
+            data: Vec::new(),
+            metadata,
+            text_encoding: TextEncoding::default(),
+        }
+    }
+
+    /// Set the [`TextEncoding`] used to encode strings.
+    pub fn with_text_encoding(mut self, text_encoding: TextEncoding) -> Self {
+        self.text_encoding = text_encoding;
+        self
+    }
+
+    pub fn get_text_encoding(&self) -> TextEncoding {
+        self.text_encoding
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn push(&mut self, byte: u8) {
+        self.data.push(byte);
+    }
+
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    /// Overwrite already written bytes starting at `offset`. This is useful
+    /// for fields like sizes that are only known after writing the data
+    /// following them.
+    pub fn overwrite<Caller>(&mut self, offset: usize, bytes: &[u8]) -> ConversionResult<()> {
+        match self.data.get_mut(offset..offset + bytes.len()) {
+            Some(slice) => {
+                slice.copy_from_slice(bytes);
+                Ok(())
+            }
+            None => Err(ConversionError::from_error_type(ConversionErrorType::DataTooBig {
+                type_name: std::any::type_name::<Caller>(),
+            })),
+        }
+    }
+
+    pub fn get_metadata<Caller, As>(&self) -> ConversionResult<&As>
+    where
+        As: 'static,
+    {
+        match TypeId::of::<Meta>() == TypeId::of::<As>() {
+            true => unsafe { Ok(std::mem::transmute::<&Meta, &As>(&self.metadata)) },
+            false => Err(ConversionError::from_error_type(ConversionErrorType::IncorrectMetadata {
+                type_name: std::any::type_name::<Caller>(),
+            })),
+        }
+    }
+
+    pub fn get_metadata_mut<Caller, As>(&mut self) -> ConversionResult<&mut As>
+    where
+        As: 'static,
+    {
+        match TypeId::of::<Meta>() == TypeId::of::<As>() {
+            true => unsafe { Ok(std::mem::transmute::<&mut Meta, &mut As>(&mut self.metadata)) },
+            false => Err(ConversionError::from_error_type(ConversionErrorType::IncorrectMetadata {
+                type_name: std::any::type_name::<Caller>(),
+            })),
+        }
+    }
+
+    pub fn into_metadata(self) -> Meta {
+        self.metadata
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod overwrite {
+    use crate::ByteWriter;
+
+    #[test]
+    fn inside() {
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        byte_writer.extend_from_slice(&[0, 0, 0, 0]);
+
+        assert!(byte_writer.overwrite::<()>(1, &[1, 2]).is_ok());
+        assert_eq!(byte_writer.into_inner(), vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn outside() {
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        byte_writer.extend_from_slice(&[0, 0]);
+
+        assert!(byte_writer.overwrite::<()>(1, &[1, 2]).is_err());
+        assert_eq!(byte_writer.into_inner(), vec![0, 0]);
+    }
+}
@@ -0,0 +1,124 @@
+//! Loading of synthetic 400x400 maps. The `one_by_one` benchmarks read the
+//! same tiles element by element, which is how repeating fields were read
+//! before they could be read in bulk.
+#![feature(test)]
+
+extern crate test;
+
+use ragnarok_bytes::{ByteStream, FromBytes};
+use ragnarok_formats::map::{GatData, GroundData, GroundTile, Tile};
+use ragnarok_formats::version::InternalVersion;
+use test::{black_box, Bencher};
+
+const MAP_SIZE: usize = 400;
+const TILE_COUNT: usize = MAP_SIZE * MAP_SIZE;
+
+/// Size of the GAT header (signature, version, width and height).
+const GAT_HEADER_SIZE: usize = 14;
+/// Size of the ground header for version 1.7 without any textures or light
+/// maps.
+const GROUND_HEADER_SIZE: usize = 46;
+const SURFACE_SIZE: usize = 40;
+
+fn floats(values: &[f32]) -> impl Iterator<Item = u8> + '_ {
+    values.iter().flat_map(|value| value.to_le_bytes())
+}
+
+fn gat_file() -> Vec<u8> {
+    let mut bytes = b"GRAT".to_vec();
+
+    bytes.extend([1, 2]);
+    bytes.extend((MAP_SIZE as i32).to_le_bytes());
+    bytes.extend((MAP_SIZE as i32).to_le_bytes());
+
+    for index in 0..TILE_COUNT {
+        bytes.extend(floats(&[index as f32, 1.0, 2.0, 3.0]));
+        bytes.extend([(index % 7) as u8, 0, 0, 0]);
+    }
+
+    bytes
+}
+
+fn ground_file() -> Vec<u8> {
+    let mut bytes = b"GRGN".to_vec();
+
+    bytes.extend([1, 7]);
+    bytes.extend((MAP_SIZE as i32).to_le_bytes());
+    bytes.extend((MAP_SIZE as i32).to_le_bytes());
+    bytes.extend(10f32.to_le_bytes());
+
+    // Textures
+    bytes.extend(0i32.to_le_bytes());
+    bytes.extend(80i32.to_le_bytes());
+
+    // Light maps
+    bytes.extend([0i32, 8, 8, 1].iter().flat_map(|value| value.to_le_bytes()));
+
+    // Surfaces
+    bytes.extend((TILE_COUNT as i32).to_le_bytes());
+
+    for index in 0..TILE_COUNT {
+        bytes.extend(floats(&[0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0]));
+        bytes.extend(0i16.to_le_bytes());
+        bytes.extend((index as i16).to_le_bytes());
+        bytes.extend([10, 20, 30, 255]);
+    }
+
+    // Ground tiles
+    for index in 0..TILE_COUNT {
+        bytes.extend(floats(&[1.0, 2.0, 3.0, 4.0]));
+        bytes.extend([index as i32, -1, -1].iter().flat_map(|value| value.to_le_bytes()));
+    }
+
+    bytes
+}
+
+fn one_by_one<T: FromBytes>(byte_stream: &mut ByteStream<Option<InternalVersion>>, count: usize) -> Vec<T> {
+    (0..count).map(|_| T::from_bytes(byte_stream).unwrap()).collect()
+}
+
+#[bench]
+fn gat_data(bencher: &mut Bencher) {
+    let input = gat_file();
+
+    bencher.iter(|| {
+        let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+        black_box(GatData::from_bytes(&mut byte_stream).unwrap())
+    });
+}
+
+#[bench]
+fn gat_tiles_one_by_one(bencher: &mut Bencher) {
+    let input = gat_file();
+
+    bencher.iter(|| {
+        let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input[GAT_HEADER_SIZE..]);
+        black_box(one_by_one::<Tile>(&mut byte_stream, TILE_COUNT))
+    });
+}
+
+#[bench]
+fn ground_data(bencher: &mut Bencher) {
+    let input = ground_file();
+
+    bencher.iter(|| {
+        let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+        black_box(GroundData::from_bytes(&mut byte_stream).unwrap())
+    });
+}
+
+#[bench]
+fn ground_tiles_one_by_one(bencher: &mut Bencher) {
+    let input = ground_file();
+    let tiles_offset = GROUND_HEADER_SIZE + TILE_COUNT * SURFACE_SIZE;
+
+    bencher.iter(|| {
+        let version = Some(InternalVersion {
+            major: 1,
+            minor: 7,
+            build_number: None,
+        });
+        let mut byte_stream = ByteStream::with_metadata(&input[tiles_offset..], version);
+        black_box(one_by_one::<GroundTile>(&mut byte_stream, TILE_COUNT))
+    });
+}
@@ -0,0 +1,279 @@
+//! The DES variant used by GRF archives. Gravity only applies a single round
+//! with an all zero key, which makes decryption and encryption the same
+//! operation.
+
+use super::FileFlags;
+
+const BLOCK_SIZE: usize = 8;
+
+/// Number of blocks at the start of a file that are always encrypted.
+const ENCRYPTED_HEADER_BLOCKS: usize = 20;
+
+/// Every seventh plain text block after the header is shuffled.
+const SHUFFLE_CYCLE: usize = 7;
+
+const INITIAL_PERMUTATION: [u8; 64] = [
+    58, 50, 42, 34, 26, 18, 10, 2, 60, 52, 44, 36, 28, 20, 12, 4, 62, 54, 46, 38, 30, 22, 14, 6, 64, 56, 48, 40, 32, 24, 16, 8, 57, 49, 41,
+    33, 25, 17, 9, 1, 59, 51, 43, 35, 27, 19, 11, 3, 61, 53, 45, 37, 29, 21, 13, 5, 63, 55, 47, 39, 31, 23, 15, 7,
+];
+
+const FINAL_PERMUTATION: [u8; 64] = [
+    40, 8, 48, 16, 56, 24, 64, 32, 39, 7, 47, 15, 55, 23, 63, 31, 38, 6, 46, 14, 54, 22, 62, 30, 37, 5, 45, 13, 53, 21, 61, 29, 36, 4, 44,
+    12, 52, 20, 60, 28, 35, 3, 43, 11, 51, 19, 59, 27, 34, 2, 42, 10, 50, 18, 58, 26, 33, 1, 41, 9, 49, 17, 57, 25,
+];
+
+const EXPANSION: [u8; 48] = [
+    32, 1, 2, 3, 4, 5, 4, 5, 6, 7, 8, 9, 8, 9, 10, 11, 12, 13, 12, 13, 14, 15, 16, 17, 16, 17, 18, 19, 20, 21, 20, 21, 22, 23, 24, 25, 24,
+    25, 26, 27, 28, 29, 28, 29, 30, 31, 32, 1,
+];
+
+const PERMUTATION: [u8; 32] = [
+    16, 7, 20, 21, 29, 12, 28, 17, 1, 15, 23, 26, 5, 18, 31, 10, 2, 8, 24, 14, 32, 27, 3, 9, 19, 13, 30, 6, 22, 11, 4, 25,
+];
+
+const SUBSTITUTION_BOXES: [[u8; 64]; 8] = [
+    [
+        14, 4, 13, 1, 2, 15, 11, 8, 3, 10, 6, 12, 5, 9, 0, 7, 0, 15, 7, 4, 14, 2, 13, 1, 10, 6, 12, 11, 9, 5, 3, 8, 4, 1, 14, 8, 13, 6, 2,
+        11, 15, 12, 9, 7, 3, 10, 5, 0, 15, 12, 8, 2, 4, 9, 1, 7, 5, 11, 3, 14, 10, 0, 6, 13,
+    ],
+    [
+        15, 1, 8, 14, 6, 11, 3, 4, 9, 7, 2, 13, 12, 0, 5, 10, 3, 13, 4, 7, 15, 2, 8, 14, 12, 0, 1, 10, 6, 9, 11, 5, 0, 14, 7, 11, 10, 4,
+        13, 1, 5, 8, 12, 6, 9, 3, 2, 15, 13, 8, 10, 1, 3, 15, 4, 2, 11, 6, 7, 12, 0, 5, 14, 9,
+    ],
+    [
+        10, 0, 9, 14, 6, 3, 15, 5, 1, 13, 12, 7, 11, 4, 2, 8, 13, 7, 0, 9, 3, 4, 6, 10, 2, 8, 5, 14, 12, 11, 15, 1, 13, 6, 4, 9, 8, 15, 3,
+        0, 11, 1, 2, 12, 5, 10, 14, 7, 1, 10, 13, 0, 6, 9, 8, 7, 4, 15, 14, 3, 11, 5, 2, 12,
+    ],
+    [
+        7, 13, 14, 3, 0, 6, 9, 10, 1, 2, 8, 5, 11, 12, 4, 15, 13, 8, 11, 5, 6, 15, 0, 3, 4, 7, 2, 12, 1, 10, 14, 9, 10, 6, 9, 0, 12, 11, 7,
+        13, 15, 1, 3, 14, 5, 2, 8, 4, 3, 15, 0, 6, 10, 1, 13, 8, 9, 4, 5, 11, 12, 7, 2, 14,
+    ],
+    [
+        2, 12, 4, 1, 7, 10, 11, 6, 8, 5, 3, 15, 13, 0, 14, 9, 14, 11, 2, 12, 4, 7, 13, 1, 5, 0, 15, 10, 3, 9, 8, 6, 4, 2, 1, 11, 10, 13, 7,
+        8, 15, 9, 12, 5, 6, 3, 0, 14, 11, 8, 12, 7, 1, 14, 2, 13, 6, 15, 0, 9, 10, 4, 5, 3,
+    ],
+    [
+        12, 1, 10, 15, 9, 2, 6, 8, 0, 13, 3, 4, 14, 7, 5, 11, 10, 15, 4, 2, 7, 12, 9, 5, 6, 1, 13, 14, 0, 11, 3, 8, 9, 14, 15, 5, 2, 8, 12,
+        3, 7, 0, 4, 10, 1, 13, 11, 6, 4, 3, 2, 12, 9, 5, 15, 10, 11, 14, 1, 7, 6, 0, 8, 13,
+    ],
+    [
+        4, 11, 2, 14, 15, 0, 8, 13, 3, 12, 9, 7, 5, 10, 6, 1, 13, 0, 11, 7, 4, 9, 1, 10, 14, 3, 5, 12, 2, 15, 8, 6, 1, 4, 11, 13, 12, 3, 7,
+        14, 10, 15, 6, 8, 0, 5, 9, 2, 6, 11, 13, 8, 1, 4, 10, 7, 9, 5, 0, 15, 14, 2, 3, 12,
+    ],
+    [
+        13, 2, 8, 4, 6, 15, 11, 1, 10, 9, 3, 14, 5, 0, 12, 7, 1, 15, 13, 8, 10, 3, 7, 4, 12, 5, 6, 11, 0, 14, 9, 2, 7, 11, 4, 1, 9, 12, 14,
+        2, 0, 6, 10, 13, 15, 3, 5, 8, 2, 1, 14, 7, 4, 10, 8, 13, 15, 12, 9, 0, 3, 5, 6, 11,
+    ],
+];
+
+/// Moves the bits of `input` according to `table`. The table uses one based
+/// bit positions counted from the most significant bit, like the DES
+/// specification does.
+fn permute(input: u64, input_bits: u32, table: &[u8]) -> u64 {
+    table.iter().fold(0, |output, &position| {
+        let bit = (input >> (input_bits - position as u32)) & 1;
+        (output << 1) | bit
+    })
+}
+
+fn round_function(right: u32) -> u32 {
+    let expanded = permute(right as u64, 32, &EXPANSION);
+
+    let substituted = SUBSTITUTION_BOXES
+        .iter()
+        .enumerate()
+        .fold(0u64, |output, (index, substitution_box)| {
+            let chunk = ((expanded >> (42 - index * 6)) & 0x3F) as usize;
+            let row = ((chunk >> 4) & 0b10) | (chunk & 0b1);
+            let column = (chunk >> 1) & 0xF;
+
+            (output << 4) | substitution_box[row * 16 + column] as u64
+        });
+
+    permute(substituted, 32, &PERMUTATION) as u32
+}
+
+/// Decrypts a single block in place.
+pub fn decrypt_block(block: &mut [u8; BLOCK_SIZE]) {
+    let permuted = permute(u64::from_be_bytes(*block), 64, &INITIAL_PERMUTATION);
+
+    let left = (permuted >> 32) as u32;
+    let right = permuted as u32;
+    let left = left ^ round_function(right);
+
+    let output = permute(((left as u64) << 32) | right as u64, 64, &FINAL_PERMUTATION);
+    *block = output.to_be_bytes();
+}
+
+fn substitute(byte: u8) -> u8 {
+    match byte {
+        0x00 => 0x2B,
+        0x2B => 0x00,
+        0x6C => 0x80,
+        0x80 => 0x6C,
+        0x01 => 0x68,
+        0x68 => 0x01,
+        0x48 => 0x77,
+        0x77 => 0x48,
+        0x60 => 0xFF,
+        0xFF => 0x60,
+        0xB9 => 0xC0,
+        0xC0 => 0xB9,
+        0xFE => 0xEB,
+        0xEB => 0xFE,
+        other => other,
+    }
+}
+
+fn unshuffle_block(block: &mut [u8; BLOCK_SIZE]) {
+    let source = *block;
+    *block = [
+        source[3],
+        source[4],
+        source[6],
+        source[0],
+        source[1],
+        source[2],
+        source[5],
+        substitute(source[7]),
+    ];
+}
+
+/// Number of blocks between two encrypted blocks of a fully encrypted file.
+/// It depends on the number of digits of the compressed size.
+fn encryption_cycle(compressed_size: u32) -> usize {
+    let digits = compressed_size.max(1).ilog10() as usize + 1;
+
+    match digits {
+        0..3 => 1,
+        3..5 => digits + 1,
+        5..7 => digits + 9,
+        _ => digits + 15,
+    }
+}
+
+/// Decrypts the (aligned) data of a file in place. Files that are not
+/// flagged as encrypted are left untouched.
+pub fn decrypt_file(data: &mut [u8], flags: FileFlags, compressed_size: u32) {
+    let mut blocks = data
+        .chunks_exact_mut(BLOCK_SIZE)
+        .map(|block| <&mut [u8; BLOCK_SIZE]>::try_from(block).unwrap());
+
+    if flags.contains(FileFlags::ENCRYPT_MIXED) {
+        let cycle = encryption_cycle(compressed_size);
+        let mut plain_block_index = 0;
+
+        for (index, block) in blocks.enumerate() {
+            if index < ENCRYPTED_HEADER_BLOCKS || index % cycle == 0 {
+                decrypt_block(block);
+                continue;
+            }
+
+            if plain_block_index % SHUFFLE_CYCLE == 0 && plain_block_index != 0 {
+                unshuffle_block(block);
+            }
+
+            plain_block_index += 1;
+        }
+    } else if flags.contains(FileFlags::ENCRYPT_HEADER) {
+        blocks.by_ref().take(ENCRYPTED_HEADER_BLOCKS).for_each(decrypt_block);
+    }
+}
+
+/// Decrypts an obfuscated file name of GRF versions 0x102 and 0x103 in
+/// place.
+pub fn decrypt_file_name(data: &mut [u8]) {
+    data.iter_mut().for_each(|byte| *byte = byte.rotate_left(4));
+    data.chunks_exact_mut(BLOCK_SIZE)
+        .map(|block| <&mut [u8; BLOCK_SIZE]>::try_from(block).unwrap())
+        .for_each(decrypt_block);
+}
+
+#[cfg(test)]
+mod decryption {
+    use super::{decrypt_block, decrypt_file, decrypt_file_name, encryption_cycle, unshuffle_block};
+    use crate::archive::FileFlags;
+
+    fn encrypt_file_name(data: &mut [u8]) {
+        data.chunks_exact_mut(8)
+            .map(|block| <&mut [u8; 8]>::try_from(block).unwrap())
+            .for_each(decrypt_block);
+        data.iter_mut().for_each(|byte| *byte = byte.rotate_left(4));
+    }
+
+    #[test]
+    fn block_is_symmetric() {
+        let input = *b"korangar";
+        let mut block = input;
+
+        decrypt_block(&mut block);
+        assert_ne!(block, input);
+
+        decrypt_block(&mut block);
+        assert_eq!(block, input);
+    }
+
+    #[test]
+    fn file_name() {
+        let mut data = *b"data\\prontera.gat\0\0\0\0\0\0\0";
+        encrypt_file_name(&mut data);
+        assert_ne!(&data, b"data\\prontera.gat\0\0\0\0\0\0\0");
+
+        decrypt_file_name(&mut data);
+        assert_eq!(&data, b"data\\prontera.gat\0\0\0\0\0\0\0");
+    }
+
+    #[test]
+    fn cycle() {
+        assert_eq!(encryption_cycle(0), 1);
+        assert_eq!(encryption_cycle(99), 1);
+        assert_eq!(encryption_cycle(100), 4);
+        assert_eq!(encryption_cycle(9999), 5);
+        assert_eq!(encryption_cycle(10000), 14);
+        assert_eq!(encryption_cycle(999999), 15);
+        assert_eq!(encryption_cycle(1000000), 22);
+    }
+
+    #[test]
+    fn header_only() {
+        let input: Vec<u8> = (0..=255).cycle().take(8 * 24).collect();
+        let mut data = input.clone();
+
+        decrypt_file(&mut data, FileFlags::FILE | FileFlags::ENCRYPT_HEADER, input.len() as u32);
+        assert_ne!(data[..8 * 20], input[..8 * 20]);
+        assert_eq!(data[8 * 20..], input[8 * 20..]);
+
+        decrypt_file(&mut data, FileFlags::FILE | FileFlags::ENCRYPT_HEADER, input.len() as u32);
+        assert_eq!(data, input);
+    }
+
+    #[test]
+    fn mixed() {
+        // A compressed size of 1000 results in every fifth block being encrypted.
+        let input: Vec<u8> = (0..=255).cycle().take(8 * 40).collect();
+        let mut data = input.clone();
+
+        decrypt_file(&mut data, FileFlags::FILE | FileFlags::ENCRYPT_MIXED, 1000);
+
+        let mut expected = input[20 * 8..21 * 8].try_into().unwrap();
+        decrypt_block(&mut expected);
+        assert_eq!(data[20 * 8..21 * 8], expected);
+
+        // Block 21 is the first plain text block after the header and therefore never
+        // shuffled. Block 29 is the seventh plain text block after that.
+        assert_eq!(data[21 * 8..22 * 8], input[21 * 8..22 * 8]);
+
+        let mut expected = input[29 * 8..30 * 8].try_into().unwrap();
+        unshuffle_block(&mut expected);
+        assert_eq!(data[29 * 8..30 * 8], expected);
+    }
+
+    #[test]
+    fn plain() {
+        let input: Vec<u8> = (0..=255).collect();
+        let mut data = input.clone();
+
+        decrypt_file(&mut data, FileFlags::FILE, input.len() as u32);
+        assert_eq!(data, input);
+    }
+}
@@ -0,0 +1,327 @@
+mod encryption;
+pub mod thor;
+
+use ragnarok_bytes::{
+    ByteConvertable, ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, ConversionResultExt, FixedByteSize, FromBytes,
+    TextEncoding, ToBytes,
+};
+
+pub use self::encryption::{decrypt_file, decrypt_file_name};
+use crate::signature::Signature;
+
+/// Represents the Header of the GRF file.
+#[derive(Clone, ByteConvertable, FixedByteSize)]
+pub struct Header {
+    #[new_default]
+    pub signature: Signature<b"Master of Magic\0">,
+    #[new_default]
+    pub encryption: [u8; 14],
+    pub file_table_offset: u32,
+    pub reserved_files: u32,
+    pub file_count: u32,
+    pub version: u32,
+}
+
+impl Header {
+    pub const FILE_OFFSET: usize = 7;
+
+    /// Fails for malformed headers that store fewer files than the reserved
+    /// files and the offset add up to.
+    pub fn get_file_count(&self) -> ConversionResult<usize> {
+        self.file_count
+            .checked_sub(self.reserved_files)
+            .and_then(|file_count| (file_count as usize).checked_sub(Self::FILE_OFFSET))
+            .ok_or_else(|| ConversionError::from_message("file count of the grf header is too small"))
+    }
+}
+
+/// Name of a file inside of a GRF. Names that are not valid in the text
+/// encoding can only be decoded lossily, so the name is written back from the
+/// bytes it was read from.
+#[derive(Clone, Debug)]
+pub struct FileName {
+    name: String,
+    raw: Vec<u8>,
+}
+
+impl FileName {
+    /// Encodes a new file name. Returns [`None`] if any character can't be
+    /// represented in the text encoding.
+    pub fn new(name: &str, text_encoding: TextEncoding) -> Option<Self> {
+        let raw = text_encoding.encode(name)?.into_owned();
+
+        Some(Self {
+            name: name.to_owned(),
+            raw,
+        })
+    }
+
+    fn from_raw(raw: Vec<u8>, text_encoding: TextEncoding) -> Self {
+        let name = text_encoding.decode(&raw).into_owned();
+        Self { name, raw }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+impl FromBytes for FileName {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
+        let mut raw = Vec::new();
+
+        loop {
+            match byte_stream.byte::<Self>()? {
+                0 => break,
+                byte => raw.push(byte),
+            }
+        }
+
+        Ok(Self::from_raw(raw, byte_stream.get_text_encoding()))
+    }
+}
+
+impl ToBytes for FileName {
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&self.raw);
+        byte_writer.push(0);
+        Ok(())
+    }
+}
+
+/// Represents file information about each of the files stored in the GRF.
+#[derive(Clone, Debug, ByteConvertable)]
+pub struct FileTableRow {
+    pub file_name: FileName,
+    pub compressed_size: u32,
+    pub compressed_size_aligned: u32,
+    pub uncompressed_size: u32,
+    pub flags: FileFlags,
+    pub offset: u32,
+}
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable, FixedByteSize)]
+    #[flags]
+    pub struct FileFlags: u8 {
+        /// Set for files, unset for directories.
+        const FILE = 0x01;
+        /// The first 20 blocks and every n-th block after that are encrypted.
+        const ENCRYPT_MIXED = 0x02;
+        /// Only the first 20 blocks are encrypted.
+        const ENCRYPT_HEADER = 0x04;
+    }
+}
+
+/// File information of GRF versions 0x102 and 0x103. The file name is
+/// stored encrypted and the sizes are stored with a constant offset. Use the
+/// [`From`] implementation to get the actual [`FileTableRow`].
+#[derive(Clone, Debug)]
+pub struct LegacyFileTableRow {
+    pub file_name: FileName,
+    pub raw_compressed_size: u32,
+    pub raw_compressed_size_aligned: u32,
+    pub uncompressed_size: u32,
+    pub flags: FileFlags,
+    pub offset: u32,
+}
+
+impl LegacyFileTableRow {
+    const COMPRESSED_SIZE_ALIGNED_OFFSET: u32 = 37579;
+    const COMPRESSED_SIZE_OFFSET: u32 = 715;
+    /// Files with these extensions only have their header encrypted, all
+    /// others are fully encrypted.
+    const HEADER_ENCRYPTED_EXTENSIONS: [&'static str; 4] = [".gnd", ".gat", ".act", ".str"];
+}
+
+impl FromBytes for LegacyFileTableRow {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
+        let text_encoding = byte_stream.get_text_encoding();
+        let name_length = u32::from_bytes(byte_stream).trace::<Self>()?;
+        let encrypted_file_name = byte_stream.slice::<Self>(name_length as usize)?;
+
+        // The first two bytes are not part of the name. Only `length - 6` bytes are
+        // encrypted, rounded up to whole blocks.
+        let mut name_bytes = encrypted_file_name.get(2..).unwrap_or_default().to_vec();
+        let encrypted_length = encrypted_file_name
+            .len()
+            .saturating_sub(6)
+            .next_multiple_of(8)
+            .min(name_bytes.len());
+        decrypt_file_name(&mut name_bytes[..encrypted_length]);
+
+        let name_end = name_bytes.iter().position(|byte| *byte == 0).unwrap_or(name_bytes.len());
+        name_bytes.truncate(name_end);
+        let file_name = FileName::from_raw(name_bytes, text_encoding);
+
+        let raw_compressed_size = u32::from_bytes(byte_stream).trace::<Self>()?;
+        let raw_compressed_size_aligned = u32::from_bytes(byte_stream).trace::<Self>()?;
+        let uncompressed_size = u32::from_bytes(byte_stream).trace::<Self>()?;
+        let flags = FileFlags::from_bytes(byte_stream).trace::<Self>()?;
+        let offset = u32::from_bytes(byte_stream).trace::<Self>()?;
+
+        Ok(Self {
+            file_name,
+            raw_compressed_size,
+            raw_compressed_size_aligned,
+            uncompressed_size,
+            flags,
+            offset,
+        })
+    }
+}
+
+impl From<LegacyFileTableRow> for FileTableRow {
+    fn from(row: LegacyFileTableRow) -> Self {
+        let file_name = row.file_name;
+        let mut flags = row.flags;
+
+        if flags.contains(FileFlags::FILE) {
+            let extension = file_name
+                .as_str()
+                .rfind('.')
+                .map(|index| file_name.as_str()[index..].to_lowercase());
+
+            match extension.is_some_and(|extension| LegacyFileTableRow::HEADER_ENCRYPTED_EXTENSIONS.contains(&extension.as_str())) {
+                true => flags |= FileFlags::ENCRYPT_HEADER,
+                false => flags |= FileFlags::ENCRYPT_MIXED,
+            }
+        }
+
+        Self {
+            file_name,
+            compressed_size: row
+                .raw_compressed_size
+                .wrapping_sub(row.uncompressed_size)
+                .wrapping_sub(LegacyFileTableRow::COMPRESSED_SIZE_OFFSET),
+            compressed_size_aligned: row
+                .raw_compressed_size_aligned
+                .wrapping_sub(LegacyFileTableRow::COMPRESSED_SIZE_ALIGNED_OFFSET),
+            uncompressed_size: row.uncompressed_size,
+            flags,
+            offset: row.offset,
+        }
+    }
+}
+
+/// Stores the table of files the parent GRF is holding.
+#[derive(Clone, ByteConvertable, FixedByteSize)]
+pub struct AssetTable {
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteStream, ByteWriter, FromBytes, TextEncoding, ToBytes};
+
+    use super::encryption::decrypt_block;
+    use super::{FileFlags, FileName, FileTableRow, Header, LegacyFileTableRow};
+
+    fn legacy_row(file_name: &str, flags: u8) -> Vec<u8> {
+        let mut name = vec![0u8; file_name.len().next_multiple_of(8)];
+        name[..file_name.len()].copy_from_slice(file_name.as_bytes());
+
+        name.chunks_exact_mut(8)
+            .map(|block| <&mut [u8; 8]>::try_from(block).unwrap())
+            .for_each(decrypt_block);
+        name.iter_mut().for_each(|byte| *byte = byte.rotate_left(4));
+
+        let name_length = name.len() as u32 + 6;
+        let mut bytes = Vec::new();
+        bytes.extend(name_length.to_le_bytes());
+        bytes.extend([0, 0]);
+        bytes.extend(name);
+        bytes.extend([0; 4]);
+        bytes.extend((100u32 + 400 + 715).to_le_bytes());
+        bytes.extend((104u32 + 37579).to_le_bytes());
+        bytes.extend(400u32.to_le_bytes());
+        bytes.push(flags);
+        bytes.extend(1234u32.to_le_bytes());
+        bytes
+    }
+
+    fn parse(bytes: &[u8]) -> FileTableRow {
+        let mut byte_stream = ByteStream::<()>::without_metadata(bytes);
+        let row = LegacyFileTableRow::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        row.into()
+    }
+
+    #[test]
+    fn legacy_file_table_row() {
+        let row = parse(&legacy_row("data\\texture\\effect\\torch_01.bmp", 0x01));
+
+        assert_eq!(row.file_name.as_str(), "data\\texture\\effect\\torch_01.bmp");
+        assert_eq!(row.compressed_size, 100);
+        assert_eq!(row.compressed_size_aligned, 104);
+        assert_eq!(row.uncompressed_size, 400);
+        assert_eq!(row.flags, FileFlags::FILE | FileFlags::ENCRYPT_MIXED);
+        assert_eq!(row.offset, 1234);
+    }
+
+    #[test]
+    fn legacy_header_encryption() {
+        let row = parse(&legacy_row("data\\prontera.GAT", 0x01));
+
+        assert_eq!(row.flags, FileFlags::FILE | FileFlags::ENCRYPT_HEADER);
+    }
+
+    #[test]
+    fn legacy_directory() {
+        let row = parse(&legacy_row("data\\texture", 0x00));
+
+        assert_eq!(row.flags, FileFlags::empty());
+    }
+
+    fn header(reserved_files: u32, file_count: u32) -> Header {
+        Header {
+            signature: Default::default(),
+            encryption: [0; 14],
+            file_table_offset: 0,
+            reserved_files,
+            file_count,
+            version: 0x103,
+        }
+    }
+
+    #[test]
+    fn file_count() {
+        assert_eq!(header(0, 7).get_file_count().unwrap(), 0);
+        assert_eq!(header(3, 20).get_file_count().unwrap(), 10);
+    }
+
+    #[test]
+    fn malformed_file_count() {
+        assert!(header(0, 6).get_file_count().is_err());
+        assert!(header(10, 9).get_file_count().is_err());
+        assert!(header(10, 16).get_file_count().is_err());
+    }
+
+    #[test]
+    fn invalid_file_name() {
+        // 0xFF can't start a character in CP949.
+        let mut input = b"data\\\xFF.txt\0".to_vec();
+        input.extend([1, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1, 5, 0, 0, 0]);
+
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input).with_text_encoding(TextEncoding::Windows949);
+        let row = FileTableRow::from_bytes(&mut byte_stream).unwrap();
+        assert!(byte_stream.is_empty());
+        assert_eq!(row.file_name.as_str(), "data\\\u{FFFD}.txt");
+
+        let mut byte_writer = ByteWriter::<()>::without_metadata().with_text_encoding(TextEncoding::Windows949);
+        row.to_bytes(&mut byte_writer).unwrap();
+        assert_eq!(byte_writer.into_inner(), input);
+    }
+
+    #[test]
+    fn new_file_name() {
+        let file_name = FileName::new("data\\아이템.txt", TextEncoding::Windows949).unwrap();
+        let mut byte_writer = ByteWriter::<()>::without_metadata();
+        file_name.to_bytes(&mut byte_writer).unwrap();
+
+        assert_eq!(byte_writer.into_inner(), b"data\\\xBE\xC6\xC0\xCC\xC5\xDB.txt\0");
+        assert!(FileName::new("data\\아이템.txt", TextEncoding::Latin1).is_none());
+    }
+}
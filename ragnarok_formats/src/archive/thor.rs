@@ -0,0 +1,215 @@
+//! THOR patch files, as created by the Thor patcher. A patch either contains
+//! a single file or a compressed table of files that are added or removed.
+use std::ops::Deref;
+
+use ragnarok_bytes::{
+    ByteConvertable, ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, FixedByteSize, FromBytes, ToBytes,
+};
+
+use crate::signature::Signature;
+
+/// A string that is prefixed by its length as a single byte.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrefixedString(pub String);
+
+impl Deref for PrefixedString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<&str> for PrefixedString {
+    fn from(string: &str) -> Self {
+        Self(string.to_owned())
+    }
+}
+
+impl FromBytes for PrefixedString {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
+        let text_encoding = byte_stream.get_text_encoding();
+        let length = byte_stream.byte::<Self>()?;
+        let bytes = byte_stream.slice::<Self>(length as usize)?;
+
+        Ok(Self(text_encoding.decode(bytes).into_owned()))
+    }
+}
+
+impl ToBytes for PrefixedString {
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        let text_encoding = byte_writer.get_text_encoding();
+        let bytes = text_encoding
+            .encode(&self.0)
+            .ok_or_else(|| ConversionError::from_message(format!("{:?} can't be represented in {text_encoding:?}", self.0)))?;
+
+        let length = u8::try_from(bytes.len()).map_err(|_| {
+            ConversionError::from_error_type(ragnarok_bytes::ConversionErrorType::DataTooBig {
+                type_name: std::any::type_name::<Self>(),
+            })
+        })?;
+
+        byte_writer.push(length);
+        byte_writer.extend_from_slice(&bytes);
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ByteConvertable)]
+#[numeric_type(u16)]
+pub enum ThorMode {
+    #[numeric_value(33)]
+    SingleFile,
+    #[numeric_value(48)]
+    MultipleFiles,
+}
+
+/// Where the files of a patch are placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ByteConvertable)]
+pub enum ThorMergeMode {
+    /// The files are placed in the client directory.
+    Client,
+    /// The files are merged into a GRF.
+    Grf,
+}
+
+#[derive(Clone, Debug, ByteConvertable)]
+#[round_trip_prefix(b"ASSF (C) 2007 Aeomin DEV\x01\x00\x00\x00\x00\x30\x00")]
+pub struct ThorHeader {
+    #[new_default]
+    pub signature: Signature<b"ASSF (C) 2007 Aeomin DEV">,
+    pub merge_mode: ThorMergeMode,
+    pub file_count: u32,
+    pub mode: ThorMode,
+    /// Name of the GRF the files are merged into. If empty, the default GRF
+    /// is used.
+    pub target_grf_name: PrefixedString,
+}
+
+/// The only entry of a patch in [`ThorMode::SingleFile`]. The compressed
+/// file data directly follows the entry.
+#[derive(Clone, Debug, ByteConvertable)]
+pub struct ThorSingleFileEntry {
+    pub _unknown: u8,
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+    pub file_name: PrefixedString,
+}
+
+/// Location of the zlib compressed file table of a patch in
+/// [`ThorMode::MultipleFiles`]. The offset is relative to the start of the
+/// patch.
+#[derive(Clone, Debug, ByteConvertable, FixedByteSize)]
+pub struct ThorFileTable {
+    pub compressed_size: u32,
+    pub offset: u32,
+}
+
+/// Location of the zlib compressed data of a file.
+#[derive(Clone, Debug, PartialEq, Eq, ByteConvertable)]
+pub struct ThorFileData {
+    pub offset: u32,
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+}
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable)]
+    #[flags]
+    pub struct ThorFileFlags: u8 {
+        /// The file is removed by the patch.
+        const REMOVED = 0x01;
+    }
+}
+
+#[derive(Clone, Debug, ByteConvertable)]
+pub struct ThorFileTableRow {
+    pub file_name: PrefixedString,
+    pub flags: ThorFileFlags,
+    /// [`None`] if the file is removed by the patch.
+    #[present_if(!flags.contains(ThorFileFlags::REMOVED))]
+    pub data: Option<ThorFileData>,
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteStream, FromBytes, ToBytesExt};
+
+    use super::{ThorFileData, ThorFileFlags, ThorFileTableRow, ThorHeader, ThorMergeMode, ThorMode, ThorSingleFileEntry};
+
+    const MAGIC: &[u8] = b"ASSF (C) 2007 Aeomin DEV";
+
+    #[test]
+    fn header() {
+        let mut input = MAGIC.to_vec();
+        input.push(1);
+        input.extend(3u32.to_le_bytes());
+        input.extend(48u16.to_le_bytes());
+        input.push(8);
+        input.extend(b"data.grf");
+
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+        let header = ThorHeader::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(header.merge_mode, ThorMergeMode::Grf);
+        assert_eq!(header.file_count, 3);
+        assert_eq!(header.mode, ThorMode::MultipleFiles);
+        assert_eq!(&*header.target_grf_name, "data.grf");
+        assert_eq!(header.to_bytes_vec::<()>().unwrap(), input);
+    }
+
+    #[test]
+    fn single_file_entry() {
+        let mut input = vec![0];
+        input.extend(10u32.to_le_bytes());
+        input.extend(20u32.to_le_bytes());
+        input.push(13);
+        input.extend(b"data\\test.txt");
+
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+        let entry = ThorSingleFileEntry::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(entry.compressed_size, 10);
+        assert_eq!(entry.uncompressed_size, 20);
+        assert_eq!(&*entry.file_name, "data\\test.txt");
+        assert_eq!(entry.to_bytes_vec::<()>().unwrap(), input);
+    }
+
+    #[test]
+    fn file_table_rows() {
+        let mut input = vec![14];
+        input.extend(b"data\\added.gat");
+        input.push(0);
+        input.extend(100u32.to_le_bytes());
+        input.extend(10u32.to_le_bytes());
+        input.extend(20u32.to_le_bytes());
+        input.push(16);
+        input.extend(b"data\\removed.gat");
+        input.push(1);
+
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+        let added = ThorFileTableRow::from_bytes(&mut byte_stream).unwrap();
+        let removed = ThorFileTableRow::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(&*added.file_name, "data\\added.gat");
+        assert_eq!(
+            added.data,
+            Some(ThorFileData {
+                offset: 100,
+                compressed_size: 10,
+                uncompressed_size: 20,
+            })
+        );
+        assert_eq!(&*removed.file_name, "data\\removed.gat");
+        assert_eq!(removed.flags, ThorFileFlags::REMOVED);
+        assert_eq!(removed.data, None);
+
+        let mut output = added.to_bytes_vec::<()>().unwrap();
+        output.extend(removed.to_bytes_vec::<()>().unwrap());
+        assert_eq!(output, input);
+    }
+}
@@ -1,3 +1,4 @@
+use bytemuck::{Pod, Zeroable};
 use ragnarok_bytes::ByteConvertable;
 
 #[derive(Clone, Copy, Debug, ByteConvertable)]
@@ -16,8 +17,10 @@ impl ColorRGB {
     }
 }
 
-#[derive(Clone, Copy, Debug, ByteConvertable)]
+#[derive(Clone, Copy, Debug, ByteConvertable, Pod, Zeroable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[repr(C)]
+#[bulk]
 pub struct ColorBGRA {
     pub blue: u8,
     pub green: u8,
@@ -1,7 +1,8 @@
-use korangar_interface::elements::PrototypeElement;
-use ragnarok_bytes::ByteConvertable;
+use ragnarok_bytes::{ByteConvertable, ByteStream, FromBytes};
 
-#[derive(Clone, Debug, ByteConvertable, PrototypeElement)]
+/// Identifier of an effect, used by map effect sources and packets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u32)]
 pub enum EffectId {
     //#[numeric_value(-1)]
@@ -1147,3 +1148,25 @@ pub enum EffectId {
     SoulExplosion,
     Max,
 }
+
+impl EffectId {
+    /// Looks up the effect with the given raw id. Returns [`None`] if the id is
+    /// not known.
+    pub fn from_raw(raw_id: u32) -> Option<Self> {
+        Self::from_bytes(&mut ByteStream::<()>::without_metadata(&raw_id.to_le_bytes())).ok()
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use super::EffectId;
+
+    #[test]
+    fn from_raw() {
+        assert_eq!(EffectId::from_raw(0), Some(EffectId::Hit1));
+        assert_eq!(EffectId::from_raw(47), Some(EffectId::Torch));
+        assert_eq!(EffectId::from_raw(349), Some(EffectId::Waterfall));
+        assert_eq!(EffectId::from_raw(1196), None);
+        assert_eq!(EffectId::from_raw(u32::MAX), None);
+    }
+}
@@ -0,0 +1,135 @@
+mod lookup;
+
+use cgmath::Vector2;
+use ragnarok_bytes::ByteConvertable;
+
+pub use self::lookup::EffectId;
+use crate::signature::Signature;
+use crate::version::{MajorFirst, Version};
+
+#[derive(Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct TextureName {
+    #[length(128)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct Frame {
+    pub frame_index: i32,
+    pub frame_type: i32,
+    pub offset: Vector2<f32>,
+    pub uv: [f32; 8],
+    pub xy: [f32; 8],
+    pub texture_index: f32,
+    pub animation_type: i32,
+    pub delay: f32,
+    pub angle: f32,
+    pub color: [f32; 4],
+    pub source_blend_factor: i32,
+    pub destination_blend_factor: i32,
+    pub mt_present: i32,
+}
+
+#[derive(Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct LayerData {
+    #[new_derive]
+    pub texture_count: i32,
+    #[repeating(texture_count)]
+    pub texture_names: Vec<TextureName>,
+    #[new_derive]
+    pub frame_count: i32,
+    #[repeating(frame_count)]
+    pub frames: Vec<Frame>,
+}
+
+#[derive(Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct EffectData {
+    #[new_default]
+    pub signature: Signature<b"STRM">,
+    #[version]
+    pub version: Version<MajorFirst>,
+    #[new_default]
+    pub _skip0: [u8; 2],
+    pub frames_per_second: u32,
+    pub max_key: u32,
+    #[new_derive]
+    pub layer_count: u32,
+    #[new_default]
+    pub _skip1: [u8; 16],
+    #[repeating(layer_count)]
+    pub layers: Vec<LayerData>,
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteStream, FromBytes};
+
+    use super::EffectData;
+    use crate::version::InternalVersion;
+
+    fn frame(frame_index: i32, offset_x: f32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend(frame_index.to_le_bytes());
+        bytes.extend(0i32.to_le_bytes());
+        bytes.extend(offset_x.to_le_bytes());
+        bytes.extend(0f32.to_le_bytes());
+        (0..16).for_each(|_| bytes.extend(1f32.to_le_bytes()));
+        bytes.extend(0f32.to_le_bytes());
+        bytes.extend(3i32.to_le_bytes());
+        bytes.extend(0.5f32.to_le_bytes());
+        bytes.extend(0f32.to_le_bytes());
+        (0..4).for_each(|_| bytes.extend(255f32.to_le_bytes()));
+        bytes.extend(5i32.to_le_bytes());
+        bytes.extend(2i32.to_le_bytes());
+        bytes.extend(1i32.to_le_bytes());
+        bytes
+    }
+
+    // Layout of a version 0x94 file with one layer, one texture and two key
+    // frames.
+    fn effect_file() -> Vec<u8> {
+        let mut bytes = b"STRM".to_vec();
+
+        bytes.extend(0x94u32.to_le_bytes());
+        bytes.extend(60u32.to_le_bytes());
+        bytes.extend(20u32.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend([0; 16]);
+
+        bytes.extend(1i32.to_le_bytes());
+        let mut texture_name = b"effect.bmp".to_vec();
+        texture_name.resize(128, 0);
+        bytes.extend(texture_name);
+
+        bytes.extend(2i32.to_le_bytes());
+        bytes.extend(frame(0, 4.0));
+        bytes.extend(frame(19, 8.0));
+        bytes
+    }
+
+    #[test]
+    fn effect_data() {
+        let input = effect_file();
+
+        let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+        let effect_data = EffectData::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(effect_data.frames_per_second, 60);
+        assert_eq!(effect_data.max_key, 20);
+
+        let layer = &effect_data.layers[0];
+        assert_eq!(layer.texture_names[0].name, "effect.bmp");
+        assert_eq!(layer.frames.len(), 2);
+        assert_eq!(layer.frames[1].frame_index, 19);
+        assert_eq!(layer.frames[1].offset.x, 8.0);
+        assert_eq!(layer.frames[1].animation_type, 3);
+        assert_eq!(layer.frames[1].delay, 0.5);
+        assert_eq!(layer.frames[1].mt_present, 1);
+    }
+}
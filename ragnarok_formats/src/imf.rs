@@ -0,0 +1,104 @@
+//! IMF files define the order in which the layers of a player sprite are
+//! drawn, separately for every motion of every action.
+use cgmath::Vector2;
+use ragnarok_bytes::ByteConvertable;
+
+#[derive(Debug, Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct ImfMotion {
+    /// Layers with a higher priority are drawn behind layers with a lower
+    /// priority.
+    pub priority: i32,
+    pub center: Vector2<i32>,
+}
+
+#[derive(Debug, Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct ImfAction {
+    #[new_derive]
+    pub motion_count: u32,
+    #[repeating(motion_count)]
+    pub motions: Vec<ImfMotion>,
+}
+
+#[derive(Debug, Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct ImfLayer {
+    #[new_derive]
+    pub action_count: u32,
+    #[repeating(action_count)]
+    pub actions: Vec<ImfAction>,
+}
+
+/// Layer 0 is the body and layer 1 is the head of the player.
+#[derive(Debug, Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct ImfData {
+    pub version: f32,
+    pub checksum: i32,
+    pub maximum_layer_index: u32,
+    #[repeating_expr(maximum_layer_index as usize + 1)]
+    pub layers: Vec<ImfLayer>,
+}
+
+impl ImfData {
+    /// Get the priority of a layer in a specific motion. Layers that are not
+    /// part of the file have a priority of 0.
+    pub fn priority(&self, layer_index: usize, action_index: usize, motion_index: usize) -> i32 {
+        self.layers
+            .get(layer_index)
+            .and_then(|layer| layer.actions.get(action_index))
+            .and_then(|action| action.motions.get(motion_index))
+            .map(|motion| motion.priority)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteStream, FromBytes, ToBytesExt};
+
+    use super::ImfData;
+
+    fn imf_file() -> Vec<u8> {
+        let mut bytes = 1.01f32.to_le_bytes().to_vec();
+        bytes.extend(1234i32.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+
+        // Body with one action and two motions.
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(2u32.to_le_bytes());
+        bytes.extend([0i32, 3, -4, 0, 5, -6].iter().flat_map(|value| value.to_le_bytes()));
+
+        // Head with one action and one motion.
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend(1u32.to_le_bytes());
+        bytes.extend([1i32, 7, -8].iter().flat_map(|value| value.to_le_bytes()));
+
+        bytes
+    }
+
+    #[test]
+    fn imf_data() {
+        let input = imf_file();
+
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+        let imf_data = ImfData::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(imf_data.layers.len(), 2);
+        assert_eq!(imf_data.layers[0].actions[0].motions[1].center, cgmath::Vector2::new(5, -6));
+        assert_eq!(imf_data.to_bytes_vec::<()>().unwrap(), input);
+    }
+
+    #[test]
+    fn priority() {
+        let input = imf_file();
+        let imf_data = ImfData::from_bytes(&mut ByteStream::<()>::without_metadata(&input)).unwrap();
+
+        assert_eq!(imf_data.priority(0, 0, 1), 0);
+        assert_eq!(imf_data.priority(1, 0, 0), 1);
+        assert_eq!(imf_data.priority(1, 0, 1), 0);
+        assert_eq!(imf_data.priority(2, 0, 0), 0);
+    }
+}
@@ -5,6 +5,7 @@ pub mod action;
 pub mod archive;
 pub mod color;
 pub mod effect;
+pub mod imf;
 pub mod map;
 pub mod model;
 pub mod signature;
@@ -1,10 +1,12 @@
-use std::collections::VecDeque;
-
-use cgmath::{Point3, Vector3};
-use korangar_interface::elements::PrototypeElement;
-use ragnarok_bytes::{ByteConvertable, ByteStream, ConversionError, ConversionResult, ConversionResultExt, FromBytes, ToBytes};
+use bytemuck::{CheckedBitPattern, Pod, Zeroable};
+use cgmath::Point3;
+use ragnarok_bytes::{
+    from_bytes_bulk, ByteConvertable, ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, ConversionResultExt,
+    FromBytes, ToBytes,
+};
 
 use crate::color::{ColorBGRA, ColorRGB};
+use crate::effect::EffectId;
 use crate::signature::Signature;
 use crate::transform::Transform;
 use crate::version::{InternalVersion, MajorFirst, Version};
@@ -20,18 +22,23 @@ pub struct MapData {
     #[version]
     pub version: Version<MajorFirst>,
     #[version_equals_or_above(2, 5)]
+    #[build_number]
     pub build_number: Option<i32>,
+    /// Flag that changes how the map is rendered. Its exact meaning is
+    /// unknown.
     #[version_equals_or_above(2, 2)]
-    pub _unknown: Option<u8>,
+    pub render_flag: Option<u8>,
+    /// Name of the INI file of the map editor. Not used by the client.
     #[length(40)]
-    pub _ini_file: String,
+    pub ini_file: String,
     #[length(40)]
     pub ground_file: String,
     #[length(40)]
     pub gat_file: String,
+    /// Name of the source file of the map editor. Not used by the client.
     #[version_equals_or_above(1, 4)]
     #[length(40)]
-    pub _source_file: Option<String>,
+    pub source_file: Option<String>,
     #[version_smaller(2, 6)]
     pub water_settings: Option<WaterSettings>,
     pub light_settings: LightSettings,
@@ -43,151 +50,86 @@ pub struct MapData {
     pub ground_left: Option<i32>,
     #[version_equals_or_above(1, 6)]
     pub ground_right: Option<i32>,
-    // TODO: Parse remaining fields
     pub resources: MapResources,
     #[version_equals_or_above(2, 1)]
     pub quadtree: Option<QuadTreeData>,
 }
 
-#[derive(Clone, PrototypeElement)]
+/// Bounding boxes of the map, stored as a complete quad tree with a depth of
+/// 5. The nodes are in depth-first order, so the first node covers the entire
+/// map and the children of a node follow it directly.
+#[derive(Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[round_trip_prefix(vec![0; QuadTreeData::NODE_COUNT * size_of::<QuadTreeNode>()])]
 pub struct QuadTreeData {
+    #[repeating_expr(QuadTreeData::NODE_COUNT)]
+    pub nodes: Vec<QuadTreeNode>,
+}
+
+impl QuadTreeData {
+    /// Number of nodes in a complete quad tree with a depth of 5.
+    pub const NODE_COUNT: usize = 1365;
+}
+
+#[derive(Debug, Clone, Copy, ByteConvertable, Pod, Zeroable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[repr(C)]
+#[bulk]
+pub struct QuadTreeNode {
     pub max: [f32; 3],
     pub min: [f32; 3],
     pub half_size: [f32; 3],
     pub center: [f32; 3],
-    pub children: Vec<QuadTreeData>,
 }
 
-bitflags::bitflags! {
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-    pub struct TileFlags: u8 {
-        const WALKABLE = 0b00000001;
-        const WATER = 0b00000010;
-        const SNIPABLE = 0b00000100;
-        const CLIFF = 0b00001000;
-    }
+/// Terrain type of a tile, as it is stored in GAT files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable, CheckedBitPattern)]
+#[numeric_type(u8)]
+#[repr(u8)]
+pub enum TileType {
+    Walkable,
+    NonWalkable,
+    NonWalkableWater,
+    WalkableWater,
+    /// Water that can't be walked on, but ranged attacks can pass over it.
+    SnipableWater,
+    /// A cliff that can't be walked on, but ranged attacks can pass over it.
+    SnipableCliff,
+    Cliff,
 }
 
-impl FromBytes for QuadTreeData {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        const MAX_DEPTH: usize = 5;
-        const CHILD_COUNT: usize = 4;
-
-        // Helper struct to keep node accesses readable.
-        struct Node {
-            data: QuadTreeData,
-            parent_index: usize,
-        }
-
-        // Simulate a DFS using a stack.
-        let mut stack = VecDeque::from([(0, 0)]);
-        let mut nodes = Vec::new();
-
-        while let Some((parent_index, depth)) = stack.pop_back() {
-            let max = FromBytes::from_bytes(byte_stream).trace::<Self>()?;
-            let min = FromBytes::from_bytes(byte_stream).trace::<Self>()?;
-            let half_size = FromBytes::from_bytes(byte_stream).trace::<Self>()?;
-            let center = FromBytes::from_bytes(byte_stream).trace::<Self>()?;
-
-            let children = match depth < MAX_DEPTH {
-                true => Vec::with_capacity(CHILD_COUNT),
-                false => Vec::new(),
-            };
-
-            // Add next node lookups to the stack.
-            if depth < MAX_DEPTH {
-                let self_index = nodes.len();
-
-                for _counter in 0..CHILD_COUNT {
-                    stack.push_back((self_index, depth + 1));
-                }
-            }
-
-            let data = QuadTreeData {
-                max,
-                min,
-                half_size,
-                center,
-                children,
-            };
-            nodes.push(Node { data, parent_index });
-        }
-
-        // Go back to front and assign nodes to their parents. Node 0 is the root
-        // node so we skip that.
-        while nodes.len() > 1 {
-            let node = nodes.pop().unwrap();
-
-            let children = &mut nodes[node.parent_index].data.children;
-            children.push(node.data);
-
-            // We reverse the nodes once all of them are collected rather than inserting
-            // them at the start of the vector to avoid unnecessary shifting of
-            // other items. This is purely an optimization.
-            if children.len() == 4 {
-                children.reverse();
-            }
-        }
-
-        let root_node = nodes.pop().unwrap().data;
-        Ok(root_node)
+impl TileType {
+    /// Whether entities can stand on and move over the tile.
+    pub fn is_walkable(self) -> bool {
+        matches!(self, TileType::Walkable | TileType::WalkableWater)
     }
-}
 
-impl FromBytes for TileFlags {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        match <Self as bitflags::Flags>::Bits::from_bytes(byte_stream).trace::<Self>()? {
-            0 => Ok(Self::WALKABLE),
-            1 => Ok(Self::empty()),
-            2 => Ok(Self::WATER),
-            3 => Ok(Self::WATER | Self::WALKABLE),
-            4 => Ok(Self::WATER | Self::SNIPABLE),
-            5 => Ok(Self::CLIFF | Self::SNIPABLE),
-            6 => Ok(Self::CLIFF),
-            invalid => Err(ConversionError::from_message(format!("invalid tile type {invalid}"))),
-        }
+    /// Whether ranged attacks and skills can pass over the tile.
+    pub fn is_snipable(self) -> bool {
+        self.is_walkable() || matches!(self, TileType::SnipableWater | TileType::SnipableCliff)
     }
-}
 
-impl TryInto<<Self as bitflags::Flags>::Bits> for TileFlags {
-    type Error = Box<ConversionError>;
-
-    fn try_into(self) -> Result<<Self as bitflags::Flags>::Bits, Self::Error> {
-        if self == Self::WALKABLE {
-            Ok(0 as <Self as bitflags::Flags>::Bits)
-        } else if self == Self::empty() {
-            Ok(1 as <Self as bitflags::Flags>::Bits)
-        } else if self == Self::WATER {
-            Ok(2 as <Self as bitflags::Flags>::Bits)
-        } else if self == Self::WATER | Self::WALKABLE {
-            Ok(3 as <Self as bitflags::Flags>::Bits)
-        } else if self == Self::WATER | Self::SNIPABLE {
-            Ok(4 as <Self as bitflags::Flags>::Bits)
-        } else if self == Self::CLIFF | Self::SNIPABLE {
-            Ok(5 as <Self as bitflags::Flags>::Bits)
-        } else if self == Self::CLIFF {
-            Ok(6 as <Self as bitflags::Flags>::Bits)
-        } else {
-            Err(ConversionError::from_message(format!("invalid tile encoding {:?}", self)))
-        }
+    pub fn is_water(self) -> bool {
+        matches!(
+            self,
+            TileType::NonWalkableWater | TileType::WalkableWater | TileType::SnipableWater
+        )
     }
-}
 
-impl ToBytes for TileFlags {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        TryInto::<<Self as bitflags::Flags>::Bits>::try_into(*self)?
-            .to_bytes()
-            .trace::<Self>()
+    pub fn is_cliff(self) -> bool {
+        matches!(self, TileType::SnipableCliff | TileType::Cliff)
     }
 }
 
-#[derive(Debug, ByteConvertable)]
+#[derive(Debug, Clone, Copy, ByteConvertable, CheckedBitPattern)]
+#[repr(C)]
+#[bulk]
 pub struct Tile {
     pub upper_left_height: f32,
     pub upper_right_height: f32,
     pub lower_left_height: f32,
     pub lower_right_height: f32,
-    pub flags: TileFlags,
+    pub tile_type: TileType,
     #[new_default]
     pub unused: [u8; 3],
 }
@@ -200,7 +142,7 @@ pub struct GatData {
     pub version: Version<MajorFirst>,
     pub map_width: i32,
     pub map_height: i32,
-    #[repeating_expr(map_width as usize * map_height as usize)]
+    #[repeating_expr((map_width as usize).saturating_mul(map_height as usize))]
     pub tiles: Vec<Tile>,
 }
 
@@ -224,21 +166,135 @@ pub struct GroundData {
     pub light_map_height: i32,
     pub light_map_cells_per_grid: i32,
     #[version_equals_or_above(1, 7)]
-    #[repeating_expr(light_map_count as usize * light_map_width as usize * light_map_height as usize * 4)]
-    #[new_default]
-    pub _skip: Option<Vec<u8>>,
+    #[repeating_expr(lightmap_count(light_map_count, light_map_width, light_map_height)?)]
+    pub light_maps: Option<Vec<LightmapData>>,
     #[version_smaller(1, 7)]
-    #[repeating_expr(light_map_count * 16)]
+    #[repeating_expr((light_map_count as usize).saturating_mul(16))]
     #[new_default]
     pub _skip2: Option<Vec<u8>>,
     #[new_derive]
     pub surface_count: i32,
     #[repeating(surface_count)]
     pub surfaces: Vec<Surface>,
-    #[repeating_expr(width as usize * height as usize)]
+    #[repeating_expr((width as usize).saturating_mul(height as usize))]
     pub ground_tiles: Vec<GroundTile>,
+    #[version_equals_or_above(1, 8)]
+    pub water_settings: Option<GroundWaterSettings>,
 }
 
+/// Water configuration of maps with RSW version 2.6 and above, which is
+/// stored in the GND instead. The water can be split into multiple planes,
+/// each covering an equally sized area of the map.
+#[derive(Clone, Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct GroundWaterSettings {
+    pub water_level: f32,
+    pub water_type: i32,
+    pub wave_height: f32,
+    pub wave_speed: f32,
+    pub wave_pitch: f32,
+    pub water_animation_speed: u32,
+    pub water_splits_width: i32,
+    pub water_splits_height: i32,
+    #[version_equals_or_above(1, 9)]
+    #[repeating_expr(i32::max(water_splits_width, 0) as usize * i32::max(water_splits_height, 0) as usize)]
+    pub water_planes: Option<Vec<WaterPlane>>,
+    #[version_smaller(1, 9)]
+    #[repeating_expr(i32::max(water_splits_width, 0) as usize * i32::max(water_splits_height, 0) as usize)]
+    pub water_plane_levels: Option<Vec<f32>>,
+}
+
+impl GroundWaterSettings {
+    /// Settings of every water plane, in row-major order. Versions below 1.9
+    /// only store the level of each plane and share all other settings.
+    pub fn planes(&self) -> Vec<WaterPlane> {
+        match (&self.water_planes, &self.water_plane_levels) {
+            (Some(water_planes), _) => water_planes.clone(),
+            (None, Some(water_plane_levels)) => water_plane_levels
+                .iter()
+                .map(|&water_level| WaterPlane {
+                    water_level,
+                    water_type: self.water_type,
+                    wave_height: self.wave_height,
+                    wave_speed: self.wave_speed,
+                    wave_pitch: self.wave_pitch,
+                    water_animation_speed: self.water_animation_speed,
+                })
+                .collect(),
+            (None, None) => Vec::new(),
+        }
+    }
+}
+
+impl From<&GroundWaterSettings> for WaterSettings {
+    fn from(settings: &GroundWaterSettings) -> Self {
+        Self {
+            water_level: Some(settings.water_level),
+            water_type: Some(settings.water_type),
+            wave_height: Some(settings.wave_height),
+            wave_speed: Some(settings.wave_speed),
+            wave_pitch: Some(settings.wave_pitch),
+            water_animation_speed: Some(settings.water_animation_speed),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct WaterPlane {
+    pub water_level: f32,
+    pub water_type: i32,
+    pub wave_height: f32,
+    pub wave_speed: f32,
+    pub wave_pitch: f32,
+    pub water_animation_speed: u32,
+}
+
+impl From<&WaterSettings> for WaterPlane {
+    /// Settings that are missing in older versions fall back to the values
+    /// the client uses for them.
+    fn from(settings: &WaterSettings) -> Self {
+        Self {
+            water_level: settings.water_level.unwrap_or(0.0),
+            water_type: settings.water_type.unwrap_or(0),
+            wave_height: settings.wave_height.unwrap_or(1.0),
+            wave_speed: settings.wave_speed.unwrap_or(2.0),
+            wave_pitch: settings.wave_pitch.unwrap_or(50.0),
+            water_animation_speed: settings.water_animation_speed.unwrap_or(3),
+        }
+    }
+}
+
+/// Width and height of a single lightmap in cells. Lightmaps of any other size
+/// are not supported by the client.
+pub const LIGHTMAP_SIZE: usize = 8;
+
+/// Number of lightmaps to read. [`LightmapData`] has a fixed size, so
+/// lightmaps of any other size are rejected instead of being misread.
+fn lightmap_count(count: i32, width: i32, height: i32) -> ConversionResult<usize> {
+    let count = usize::try_from(count).unwrap_or_default();
+
+    match count == 0 || (width == LIGHTMAP_SIZE as i32 && height == LIGHTMAP_SIZE as i32) {
+        true => Ok(count),
+        false => Err(ConversionError::from_message(format!(
+            "lightmaps of size {width}x{height} are not supported"
+        ))),
+    }
+}
+
+/// Baked lighting of a single ground surface.
+#[derive(Clone, Copy, Debug, ByteConvertable, Pod, Zeroable)]
+#[repr(C)]
+#[bulk]
+pub struct LightmapData {
+    /// Intensity of the baked shadow for each cell, where 255 means no shadow.
+    pub shadow_map: [u8; LIGHTMAP_SIZE * LIGHTMAP_SIZE],
+    /// Color of the baked light for each cell.
+    pub color_map: [[u8; 3]; LIGHTMAP_SIZE * LIGHTMAP_SIZE],
+}
+
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
 pub struct GroundTile {
     pub upper_left_height: f32,
     pub upper_right_height: f32,
@@ -250,7 +306,7 @@ pub struct GroundTile {
 }
 
 impl FromBytes for GroundTile {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let upper_left_height = f32::from_bytes(byte_stream).trace::<Self>()?;
         let upper_right_height = f32::from_bytes(byte_stream).trace::<Self>()?;
         let lower_left_height = f32::from_bytes(byte_stream).trace::<Self>()?;
@@ -285,11 +341,45 @@ impl FromBytes for GroundTile {
             right_surface_index,
         })
     }
+
+    fn from_bytes_repeated<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>, count: usize) -> ConversionResult<Vec<Self>> {
+        let version = byte_stream
+            .get_metadata::<Self, Option<InternalVersion>>()?
+            .ok_or(ConversionError::from_message("version not set"))?;
+
+        // Older versions store the surface indices as i16, so only newer tiles
+        // match their in-memory representation.
+        match version.equals_or_above(1, 7) {
+            true => from_bytes_bulk(byte_stream, count),
+            false => (0..count).map(|index| Self::from_bytes(byte_stream).index(index)).collect(),
+        }
+    }
 }
 
 impl ToBytes for GroundTile {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        panic!("GroundTile can not be serialized currently because it depends on a version requirement");
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.upper_left_height.to_bytes(byte_writer).trace::<Self>()?;
+        self.upper_right_height.to_bytes(byte_writer).trace::<Self>()?;
+        self.lower_left_height.to_bytes(byte_writer).trace::<Self>()?;
+        self.lower_right_height.to_bytes(byte_writer).trace::<Self>()?;
+
+        let version = byte_writer
+            .get_metadata::<Self, Option<InternalVersion>>()?
+            .ok_or(ConversionError::from_message("version not set"))?;
+
+        for surface_index in [self.top_surface_index, self.front_surface_index, self.right_surface_index] {
+            match version.equals_or_above(1, 7) {
+                true => surface_index.to_bytes(byte_writer).trace::<Self>()?,
+                false => {
+                    let surface_index: i16 = surface_index
+                        .try_into()
+                        .map_err(|_| ConversionError::from_message("surface index out of range"))?;
+                    surface_index.to_bytes(byte_writer).trace::<Self>()?
+                }
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -300,7 +390,9 @@ pub enum SurfaceType {
     Top,
 }
 
-#[derive(ByteConvertable)]
+#[derive(Clone, Copy, ByteConvertable, Pod, Zeroable)]
+#[repr(C)]
+#[bulk]
 pub struct Surface {
     pub u: [f32; 4],
     pub v: [f32; 4],
@@ -309,27 +401,6 @@ pub struct Surface {
     pub color: ColorBGRA,
 }
 
-#[derive(Copy, Clone, Debug)]
-pub enum ResourceType {
-    Object,
-    LightSource,
-    SoundSource,
-    EffectSource,
-}
-
-impl FromBytes for ResourceType {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        let index = i32::from_bytes(byte_stream).trace::<Self>()?;
-        match index {
-            1 => Ok(ResourceType::Object),
-            2 => Ok(ResourceType::LightSource),
-            3 => Ok(ResourceType::SoundSource),
-            4 => Ok(ResourceType::EffectSource),
-            _ => Err(ConversionError::from_message(format!("invalid object type {index}"))),
-        }
-    }
-}
-
 #[derive(Clone, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct ObjectData {
@@ -337,13 +408,13 @@ pub struct ObjectData {
     #[version_equals_or_above(1, 3)]
     pub name: Option<String>,
     #[version_equals_or_above(1, 3)]
-    pub _animation_type: Option<i32>,
+    pub animation_type: Option<i32>,
     #[version_equals_or_above(1, 3)]
-    pub _animation_speed: Option<f32>,
+    pub animation_speed: Option<f32>,
     #[version_equals_or_above(1, 3)]
     pub _block_type: Option<i32>,
-    // FIX: only if build_version >= 186
     #[version_equals_or_above(2, 6)]
+    #[build_number_equals_or_above(186)]
     #[new_default]
     pub _unknown: Option<u8>,
     #[length(80)]
@@ -353,122 +424,101 @@ pub struct ObjectData {
     pub transform: Transform,
 }
 
-#[derive(Clone)]
-#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
-pub struct MapResources {
-    pub resources_amount: u32,
-    pub objects: Vec<ObjectData>,
-    pub light_sources: Vec<LightSource>,
-    pub sound_sources: Vec<SoundSource>,
-    pub effect_sources: Vec<EffectSource>,
+/// A single resource of a map, tagged with its type.
+///
+/// The y-axis of the position of light, sound, and effect sources points
+/// down, unlike the one of the object [`Transform`].
+#[derive(Clone, ByteConvertable)]
+#[numeric_type(i32)]
+pub enum Resource {
+    #[numeric_value(1)]
+    Object(ObjectData),
+    LightSource(LightSource),
+    SoundSource(SoundSource),
+    EffectSource(EffectSource),
 }
 
-impl MapResources {
-    pub fn new(
-        objects: Vec<ObjectData>,
-        light_sources: Vec<LightSource>,
-        sound_sources: Vec<SoundSource>,
-        effect_sources: Vec<EffectSource>,
-    ) -> Self {
-        let resources_amount = (objects.len() + light_sources.len() + sound_sources.len() + effect_sources.len())
-            .try_into()
-            .expect("too many resources");
-
-        Self {
-            resources_amount,
-            objects,
-            light_sources,
-            sound_sources,
-            effect_sources,
+#[cfg(feature = "interface")]
+impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for Resource {
+    fn to_element(&self, display: String) -> korangar_interface::elements::ElementCell<App> {
+        match self {
+            Resource::Object(object) => object.to_element(display),
+            Resource::LightSource(light_source) => light_source.to_element(display),
+            Resource::SoundSource(sound_source) => sound_source.to_element(display),
+            Resource::EffectSource(effect_source) => effect_source.to_element(display),
         }
     }
 }
 
-impl FromBytes for MapResources {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        let resources_amount = u32::from_bytes(byte_stream).trace::<Self>()?;
-
-        let mut objects = Vec::new();
-        let mut light_sources = Vec::new();
-        let mut sound_sources = Vec::new();
-        let mut effect_sources = Vec::new();
-
-        for index in 0..resources_amount {
-            let resource_type = ResourceType::from_bytes(byte_stream).trace::<Self>()?;
-
-            match resource_type {
-                ResourceType::Object => {
-                    let mut object = ObjectData::from_bytes(byte_stream).trace::<Self>()?;
-                    // offset the objects slightly to avoid depth buffer fighting
-                    object.transform.position += Vector3::new(0.0, 0.0005, 0.0) * index as f32;
-                    objects.push(object);
-                }
-                ResourceType::LightSource => {
-                    let mut light_source = LightSource::from_bytes(byte_stream).trace::<Self>()?;
-                    light_source.position.y = -light_source.position.y;
-
-                    // Some light sources have color channels with values bigger than 1.0 (255), so
-                    // we need to clamp them.
-                    // TODO: Does this maybe have a special meaning?
-                    light_source.color.clamp_color_channels();
-
-                    light_sources.push(light_source);
-                }
-                ResourceType::SoundSource => {
-                    let mut sound_source = SoundSource::from_bytes(byte_stream).trace::<Self>()?;
-                    sound_source.position.y = -sound_source.position.y;
-
-                    if sound_source.cycle.is_none() {
-                        sound_source.cycle = Some(4.0);
-                    }
-
-                    sound_sources.push(sound_source);
-                }
-                ResourceType::EffectSource => {
-                    let mut effect_source = EffectSource::from_bytes(byte_stream).trace::<Self>()?;
-                    effect_source.position.y = -effect_source.position.y;
-                    effect_sources.push(effect_source);
-                }
-            }
-        }
+/// The resources of a map in the order they appear in the file.
+#[derive(Clone, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct MapResources {
+    #[new_derive]
+    pub resources_amount: u32,
+    #[repeating(resources_amount)]
+    pub resources: Vec<Resource>,
+}
 
-        Ok(Self {
-            resources_amount,
-            objects,
-            light_sources,
-            sound_sources,
-            effect_sources,
+impl MapResources {
+    pub fn objects(&self) -> impl Iterator<Item = &ObjectData> {
+        self.resources.iter().filter_map(|resource| match resource {
+            Resource::Object(object) => Some(object),
+            _ => None,
         })
     }
-}
 
-impl ToBytes for MapResources {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut data = Vec::new();
+    pub fn objects_mut(&mut self) -> impl Iterator<Item = &mut ObjectData> {
+        self.resources.iter_mut().filter_map(|resource| match resource {
+            Resource::Object(object) => Some(object),
+            _ => None,
+        })
+    }
 
-        data.extend(self.resources_amount.to_bytes()?);
+    pub fn light_sources(&self) -> impl Iterator<Item = &LightSource> {
+        self.resources.iter().filter_map(|resource| match resource {
+            Resource::LightSource(light_source) => Some(light_source),
+            _ => None,
+        })
+    }
 
-        for object in &self.objects {
-            data.extend(object.to_bytes()?);
-        }
+    pub fn light_sources_mut(&mut self) -> impl Iterator<Item = &mut LightSource> {
+        self.resources.iter_mut().filter_map(|resource| match resource {
+            Resource::LightSource(light_source) => Some(light_source),
+            _ => None,
+        })
+    }
 
-        for light_source in &self.light_sources {
-            data.extend(light_source.to_bytes()?);
-        }
+    pub fn sound_sources(&self) -> impl Iterator<Item = &SoundSource> {
+        self.resources.iter().filter_map(|resource| match resource {
+            Resource::SoundSource(sound_source) => Some(sound_source),
+            _ => None,
+        })
+    }
 
-        for sound_source in &self.sound_sources {
-            data.extend(sound_source.to_bytes()?);
-        }
+    pub fn sound_sources_mut(&mut self) -> impl Iterator<Item = &mut SoundSource> {
+        self.resources.iter_mut().filter_map(|resource| match resource {
+            Resource::SoundSource(sound_source) => Some(sound_source),
+            _ => None,
+        })
+    }
 
-        for effect_source in &self.effect_sources {
-            data.extend(effect_source.to_bytes()?);
-        }
+    pub fn effect_sources(&self) -> impl Iterator<Item = &EffectSource> {
+        self.resources.iter().filter_map(|resource| match resource {
+            Resource::EffectSource(effect_source) => Some(effect_source),
+            _ => None,
+        })
+    }
 
-        Ok(data)
+    pub fn effect_sources_mut(&mut self) -> impl Iterator<Item = &mut EffectSource> {
+        self.resources.iter_mut().filter_map(|resource| match resource {
+            Resource::EffectSource(effect_source) => Some(effect_source),
+            _ => None,
+        })
     }
 }
 
-#[derive(Clone, Debug, ByteConvertable)]
+#[derive(Clone, Debug, Default, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct WaterSettings {
     #[version_equals_or_above(1, 3)]
@@ -520,12 +570,19 @@ pub struct EffectSource {
     #[length(80)]
     pub name: String,
     pub position: Point3<f32>,
-    pub effect_type: u32, // TODO: fix this
+    /// Raw id of the effect. Maps may use ids that are not part of
+    /// [`EffectId`], so the id is only looked up when needed.
+    pub effect_id: u32,
+    /// Settings of effects that are not backed by an STR file. Their meaning
+    /// is not documented, so these effects are not rendered.
     pub emit_speed: f32,
-    pub _param0: f32,
-    pub _param1: f32,
-    pub _param2: f32,
-    pub _param3: f32,
+    pub parameters: [f32; 4],
+}
+
+impl EffectSource {
+    pub fn effect(&self) -> Option<EffectId> {
+        EffectId::from_raw(self.effect_id)
+    }
 }
 
 #[derive(Clone, ByteConvertable)]
@@ -548,110 +605,505 @@ pub struct SoundSource {
 
 #[cfg(test)]
 mod conversion {
-    // The way these tests are written might seem a bit strange, but it's to allow
-    // changes to the `TileFlags` type without completely breaking the tests.
-    //
-    // The goal here is to verify the logic behind encoding and decoding, without
-    // checking for specific values. This way, you can add more permutations to
-    // `TileFlags` or even change the underlying data type without breaking the
-    // tests.
-    //
-    // When adding new permutations `ENCODED_TILE_COUNT` needs to be adjusted.
-    mod tile_flags {
-        use bitflags::Flags;
-        use ragnarok_bytes::{ByteStream, FromBytes, ToBytes};
+    mod tile_type {
+        use ragnarok_bytes::{ByteStream, FromBytes, ToBytesExt};
+
+        use crate::map::TileType;
+
+        // Every known value with the expected walkable, snipable, water and cliff
+        // properties.
+        const TILE_TYPES: [(u8, TileType, bool, bool, bool, bool); 7] = [
+            (0, TileType::Walkable, true, true, false, false),
+            (1, TileType::NonWalkable, false, false, false, false),
+            (2, TileType::NonWalkableWater, false, false, true, false),
+            (3, TileType::WalkableWater, true, true, true, false),
+            (4, TileType::SnipableWater, false, true, true, false),
+            (5, TileType::SnipableCliff, false, true, false, true),
+            (6, TileType::Cliff, false, false, false, true),
+        ];
 
-        use crate::map::TileFlags;
+        #[test]
+        fn decode_encode() {
+            for (value, tile_type, ..) in TILE_TYPES {
+                let bytes = [value];
+                let mut byte_stream = ByteStream::<()>::without_metadata(&bytes);
 
-        type EncodedType = <TileFlags as Flags>::Bits;
+                assert_eq!(TileType::from_bytes(&mut byte_stream).unwrap(), tile_type);
+                assert_eq!(tile_type.to_bytes_vec::<()>().unwrap(), [value]);
+            }
+        }
 
-        const ENCODED_TILE_COUNT: usize = 7;
+        #[test]
+        fn unknown_values() {
+            for value in TILE_TYPES.len() as u8..=u8::MAX {
+                let bytes = [value];
+                let mut byte_stream = ByteStream::<()>::without_metadata(&bytes);
+                assert!(TileType::from_bytes(&mut byte_stream).is_err());
+            }
+        }
 
-        #[derive(Default)]
-        struct HitCounter {
-            slots: [bool; ENCODED_TILE_COUNT],
+        #[test]
+        fn properties() {
+            for (value, tile_type, walkable, snipable, water, cliff) in TILE_TYPES {
+                assert_eq!(tile_type.is_walkable(), walkable, "walkable of {value}");
+                assert_eq!(tile_type.is_snipable(), snipable, "snipable of {value}");
+                assert_eq!(tile_type.is_water(), water, "water of {value}");
+                assert_eq!(tile_type.is_cliff(), cliff, "cliff of {value}");
+            }
         }
+    }
+
+    mod round_trip {
+        use ragnarok_bytes::{ByteStream, ByteWriter, FromBytes, ReaderByteStream, ToBytes};
+
+        use crate::effect::EffectId;
+        use crate::map::{GroundData, MapData, MapResources, Resource, LIGHTMAP_SIZE};
+        use crate::version::InternalVersion;
 
-        impl HitCounter {
-            fn register(&mut self, index: EncodedType) {
-                assert!((index as usize) < ENCODED_TILE_COUNT, "index {index} is out of bounds");
+        fn round_trip<T: FromBytes + ToBytes>(input: &[u8]) {
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(input);
+            let data = T::from_bytes(&mut byte_stream).unwrap();
+            assert!(byte_stream.is_empty());
 
-                let slot = &mut self.slots[index as usize];
+            let mut byte_writer = ByteWriter::<Option<InternalVersion>>::without_metadata();
+            data.to_bytes(&mut byte_writer).unwrap();
 
-                assert!(!*slot, "index {index} was hit multiple times");
+            assert_eq!(input, byte_writer.into_inner().as_slice());
+        }
+
+        fn string(value: &str, length: usize) -> Vec<u8> {
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.resize(length, 0);
+            bytes
+        }
 
-                *slot = true;
+        fn floats(values: &[f32]) -> Vec<u8> {
+            values.iter().flat_map(|value| value.to_le_bytes()).collect()
+        }
+
+        fn ground_file(major: u8, minor: u8) -> Vec<u8> {
+            ground_file_with_lightmap_size(major, minor, LIGHTMAP_SIZE, LIGHTMAP_SIZE)
+        }
+
+        fn ground_file_with_lightmap_size(major: u8, minor: u8, lightmap_width: usize, lightmap_height: usize) -> Vec<u8> {
+            let above_1_7 = (major, minor) >= (1, 7);
+            let mut bytes = b"GRGN".to_vec();
+
+            bytes.extend([major, minor]);
+            bytes.extend(2i32.to_le_bytes());
+            bytes.extend(1i32.to_le_bytes());
+            bytes.extend(10f32.to_le_bytes());
+
+            // Textures
+            bytes.extend(1i32.to_le_bytes());
+            bytes.extend(80i32.to_le_bytes());
+            bytes.extend(string("texture.bmp", 80));
+
+            // Lightmaps
+            bytes.extend(1i32.to_le_bytes());
+            bytes.extend((lightmap_width as i32).to_le_bytes());
+            bytes.extend((lightmap_height as i32).to_le_bytes());
+            bytes.extend(1i32.to_le_bytes());
+            match above_1_7 {
+                true => bytes.extend((0..lightmap_width * lightmap_height * 4).map(|index| index as u8)),
+                false => bytes.extend(0..16u8),
+            }
+
+            // Surfaces
+            bytes.extend(1i32.to_le_bytes());
+            bytes.extend(floats(&[0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0]));
+            bytes.extend(0i16.to_le_bytes());
+            bytes.extend(0i16.to_le_bytes());
+            bytes.extend([10, 20, 30, 255]);
+
+            // Ground tiles
+            for _ in 0..2 {
+                bytes.extend(floats(&[1.0, 2.0, 3.0, 4.0]));
+
+                for surface_index in [0i32, -1, -1] {
+                    match above_1_7 {
+                        true => bytes.extend(surface_index.to_le_bytes()),
+                        false => bytes.extend((surface_index as i16).to_le_bytes()),
+                    }
+                }
             }
 
-            fn assert_all_slots_hit(self) {
-                for (index, hit) in self.slots.into_iter().enumerate() {
-                    if !hit {
-                        panic!("index {index} was never hit");
+            // Water settings
+            if (major, minor) >= (1, 8) {
+                bytes.extend(floats(&[1.5]));
+                bytes.extend(3i32.to_le_bytes());
+                bytes.extend(floats(&[1.0, 2.0, 50.0]));
+                bytes.extend(3u32.to_le_bytes());
+                bytes.extend(2i32.to_le_bytes());
+                bytes.extend(1i32.to_le_bytes());
+
+                for (water_level, water_type) in [(1.5, 3i32), (-2.5, 5)] {
+                    bytes.extend(floats(&[water_level]));
+
+                    if (major, minor) >= (1, 9) {
+                        bytes.extend(water_type.to_le_bytes());
+                        bytes.extend(floats(&[1.0, 2.0, 50.0]));
+                        bytes.extend(3u32.to_le_bytes());
                     }
                 }
             }
+
+            bytes
         }
 
-        // This test ensures that no more than one combination of flags is encoded
-        // to a set of bytes. It also ensures that all possible bytes can be encoded.
-        #[test]
-        fn encode() {
-            let mut hit_counter = HitCounter::default();
-
-            let mut test = |flags: TileFlags| {
-                if let Ok(bytes) = flags.to_bytes() {
-                    let mut byte_stream = ByteStream::<()>::without_metadata(&bytes);
-                    let index = EncodedType::from_bytes(&mut byte_stream).unwrap();
-                    hit_counter.register(index);
+        fn map_file(major: u8, minor: u8) -> Vec<u8> {
+            map_file_with_resource_order(major, minor, [0, 1, 2, 3])
+        }
+
+        fn map_file_with_resource_order(major: u8, minor: u8, resource_order: [usize; 4]) -> Vec<u8> {
+            map_file_with_build_number(major, minor, 187, resource_order)
+        }
+
+        /// The resources are an object, a light source, a sound source and an
+        /// effect source, written in the given order.
+        fn map_file_with_build_number(major: u8, minor: u8, build_number: i32, resource_order: [usize; 4]) -> Vec<u8> {
+            let version = (major, minor);
+            let mut bytes = b"GRSW".to_vec();
+
+            bytes.extend([major, minor]);
+
+            if version >= (2, 5) {
+                bytes.extend(build_number.to_le_bytes());
+            }
+
+            if version >= (2, 2) {
+                bytes.push(1);
+            }
+
+            bytes.extend(string("map.ini", 40));
+            bytes.extend(string("map.gnd", 40));
+            bytes.extend(string("map.gat", 40));
+
+            if version >= (1, 4) {
+                bytes.extend(string("map.src", 40));
+            }
+
+            // Water settings
+            if version < (2, 6) {
+                if version >= (1, 3) {
+                    bytes.extend(floats(&[1.5]));
                 }
-            };
 
-            // Test with no bits set.
-            test(TileFlags::empty());
+                if version >= (1, 8) {
+                    bytes.extend(3i32.to_le_bytes());
+                    bytes.extend(floats(&[1.0, 2.0, 50.0]));
+                }
 
-            // All other possible premutations (but only once).
-            for left in TileFlags::all().iter() {
-                for right in TileFlags::all().iter() {
-                    if left.bits() <= right.bits() {
-                        test(left | right);
-                    }
+                if version >= (1, 9) {
+                    bytes.extend(3u32.to_le_bytes());
+                }
+            }
+
+            // Light settings
+            if version >= (1, 5) {
+                bytes.extend(45i32.to_le_bytes());
+                bytes.extend(45i32.to_le_bytes());
+                bytes.extend(floats(&[1.0, 1.0, 1.0, 0.3, 0.3, 0.3]));
+            }
+
+            if version >= (1, 7) {
+                bytes.extend(floats(&[0.5]));
+            }
+
+            // Ground bounds
+            if version >= (1, 6) {
+                bytes.extend([-500i32, 500, -500, 500].iter().flat_map(|value| value.to_le_bytes()));
+            }
+
+            // Resources
+            bytes.extend(4u32.to_le_bytes());
+
+            let mut object = 1i32.to_le_bytes().to_vec();
+            if version >= (1, 3) {
+                object.extend(string("object", 40));
+                object.extend(0i32.to_le_bytes());
+                object.extend(floats(&[1.0]));
+                object.extend(0i32.to_le_bytes());
+            }
+            if version >= (2, 6) && build_number >= 186 {
+                object.push(0);
+            }
+            object.extend(string("model.rsm", 80));
+            object.extend(string("node", 80));
+            object.extend(floats(&[10.0, -5.0, 20.0, 0.0, 0.0, 0.0, 1.0, 2.0, 1.0]));
+
+            let mut light_source = 2i32.to_le_bytes().to_vec();
+            light_source.extend(string("light", 80));
+            light_source.extend(floats(&[10.0, -5.0, 20.0, 1.0, 0.5, 0.25, 40.0]));
+
+            let mut sound_source = 3i32.to_le_bytes().to_vec();
+            sound_source.extend(string("sound", 80));
+            sound_source.extend(string("sound.wav", 80));
+            sound_source.extend(floats(&[10.0, -5.0, 20.0, 0.8]));
+            sound_source.extend(10u32.to_le_bytes());
+            sound_source.extend(10u32.to_le_bytes());
+            sound_source.extend(floats(&[100.0]));
+            if version >= (2, 0) {
+                sound_source.extend(floats(&[4.0]));
+            }
+
+            let mut effect_source = 4i32.to_le_bytes().to_vec();
+            effect_source.extend(string("effect", 80));
+            effect_source.extend(floats(&[10.0, -5.0, 20.0]));
+            effect_source.extend(47u32.to_le_bytes());
+            effect_source.extend(floats(&[1.0, 0.5, 1.0, 2.0, 4.0]));
+
+            let resources = [object, light_source, sound_source, effect_source];
+            for index in resource_order {
+                bytes.extend(&resources[index]);
+            }
+
+            // Quad tree
+            if version >= (2, 1) {
+                let node_count = (0..=5).map(|depth| 4usize.pow(depth)).sum::<usize>();
+                bytes.extend((0..node_count * 12).flat_map(|index| (index as f32).to_le_bytes()));
+            }
+
+            bytes
+        }
+
+        #[test]
+        fn truncated_map_data() {
+            let mut input = map_file(2, 1);
+            // Cut the file inside the transform of the object.
+            let offset = input.windows(9).position(|window| window == b"model.rsm").unwrap() + 160 + 8;
+            input.truncate(offset);
+
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let error = MapData::from_bytes(&mut byte_stream).err().unwrap();
+
+            assert_eq!(error.field_path().unwrap(), "MapData.resources.resources[0].Object.0.transform");
+            assert_eq!(error.offset(), Some(offset - 8));
+            assert_eq!(error.version(), Some("2.1"));
+
+            let byte_window = error.byte_window().unwrap();
+            assert_eq!(byte_window.start, offset - 8 - 16);
+            assert_eq!(byte_window.bytes, input[offset - 8 - 16..]);
+        }
+
+        #[test]
+        fn ground_data_from_reader() {
+            /// Reader that returns at most three bytes per read.
+            struct ChunkedReader<'a>(&'a [u8]);
+
+            impl std::io::Read for ChunkedReader<'_> {
+                fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+                    let count = usize::min(3, buffer.len()).min(self.0.len());
+                    buffer[..count].copy_from_slice(&self.0[..count]);
+                    self.0 = &self.0[count..];
+                    Ok(count)
                 }
             }
 
-            hit_counter.assert_all_slots_hit();
+            let input = ground_file(1, 9);
+            let mut byte_stream = ReaderByteStream::<_, Option<InternalVersion>>::from_reader(ChunkedReader(&input));
+            let data = GroundData::from_bytes(&mut byte_stream).unwrap();
+            assert!(byte_stream.is_empty());
+
+            let mut byte_writer = ByteWriter::<Option<InternalVersion>>::without_metadata();
+            data.to_bytes(&mut byte_writer).unwrap();
+
+            assert_eq!(input, byte_writer.into_inner());
         }
 
-        // This test ensures that no more than one set of bytes is decoded
-        // to the combination of flags. It also ensures that all possible flags can be
-        // decoded.
         #[test]
-        fn decode() {
-            let mut hit_counter = HitCounter::default();
+        fn ground_data() {
+            for (major, minor) in [(1, 6), (1, 7), (1, 8), (1, 9)] {
+                round_trip::<GroundData>(&ground_file(major, minor));
+            }
+        }
 
-            for input in 0..EncodedType::MAX {
-                let bytes = input.to_bytes().unwrap();
-                let mut byte_stream = ByteStream::<()>::without_metadata(&bytes);
+        #[test]
+        fn lightmap_size() {
+            for (width, height) in [(4, 4), (LIGHTMAP_SIZE, 16)] {
+                let input = ground_file_with_lightmap_size(1, 9, width, height);
+                let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+
+                assert!(GroundData::from_bytes(&mut byte_stream).is_err());
+            }
+
+            // The size doesn't matter if there are no lightmaps and older versions don't
+            // contain the lightmaps.
+            round_trip::<GroundData>(&ground_file_with_lightmap_size(1, 6, 4, 4));
+        }
 
-                if TileFlags::from_bytes(&mut byte_stream).is_ok() {
-                    hit_counter.register(input)
+        #[test]
+        fn water_planes() {
+            for (major, minor) in [(1, 8), (1, 9)] {
+                let input = ground_file(major, minor);
+                let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+                let ground_data = GroundData::from_bytes(&mut byte_stream).unwrap();
+
+                let planes = ground_data.water_settings.unwrap().planes();
+                let levels: Vec<f32> = planes.iter().map(|plane| plane.water_level).collect();
+                let types: Vec<i32> = planes.iter().map(|plane| plane.water_type).collect();
+
+                assert_eq!(levels, vec![1.5, -2.5]);
+                assert!(planes.iter().all(|plane| plane.wave_pitch == 50.0));
+
+                match (major, minor) >= (1, 9) {
+                    true => assert_eq!(types, vec![3, 5]),
+                    false => assert_eq!(types, vec![3, 3]),
                 }
             }
+        }
 
-            hit_counter.assert_all_slots_hit();
+        #[test]
+        fn map_data_fields() {
+            let input = map_file(2, 5);
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let map_data = MapData::from_bytes(&mut byte_stream).unwrap();
+
+            assert_eq!(map_data.build_number, Some(187));
+            assert_eq!(map_data.render_flag, Some(1));
+            assert_eq!(map_data.ini_file, "map.ini");
+            assert_eq!(map_data.source_file.as_deref(), Some("map.src"));
         }
 
-        // Make sure that encoding and decoding agree.
         #[test]
-        fn decode_encode() {
-            for input in 0..EncodedType::MAX {
-                let bytes = input.to_bytes().unwrap();
-                let mut byte_stream = ByteStream::<()>::without_metadata(&bytes);
+        fn map_data() {
+            for (major, minor) in [
+                (1, 2),
+                (1, 3),
+                (1, 4),
+                (1, 5),
+                (1, 6),
+                (1, 7),
+                (1, 8),
+                (1, 9),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (2, 5),
+                (2, 6),
+            ] {
+                round_trip::<MapData>(&map_file(major, minor));
+            }
+        }
 
-                if let Ok(decoded) = TileFlags::from_bytes(&mut byte_stream) {
-                    let encoded = decoded.to_bytes().unwrap();
-                    assert_eq!(encoded.as_slice(), bytes);
-                }
+        #[test]
+        fn build_number() {
+            for (build_number, has_unknown) in [(185, false), (186, true)] {
+                let input = map_file_with_build_number(2, 6, build_number, [0, 1, 2, 3]);
+                round_trip::<MapData>(&input);
+
+                let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+                let map_data = MapData::from_bytes(&mut byte_stream).unwrap();
+                let object = map_data.resources.objects().next().unwrap();
+
+                assert_eq!(map_data.build_number, Some(build_number));
+                assert_eq!(object._unknown.is_some(), has_unknown);
+                assert_eq!(object.model_name, "model.rsm");
+            }
+        }
+
+        #[test]
+        fn interleaved_resources() {
+            round_trip::<MapData>(&map_file_with_resource_order(2, 1, [3, 0, 2, 1]));
+            round_trip::<MapData>(&map_file_with_resource_order(2, 6, [1, 3, 0, 2]));
+        }
+
+        #[test]
+        fn added_resources() {
+            let input = map_file_with_resource_order(2, 1, [3, 0, 2, 1]);
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let mut map_data = MapData::from_bytes(&mut byte_stream).unwrap();
+
+            let light_source = map_data.resources.light_sources().next().unwrap().clone();
+            let mut resources = std::mem::take(&mut map_data.resources.resources);
+            resources.retain(|resource| !matches!(resource, Resource::Object(_)));
+            resources.push(Resource::LightSource(light_source));
+            map_data.resources = MapResources::new(resources);
+
+            let mut byte_writer = ByteWriter::<Option<InternalVersion>>::without_metadata();
+            map_data.to_bytes(&mut byte_writer).unwrap();
+            let output = byte_writer.into_inner();
+
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&output);
+            let resources = MapData::from_bytes(&mut byte_stream).unwrap().resources;
+
+            assert_eq!(resources.resources_amount, 4);
+            assert!(matches!(resources.resources.as_slice(), [
+                Resource::EffectSource(_),
+                Resource::SoundSource(_),
+                Resource::LightSource(_),
+                Resource::LightSource(_)
+            ]));
+        }
+
+        #[test]
+        fn effect_source() {
+            let input = map_file(2, 6);
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let map_data = MapData::from_bytes(&mut byte_stream).unwrap();
+            let effect_source = map_data.resources.effect_sources().next().unwrap();
+
+            assert_eq!(effect_source.effect(), Some(EffectId::Torch));
+            assert_eq!(effect_source.emit_speed, 1.0);
+            assert_eq!(effect_source.parameters, [0.5, 1.0, 2.0, 4.0]);
+        }
+
+        #[test]
+        fn version_mismatch() {
+            let input = ground_file(1, 7);
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let mut ground_data = GroundData::from_bytes(&mut byte_stream).unwrap();
+
+            // Lightmaps are only stored for version 1.7 and above.
+            ground_data.version.minor = 6;
+
+            let mut byte_writer = ByteWriter::<Option<InternalVersion>>::without_metadata();
+            assert!(ground_data.to_bytes(&mut byte_writer).is_err());
+        }
+    }
+
+    mod bulk {
+        use ragnarok_bytes::{ByteStream, FromBytes};
+
+        use crate::map::{GatData, TileType};
+        use crate::version::InternalVersion;
+
+        fn gat_file(tile_types: &[u8]) -> Vec<u8> {
+            let mut bytes = b"GRAT".to_vec();
+
+            bytes.extend([1, 2]);
+            bytes.extend((tile_types.len() as i32).to_le_bytes());
+            bytes.extend(1i32.to_le_bytes());
+
+            for (index, tile_type) in tile_types.iter().enumerate() {
+                bytes.extend([index as f32, 1.0, 2.0, 3.0].iter().flat_map(|height| height.to_le_bytes()));
+                bytes.extend([*tile_type, 0, 0, 0]);
             }
+
+            bytes
+        }
+
+        #[test]
+        fn tiles() {
+            let input = gat_file(&[0, 3, 6]);
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let gat_data = GatData::from_bytes(&mut byte_stream).unwrap();
+
+            let tile_types: Vec<_> = gat_data.tiles.iter().map(|tile| tile.tile_type).collect();
+            let heights: Vec<_> = gat_data.tiles.iter().map(|tile| tile.upper_left_height).collect();
+
+            assert_eq!(tile_types, [TileType::Walkable, TileType::WalkableWater, TileType::Cliff]);
+            assert_eq!(heights, [0.0, 1.0, 2.0]);
+            assert!(gat_data.tiles.iter().all(|tile| tile.lower_right_height == 3.0));
+        }
+
+        #[test]
+        fn invalid_tile_type() {
+            let input = gat_file(&[0, 7]);
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let error = GatData::from_bytes(&mut byte_stream).err().unwrap();
+
+            assert_eq!(error.field_path().unwrap(), "GatData.tiles[1]");
         }
     }
 }
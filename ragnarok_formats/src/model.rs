@@ -1,6 +1,7 @@
 use cgmath::{Matrix3, Point3, Quaternion, Vector2, Vector3};
 use ragnarok_bytes::{
-    ByteConvertable, ByteStream, ConversionError, ConversionResult, ConversionResultExt, FromBytes, FromBytesExt, ToBytes,
+    ByteConvertable, ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, ConversionResultExt, FromBytes, FromBytesExt,
+    ToBytes, ToBytesExt,
 };
 
 use crate::signature::Signature;
@@ -8,34 +9,80 @@ use crate::version::{InternalVersion, MajorFirst, Version};
 
 /// A string that can either have a fixed lenght or be length prefixed, based on
 /// the file format version.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct ModelString<const LENGTH: usize> {
     pub inner: String,
+    /// Length prefixed strings may or may not include a null terminator, so it
+    /// is tracked to write the string back the way it was read.
+    pub null_terminated: bool,
 }
 
+/// Strings are equal if their text is equal, regardless of how they are
+/// stored.
+impl<const LENGTH: usize> PartialEq for ModelString<LENGTH> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<const LENGTH: usize> Eq for ModelString<LENGTH> {}
+
 impl<const LENGTH: usize> FromBytes for ModelString<LENGTH> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        let inner = if byte_stream
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
+        if byte_stream
             .get_metadata::<Self, Option<InternalVersion>>()?
             .ok_or(ConversionError::from_message("version not set"))?
             .equals_or_above(2, 2)
         {
+            // Length prefixed strings are not guaranteed to be null terminated, so we
+            // take everything up to the first null byte.
             let length = u32::from_bytes(byte_stream).trace::<Self>()? as usize;
-            let mut inner = String::from_n_bytes(byte_stream, length).trace::<Self>()?;
-            // need to remove the last character for some reason
-            inner.pop();
-            inner
+            let text_encoding = byte_stream.get_text_encoding();
+            let bytes = byte_stream.slice::<Self>(length)?;
+            let text_length = bytes.iter().position(|byte| *byte == 0).unwrap_or(bytes.len());
+            let inner = text_encoding.decode(&bytes[..text_length]).into_owned();
+            let null_terminated = bytes.last() == Some(&0);
+
+            Ok(Self { inner, null_terminated })
         } else {
-            String::from_n_bytes(byte_stream, LENGTH).trace::<Self>()?
-        };
+            let inner = String::from_n_bytes(byte_stream, LENGTH).trace::<Self>()?;
 
-        Ok(Self { inner })
+            Ok(Self {
+                inner,
+                null_terminated: true,
+            })
+        }
     }
 }
 
 impl<const LENGTH: usize> ToBytes for ModelString<LENGTH> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        panic!("ModelString can not be serialized currently because it depends on a version requirement");
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        if byte_writer
+            .get_metadata::<Self, Option<InternalVersion>>()?
+            .ok_or(ConversionError::from_message("version not set"))?
+            .equals_or_above(2, 2)
+        {
+            let text_encoding = byte_writer.get_text_encoding();
+            let mut bytes = text_encoding
+                .encode(&self.inner)
+                .ok_or_else(|| ConversionError::from_message(format!("{:?} can't be represented in {text_encoding:?}", self.inner)))
+                .trace::<Self>()?
+                .into_owned();
+
+            if self.null_terminated {
+                bytes.push(0);
+            }
+
+            u32::try_from(bytes.len())
+                .map_err(|_| ConversionError::from_message("string too long"))
+                .trace::<Self>()?
+                .to_bytes(byte_writer)
+                .trace::<Self>()?;
+            byte_writer.extend_from_slice(&bytes);
+            Ok(())
+        } else {
+            self.inner.to_n_bytes(byte_writer, LENGTH).trace::<Self>()
+        }
     }
 }
 
@@ -55,11 +102,20 @@ where
     }
 }
 
-#[derive(Debug, ByteConvertable)]
+#[derive(Clone, Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct ScaleKeyframeData {
+    pub frame: u32,
+    pub scale: Vector3<f32>,
+    pub data: f32,
+}
+
+#[derive(Clone, Debug, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct PositionKeyframeData {
     pub frame: u32,
     pub position: Point3<f32>,
+    pub data: u32,
 }
 
 #[derive(Clone, Debug, ByteConvertable)]
@@ -69,15 +125,48 @@ pub struct RotationKeyframeData {
     pub quaternions: Quaternion<f32>,
 }
 
+#[derive(Clone, Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct TextureKeyframeData {
+    pub frame: u32,
+    pub offset: f32,
+}
+
+#[derive(Clone, Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct TextureAnimationData {
+    pub animation_type: u32,
+    #[new_derive]
+    pub keyframe_count: u32,
+    #[repeating(keyframe_count)]
+    pub keyframes: Vec<TextureKeyframeData>,
+}
+
+#[derive(Clone, Debug, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct TextureAnimationsData {
+    pub texture_index: u32,
+    #[new_derive]
+    pub animation_count: u32,
+    #[repeating(animation_count)]
+    pub animations: Vec<TextureAnimationData>,
+}
+
 #[derive(Debug, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct FaceData {
+    /// Size of the face in bytes, not including this field. Anything beyond
+    /// the base size are additional smooth groups.
+    #[version_equals_or_above(2, 2)]
+    pub length: Option<u32>,
     pub vertex_position_indices: [u16; 3],
     pub texture_coordinate_indices: [u16; 3],
     pub texture_index: u16,
     pub padding: u16,
     pub two_sided: i32,
     pub smooth_group: i32,
+    #[repeating_expr(length.map(|length: u32| length.saturating_sub(24) / 4).unwrap_or_default())]
+    pub additional_smooth_groups: Vec<i32>,
 }
 
 #[derive(Debug, ByteConvertable)]
@@ -92,17 +181,28 @@ pub struct TextureCoordinateData {
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct NodeData {
     pub node_name: ModelString<40>,
-    pub parent_node_name: ModelString<40>, // This is where 2.2 starts failing
-    pub texture_count: u32,
-    #[repeating(texture_count)]
+    pub parent_node_name: ModelString<40>,
+    #[version_smaller(2, 3)]
+    #[new_derive]
+    pub texture_count: Option<u32>,
+    #[repeating_option(texture_count)]
     pub texture_indices: Vec<u32>,
+    #[version_equals_or_above(2, 3)]
+    #[new_derive]
+    pub texture_name_count: Option<u32>,
+    #[repeating_option(texture_name_count)]
+    pub texture_names: Vec<ModelString<40>>,
     #[cfg_attr(feature = "interface", hidden_element)]
     pub offset_matrix: Matrix3<f32>,
     pub translation1: Vector3<f32>,
-    pub translation2: Vector3<f32>,
-    pub rotation_angle: f32,
-    pub rotation_axis: Vector3<f32>,
-    pub scale: Vector3<f32>,
+    #[version_smaller(2, 2)]
+    pub translation2: Option<Vector3<f32>>,
+    #[version_smaller(2, 2)]
+    pub rotation_angle: Option<f32>,
+    #[version_smaller(2, 2)]
+    pub rotation_axis: Option<Vector3<f32>>,
+    #[version_smaller(2, 2)]
+    pub scale: Option<Vector3<f32>>,
     #[new_derive]
     pub vertex_position_count: u32,
     #[repeating(vertex_position_count)]
@@ -115,15 +215,25 @@ pub struct NodeData {
     pub face_count: u32,
     #[repeating(face_count)]
     pub faces: Vec<FaceData>,
-    #[version_equals_or_above(2, 5)] // unsure what vesion this is supposed to be (must be > 1.5)
+    #[version_equals_or_above(1, 6)]
     #[new_derive]
-    pub position_keyframe_count: Option<u32>,
-    #[repeating_option(position_keyframe_count)]
-    pub position_keyframes: Vec<PositionKeyframeData>,
+    pub scale_keyframe_count: Option<u32>,
+    #[repeating_option(scale_keyframe_count)]
+    pub scale_keyframes: Vec<ScaleKeyframeData>,
     #[new_derive]
     pub rotation_keyframe_count: u32,
     #[repeating(rotation_keyframe_count)]
     pub rotation_keyframes: Vec<RotationKeyframeData>,
+    #[version_equals_or_above(2, 2)]
+    #[new_derive]
+    pub position_keyframe_count: Option<u32>,
+    #[repeating_option(position_keyframe_count)]
+    pub position_keyframes: Vec<PositionKeyframeData>,
+    #[version_equals_or_above(2, 3)]
+    #[new_derive]
+    pub texture_animation_count: Option<u32>,
+    #[repeating_option(texture_animation_count)]
+    pub texture_animations: Vec<TextureAnimationsData>,
 }
 
 #[derive(Debug, ByteConvertable)]
@@ -137,22 +247,200 @@ pub struct ModelData {
     pub shade_type: u32,
     #[version_equals_or_above(1, 4)]
     pub alpha: Option<u8>,
-    #[version_smaller(2, 2)]
-    #[new_default]
-    pub reserved0: Option<[u8; 16]>,
     #[version_equals_or_above(2, 2)]
+    pub frames_per_second: Option<f32>,
+    #[version_smaller(2, 2)]
     #[new_default]
-    pub reserved1: Option<[u8; 4]>,
+    pub reserved: Option<[u8; 16]>,
+    #[version_smaller(2, 3)]
     #[new_derive]
-    pub texture_count: u32,
-    #[repeating(texture_count)]
+    pub texture_count: Option<u32>,
+    #[repeating_option(texture_count)]
     pub texture_names: Vec<ModelString<40>>,
+    #[version_smaller(2, 2)]
+    pub root_node_name: Option<ModelString<40>>,
     #[version_equals_or_above(2, 2)]
-    #[new_default]
-    pub skip: Option<u32>,
-    pub root_node_name: ModelString<40>,
+    #[new_derive]
+    pub root_node_count: Option<u32>,
+    #[repeating_option(root_node_count)]
+    pub root_node_names: Vec<ModelString<40>>,
     #[new_derive]
     pub node_count: u32,
     #[repeating(node_count)]
     pub nodes: Vec<NodeData>,
 }
+
+impl ModelData {
+    /// Names of all root nodes. Models before version 2.2 always have exactly
+    /// one root node, newer models may have multiple.
+    pub fn root_node_names(&self) -> impl Iterator<Item = &ModelString<40>> {
+        self.root_node_name.iter().chain(self.root_node_names.iter())
+    }
+}
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteStream, ByteWriter, FromBytes, TextEncoding, ToBytes};
+
+    use super::{ModelData, ModelString};
+    use crate::version::InternalVersion;
+
+    fn push_string(data: &mut Vec<u8>, value: &str) {
+        data.extend((value.len() as u32).to_le_bytes());
+        data.extend(value.as_bytes());
+    }
+
+    fn push_terminated_string(data: &mut Vec<u8>, value: &str) {
+        data.extend((value.len() as u32 + 1).to_le_bytes());
+        data.extend(value.as_bytes());
+        data.push(0);
+    }
+
+    fn push_floats(data: &mut Vec<u8>, values: &[f32]) {
+        values.iter().for_each(|value| data.extend(value.to_le_bytes()));
+    }
+
+    fn push_node(data: &mut Vec<u8>, name: &str, parent_name: &str) {
+        push_string(data, name);
+        push_string(data, parent_name);
+
+        // Texture names.
+        data.extend(1u32.to_le_bytes());
+        push_string(data, "texture.bmp");
+
+        // Offset matrix and translation.
+        push_floats(data, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        push_floats(data, &[1.0, 2.0, 3.0]);
+
+        // Vertex positions.
+        data.extend(3u32.to_le_bytes());
+        push_floats(data, &[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+
+        // Texture coordinates.
+        data.extend(3u32.to_le_bytes());
+        for coordinates in [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]] {
+            data.extend(0xFFFFFFFFu32.to_le_bytes());
+            push_floats(data, &coordinates);
+        }
+
+        // A single face with one additional smooth group.
+        data.extend(1u32.to_le_bytes());
+        data.extend(28u32.to_le_bytes());
+        [0u16, 1, 2, 0, 1, 2, 0, 0]
+            .iter()
+            .for_each(|value| data.extend(value.to_le_bytes()));
+        data.extend(0i32.to_le_bytes());
+        data.extend(0i32.to_le_bytes());
+        data.extend(1i32.to_le_bytes());
+
+        // Scale keyframes.
+        data.extend(1u32.to_le_bytes());
+        data.extend(10u32.to_le_bytes());
+        push_floats(data, &[2.0, 2.0, 2.0, 0.0]);
+
+        // Rotation keyframes.
+        data.extend(0u32.to_le_bytes());
+
+        // Position keyframes.
+        data.extend(1u32.to_le_bytes());
+        data.extend(10u32.to_le_bytes());
+        push_floats(data, &[4.0, 5.0, 6.0]);
+        data.extend(0u32.to_le_bytes());
+
+        // Texture animations.
+        data.extend(1u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(2u32.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(5u32.to_le_bytes());
+        push_floats(data, &[0.5]);
+    }
+
+    #[test]
+    fn rsm2_multiple_root_nodes() {
+        let mut data = b"GRSM".to_vec();
+        data.extend([2, 3]);
+        data.extend(100u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.push(255);
+        push_floats(&mut data, &[30.0]);
+
+        data.extend(2u32.to_le_bytes());
+        push_string(&mut data, "first");
+        push_string(&mut data, "second");
+
+        data.extend(3u32.to_le_bytes());
+        push_node(&mut data, "first", "");
+        push_node(&mut data, "second", "");
+        push_node(&mut data, "child", "first");
+
+        let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&data);
+        let model_data = ModelData::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(model_data.frames_per_second, Some(30.0));
+
+        let root_node_names: Vec<&str> = model_data.root_node_names().map(|name| name.as_ref()).collect();
+        assert_eq!(root_node_names, ["first", "second"]);
+
+        let child = &model_data.nodes[2];
+        assert_eq!(child.node_name.as_ref(), "child");
+        assert_eq!(child.parent_node_name.as_ref(), "first");
+        assert_eq!(child.texture_names[0].as_ref(), "texture.bmp");
+        assert_eq!(child.faces[0].additional_smooth_groups, [1]);
+        assert_eq!(child.scale_keyframes.len(), 1);
+        assert_eq!(child.position_keyframes.len(), 1);
+        assert_eq!(child.texture_animations[0].animations[0].keyframes[0].frame, 5);
+    }
+
+    #[test]
+    fn rsm2_round_trip() {
+        let mut data = b"GRSM".to_vec();
+        data.extend([2, 3]);
+        data.extend(100u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.push(255);
+        push_floats(&mut data, &[30.0]);
+
+        data.extend(2u32.to_le_bytes());
+        push_terminated_string(&mut data, "first");
+        push_string(&mut data, "second");
+
+        data.extend(2u32.to_le_bytes());
+        push_node(&mut data, "first", "");
+        push_node(&mut data, "second", "");
+
+        let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&data);
+        let model_data = ModelData::from_bytes(&mut byte_stream).unwrap();
+        assert!(byte_stream.is_empty());
+
+        let mut byte_writer = ByteWriter::<Option<InternalVersion>>::without_metadata();
+        model_data.to_bytes(&mut byte_writer).unwrap();
+
+        assert_eq!(byte_writer.into_inner(), data);
+    }
+
+    #[test]
+    fn rsm2_string_with_text_encoding() {
+        let mut data = 7u32.to_le_bytes().to_vec();
+        data.extend([0xBE, 0xC6, 0xC0, 0xCC, 0xC5, 0xDB, 0]);
+
+        let version = Some(InternalVersion {
+            major: 2,
+            minor: 2,
+            build_number: None,
+        });
+
+        let mut byte_stream = ByteStream::with_metadata(&data, version).with_text_encoding(TextEncoding::Windows949);
+        let model_string = ModelString::<40>::from_bytes(&mut byte_stream).unwrap();
+        assert_eq!(model_string.inner, "아이템");
+
+        let mut byte_writer = ByteWriter::with_metadata(version).with_text_encoding(TextEncoding::Windows949);
+        model_string.to_bytes(&mut byte_writer).unwrap();
+        assert_eq!(byte_writer.into_inner(), data);
+
+        let mut byte_writer = ByteWriter::with_metadata(version);
+        assert!(model_string.to_bytes(&mut byte_writer).is_err());
+    }
+}
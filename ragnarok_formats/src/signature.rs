@@ -1,4 +1,4 @@
-use ragnarok_bytes::{ByteStream, ConversionError, ConversionResult, FixedByteSize, FromBytes, ToBytes};
+use ragnarok_bytes::{ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, FixedByteSize, FromBytes, ToBytes};
 
 #[derive(Debug, Clone, Default)]
 pub struct Signature<const MAGIC: &'static [u8]>;
@@ -10,7 +10,7 @@ impl<const MAGIC: &'static [u8]> FixedByteSize for Signature<MAGIC> {
 }
 
 impl<const MAGIC: &'static [u8]> FromBytes for Signature<MAGIC> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self>
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self>
     where
         Self: Sized,
     {
@@ -23,8 +23,9 @@ impl<const MAGIC: &'static [u8]> FromBytes for Signature<MAGIC> {
 }
 
 impl<const MAGIC: &'static [u8]> ToBytes for Signature<MAGIC> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(MAGIC.to_vec())
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(MAGIC);
+        Ok(())
     }
 }
 
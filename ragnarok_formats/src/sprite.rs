@@ -1,10 +1,37 @@
+use cgmath::Vector2;
 use ragnarok_bytes::{
-    ByteConvertable, ByteStream, ConversionError, ConversionResult, ConversionResultExt, FromBytes, FromBytesExt, ToBytes,
+    ByteConvertable, ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, ConversionResultExt, FromBytes, FromBytesExt,
+    ToBytes,
 };
 
+use crate::action::{Action, ActionsData, Motion, SpriteClip};
 use crate::signature::Signature;
 use crate::version::{InternalVersion, MinorFirst, Version};
 
+/// Run-length encodes the pixels of a palette image, as it is done in
+/// sprites starting from version 2.1. Runs of zeros (the transparent color)
+/// are encoded as a zero followed by the length of the run.
+pub fn encode_palette_image(data: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    let mut index = 0;
+
+    while index < data.len() {
+        match data[index] {
+            0 => {
+                let length = data[index..].iter().take(u8::MAX as usize).take_while(|byte| **byte == 0).count();
+                encoded.extend_from_slice(&[0, length as u8]);
+                index += length;
+            }
+            byte => {
+                encoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    encoded
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct PaletteImageData {
@@ -18,7 +45,7 @@ pub struct PaletteImageData {
 pub struct EncodedData(pub Vec<u8>);
 
 impl FromBytes for PaletteImageData {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self>
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self>
     where
         Self: Sized,
     {
@@ -45,6 +72,11 @@ impl FromBytes for PaletteImageData {
                     encoded -= 1;
 
                     if byte == 0 {
+                        // Runs of zeros are encoded with two bytes.
+                        if encoded == 0 {
+                            return Err(ConversionError::from_message("badly encoded palette image"));
+                        }
+
                         let length = usize::max(byte_stream.byte::<Self>()? as usize, 1);
                         encoded -= 1;
 
@@ -76,8 +108,39 @@ impl FromBytes for PaletteImageData {
 }
 
 impl ToBytes for PaletteImageData {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        panic!("PalletteImageData can not be serialized currently because it depends on a version requirement");
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        self.width.to_bytes(byte_writer).trace::<Self>()?;
+        self.height.to_bytes(byte_writer).trace::<Self>()?;
+
+        let data = &self.data.0;
+
+        if data.len() != self.width as usize * self.height as usize {
+            return Err(ConversionError::from_message("palette image data doesn't match its size"));
+        }
+
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        if byte_writer
+            .get_metadata::<Self, Option<InternalVersion>>()?
+            .ok_or(ConversionError::from_message("version not set"))?
+            .smaller(2, 1)
+        {
+            byte_writer.extend_from_slice(data);
+            return Ok(());
+        }
+
+        let encoded = encode_palette_image(data);
+        let encoded_length: u16 = encoded
+            .len()
+            .try_into()
+            .map_err(|_| ConversionError::from_message("encoded palette image is too big"))?;
+
+        encoded_length.to_bytes(byte_writer).trace::<Self>()?;
+        byte_writer.extend_from_slice(&encoded);
+
+        Ok(())
     }
 }
 
@@ -90,6 +153,42 @@ pub struct RgbaImageData {
     pub data: Vec<u8>,
 }
 
+impl RgbaImageData {
+    /// Converts an image with rows from top to bottom and pixels in RGBA
+    /// format to the layout of a sprite file, which stores the rows from
+    /// bottom to top and the pixels in ABGR format.
+    pub fn from_rgba(width: u16, height: u16, data: &[u8]) -> ConversionResult<Self> {
+        if data.len() != width as usize * height as usize * 4 {
+            return Err(ConversionError::from_message("RGBA image data doesn't match its size"));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data: Self::swap_layout(width, data),
+        })
+    }
+
+    /// Converts the image to rows from top to bottom and pixels in RGBA
+    /// format.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        Self::swap_layout(self.width, &self.data)
+    }
+
+    // Reversing the rows and the channels of each pixel is its own inverse, so
+    // this works in both directions.
+    fn swap_layout(width: u16, data: &[u8]) -> Vec<u8> {
+        if width == 0 {
+            return Vec::new();
+        }
+
+        data.chunks_exact(4 * width as usize)
+            .rev()
+            .flat_map(|pixels| pixels.chunks_exact(4).flat_map(|pixel| [pixel[3], pixel[2], pixel[1], pixel[0]]))
+            .collect()
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct PaletteColor {
@@ -99,6 +198,8 @@ pub struct PaletteColor {
     pub reserved: u8,
 }
 
+/// Palette of a sprite. Standalone `.pal` files, as they are used for hair and
+/// cloth dyes, contain nothing but a palette.
 #[derive(Clone, Debug, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct Palette {
@@ -124,3 +225,253 @@ pub struct SpriteData {
     #[version_equals_or_above(1, 1)]
     pub palette: Option<Palette>,
 }
+
+/// Image that is used to author a new sprite.
+#[derive(Clone, Debug)]
+pub struct SpriteFrame {
+    pub width: u16,
+    pub height: u16,
+    /// Rows from top to bottom and pixels in RGBA format.
+    pub rgba: Vec<u8>,
+    /// Pixel of the image that is placed at the position of the entity.
+    pub anchor: Vector2<i32>,
+}
+
+/// Animation that is used to author a new sprite. It looks the same in all
+/// eight directions.
+#[derive(Clone, Debug)]
+pub struct SpriteAnimation {
+    /// Indices of the frames in the order they are shown.
+    pub frames: Vec<usize>,
+    /// Delay between two frames, as it is stored in the actions.
+    pub delay: f32,
+}
+
+/// Builds a sprite and the matching actions from a list of frames. Every
+/// animation becomes one action, so the first animation is the idle action.
+pub fn build_sprite(frames: &[SpriteFrame], animations: &[SpriteAnimation]) -> ConversionResult<(SpriteData, ActionsData)> {
+    const DIRECTION_COUNT: usize = 8;
+    const RGBA_SPRITE_TYPE: u32 = 1;
+    const NO_EVENT: i32 = -1;
+
+    let rgba_image_data: Vec<RgbaImageData> = frames
+        .iter()
+        .map(|frame| RgbaImageData::from_rgba(frame.width, frame.height, &frame.rgba))
+        .collect::<ConversionResult<_>>()?;
+
+    let palette = Palette {
+        colors: [PaletteColor::default(); 256],
+    };
+    let sprite_data = SpriteData::new(Version::new(2, 1), Vec::new(), rgba_image_data, Some(palette));
+
+    let mut actions = Vec::with_capacity(animations.len() * DIRECTION_COUNT);
+
+    for animation in animations {
+        let motions = animation
+            .frames
+            .iter()
+            .map(|&frame_index| {
+                let frame = frames
+                    .get(frame_index)
+                    .ok_or_else(|| ConversionError::from_message(format!("animation references missing frame {frame_index}")))?;
+
+                // The position of a sprite clip is the offset of the center of the image from
+                // the position of the entity.
+                let size = Vector2::new(frame.width as i32, frame.height as i32);
+                let position = size / 2 - frame.anchor;
+
+                let sprite_clip = SpriteClip::new(
+                    position,
+                    frame_index as i32,
+                    0,
+                    Some(u32::MAX),
+                    None,
+                    Some(Vector2::new(1.0, 1.0)),
+                    Some(0),
+                    Some(RGBA_SPRITE_TYPE),
+                    Some(size.map(|component| component as u32)),
+                );
+
+                Ok(Motion::new(
+                    [0; 4],
+                    [0; 4],
+                    vec![sprite_clip],
+                    Some(NO_EVENT),
+                    Some(0),
+                    Vec::new(),
+                ))
+            })
+            .collect::<ConversionResult<Vec<Motion>>>()?;
+
+        let action = Action::new(motions);
+        actions.extend(std::iter::repeat(action).take(DIRECTION_COUNT));
+    }
+
+    let action_count = u16::try_from(actions.len()).map_err(|_| ConversionError::from_message("too many animations"))?;
+    let delays = animations
+        .iter()
+        .flat_map(|animation| std::iter::repeat(animation.delay).take(DIRECTION_COUNT))
+        .collect();
+    let actions_data = ActionsData::new(Version::new(2, 5), action_count, actions, Vec::new(), Some(delays));
+
+    Ok((sprite_data, actions_data))
+}
+
+#[cfg(test)]
+mod conversion {
+    use cgmath::Vector2;
+    use ragnarok_bytes::{ByteStream, ByteWriter, FromBytes, ToBytes, ToBytesExt};
+
+    use super::{Palette, RgbaImageData, SpriteAnimation, SpriteData, SpriteFrame};
+    use crate::action::ActionsData;
+    use crate::version::InternalVersion;
+
+    // Contains a literal, a short run of zeros and a run that is longer than
+    // the maximum run length.
+    fn image_data() -> Vec<u8> {
+        let mut data = vec![0; 300];
+        data[0] = 7;
+        data[1] = 0;
+        data[2] = 0;
+        data[3] = 9;
+        data
+    }
+
+    fn encoded_image_data() -> Vec<u8> {
+        vec![7, 0, 2, 9, 0, 255, 0, 41]
+    }
+
+    fn sprite_file(major: u8, minor: u8) -> Vec<u8> {
+        let version = (major, minor);
+        let mut bytes = b"SP".to_vec();
+
+        bytes.extend([minor, major]);
+        bytes.extend(1u16.to_le_bytes());
+
+        if version >= (1, 2) {
+            bytes.extend(1u16.to_le_bytes());
+        }
+
+        bytes.extend(20u16.to_le_bytes());
+        bytes.extend(15u16.to_le_bytes());
+
+        match version >= (2, 1) {
+            true => {
+                let encoded = encoded_image_data();
+                bytes.extend((encoded.len() as u16).to_le_bytes());
+                bytes.extend(encoded);
+            }
+            false => bytes.extend(image_data()),
+        }
+
+        if version >= (1, 2) {
+            bytes.extend(2u16.to_le_bytes());
+            bytes.extend(1u16.to_le_bytes());
+            bytes.extend([1, 2, 3, 4, 5, 6, 7, 8]);
+        }
+
+        if version >= (1, 1) {
+            bytes.extend((0..1024).map(|index| index as u8));
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn sprite_data() {
+        for (major, minor) in [(1, 0), (1, 1), (1, 2), (2, 0), (2, 1)] {
+            let input = sprite_file(major, minor);
+
+            let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&input);
+            let sprite_data = SpriteData::from_bytes(&mut byte_stream).unwrap();
+
+            assert!(byte_stream.is_empty());
+            assert_eq!(sprite_data.palette_image_data[0].data.0, image_data());
+
+            let mut byte_writer = ByteWriter::<Option<InternalVersion>>::without_metadata();
+            sprite_data.to_bytes(&mut byte_writer).unwrap();
+
+            assert_eq!(input, byte_writer.into_inner());
+        }
+    }
+
+    #[test]
+    fn palette_file() {
+        let input: Vec<u8> = (0..1024).map(|index| (index / 4) as u8).collect();
+
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+        let palette = Palette::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(palette.colors[7].red, 7);
+        assert_eq!(palette.colors[255].reserved, 255);
+        assert_eq!(palette.to_bytes_vec::<()>().unwrap(), input);
+    }
+
+    #[test]
+    fn encode_palette_image() {
+        assert_eq!(super::encode_palette_image(&image_data()), encoded_image_data());
+        assert!(super::encode_palette_image(&[]).is_empty());
+    }
+
+    #[test]
+    fn rgba_image() {
+        // Two rows with one red and one blue pixel each.
+        let rgba = [255, 0, 0, 255, 0, 0, 255, 128];
+        let image_data = RgbaImageData::from_rgba(1, 2, &rgba).unwrap();
+
+        assert_eq!(image_data.data, [128, 255, 0, 0, 255, 0, 0, 255]);
+        assert_eq!(image_data.to_rgba(), rgba);
+        assert!(RgbaImageData::from_rgba(2, 2, &rgba).is_err());
+    }
+
+    #[test]
+    fn build_sprite() {
+        let frame = |width: u16, height: u16| SpriteFrame {
+            width,
+            height,
+            rgba: vec![255; width as usize * height as usize * 4],
+            anchor: Vector2::new(width as i32 / 2, height as i32),
+        };
+        let frames = [frame(4, 6), frame(8, 10)];
+        let animations = [
+            SpriteAnimation {
+                frames: vec![0],
+                delay: 4.0,
+            },
+            SpriteAnimation {
+                frames: vec![0, 1],
+                delay: 2.0,
+            },
+        ];
+
+        let (sprite_data, actions_data) = super::build_sprite(&frames, &animations).unwrap();
+
+        let sprite_bytes = sprite_data.to_bytes_vec::<Option<InternalVersion>>().unwrap();
+        let sprite_data = SpriteData::from_bytes(&mut ByteStream::<Option<InternalVersion>>::without_metadata(&sprite_bytes)).unwrap();
+        assert_eq!(sprite_data.rgba_image_data.len(), 2);
+        assert_eq!(sprite_data.rgba_image_data[1].width, 8);
+
+        let actions_bytes = actions_data.to_bytes_vec::<Option<InternalVersion>>().unwrap();
+        let mut byte_stream = ByteStream::<Option<InternalVersion>>::without_metadata(&actions_bytes);
+        let actions_data = ActionsData::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(actions_data.actions.len(), 16);
+        assert_eq!(actions_data.delays.unwrap()[8], 2.0);
+
+        let sprite_clip = &actions_data.actions[15].motions[1].sprite_clips[0];
+        assert_eq!(sprite_clip.sprite_number, 1);
+        assert_eq!(sprite_clip.position, Vector2::new(0, -5));
+    }
+
+    #[test]
+    fn build_sprite_missing_frame() {
+        let animations = [SpriteAnimation {
+            frames: vec![0],
+            delay: 4.0,
+        }];
+
+        assert!(super::build_sprite(&[], &animations).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use std::ops::Add;
 
 use cgmath::{Deg, EuclideanSpace, Point3, Rad, Vector3};
-use ragnarok_bytes::{ByteStream, ConversionResult, ConversionResultExt, FromBytes, ToBytes};
+use ragnarok_bytes::{ByteSource, ByteStream, ByteWriter, ConversionResult, ConversionResultExt, FromBytes, ToBytes};
 
 #[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
@@ -13,7 +13,7 @@ pub struct Transform {
 }
 
 impl FromBytes for Transform {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let mut position = <Point3<f32>>::from_bytes(byte_stream).trace::<Self>()?;
         let rotation = <Vector3<f32>>::from_bytes(byte_stream).trace::<Self>()?;
         let scale = <Vector3<f32>>::from_bytes(byte_stream).trace::<Self>()?;
@@ -31,16 +31,16 @@ impl FromBytes for Transform {
 }
 
 impl ToBytes for Transform {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
         let position = Vector3::new(self.position.x, -self.position.y, self.position.z);
         let rotation = self.rotation.map(|radiants| Deg::from(radiants).0);
         let scale = self.scale;
 
-        let mut bytes = position.to_bytes().trace::<Self>()?;
-        bytes.extend(rotation.to_bytes().trace::<Self>()?);
-        bytes.extend(scale.to_bytes().trace::<Self>()?);
+        position.to_bytes(byte_writer).trace::<Self>()?;
+        rotation.to_bytes(byte_writer).trace::<Self>()?;
+        scale.to_bytes(byte_writer).trace::<Self>()?;
 
-        Ok(bytes)
+        Ok(())
     }
 }
 
@@ -81,7 +81,7 @@ impl Add for Transform {
 
 #[cfg(test)]
 mod conversion {
-    use ragnarok_bytes::{ByteStream, FromBytes, ToBytes};
+    use ragnarok_bytes::{ByteStream, FromBytes, ToBytesExt};
 
     use super::Transform;
 
@@ -93,7 +93,7 @@ mod conversion {
         let mut byte_stream = ByteStream::<()>::without_metadata(input);
 
         let transform = Transform::from_bytes(&mut byte_stream).unwrap();
-        let output = transform.to_bytes().unwrap();
+        let output = transform.to_bytes_vec::<()>().unwrap();
 
         assert_eq!(input, output.as_slice());
     }
@@ -1,7 +1,7 @@
 use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
 
-use ragnarok_bytes::{ByteStream, ConversionResult, FromBytes, ToBytes};
+use ragnarok_bytes::{ByteSource, ByteStream, ByteWriter, ConversionResult, FromBytes, ToBytes};
 
 #[derive(Copy, Clone, Debug)]
 pub struct MajorFirst;
@@ -16,8 +16,18 @@ pub struct Version<T> {
     phantom_data: PhantomData<T>,
 }
 
+impl<T> Version<T> {
+    pub fn new(major: u8, minor: u8) -> Self {
+        Self {
+            major,
+            minor,
+            phantom_data: PhantomData,
+        }
+    }
+}
+
 impl FromBytes for Version<MajorFirst> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let major = byte_stream.byte::<Self>()?;
         let minor = byte_stream.byte::<Self>()?;
 
@@ -30,7 +40,7 @@ impl FromBytes for Version<MajorFirst> {
 }
 
 impl FromBytes for Version<MinorFirst> {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let minor = byte_stream.byte::<Self>()?;
         let major = byte_stream.byte::<Self>()?;
 
@@ -43,14 +53,16 @@ impl FromBytes for Version<MinorFirst> {
 }
 
 impl ToBytes for Version<MajorFirst> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(vec![self.major, self.minor])
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&[self.major, self.minor]);
+        Ok(())
     }
 }
 
 impl ToBytes for Version<MinorFirst> {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        Ok(vec![self.minor, self.major])
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        byte_writer.extend_from_slice(&[self.minor, self.major]);
+        Ok(())
     }
 }
 
@@ -64,12 +76,19 @@ impl<T> Display for Version<T> {
 pub struct InternalVersion {
     pub major: u8,
     pub minor: u8,
+    /// Build number of formats that store one after their version. It is only
+    /// known once it is read, so only later fields can depend on it.
+    pub build_number: Option<i32>,
 }
 
 impl<T> From<Version<T>> for InternalVersion {
     fn from(version: Version<T>) -> Self {
         let Version { major, minor, .. } = version;
-        Self { major, minor }
+        Self {
+            major,
+            minor,
+            build_number: None,
+        }
     }
 }
 
@@ -81,6 +100,10 @@ impl InternalVersion {
     pub fn equals_or_above(&self, major: u8, minor: u8) -> bool {
         self.major > major || (self.major == major && self.minor >= minor)
     }
+
+    pub fn build_number_equals_or_above(&self, build_number: i32) -> bool {
+        self.build_number.is_some_and(|own_build_number| own_build_number >= build_number)
+    }
 }
 
 impl Display for InternalVersion {
@@ -91,7 +114,7 @@ impl Display for InternalVersion {
 
 #[cfg(test)]
 mod conversion {
-    use ragnarok_bytes::{ByteStream, FromBytes, ToBytes};
+    use ragnarok_bytes::{ByteStream, FromBytes, ToBytesExt};
 
     use super::{MajorFirst, Version};
     use crate::version::MinorFirst;
@@ -102,7 +125,7 @@ mod conversion {
         let mut byte_stream = ByteStream::<()>::without_metadata(input);
 
         let version = Version::<MajorFirst>::from_bytes(&mut byte_stream).unwrap();
-        let output = version.to_bytes().unwrap();
+        let output = version.to_bytes_vec::<()>().unwrap();
 
         assert_eq!(input, output.as_slice());
     }
@@ -113,7 +136,7 @@ mod conversion {
         let mut byte_stream = ByteStream::<()>::without_metadata(input);
 
         let version = Version::<MinorFirst>::from_bytes(&mut byte_stream).unwrap();
-        let output = version.to_bytes().unwrap();
+        let output = version.to_bytes_vec::<()>().unwrap();
 
         assert_eq!(input, output.as_slice());
     }
@@ -357,22 +357,28 @@ fn main() {
 
                     if source_port == LOGIN_SERVER_PORT {
                         let mut byte_stream = ByteStream::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = client_login_handler.process_one(&mut byte_stream) {}
+                        while let HandlerResult::Ok(_) | HandlerResult::SkippedPacket = client_login_handler.process_one(&mut byte_stream) {
+                        }
                     } else if destination_port == LOGIN_SERVER_PORT {
                         let mut byte_stream = ByteStream::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = server_login_handler.process_one(&mut byte_stream) {}
+                        while let HandlerResult::Ok(_) | HandlerResult::SkippedPacket = server_login_handler.process_one(&mut byte_stream) {
+                        }
                     } else if source_port == CHARACTER_SERVER_PORT {
                         let mut byte_stream = ByteStream::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = client_character_handler.process_one(&mut byte_stream) {}
+                        while let HandlerResult::Ok(_) | HandlerResult::SkippedPacket =
+                            client_character_handler.process_one(&mut byte_stream)
+                        {}
                     } else if destination_port == CHARACTER_SERVER_PORT {
                         let mut byte_stream = ByteStream::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = server_character_handler.process_one(&mut byte_stream) {}
+                        while let HandlerResult::Ok(_) | HandlerResult::SkippedPacket =
+                            server_character_handler.process_one(&mut byte_stream)
+                        {}
                     } else if source_port == MAP_SERVER_PORT {
                         let mut byte_stream = ByteStream::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = client_map_handler.process_one(&mut byte_stream) {}
+                        while let HandlerResult::Ok(_) | HandlerResult::SkippedPacket = client_map_handler.process_one(&mut byte_stream) {}
                     } else if destination_port == MAP_SERVER_PORT {
                         let mut byte_stream = ByteStream::without_metadata(tcp_slice.payload());
-                        while let HandlerResult::Ok(_) = server_map_handler.process_one(&mut byte_stream) {}
+                        while let HandlerResult::Ok(_) | HandlerResult::SkippedPacket = server_map_handler.process_one(&mut byte_stream) {}
                     }
                 };
             }
@@ -2,13 +2,17 @@ use std::collections::HashMap;
 
 use ragnarok_bytes::{ByteStream, ConversionError, ConversionResult, FromBytes};
 
-use crate::PacketHeader;
+use crate::{PacketHeader, PacketLength, PacketLengthTable, PacketVersion};
 
 /// Possible results of [`PacketHandler::process_one`].
 pub enum HandlerResult<Output> {
     /// Packet was successfully processed and produced some output.
     Ok(Output),
-    /// No packet handler was registered for the incoming packet.
+    /// No packet handler was registered for the incoming packet, but its
+    /// length is known, so it was skipped.
+    SkippedPacket,
+    /// No packet handler was registered for the incoming packet and its
+    /// length is unknown.
     UnhandledPacket,
     /// Packet was most likely cut-off.
     PacketCutOff,
@@ -70,6 +74,8 @@ where
 {
     handlers: HashMap<PacketHeader, HandlerFunction<Output, Meta>>,
     packet_callback: Callback,
+    packet_version: PacketVersion,
+    packet_lengths: PacketLengthTable,
 }
 
 impl<Output, Meta, Callback> Default for PacketHandler<Output, Meta, Callback>
@@ -81,6 +87,8 @@ where
         Self {
             handlers: Default::default(),
             packet_callback: Default::default(),
+            packet_version: Default::default(),
+            packet_lengths: Default::default(),
         }
     }
 }
@@ -93,9 +101,19 @@ where
 {
     /// Create a new packet handler with a callback.
     pub fn with_callback(packet_callback: Callback) -> Self {
+        Self::with_packet_version(packet_callback, PacketVersion::DEFAULT)
+    }
+
+    /// Create a new packet handler with a callback for a specific packet
+    /// version. Packets are registered with their header in that version.
+    /// The packet version is only used to select the headers, so the byte
+    /// stream should carry the same version for the packet layouts.
+    pub fn with_packet_version(packet_callback: Callback, packet_version: PacketVersion) -> Self {
         Self {
             handlers: Default::default(),
             packet_callback,
+            packet_version,
+            packet_lengths: PacketLengthTable::for_packet_version(packet_version),
         }
     }
 
@@ -105,9 +123,10 @@ where
         Packet: ragnarok_packets::Packet,
         Return: Into<Output>,
     {
+        let packet_header = Packet::header(self.packet_version);
         let packet_callback = self.packet_callback.clone();
         let old_handler = self.handlers.insert(
-            Packet::HEADER,
+            packet_header,
             Box::new(move |byte_stream| {
                 let packet = Packet::payload_from_bytes(byte_stream)?;
 
@@ -118,9 +137,7 @@ where
         );
 
         match old_handler.is_some() {
-            true => Err(DuplicateHandlerError {
-                packet_header: Packet::HEADER,
-            }),
+            true => Err(DuplicateHandlerError { packet_header }),
             false => Ok(()),
         }
     }
@@ -130,9 +147,10 @@ where
     where
         Packet: ragnarok_packets::Packet,
     {
+        let packet_header = Packet::header(self.packet_version);
         let packet_callback = self.packet_callback.clone();
         let old_handler = self.handlers.insert(
-            Packet::HEADER,
+            packet_header,
             Box::new(move |byte_stream| {
                 let packet = Packet::payload_from_bytes(byte_stream)?;
 
@@ -143,25 +161,98 @@ where
         );
 
         match old_handler.is_some() {
-            true => Err(DuplicateHandlerError {
-                packet_header: Packet::HEADER,
-            }),
+            true => Err(DuplicateHandlerError { packet_header }),
             false => Ok(()),
         }
     }
 
+    /// Register a noop packet handler for every server packet defined in this
+    /// crate. Some packets share a header, in which case the last one wins.
+    pub fn register_all_noop(&mut self) {
+        macro_rules! register_noop {
+            ($($packet:ty),* $(,)?) => {
+                $(let _ = self.register_noop::<$packet>();)*
+            };
+        }
+
+        crate::for_each_server_packet!(register_noop);
+    }
+
+    /// The callback that monitors the packets of this handler.
+    pub fn packet_callback(&self) -> &Callback {
+        &self.packet_callback
+    }
+
+    /// Length of the packet at the start of `bytes`, either from the packet
+    /// length table or, for variable length packets, from the packet itself.
+    /// Returns [`None`] if the packet is unknown or its length is cut off.
+    pub fn packet_length(&self, bytes: &[u8]) -> Option<usize> {
+        let mut byte_stream = ByteStream::<()>::without_metadata(bytes);
+        let header = PacketHeader::from_bytes(&mut byte_stream).ok()?;
+
+        match self.packet_lengths.get(header)? {
+            PacketLength::Fixed(packet_length) => Some(packet_length as usize),
+            PacketLength::Variable => u16::from_bytes(&mut byte_stream).ok().map(usize::from),
+        }
+    }
+
+    /// Skip a packet that doesn't have a handler registered. The byte stream
+    /// has to be at the start of the packet.
+    fn skip_packet(&self, byte_stream: &mut ByteStream<Meta>, packet_length: PacketLength) -> HandlerResult<Output> {
+        let packet_length = match packet_length {
+            PacketLength::Fixed(packet_length) => packet_length,
+            PacketLength::Variable => {
+                let save_point = byte_stream.create_save_point();
+                let packet_length = PacketHeader::from_bytes(byte_stream).and_then(|_| u16::from_bytes(byte_stream));
+
+                if let Err(error) = byte_stream.restore_save_point(save_point) {
+                    return HandlerResult::InternalError(error);
+                }
+
+                match packet_length {
+                    // The length has to at least cover the header and the length itself,
+                    // otherwise there is no telling where the next packet starts.
+                    Ok(packet_length) if packet_length >= 4 => packet_length,
+                    Ok(..) => {
+                        self.packet_callback.unknown_packet(byte_stream.remaining_bytes());
+                        return HandlerResult::UnhandledPacket;
+                    }
+                    Err(..) => return HandlerResult::PacketCutOff,
+                }
+            }
+        };
+
+        match byte_stream.slice::<Self>(packet_length as usize) {
+            Ok(bytes) => {
+                self.packet_callback.unknown_packet(bytes.to_vec());
+                HandlerResult::SkippedPacket
+            }
+            Err(..) => HandlerResult::PacketCutOff,
+        }
+    }
+
     /// Take a single packet from the byte stream.
     pub fn process_one(&mut self, byte_stream: &mut ByteStream<Meta>) -> HandlerResult<Output> {
         let save_point = byte_stream.create_save_point();
 
         let Ok(header) = PacketHeader::from_bytes(byte_stream) else {
             // Packet is cut-off at the header.
-            byte_stream.restore_save_point(save_point);
-            return HandlerResult::PacketCutOff;
+            return match byte_stream.restore_save_point(save_point) {
+                Ok(()) => HandlerResult::PacketCutOff,
+                Err(error) => HandlerResult::InternalError(error),
+            };
         };
 
         let Some(handler) = self.handlers.get(&header) else {
-            byte_stream.restore_save_point(save_point);
+            if let Err(error) = byte_stream.restore_save_point(save_point) {
+                return HandlerResult::InternalError(error);
+            }
+
+            // If we know the length of the packet we can skip it and continue with the
+            // next one.
+            if let Some(packet_length) = self.packet_lengths.get(header) {
+                return self.skip_packet(byte_stream, packet_length);
+            }
 
             self.packet_callback.unknown_packet(byte_stream.remaining_bytes());
 
@@ -170,18 +261,164 @@ where
 
         match handler(byte_stream) {
             Ok(output) => HandlerResult::Ok(output),
-            // Cut-off packet (probably).
-            Err(error) if error.is_byte_stream_too_short() => {
-                byte_stream.restore_save_point(save_point);
-                HandlerResult::PacketCutOff
-            }
-            Err(error) => {
-                byte_stream.restore_save_point(save_point);
+            Err(error) => match byte_stream.restore_save_point(save_point) {
+                // The handler read past the bytes that the stream retains, so the packet
+                // can't be read again.
+                Err(restore_error) => HandlerResult::InternalError(restore_error),
+                // Cut-off packet (probably).
+                Ok(()) if error.is_byte_stream_too_short() => HandlerResult::PacketCutOff,
+                Ok(()) => {
+                    self.packet_callback.failed_packet(byte_stream.remaining_bytes(), error.clone());
+
+                    HandlerResult::InternalError(error)
+                }
+            },
+        }
+    }
+}
 
-                self.packet_callback.failed_packet(byte_stream.remaining_bytes(), error.clone());
+#[cfg(test)]
+mod skipping {
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
-                HandlerResult::InternalError(error)
-            }
+    use ragnarok_bytes::ByteStream;
+
+    use super::{HandlerResult, PacketCallback, PacketHandler};
+    use crate::{PacketVersion, ServerTickPacket};
+
+    #[derive(Clone, Default)]
+    struct UnknownPackets(Rc<RefCell<Vec<Vec<u8>>>>);
+
+    impl PacketCallback for UnknownPackets {
+        fn unknown_packet(&self, bytes: Vec<u8>) {
+            self.0.borrow_mut().push(bytes);
+        }
+    }
+
+    fn create_packet_handler(packet_version: PacketVersion) -> (PacketHandler<u32, PacketVersion, UnknownPackets>, UnknownPackets) {
+        let unknown_packets = UnknownPackets::default();
+        let mut packet_handler = PacketHandler::with_packet_version(unknown_packets.clone(), packet_version);
+
+        packet_handler.register(|packet: ServerTickPacket| packet.client_tick.0).unwrap();
+
+        (packet_handler, unknown_packets)
+    }
+
+    #[test]
+    fn fixed_length() {
+        let input = [0xA1, 0x00, 1, 2, 3, 4, 0x7F, 0x00, 9, 0, 0, 0];
+        let (mut packet_handler, unknown_packets) = create_packet_handler(PacketVersion::DEFAULT);
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion::DEFAULT);
+
+        assert!(matches!(
+            packet_handler.process_one(&mut byte_stream),
+            HandlerResult::SkippedPacket
+        ));
+        assert!(matches!(packet_handler.process_one(&mut byte_stream), HandlerResult::Ok(9)));
+        assert!(byte_stream.is_empty());
+        assert_eq!(*unknown_packets.0.borrow(), [input[..6].to_vec()]);
+    }
+
+    #[test]
+    fn variable_length() {
+        let input = [0x09, 0x01, 7, 0, 1, 2, 3, 0x7F, 0x00, 9, 0, 0, 0];
+        let (mut packet_handler, unknown_packets) = create_packet_handler(PacketVersion::DEFAULT);
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion::DEFAULT);
+
+        assert!(matches!(
+            packet_handler.process_one(&mut byte_stream),
+            HandlerResult::SkippedPacket
+        ));
+        assert!(matches!(packet_handler.process_one(&mut byte_stream), HandlerResult::Ok(9)));
+        assert_eq!(*unknown_packets.0.borrow(), [input[..7].to_vec()]);
+    }
+
+    #[test]
+    fn invalid_variable_length() {
+        let input = [0x09, 0x01, 3, 0, 0x7F, 0x00, 9, 0, 0, 0];
+        let (mut packet_handler, unknown_packets) = create_packet_handler(PacketVersion::DEFAULT);
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion::DEFAULT);
+
+        assert!(matches!(
+            packet_handler.process_one(&mut byte_stream),
+            HandlerResult::UnhandledPacket
+        ));
+        assert_eq!(*unknown_packets.0.borrow(), [input.to_vec()]);
+    }
+
+    #[test]
+    fn cut_off() {
+        for input in [[0xA1, 0x00, 1, 2].as_slice(), &[0x09, 0x01, 7], &[0x09, 0x01, 7, 0, 1]] {
+            let (mut packet_handler, unknown_packets) = create_packet_handler(PacketVersion::DEFAULT);
+            let mut byte_stream = ByteStream::with_metadata(input, PacketVersion::DEFAULT);
+
+            assert!(matches!(
+                packet_handler.process_one(&mut byte_stream),
+                HandlerResult::PacketCutOff
+            ));
+            assert_eq!(byte_stream.get_offset(), 0);
+            assert!(unknown_packets.0.borrow().is_empty());
         }
     }
+
+    #[test]
+    fn unknown_length() {
+        let input = [0xFF, 0xFF, 1, 2, 0x7F, 0x00, 9, 0, 0, 0];
+        let (mut packet_handler, unknown_packets) = create_packet_handler(PacketVersion::DEFAULT);
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion::DEFAULT);
+
+        assert!(matches!(
+            packet_handler.process_one(&mut byte_stream),
+            HandlerResult::UnhandledPacket
+        ));
+        assert_eq!(*unknown_packets.0.borrow(), [input.to_vec()]);
+    }
+
+    #[test]
+    fn all_noop() {
+        let input = [0xA1, 0x00, 1, 2, 3, 4, 0x7F, 0x00, 9, 0, 0, 0];
+        let mut packet_handler =
+            PacketHandler::<(), PacketVersion, UnknownPackets>::with_packet_version(UnknownPackets::default(), PacketVersion::DEFAULT);
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion::DEFAULT);
+
+        packet_handler.register_all_noop();
+
+        assert!(matches!(
+            packet_handler.process_one(&mut byte_stream),
+            HandlerResult::SkippedPacket
+        ));
+        assert!(matches!(packet_handler.process_one(&mut byte_stream), HandlerResult::Ok(())));
+        assert!(byte_stream.is_empty());
+    }
+
+    #[test]
+    fn packet_length() {
+        let (packet_handler, _) = create_packet_handler(PacketVersion::DEFAULT);
+
+        assert_eq!(packet_handler.packet_length(&[0xA1, 0x00]), Some(6));
+        assert_eq!(packet_handler.packet_length(&[0x09, 0x01, 0x10, 0x27]), Some(10000));
+        assert_eq!(packet_handler.packet_length(&[0x09, 0x01, 0x10]), None);
+        assert_eq!(packet_handler.packet_length(&[0xFF, 0xFF, 1, 2]), None);
+        assert_eq!(packet_handler.packet_length(&[0xA1]), None);
+    }
+
+    #[test]
+    fn length_by_packet_version() {
+        let input = [0x69, 0x00, 4, 0];
+
+        let (mut packet_handler, _) = create_packet_handler(PacketVersion(20170314));
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion(20170314));
+        assert!(matches!(
+            packet_handler.process_one(&mut byte_stream),
+            HandlerResult::SkippedPacket
+        ));
+
+        let (mut packet_handler, _) = create_packet_handler(PacketVersion(20170315));
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion(20170315));
+        assert!(matches!(
+            packet_handler.process_one(&mut byte_stream),
+            HandlerResult::UnhandledPacket
+        ));
+    }
 }
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use crate::{PacketHeader, PacketVersion, ServerPacket};
+
+/// The length of a packet in bytes, *including* the header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketLength {
+    Fixed(u16),
+    /// The length is stored in the two bytes following the header.
+    Variable,
+}
+
+/// Length of a packet in the packet versions from `since` up to (but not
+/// including) `before`. Entries without bounds apply to every packet version.
+struct PacketLengthEntry {
+    header: PacketHeader,
+    length: PacketLength,
+    since: Option<PacketVersion>,
+    before: Option<PacketVersion>,
+}
+
+impl PacketLengthEntry {
+    const fn fixed(header: u16, length: u16) -> Self {
+        Self {
+            header: PacketHeader(header),
+            length: PacketLength::Fixed(length),
+            since: None,
+            before: None,
+        }
+    }
+
+    const fn variable(header: u16) -> Self {
+        Self {
+            header: PacketHeader(header),
+            length: PacketLength::Variable,
+            since: None,
+            before: None,
+        }
+    }
+
+    const fn since(self, packet_version: u32) -> Self {
+        Self {
+            since: Some(PacketVersion(packet_version)),
+            ..self
+        }
+    }
+
+    fn applies_to(&self, packet_version: PacketVersion) -> bool {
+        self.since.is_none_or(|since| since <= packet_version) && self.before.is_none_or(|before| packet_version < before)
+    }
+}
+
+/// Header and length of a packet defined in this crate in a specific packet
+/// version.
+fn defined_packet_length<P: ServerPacket>(packet_version: PacketVersion) -> (PacketHeader, PacketLength) {
+    (P::header(packet_version), P::length(packet_version))
+}
+
+macro_rules! defined_packet_lengths {
+    ($($packet:ty),* $(,)?) => {
+        /// Lengths of the packets defined in this crate, as generated by the
+        /// packet derive.
+        const DEFINED_PACKET_LENGTHS: &[fn(PacketVersion) -> (PacketHeader, PacketLength)] =
+            &[$(defined_packet_length::<$packet>),*];
+    };
+}
+
+crate::for_each_server_packet!(defined_packet_lengths);
+
+/// Lengths of packets that the server might send but that are not defined in
+/// this crate, taken from the packet database of rAthena. Like the headers of
+/// the defined packets, entries can be limited to the packet versions that
+/// use them.
+const PACKET_LENGTHS: &[PacketLengthEntry] = &[
+    PacketLengthEntry::fixed(0x006A, 23),
+    PacketLengthEntry::fixed(0x0092, 28),
+    PacketLengthEntry::fixed(0x0095, 30),
+    PacketLengthEntry::fixed(0x009D, 17),
+    PacketLengthEntry::fixed(0x009E, 17),
+    PacketLengthEntry::fixed(0x00A0, 23),
+    PacketLengthEntry::fixed(0x00A1, 6),
+    PacketLengthEntry::fixed(0x00AC, 7),
+    PacketLengthEntry::fixed(0x00AF, 6),
+    PacketLengthEntry::fixed(0x00C3, 8),
+    PacketLengthEntry::variable(0x00D7),
+    PacketLengthEntry::fixed(0x00D8, 6),
+    PacketLengthEntry::fixed(0x00DA, 3),
+    PacketLengthEntry::fixed(0x00FD, 27),
+    PacketLengthEntry::fixed(0x00FE, 30),
+    PacketLengthEntry::fixed(0x0101, 6),
+    PacketLengthEntry::fixed(0x0106, 10),
+    PacketLengthEntry::fixed(0x0114, 31),
+    PacketLengthEntry::fixed(0x0119, 13),
+    PacketLengthEntry::fixed(0x011A, 15),
+    PacketLengthEntry::fixed(0x0147, 39),
+    PacketLengthEntry::variable(0x017F),
+    PacketLengthEntry::fixed(0x0194, 30),
+    PacketLengthEntry::fixed(0x0195, 102),
+    PacketLengthEntry::fixed(0x01B0, 11),
+    PacketLengthEntry::fixed(0x01D0, 8),
+    PacketLengthEntry::fixed(0x01D2, 10),
+    PacketLengthEntry::fixed(0x043F, 25),
+    PacketLengthEntry::fixed(0x0A18, 14).since(20141015),
+];
+
+/// Lengths of packets in a specific packet version. This allows skipping
+/// packets that don't have a handler registered without losing track of where
+/// the next packet starts.
+#[derive(Clone, Debug)]
+pub struct PacketLengthTable {
+    lengths: HashMap<PacketHeader, PacketLength>,
+}
+
+impl PacketLengthTable {
+    pub fn for_packet_version(packet_version: PacketVersion) -> Self {
+        let lengths = PACKET_LENGTHS
+            .iter()
+            .filter(|entry| entry.applies_to(packet_version))
+            .map(|entry| (entry.header, entry.length))
+            .chain(DEFINED_PACKET_LENGTHS.iter().map(|packet_length| packet_length(packet_version)))
+            .collect();
+
+        Self { lengths }
+    }
+
+    pub fn get(&self, header: PacketHeader) -> Option<PacketLength> {
+        self.lengths.get(&header).copied()
+    }
+}
+
+impl Default for PacketLengthTable {
+    fn default() -> Self {
+        Self::for_packet_version(PacketVersion::DEFAULT)
+    }
+}
+
+#[cfg(test)]
+mod lengths {
+    use std::collections::HashMap;
+
+    use super::{PacketLengthEntry, PacketLengthTable, DEFINED_PACKET_LENGTHS, PACKET_LENGTHS};
+    use crate::{PacketHeader, PacketLength, PacketVersion};
+
+    const PACKET_VERSIONS: [PacketVersion; 3] = [PacketVersion(20120307), PacketVersion(20170315), PacketVersion::DEFAULT];
+
+    #[test]
+    fn every_server_packet_defined() {
+        let derive_count = include_str!("lib.rs")
+            .lines()
+            .filter(|line| line.starts_with("#[derive(") && line.contains("ServerPacket"))
+            .count();
+
+        assert_eq!(DEFINED_PACKET_LENGTHS.len(), derive_count);
+    }
+
+    #[test]
+    fn shared_headers_agree() {
+        for packet_version in PACKET_VERSIONS {
+            let mut lengths = HashMap::new();
+
+            for (header, length) in DEFINED_PACKET_LENGTHS.iter().map(|packet_length| packet_length(packet_version)) {
+                if let Some(other_length) = lengths.insert(header, length) {
+                    assert_eq!(
+                        length, other_length,
+                        "conflicting lengths for header {header:?} in {packet_version:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn no_defined_packets_in_database() {
+        for packet_version in PACKET_VERSIONS {
+            let headers: Vec<_> = DEFINED_PACKET_LENGTHS
+                .iter()
+                .map(|packet_length| packet_length(packet_version).0)
+                .collect();
+
+            for entry in PACKET_LENGTHS {
+                assert!(
+                    !headers.contains(&entry.header),
+                    "{:?} is defined in {packet_version:?}",
+                    entry.header
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn version_dependent_length() {
+        let length = |packet_version| {
+            DEFINED_PACKET_LENGTHS
+                .iter()
+                .map(|packet_length| packet_length(packet_version))
+                .find(|(header, _)| header.0 == 0x0A43 || header.0 == 0x01E9)
+                .map(|(_, length)| length)
+        };
+
+        assert_eq!(length(PacketVersion(20120307)), Some(PacketLength::Fixed(81)));
+        assert_eq!(length(PacketVersion::DEFAULT), Some(PacketLength::Fixed(89)));
+    }
+
+    #[test]
+    fn versioned_database_entries() {
+        let entry = PacketLengthEntry {
+            before: Some(PacketVersion(20170315)),
+            ..PacketLengthEntry::fixed(0x0001, 4).since(20120307)
+        };
+
+        assert!(!entry.applies_to(PacketVersion(20120306)));
+        assert!(entry.applies_to(PacketVersion(20120307)));
+        assert!(entry.applies_to(PacketVersion(20170314)));
+        assert!(!entry.applies_to(PacketVersion(20170315)));
+
+        let accept_enter = PacketHeader(0x0A18);
+
+        assert_eq!(
+            PacketLengthTable::for_packet_version(PacketVersion(20120307)).get(accept_enter),
+            None
+        );
+        assert_eq!(
+            PacketLengthTable::for_packet_version(PacketVersion::DEFAULT).get(accept_enter),
+            Some(PacketLength::Fixed(14))
+        );
+    }
+}
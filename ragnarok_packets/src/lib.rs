@@ -1,17 +1,21 @@
 pub mod handler;
+mod length;
 mod position;
+mod version;
 
 use std::net::Ipv4Addr;
 
 use ragnarok_bytes::{
-    ByteConvertable, ByteStream, ConversionError, ConversionResult, ConversionResultExt, FixedByteSize, FromBytes, ToBytes,
+    ByteConvertable, ByteSource, ByteStream, ByteWriter, ConversionError, ConversionResult, FixedByteSize, FromBytes, ToBytes,
 };
 #[cfg(feature = "derive")]
 pub use ragnarok_procedural::{CharacterServer, ClientPacket, LoginServer, MapServer, Packet, ServerPacket};
 #[cfg(not(feature = "derive"))]
 use ragnarok_procedural::{CharacterServer, ClientPacket, LoginServer, MapServer, Packet, ServerPacket};
 
+pub use self::length::{PacketLength, PacketLengthTable};
 pub use self::position::{WorldPosition, WorldPosition2};
+pub use self::version::PacketVersion;
 
 // To make proc macros work in korangar_interface.
 extern crate self as ragnarok_packets;
@@ -20,6 +24,32 @@ extern crate self as ragnarok_packets;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable, PartialOrd, Ord, Hash)]
 pub struct PacketHeader(pub u16);
 
+impl PacketHeader {
+    /// Selects the header of a packet for `packet_version`. `versioned`
+    /// contains the headers that replaced `base` over time, ordered by the
+    /// packet version they were introduced in.
+    pub const fn for_packet_version(
+        base: PacketHeader,
+        versioned: &[(PacketVersion, PacketHeader)],
+        packet_version: PacketVersion,
+    ) -> Self {
+        let mut header = base;
+        let mut index = 0;
+
+        while index < versioned.len() {
+            let (since, versioned_header) = versioned[index];
+
+            if since.0 <= packet_version.0 {
+                header = versioned_header;
+            }
+
+            index += 1;
+        }
+
+        header
+    }
+}
+
 /// Base trait that all packets implement.
 /// All packets in Ragnarok online consist of a header, two bytes in size,
 /// followed by the packet data. If the packet does not have a fixed size,
@@ -30,16 +60,25 @@ pub trait Packet: std::fmt::Debug + Clone {
     /// marked as a ping. This is mostly for filtering when logging
     /// packet traffic.
     const IS_PING: bool;
-    /// The header of the Packet.
+    /// The header of the Packet in [`PacketVersion::DEFAULT`].
     const HEADER: PacketHeader;
 
+    /// The header of the Packet in `packet_version`. Only packets that changed
+    /// their header over time need to override this.
+    fn header(_packet_version: PacketVersion) -> PacketHeader {
+        Self::HEADER
+    }
+
+    /// The length of the packet in `packet_version`, *including* the header.
+    fn length(packet_version: PacketVersion) -> PacketLength;
+
     /// Read packet **without the header**. To read the packet with the header,
     /// use [`PacketExt::packet_from_bytes`].
-    fn payload_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self>;
+    fn payload_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self>;
 
     /// Write packet **without the header**. To write the packet with the
     /// header, use [`PacketExt::packet_to_bytes`].
-    fn payload_to_bytes(&self) -> ConversionResult<Vec<u8>>;
+    fn payload_to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()>;
 
     /// Implementation detail of Korangar. Can be used to convert a packet to an
     /// UI element in the packet viewer.
@@ -53,33 +92,30 @@ pub trait Packet: std::fmt::Debug + Clone {
 pub trait PacketExt: Packet {
     /// Read packet **with the header**. To read the packet without the header,
     /// use [`Packet::payload_from_bytes`].
-    fn packet_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self>;
+    fn packet_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self>;
 
     /// Write packet **with the header**. To write the packet without the
     /// header, use [`Packet::payload_to_bytes`].
-    fn packet_to_bytes(&self) -> ConversionResult<Vec<u8>>;
+    fn packet_to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()>;
 }
 
 impl<T> PacketExt for T
 where
     T: Packet,
 {
-    fn packet_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn packet_from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let header = PacketHeader::from_bytes(byte_stream)?;
 
-        if header != Self::HEADER {
+        if header != Self::header(PacketVersion::of_stream(byte_stream)) {
             return Err(ConversionError::from_message("mismatched header"));
         }
 
         Self::payload_from_bytes(byte_stream)
     }
 
-    fn packet_to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = Self::HEADER.to_bytes()?;
-
-        bytes.extend(self.payload_to_bytes()?);
-
-        Ok(bytes)
+    fn packet_to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        Self::header(PacketVersion::of_writer(byte_writer)).to_bytes(byte_writer)?;
+        self.payload_to_bytes(byte_writer)
     }
 }
 
@@ -183,7 +219,7 @@ pub struct ColorBGRA {
     pub alpha: u8,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct ColorRGBA {
     pub red: u8,
@@ -192,20 +228,21 @@ pub struct ColorRGBA {
     pub alpha: u8,
 }
 
-/// Item index is always actual index + 2.
+/// Item index is always actual index + 2. Invalid indices wrap around instead
+/// of failing, so that they can still be written back unchanged.
 #[derive(Clone, Copy, Debug, FixedByteSize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct InventoryIndex(pub u16);
 
 impl FromBytes for InventoryIndex {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        u16::from_bytes(byte_stream).map(|raw| Self(raw - 2))
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
+        u16::from_bytes(byte_stream).map(|raw| Self(raw.wrapping_sub(2)))
     }
 }
 
 impl ToBytes for InventoryIndex {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        u16::to_bytes(&(self.0 + 2))
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        u16::to_bytes(&self.0.wrapping_add(2), byte_writer)
     }
 }
 
@@ -246,7 +283,8 @@ pub struct LoginServerLoginPacket {
 /// the character servers provided by this packet.
 #[derive(Debug, Clone, Packet, ServerPacket, LoginServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
-#[header(0x0AC4)]
+#[header(0x0069)]
+#[header(0x0AC4, since = 20170315)]
 #[variable_length]
 pub struct LoginServerLoginSuccessPacket {
     pub login_id1: u32,
@@ -262,8 +300,9 @@ pub struct LoginServerLoginSuccessPacket {
     #[new_default]
     pub unknown: u16,
     pub sex: Sex,
-    pub auth_token: AuthToken,
-    #[repeating_remaining]
+    #[since(20170315)]
+    pub auth_token: Option<AuthToken>,
+    #[length_remaining]
     pub character_server_information: Vec<CharacterServerInformation>,
 }
 
@@ -319,7 +358,7 @@ pub struct MapServerLoginSuccessPacket {
     pub font: u16,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum LoginFailedReason {
     #[numeric_value(1)]
@@ -346,7 +385,7 @@ pub struct MapServerUnavailablePacket {
     pub unknown: String,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum LoginFailedReason2 {
     UnregisteredId,
@@ -367,7 +406,7 @@ pub struct LoginFailedPacket2 {
     pub reason: LoginFailedReason2,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum CharacterSelectionFailedReason {
     RejectedFromServer,
@@ -387,7 +426,8 @@ pub struct CharacterSelectionFailedPacket {
 /// selected character.
 #[derive(Debug, Clone, Packet, ServerPacket, CharacterServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
-#[header(0x0AC5)]
+#[header(0x0071)]
+#[header(0x0AC5, since = 20170315)]
 pub struct CharacterSelectionSuccessPacket {
     pub character_id: CharacterId,
     #[length(16)]
@@ -395,11 +435,12 @@ pub struct CharacterSelectionSuccessPacket {
     pub map_server_ip: ServerAddress,
     pub map_server_port: u16,
     // NOTE: Could be `new_default` but Rust doesn't implement `[u8; 128]: Default`.
-    #[new_value([0; 128])]
-    pub unknown: [u8; 128],
+    #[since(20170315)]
+    #[new_value(Some([0; 128]))]
+    pub unknown: Option<[u8; 128]>,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum CharacterCreationFailedReason {
     CharacterNameAlreadyUsed,
@@ -430,7 +471,7 @@ pub struct LoginServerKeepalivePacket {
     pub user_id: UserId,
 }
 
-#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
+#[derive(Debug, Clone, ByteConvertable)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct CharacterServerInformation {
     pub server_ip: ServerAddress,
@@ -440,8 +481,9 @@ pub struct CharacterServerInformation {
     pub user_count: u16,
     pub server_type: u16, // ServerType
     pub display_new: u16, // bool16 ?
-    #[new_value([0; 128])]
-    pub unknown: [u8; 128],
+    #[since(20170315)]
+    #[new_value(Some([0; 128]))]
+    pub unknown: Option<[u8; 128]>,
 }
 
 /// Sent by the client to the character server after after successfully logging
@@ -659,7 +701,7 @@ pub struct DeleteCharacterPacket {
     pub unknown: [u8; 10],
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum CharacterDeletionFailedReason {
     NotAllowed,
@@ -752,7 +794,7 @@ pub struct NewMailStatusPacket {
     pub new_available: u8,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct AchievementData {
     pub acheivement_id: u32,
@@ -832,32 +874,15 @@ pub struct ItemOptions {
 }
 
 bitflags::bitflags! {
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
     #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+    #[flags]
     pub struct RegularItemFlags: u8 {
         const IDENTIFIED = 0b01;
         const IN_ETC_TAB = 0b10;
     }
 }
 
-impl FixedByteSize for RegularItemFlags {
-    fn size_in_bytes() -> usize {
-        <<Self as bitflags::Flags>::Bits as FixedByteSize>::size_in_bytes()
-    }
-}
-
-impl FromBytes for RegularItemFlags {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        <Self as bitflags::Flags>::Bits::from_bytes(byte_stream).map(|raw| Self::from_bits(raw).expect("Invalid equip position"))
-    }
-}
-
-impl ToBytes for RegularItemFlags {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        self.bits().to_bytes()
-    }
-}
-
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct RegularItemInformation {
@@ -882,8 +907,9 @@ pub struct RegularItemListPacket {
 }
 
 bitflags::bitflags! {
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
     #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+    #[flags]
     pub struct EquippableItemFlags: u8 {
         const IDENTIFIED = 0b001;
         const IS_BROKEN = 0b010;
@@ -891,24 +917,6 @@ bitflags::bitflags! {
     }
 }
 
-impl FixedByteSize for EquippableItemFlags {
-    fn size_in_bytes() -> usize {
-        <<Self as bitflags::Flags>::Bits as FixedByteSize>::size_in_bytes()
-    }
-}
-
-impl FromBytes for EquippableItemFlags {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        <Self as bitflags::Flags>::Bits::from_bytes(byte_stream).map(|raw| Self::from_bits(raw).expect("Invalid equip position"))
-    }
-}
-
-impl ToBytes for EquippableItemFlags {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        self.bits().to_bytes()
-    }
-}
-
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct EquippableItemInformation {
@@ -1029,157 +1037,141 @@ pub struct DisplayEmotionPacket {
 /// [UpdateStatusPacket1], [UpdateStatusPacket2], and [UpdateStatusPacket3].
 /// All UpdateStatusPackets do the same, they just have different sizes
 /// correlating to the space the updated value requires.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, ByteConvertable)]
+#[numeric_type(u16)]
 pub enum StatusType {
+    #[numeric_value(24)]
     Weight(u32),
+    #[numeric_value(25)]
     MaximumWeight(u32),
+    #[numeric_value(0)]
     MovementSpeed(u32),
+    #[numeric_value(11)]
     BaseLevel(u32),
+    #[numeric_value(55)]
     JobLevel(u32),
+    #[numeric_value(3)]
     Karma(u32),
+    #[numeric_value(4)]
     Manner(u32),
+    #[numeric_value(9)]
     StatusPoint(u32),
+    #[numeric_value(12)]
     SkillPoint(u32),
+    #[numeric_value(49)]
     Hit(u32),
+    #[numeric_value(50)]
     Flee1(u32),
+    #[numeric_value(51)]
     Flee2(u32),
+    #[numeric_value(6)]
     MaximumHealthPoints(u32),
+    #[numeric_value(8)]
     MaximumSpellPoints(u32),
+    #[numeric_value(5)]
     HealthPoints(u32),
+    #[numeric_value(7)]
     SpellPoints(u32),
+    #[numeric_value(53)]
     AttackSpeed(u32),
+    #[numeric_value(41)]
     Attack1(u32),
+    #[numeric_value(45)]
     Defense1(u32),
+    #[numeric_value(47)]
     MagicDefense1(u32),
+    #[numeric_value(42)]
     Attack2(u32),
+    #[numeric_value(46)]
     Defense2(u32),
+    #[numeric_value(48)]
     MagicDefense2(u32),
+    #[numeric_value(52)]
     Critical(u32),
+    #[numeric_value(43)]
     MagicAttack1(u32),
+    #[numeric_value(44)]
     MagicAttack2(u32),
+    #[numeric_value(20)]
     Zeny(u32),
+    #[numeric_value(1)]
     BaseExperience(u64),
+    #[numeric_value(2)]
     JobExperience(u64),
+    #[numeric_value(22)]
     NextBaseExperience(u64),
+    #[numeric_value(23)]
     NextJobExperience(u64),
+    #[numeric_value(32)]
     SpUstr(u8),
+    #[numeric_value(33)]
     SpUagi(u8),
+    #[numeric_value(34)]
     SpUvit(u8),
+    #[numeric_value(35)]
     SpUint(u8),
+    #[numeric_value(36)]
     SpUdex(u8),
+    #[numeric_value(37)]
     SpUluk(u8),
+    #[numeric_value(13)]
     Strength(u32, u32),
+    #[numeric_value(14)]
     Agility(u32, u32),
+    #[numeric_value(15)]
     Vitality(u32, u32),
+    #[numeric_value(16)]
     Intelligence(u32, u32),
+    #[numeric_value(17)]
     Dexterity(u32, u32),
+    #[numeric_value(18)]
     Luck(u32, u32),
+    #[numeric_value(99)]
     CartInfo(u16, u32, u32),
+    #[numeric_value(232)]
     ActivityPoints(u32),
+    #[numeric_value(231)]
     TraitPoint(u32),
+    #[numeric_value(233)]
     MaximumActivityPoints(u32),
+    #[numeric_value(219)]
     Power(u32, u32),
+    #[numeric_value(220)]
     Stamina(u32, u32),
+    #[numeric_value(221)]
     Wisdom(u32, u32),
+    #[numeric_value(222)]
     Spell(u32, u32),
+    #[numeric_value(223)]
     Concentration(u32, u32),
+    #[numeric_value(224)]
     Creativity(u32, u32),
+    #[numeric_value(247)]
     SpUpow(u8),
+    #[numeric_value(248)]
     SpUsta(u8),
+    #[numeric_value(249)]
     SpUwis(u8),
+    #[numeric_value(250)]
     SpUspl(u8),
+    #[numeric_value(251)]
     SpUcon(u8),
+    #[numeric_value(252)]
     SpUcrt(u8),
+    #[numeric_value(225)]
     PhysicalAttack(u32),
+    #[numeric_value(226)]
     SpellMagicAttack(u32),
+    #[numeric_value(227)]
     Resistance(u32),
+    #[numeric_value(228)]
     MagicResistance(u32),
+    #[numeric_value(229)]
     HealingPlus(u32),
+    #[numeric_value(230)]
     CriticalDamageRate(u32),
 }
 
-impl FromBytes for StatusType {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        let status = match u16::from_bytes(byte_stream).trace::<Self>()? {
-            0 => u32::from_bytes(byte_stream).map(Self::MovementSpeed),
-            1 => u64::from_bytes(byte_stream).map(Self::BaseExperience),
-            2 => u64::from_bytes(byte_stream).map(Self::JobExperience),
-            3 => u32::from_bytes(byte_stream).map(Self::Karma),
-            4 => u32::from_bytes(byte_stream).map(Self::Manner),
-            5 => u32::from_bytes(byte_stream).map(Self::HealthPoints),
-            6 => u32::from_bytes(byte_stream).map(Self::MaximumHealthPoints),
-            7 => u32::from_bytes(byte_stream).map(Self::SpellPoints),
-            8 => u32::from_bytes(byte_stream).map(Self::MaximumSpellPoints),
-            9 => u32::from_bytes(byte_stream).map(Self::StatusPoint),
-            11 => u32::from_bytes(byte_stream).map(Self::BaseLevel),
-            12 => u32::from_bytes(byte_stream).map(Self::SkillPoint),
-            13 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Strength(a, u32::from_bytes(byte_stream)?))),
-            14 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Agility(a, u32::from_bytes(byte_stream)?))),
-            15 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Vitality(a, u32::from_bytes(byte_stream)?))),
-            16 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Intelligence(a, u32::from_bytes(byte_stream)?))),
-            17 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Dexterity(a, u32::from_bytes(byte_stream)?))),
-            18 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Luck(a, u32::from_bytes(byte_stream)?))),
-            20 => u32::from_bytes(byte_stream).map(Self::Zeny),
-            22 => u64::from_bytes(byte_stream).map(Self::NextBaseExperience),
-            23 => u64::from_bytes(byte_stream).map(Self::NextJobExperience),
-            24 => u32::from_bytes(byte_stream).map(Self::Weight),
-            25 => u32::from_bytes(byte_stream).map(Self::MaximumWeight),
-            32 => u8::from_bytes(byte_stream).map(Self::SpUstr),
-            33 => u8::from_bytes(byte_stream).map(Self::SpUagi),
-            34 => u8::from_bytes(byte_stream).map(Self::SpUvit),
-            35 => u8::from_bytes(byte_stream).map(Self::SpUint),
-            36 => u8::from_bytes(byte_stream).map(Self::SpUdex),
-            37 => u8::from_bytes(byte_stream).map(Self::SpUluk),
-            41 => u32::from_bytes(byte_stream).map(Self::Attack1),
-            42 => u32::from_bytes(byte_stream).map(Self::Attack2),
-            43 => u32::from_bytes(byte_stream).map(Self::MagicAttack1),
-            44 => u32::from_bytes(byte_stream).map(Self::MagicAttack2),
-            45 => u32::from_bytes(byte_stream).map(Self::Defense1),
-            46 => u32::from_bytes(byte_stream).map(Self::Defense2),
-            47 => u32::from_bytes(byte_stream).map(Self::MagicDefense1),
-            48 => u32::from_bytes(byte_stream).map(Self::MagicDefense2),
-            49 => u32::from_bytes(byte_stream).map(Self::Hit),
-            50 => u32::from_bytes(byte_stream).map(Self::Flee1),
-            51 => u32::from_bytes(byte_stream).map(Self::Flee2),
-            52 => u32::from_bytes(byte_stream).map(Self::Critical),
-            53 => u32::from_bytes(byte_stream).map(Self::AttackSpeed),
-            55 => u32::from_bytes(byte_stream).map(Self::JobLevel),
-            99 => u16::from_bytes(byte_stream)
-                .and_then(|a| Ok(Self::CartInfo(a, u32::from_bytes(byte_stream)?, u32::from_bytes(byte_stream)?))),
-            219 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Power(a, u32::from_bytes(byte_stream)?))),
-            220 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Stamina(a, u32::from_bytes(byte_stream)?))),
-            221 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Wisdom(a, u32::from_bytes(byte_stream)?))),
-            222 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Spell(a, u32::from_bytes(byte_stream)?))),
-            223 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Concentration(a, u32::from_bytes(byte_stream)?))),
-            224 => u32::from_bytes(byte_stream).and_then(|a| Ok(Self::Creativity(a, u32::from_bytes(byte_stream)?))),
-            225 => u32::from_bytes(byte_stream).map(Self::PhysicalAttack),
-            226 => u32::from_bytes(byte_stream).map(Self::SpellMagicAttack),
-            227 => u32::from_bytes(byte_stream).map(Self::Resistance),
-            228 => u32::from_bytes(byte_stream).map(Self::MagicResistance),
-            229 => u32::from_bytes(byte_stream).map(Self::HealingPlus),
-            230 => u32::from_bytes(byte_stream).map(Self::CriticalDamageRate),
-            231 => u32::from_bytes(byte_stream).map(Self::TraitPoint),
-            232 => u32::from_bytes(byte_stream).map(Self::ActivityPoints),
-            233 => u32::from_bytes(byte_stream).map(Self::MaximumActivityPoints),
-            247 => u8::from_bytes(byte_stream).map(Self::SpUpow),
-            248 => u8::from_bytes(byte_stream).map(Self::SpUsta),
-            249 => u8::from_bytes(byte_stream).map(Self::SpUwis),
-            250 => u8::from_bytes(byte_stream).map(Self::SpUspl),
-            251 => u8::from_bytes(byte_stream).map(Self::SpUcon),
-            252 => u8::from_bytes(byte_stream).map(Self::SpUcrt),
-            invalid => Err(ConversionError::from_message(format!("invalid status code {invalid}"))),
-        };
-
-        status.trace::<Self>()
-    }
-}
-
-impl ToBytes for StatusType {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        panic!("this should be derived");
-    }
-}
-
 // TODO: make StatusType derivable
 #[cfg(feature = "interface")]
 impl<App: korangar_interface::application::Application> korangar_interface::elements::PrototypeElement<App> for StatusType {
@@ -1286,7 +1278,7 @@ pub struct SwitchCharacterSlotPacket {
     pub remaining_moves: u16,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum Action {
     Attack,
@@ -1336,7 +1328,7 @@ pub struct UpdateEntityHealthPointsPacket {
     pub maximum_health_points: u32,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum DamageType {
     Damage,
@@ -1404,7 +1396,7 @@ pub struct RequestServerTickPacket {
     pub client_tick: ClientTick,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum SwitchCharacterSlotResponseStatus {
@@ -1431,7 +1423,7 @@ pub struct ChangeMapPacket {
     pub position: TilePosition,
 }
 
-#[derive(Debug, Clone, ByteConvertable, PartialEq)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize, PartialEq)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum DisappearanceReason {
     OutOfSight,
@@ -1627,7 +1619,7 @@ pub struct UpdateSkillTreePacket {
     pub skill_information: Vec<SkillInformation>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct HotkeyData {
     pub is_skill: u8,
@@ -1704,7 +1696,7 @@ pub struct NavigateToMonsterPacket {
     pub target_monster_id: u16,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u32)]
 pub enum MarkerType {
@@ -1780,7 +1772,7 @@ pub struct DisplaySkillEffectAndDamagePacket {
     pub skill_type: u8,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum HealType {
@@ -1821,7 +1813,7 @@ pub struct StatusChangePacket {
     pub value: [u32; 3],
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct ObjectiveDetails1 {
     pub hunt_identification: u32,
@@ -1921,7 +1913,7 @@ pub struct QuestListPacket {
     pub quests: Vec<Quest>,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u32)]
 pub enum VisualEffect {
@@ -1945,7 +1937,7 @@ pub struct VisualEffectPacket {
     pub effect: VisualEffect,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum ExperienceType {
@@ -1954,7 +1946,7 @@ pub enum ExperienceType {
     JobExperience,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum ExperienceSource {
@@ -1972,7 +1964,7 @@ pub struct DisplayGainedExperiencePacket {
     pub experience_source: ExperienceSource,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum ImageLocation {
     BottomLeft,
@@ -2004,7 +1996,7 @@ pub struct StateChangePacket {
     pub is_pk_mode_on: u8,
 }
 
-#[derive(Debug, Clone, ByteConvertable, PartialEq, Eq)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum ItemPickupResult {
     Success,
@@ -2039,7 +2031,7 @@ pub struct ItemPickupPacket {
     pub enchantment_level: u8,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum RemoveItemReason {
@@ -2063,7 +2055,7 @@ pub struct RemoveItemFromInventoryPacket {
 }
 
 // TODO: improve names
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum QuestEffect {
@@ -2082,7 +2074,7 @@ pub enum QuestEffect {
     None,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum QuestColor {
@@ -2159,8 +2151,9 @@ pub struct ChooseDialogOptionPacket {
 }
 
 bitflags::bitflags! {
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, ByteConvertable, FixedByteSize)]
     #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+    #[flags]
     pub struct EquipPosition: u32 {
         const NONE = 0;
         const HEAD_LOWER = 1;
@@ -2190,24 +2183,6 @@ bitflags::bitflags! {
     }
 }
 
-impl FixedByteSize for EquipPosition {
-    fn size_in_bytes() -> usize {
-        <<Self as bitflags::Flags>::Bits as FixedByteSize>::size_in_bytes()
-    }
-}
-
-impl FromBytes for EquipPosition {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
-        <Self as bitflags::Flags>::Bits::from_bytes(byte_stream).map(|raw| Self::from_bits(raw).expect("Invalid equip position"))
-    }
-}
-
-impl ToBytes for EquipPosition {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        self.bits().to_bytes()
-    }
-}
-
 #[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[header(0x0998)]
@@ -2216,7 +2191,7 @@ pub struct RequestEquipItemPacket {
     pub equip_position: EquipPosition,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum RequestEquipItemStatus {
     Success,
@@ -2241,7 +2216,7 @@ pub struct RequestUnequipItemPacket {
     pub inventory_index: InventoryIndex,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum RequestUnequipItemStatus {
     Success,
@@ -2257,7 +2232,7 @@ pub struct RequestUnequipItemStatusPacket {
     pub result: RequestUnequipItemStatus,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum RestartType {
     Respawn,
@@ -2281,7 +2256,7 @@ pub struct RestartPacket {
 
 // TODO: check that this can be only 1 and 0, if not ByteConvertable
 // should be implemented manually
-#[derive(Debug, Clone, ByteConvertable, PartialEq, Eq)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum RestartResponseStatus {
     Nothing,
@@ -2297,7 +2272,7 @@ pub struct RestartResponsePacket {
 
 // TODO: check that this can be only 1 and 0, if not Named, ByteConvertable
 // should be implemented manually
-#[derive(Debug, Clone, ByteConvertable, PartialEq, Eq)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize, PartialEq, Eq)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum DisconnectResponseStatus {
@@ -2372,7 +2347,7 @@ pub struct ToUseSkillSuccessPacket {
     pub cause: u8,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u32)]
 pub enum UnitId {
@@ -2641,7 +2616,7 @@ pub struct FriendListPacket {
     pub friends: Vec<Friend>,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum OnlineState {
     Online,
@@ -2666,7 +2641,7 @@ pub struct FriendRequestPacket {
     pub requestee: Friend,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u32)]
 pub enum FriendRequestResponse {
@@ -2683,7 +2658,7 @@ pub struct FriendRequestResponsePacket {
     pub response: FriendRequestResponse,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 #[numeric_type(u16)]
 pub enum FriendRequestResult {
@@ -2710,6 +2685,277 @@ pub struct PartyInvitePacket {
     pub party_name: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[numeric_type(u32)]
+pub enum PartyExperienceShare {
+    EachTake,
+    EvenShare,
+    /// Sent by the server if even sharing was requested but the level
+    /// difference between the party members is too big.
+    EvenShareNotAllowed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub enum PartyItemShare {
+    EachTake,
+    PartyShare,
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x01E8)]
+pub struct CreatePartyPacket {
+    #[length(24)]
+    pub name: String,
+    pub item_pickup: PartyItemShare,
+    pub item_division: PartyItemShare,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub enum PartyCreationResult {
+    Success,
+    NameAlreadyExists,
+    AlreadyInParty,
+    NotAllowedOnMap,
+}
+
+/// Sent by the map server as a response to [CreatePartyPacket]. If the party
+/// was created successfully, the member list follows in a [PartyInfoPacket].
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x00FA)]
+pub struct PartyCreatedPacket {
+    pub result: PartyCreationResult,
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x02C4)]
+pub struct InviteToPartyPacket {
+    #[length(24)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[numeric_type(u32)]
+pub enum PartyInvitationResult {
+    AlreadyInOtherParty,
+    Rejected,
+    Accepted,
+    PartyFull,
+    SameAccount,
+    InvitationsBlocked,
+    UnknownError,
+    NotOnline,
+    InvalidMap,
+    InvalidMapOwn,
+}
+
+/// Sent by the map server as a response to [InviteToPartyPacket] once the
+/// invited character replied or the invitation failed.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x02C5)]
+pub struct PartyInvitationResultPacket {
+    #[length(24)]
+    pub name: String,
+    pub result: PartyInvitationResult,
+}
+
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub enum PartyInvitationResponse {
+    Reject,
+    Accept,
+}
+
+/// Sent by the client in response to [PartyInvitePacket].
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x02C7)]
+pub struct PartyInvitationResponsePacket {
+    pub party_id: PartyId,
+    pub response: PartyInvitationResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub struct PartyMemberInformation {
+    pub account_id: AccountId,
+    #[since(20171207)]
+    pub character_id: Option<CharacterId>,
+    #[length(24)]
+    pub name: String,
+    #[length(16)]
+    pub map_name: String,
+    /// 0 for the leader of the party.
+    pub leader: u8,
+    pub state: OnlineState,
+    #[since(20171207)]
+    pub job: Option<u16>,
+    #[since(20171207)]
+    pub level: Option<u16>,
+}
+
+/// Sent by the map server when the character joins a party or logs in while
+/// being in one. Contains all members of the party.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x00FB)]
+#[header(0x0A44, since = 20171207)]
+#[variable_length]
+pub struct PartyInfoPacket {
+    #[length(24)]
+    pub party_name: String,
+    #[length_remaining]
+    pub members: Vec<PartyMemberInformation>,
+}
+
+/// Sent by the map server when a character joins the party or one of the
+/// members changes their map or online state.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x01E9)]
+#[header(0x0A43, since = 20171207)]
+pub struct PartyMemberAddedPacket {
+    pub account_id: AccountId,
+    #[since(20171207)]
+    pub character_id: Option<CharacterId>,
+    /// 0 for the leader of the party.
+    pub leader: u32,
+    #[since(20171207)]
+    pub job: Option<u16>,
+    #[since(20171207)]
+    pub level: Option<u16>,
+    pub position: TilePosition,
+    pub state: OnlineState,
+    #[length(24)]
+    pub party_name: String,
+    #[length(24)]
+    pub name: String,
+    #[length(16)]
+    pub map_name: String,
+    pub item_pickup: PartyItemShare,
+    pub item_division: PartyItemShare,
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0100)]
+pub struct LeavePartyPacket {}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0103)]
+pub struct ExpelPartyMemberPacket {
+    pub account_id: AccountId,
+    #[length(24)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ByteConvertable, FixedByteSize)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+pub enum PartyMemberLeftReason {
+    Left,
+    Expelled,
+    LeavingNotAllowed,
+    ExpellingNotAllowed,
+}
+
+/// Sent by the map server when a member left or was expelled from the party.
+/// If the account id is the one of the player, the player is no longer in a
+/// party.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0105)]
+pub struct PartyMemberLeftPacket {
+    pub account_id: AccountId,
+    #[length(24)]
+    pub name: String,
+    pub reason: PartyMemberLeftReason,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x080E)]
+pub struct PartyMemberHealthPacket {
+    pub account_id: AccountId,
+    pub health_points: u32,
+    pub maximum_health_points: u32,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0107)]
+pub struct PartyMemberPositionPacket {
+    pub account_id: AccountId,
+    pub position: TilePosition,
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x07D7)]
+pub struct ChangePartyOptionsPacket {
+    pub experience: PartyExperienceShare,
+    pub item_pickup: PartyItemShare,
+    pub item_division: PartyItemShare,
+}
+
+/// Sent by the map server when the party is created, the options are changed
+/// or a member joins.
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0101)]
+#[header(0x07D8, since = 20090603)]
+pub struct PartyOptionsPacket {
+    pub experience: PartyExperienceShare,
+    #[since(20090603)]
+    pub item_pickup: Option<PartyItemShare>,
+    #[since(20090603)]
+    pub item_division: Option<PartyItemShare>,
+}
+
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x07DA)]
+pub struct ChangePartyLeaderPacket {
+    pub account_id: AccountId,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x07FC)]
+pub struct PartyLeaderChangedPacket {
+    pub old_leader: AccountId,
+    pub new_leader: AccountId,
+}
+
+/// Sent by the client to send a message to all members of the party. Just like
+/// [GlobalMessagePacket], the message is expected to be prefixed with the name
+/// of the player.
+#[derive(Debug, Clone, Packet, ClientPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0108)]
+#[variable_length]
+pub struct PartyMessagePacket {
+    #[length_remaining_off_by_one]
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Packet, ServerPacket, MapServer)]
+#[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
+#[header(0x0109)]
+#[variable_length]
+pub struct PartyChatMessagePacket {
+    pub account_id: AccountId,
+    #[length_remaining]
+    pub message: String,
+}
+
 #[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub struct ReputationEntry {
@@ -2828,7 +3074,7 @@ pub struct BuyOrSellPacket {
     pub shop_id: ShopId,
 }
 
-#[derive(Debug, Clone, ByteConvertable)]
+#[derive(Debug, Clone, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum BuyOrSellOption {
     Buy,
@@ -2965,7 +3211,7 @@ pub struct SellItemsPacket {
     pub items: Vec<SoldItemInformation>,
 }
 
-#[derive(Debug, Clone, Copy, ByteConvertable)]
+#[derive(Debug, Clone, Copy, ByteConvertable, FixedByteSize)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
 pub enum SellItemsResult {
     Success,
@@ -2978,3 +3224,532 @@ pub enum SellItemsResult {
 pub struct SellItemsResultPacket {
     pub result: SellItemsResult,
 }
+
+/// Invokes `$macro` with every packet sent by the server that is defined in
+/// this crate.
+macro_rules! for_each_server_packet {
+    ($macro:ident) => {
+        $macro!(
+            $crate::LoginServerLoginSuccessPacket,
+            $crate::CharacterServerLoginSuccessPacket,
+            $crate::Packet006b,
+            $crate::Packet0b18,
+            $crate::MapServerLoginSuccessPacket,
+            $crate::LoginFailedPacket,
+            $crate::MapServerUnavailablePacket,
+            $crate::LoginFailedPacket2,
+            $crate::CharacterSelectionFailedPacket,
+            $crate::CharacterSelectionSuccessPacket,
+            $crate::CharacterCreationFailedPacket,
+            $crate::Packet8302,
+            $crate::CreateCharacterSuccessPacket,
+            $crate::RequestCharacterListSuccessPacket,
+            $crate::MapServerPingPacket,
+            $crate::EntityMovePacket,
+            $crate::EntityStopMovePacket,
+            $crate::PlayerMovePacket,
+            $crate::CharacterDeletionFailedPacket,
+            $crate::CharacterDeletionSuccessPacket,
+            $crate::ServerMessagePacket,
+            $crate::RequestPlayerDetailsSuccessPacket,
+            $crate::RequestEntityDetailsSuccessPacket,
+            $crate::NewMailStatusPacket,
+            $crate::AchievementUpdatePacket,
+            $crate::AchievementListPacket,
+            $crate::CriticalWeightUpdatePacket,
+            $crate::SpriteChangePacket,
+            $crate::InventoyStartPacket,
+            $crate::InventoyEndPacket,
+            $crate::RegularItemListPacket,
+            $crate::EquippableItemListPacket,
+            $crate::EquippableSwitchItemListPacket,
+            $crate::MapTypePacket,
+            $crate::Broadcast2MessagePacket,
+            $crate::BroadcastMessagePacket,
+            $crate::OverheadMessagePacket,
+            $crate::EntityMessagePacket,
+            $crate::DisplayEmotionPacket,
+            $crate::UpdateStatusPacket,
+            $crate::StatusChangeSequencePacket,
+            $crate::InitialStatusPacket,
+            $crate::UpdateStatusPacket1,
+            $crate::UpdateStatusPacket2,
+            $crate::UpdateStatusPacket3,
+            $crate::UpdateAttackRangePacket,
+            $crate::RequestPlayerAttackFailedPacket,
+            $crate::UpdateEntityHealthPointsPacket,
+            $crate::DamagePacket1,
+            $crate::DamagePacket3,
+            $crate::ServerTickPacket,
+            $crate::SwitchCharacterSlotResponsePacket,
+            $crate::ChangeMapPacket,
+            $crate::EntityDisappearedPacket,
+            $crate::MovingEntityAppearedPacket,
+            $crate::ResurrectionPacket,
+            $crate::EntityAppearedPacket,
+            $crate::EntityAppeared2Packet,
+            $crate::UpdateSkillTreePacket,
+            $crate::UpdateHotkeysPacket,
+            $crate::UpdatePartyInvitationStatePacket,
+            $crate::UpdateShowEquipPacket,
+            $crate::UpdateConfigurationPacket,
+            $crate::NavigateToMonsterPacket,
+            $crate::MarkMinimapPositionPacket,
+            $crate::NextButtonPacket,
+            $crate::CloseButtonPacket,
+            $crate::DialogMenuPacket,
+            $crate::DisplaySpecialEffectPacket,
+            $crate::DisplaySkillCooldownPacket,
+            $crate::DisplaySkillEffectAndDamagePacket,
+            $crate::DisplayPlayerHealEffect,
+            $crate::DisplaySkillEffectNoDamagePacket,
+            $crate::StatusChangePacket,
+            $crate::QuestNotificationPacket1,
+            $crate::HuntingQuestNotificationPacket,
+            $crate::HuntingQuestUpdateObjectivePacket,
+            $crate::QuestRemovedPacket,
+            $crate::QuestListPacket,
+            $crate::VisualEffectPacket,
+            $crate::DisplayGainedExperiencePacket,
+            $crate::DisplayImagePacket,
+            $crate::StateChangePacket,
+            $crate::ItemPickupPacket,
+            $crate::RemoveItemFromInventoryPacket,
+            $crate::QuestEffectPacket,
+            $crate::NpcDialogPacket,
+            $crate::RequestEquipItemStatusPacket,
+            $crate::RequestUnequipItemStatusPacket,
+            $crate::ParameterChangePacket,
+            $crate::RestartResponsePacket,
+            $crate::DisconnectResponsePacket,
+            $crate::UseSkillSuccessPacket,
+            $crate::ToUseSkillSuccessPacket,
+            $crate::NotifySkillUnitPacket,
+            $crate::NotifyGroundSkillPacket,
+            $crate::SkillUnitDisappearPacket,
+            $crate::NotifyFriendRemovedPacket,
+            $crate::FriendListPacket,
+            $crate::FriendOnlineStatusPacket,
+            $crate::FriendRequestPacket,
+            $crate::FriendRequestResultPacket,
+            $crate::PartyInvitePacket,
+            $crate::PartyCreatedPacket,
+            $crate::PartyInvitationResultPacket,
+            $crate::PartyInfoPacket,
+            $crate::PartyMemberAddedPacket,
+            $crate::PartyMemberLeftPacket,
+            $crate::PartyMemberHealthPacket,
+            $crate::PartyMemberPositionPacket,
+            $crate::PartyOptionsPacket,
+            $crate::PartyLeaderChangedPacket,
+            $crate::PartyChatMessagePacket,
+            $crate::ReputationPacket,
+            $crate::ClanInfoPacket,
+            $crate::ClanOnlineCountPacket,
+            $crate::ChangeMapCellPacket,
+            $crate::OpenMarketPacket,
+            $crate::ShopItemListPacket,
+            $crate::BuyOrSellPacket,
+            $crate::BuyShopItemsResultPacket,
+            $crate::SellListPacket,
+            $crate::SellItemsResultPacket,
+        );
+    };
+}
+
+pub(crate) use for_each_server_packet;
+
+#[cfg(test)]
+mod conversion {
+    use ragnarok_bytes::{ByteConvertable, ByteStream, ByteWriter, FixedByteSize, FromBytes, ToBytes, ToBytesExt};
+
+    use crate::handler::{HandlerResult, NoPacketCallback, PacketHandler};
+    use crate::{
+        AccountId, CharacterId, CharacterSelectionSuccessPacket, EquipPosition, LoginServerLoginSuccessPacket, OnlineState, Packet,
+        PacketExt, PacketHeader, PacketLength, PacketVersion, PartyInfoPacket, PartyItemShare, PartyMemberAddedPacket,
+        PartyMemberInformation, StatusType, TilePosition, UpdateStatusPacket1,
+    };
+
+    #[test]
+    fn status_type() {
+        let input = [13, 0, 5, 0, 0, 0, 2, 0, 0, 0];
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+
+        let status_type = StatusType::from_bytes(&mut byte_stream).unwrap();
+
+        assert!(matches!(status_type, StatusType::Strength(5, 2)));
+        assert_eq!(status_type.to_bytes_vec::<()>().unwrap(), input);
+    }
+
+    #[test]
+    fn invalid_status_type() {
+        let input = [10, 0, 5, 0, 0, 0];
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+
+        assert!(StatusType::from_bytes(&mut byte_stream).is_err());
+    }
+
+    #[test]
+    fn padded_status_type() {
+        let input = [24, 0, 100, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+
+        let packet = <UpdateStatusPacket1 as crate::Packet>::payload_from_bytes(&mut byte_stream).unwrap();
+
+        assert!(matches!(packet.status_type, StatusType::Weight(100)));
+        assert_eq!(byte_stream.get_offset(), input.len());
+    }
+
+    #[test]
+    fn flags() {
+        let input = [0x22, 0, 0, 0];
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+
+        let equip_position = EquipPosition::from_bytes(&mut byte_stream).unwrap();
+
+        assert_eq!(EquipPosition::size_in_bytes(), 4);
+        assert_eq!(equip_position, EquipPosition::LEFT_RIGHT_HAND);
+        assert_eq!(equip_position.to_bytes_vec::<()>().unwrap(), input);
+    }
+
+    #[derive(Debug, PartialEq, ByteConvertable)]
+    enum Payload {
+        Empty,
+        Single(u16),
+        #[numeric_value(5)]
+        Named {
+            value: u8,
+            #[present_if(value > 1)]
+            extra: Option<u8>,
+        },
+    }
+
+    #[derive(Debug, PartialEq, ByteConvertable)]
+    struct Tagged {
+        kind: u8,
+        #[present_if(kind != 0)]
+        #[discriminant(kind)]
+        payload: Option<Payload>,
+    }
+
+    fn round_trip<T: FromBytes + ToBytesExt + std::fmt::Debug + PartialEq>(input: &[u8], expected: T) {
+        let mut byte_stream = ByteStream::<()>::without_metadata(input);
+
+        assert_eq!(T::from_bytes(&mut byte_stream).unwrap(), expected);
+        assert!(byte_stream.is_empty());
+        assert_eq!(expected.to_bytes_vec::<()>().unwrap(), input);
+    }
+
+    #[test]
+    fn data_enum() {
+        round_trip(&[0], Payload::Empty);
+        round_trip(&[1, 7, 0], Payload::Single(7));
+        round_trip(&[5, 1], Payload::Named { value: 1, extra: None });
+        round_trip(&[5, 2, 9], Payload::Named { value: 2, extra: Some(9) });
+    }
+
+    #[test]
+    fn discriminant() {
+        round_trip(&[0], Tagged { kind: 0, payload: None });
+        round_trip(&[1, 7, 0], Tagged {
+            kind: 1,
+            payload: Some(Payload::Single(7)),
+        });
+        round_trip(&[5, 2, 9], Tagged {
+            kind: 5,
+            payload: Some(Payload::Named { value: 2, extra: Some(9) }),
+        });
+    }
+
+    #[test]
+    fn mismatched_discriminant() {
+        let tagged = Tagged {
+            kind: 5,
+            payload: Some(Payload::Single(7)),
+        };
+
+        assert!(tagged.to_bytes_vec::<()>().is_err());
+    }
+
+    #[test]
+    fn mismatched_present_if() {
+        let present_without_condition = Tagged {
+            kind: 0,
+            payload: Some(Payload::Empty),
+        };
+        let missing_with_condition = Tagged { kind: 1, payload: None };
+        let nested = Tagged {
+            kind: 5,
+            payload: Some(Payload::Named { value: 1, extra: Some(2) }),
+        };
+
+        assert!(present_without_condition.to_bytes_vec::<()>().is_err());
+        assert!(missing_with_condition.to_bytes_vec::<()>().is_err());
+        assert!(nested.to_bytes_vec::<()>().is_err());
+    }
+
+    #[test]
+    fn data_enum_error_path() {
+        let input = [1, 7];
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+
+        let error = Tagged::from_bytes(&mut byte_stream).err().unwrap();
+
+        assert_eq!(error.field_path().as_deref(), Some("Tagged.payload.Single.0"));
+    }
+
+    #[derive(Debug, PartialEq, ByteConvertable)]
+    struct Versioned {
+        value: u8,
+        #[since(20170315)]
+        added: Option<u8>,
+        #[before(20170315)]
+        removed: Option<u8>,
+    }
+
+    fn round_trip_with_version<T: FromBytes + ToBytes + std::fmt::Debug + PartialEq>(
+        input: &[u8],
+        expected: T,
+        packet_version: PacketVersion,
+    ) {
+        let mut byte_stream = ByteStream::with_metadata(input, packet_version);
+
+        assert_eq!(T::from_bytes(&mut byte_stream).unwrap(), expected);
+        assert!(byte_stream.is_empty());
+
+        let mut byte_writer = ByteWriter::with_metadata(packet_version);
+        expected.to_bytes(&mut byte_writer).unwrap();
+
+        assert_eq!(byte_writer.into_inner(), input);
+    }
+
+    #[test]
+    fn versioned_header() {
+        assert_eq!(LoginServerLoginSuccessPacket::HEADER, PacketHeader(0x0AC4));
+        assert_eq!(
+            LoginServerLoginSuccessPacket::header(PacketVersion(20170314)),
+            PacketHeader(0x0069)
+        );
+        assert_eq!(
+            LoginServerLoginSuccessPacket::header(PacketVersion(20170315)),
+            PacketHeader(0x0AC4)
+        );
+        assert_eq!(
+            UpdateStatusPacket1::header(PacketVersion(20000101)),
+            UpdateStatusPacket1::HEADER
+        );
+    }
+
+    #[test]
+    fn versioned_field() {
+        round_trip_with_version(
+            &[1, 2],
+            Versioned {
+                value: 1,
+                added: None,
+                removed: Some(2),
+            },
+            PacketVersion(20170314),
+        );
+        round_trip_with_version(
+            &[1, 2],
+            Versioned {
+                value: 1,
+                added: Some(2),
+                removed: None,
+            },
+            PacketVersion(20170315),
+        );
+    }
+
+    #[test]
+    fn versioned_field_without_version() {
+        let input = [1, 2];
+        let mut byte_stream = ByteStream::<()>::without_metadata(&input);
+
+        let versioned = Versioned::from_bytes(&mut byte_stream).unwrap();
+
+        assert_eq!(versioned, Versioned {
+            value: 1,
+            added: Some(2),
+            removed: None,
+        });
+    }
+
+    #[test]
+    fn handler_with_packet_version() {
+        let mut input = vec![0x69, 0x00, 79, 0];
+        input.extend([1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0]);
+        input.extend([0; 24]);
+        input.extend([0, 0, 1]);
+        // One character server without the trailing unknown bytes.
+        input.extend([127, 0, 0, 1, 0x79, 0x17]);
+        input.extend([0; 20]);
+        input.extend([0; 6]);
+
+        let mut packet_handler =
+            PacketHandler::<u32, PacketVersion, NoPacketCallback>::with_packet_version(NoPacketCallback, PacketVersion(20170314));
+        packet_handler
+            .register(|packet: LoginServerLoginSuccessPacket| {
+                assert!(packet.auth_token.is_none());
+                assert_eq!(packet.character_server_information.len(), 1);
+                assert_eq!(packet.character_server_information[0].server_port, 6009);
+                assert!(packet.character_server_information[0].unknown.is_none());
+
+                packet.login_id1
+            })
+            .unwrap();
+
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion(20170314));
+
+        assert!(matches!(packet_handler.process_one(&mut byte_stream), HandlerResult::Ok(1)));
+        assert!(byte_stream.is_empty());
+    }
+
+    #[test]
+    fn character_selection_success() {
+        assert_eq!(
+            CharacterSelectionSuccessPacket::header(PacketVersion(20170314)),
+            PacketHeader(0x0071)
+        );
+        assert_eq!(
+            CharacterSelectionSuccessPacket::header(PacketVersion(20170315)),
+            PacketHeader(0x0AC5)
+        );
+        assert_eq!(
+            CharacterSelectionSuccessPacket::length(PacketVersion(20170314)),
+            PacketLength::Fixed(28)
+        );
+        assert_eq!(
+            CharacterSelectionSuccessPacket::length(PacketVersion(20170315)),
+            PacketLength::Fixed(156)
+        );
+
+        let mut input = vec![0x71, 0x00, 150, 0, 0, 0];
+        input.extend(b"prontera.gat");
+        input.extend([0; 4]);
+        input.extend([127, 0, 0, 1, 0x79, 0x17]);
+
+        let mut byte_stream = ByteStream::with_metadata(input.as_slice(), PacketVersion(20170314));
+        let packet = CharacterSelectionSuccessPacket::packet_from_bytes(&mut byte_stream).unwrap();
+
+        assert!(byte_stream.is_empty());
+        assert_eq!(packet.character_id, CharacterId(150));
+        assert_eq!(packet.map_name, "prontera.gat");
+        assert_eq!(packet.map_server_port, 6009);
+        assert!(packet.unknown.is_none());
+
+        let mut byte_writer = ByteWriter::with_metadata(PacketVersion(20170314));
+        packet.packet_to_bytes(&mut byte_writer).unwrap();
+
+        assert_eq!(byte_writer.into_inner(), input);
+    }
+
+    fn party_member_bytes(packet_version: PacketVersion) -> Vec<u8> {
+        let mut bytes = vec![5, 0, 0, 0];
+
+        if packet_version >= PacketVersion(20171207) {
+            bytes.extend([150, 0, 0, 0]);
+        }
+
+        bytes.extend(b"Member");
+        bytes.extend([0; 18]);
+        bytes.extend(b"prontera.gat");
+        bytes.extend([0; 4]);
+        bytes.extend([0, 1]);
+
+        if packet_version >= PacketVersion(20171207) {
+            bytes.extend([7, 0, 99, 0]);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn party_member_information() {
+        round_trip_with_version(
+            &party_member_bytes(PacketVersion(20171206)),
+            PartyMemberInformation {
+                account_id: AccountId(5),
+                character_id: None,
+                name: "Member".to_owned(),
+                map_name: "prontera.gat".to_owned(),
+                leader: 0,
+                state: OnlineState::Offline,
+                job: None,
+                level: None,
+            },
+            PacketVersion(20171206),
+        );
+        round_trip_with_version(
+            &party_member_bytes(PacketVersion(20171207)),
+            PartyMemberInformation {
+                account_id: AccountId(5),
+                character_id: Some(CharacterId(150)),
+                name: "Member".to_owned(),
+                map_name: "prontera.gat".to_owned(),
+                leader: 0,
+                state: OnlineState::Offline,
+                job: Some(7),
+                level: Some(99),
+            },
+            PacketVersion(20171207),
+        );
+    }
+
+    #[test]
+    fn party_info_with_packet_version() {
+        for (packet_version, header) in [(PacketVersion(20171206), 0x00FBu16), (PacketVersion(20171207), 0x0A44)] {
+            let member = party_member_bytes(packet_version);
+            let packet_length = 4 + 24 + 2 * member.len() as u16;
+
+            let mut input = header.to_le_bytes().to_vec();
+            input.extend(packet_length.to_le_bytes());
+            input.extend(b"Party");
+            input.extend([0; 19]);
+            input.extend(&member);
+            input.extend(&member);
+
+            let mut packet_handler =
+                PacketHandler::<usize, PacketVersion, NoPacketCallback>::with_packet_version(NoPacketCallback, packet_version);
+            packet_handler
+                .register(|packet: PartyInfoPacket| {
+                    assert_eq!(packet.party_name, "Party");
+                    assert!(packet.members.iter().all(|member| member.name == "Member"));
+
+                    packet.members.len()
+                })
+                .unwrap();
+
+            let mut byte_stream = ByteStream::with_metadata(input.as_slice(), packet_version);
+
+            assert!(matches!(packet_handler.process_one(&mut byte_stream), HandlerResult::Ok(2)));
+            assert!(byte_stream.is_empty());
+        }
+    }
+
+    #[test]
+    fn party_member_added_size() {
+        for (packet_version, size) in [(PacketVersion(20171206), 81), (PacketVersion(20171207), 89)] {
+            let packet = PartyMemberAddedPacket {
+                account_id: AccountId(5),
+                character_id: (packet_version >= PacketVersion(20171207)).then_some(CharacterId(150)),
+                leader: 0,
+                job: (packet_version >= PacketVersion(20171207)).then_some(7),
+                level: (packet_version >= PacketVersion(20171207)).then_some(99),
+                position: TilePosition { x: 10, y: 20 },
+                state: OnlineState::Online,
+                party_name: "Party".to_owned(),
+                name: "Member".to_owned(),
+                map_name: "prontera.gat".to_owned(),
+                item_pickup: PartyItemShare::EachTake,
+                item_division: PartyItemShare::PartyShare,
+            };
+
+            let mut byte_writer = ByteWriter::with_metadata(packet_version);
+            packet.packet_to_bytes(&mut byte_writer).unwrap();
+
+            assert_eq!(byte_writer.into_inner().len(), size);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use ragnarok_bytes::{ByteStream, ConversionResult, FromBytes, ToBytes};
+use ragnarok_bytes::{ByteSource, ByteStream, ByteWriter, ConversionResult, FixedByteSize, FromBytes, ToBytes};
 
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "interface", derive(korangar_interface::elements::PrototypeElement))]
@@ -13,8 +13,14 @@ impl WorldPosition {
     }
 }
 
+impl FixedByteSize for WorldPosition {
+    fn size_in_bytes() -> usize {
+        3
+    }
+}
+
 impl FromBytes for WorldPosition {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let coordinates: Vec<usize> = byte_stream.slice::<Self>(3)?.iter().map(|byte| *byte as usize).collect();
 
         let x = (coordinates[1] >> 6) | (coordinates[0] << 2);
@@ -26,14 +32,15 @@ impl FromBytes for WorldPosition {
 }
 
 impl ToBytes for WorldPosition {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut coordinates = vec![0, 0, 0];
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        let mut coordinates = [0; 3];
 
         coordinates[0] = (self.x >> 2) as u8;
         coordinates[1] = ((self.x << 6) as u8) | (((self.y >> 4) & 0x3F) as u8);
         coordinates[2] = (self.y << 4) as u8;
 
-        Ok(coordinates)
+        byte_writer.extend_from_slice(&coordinates);
+        Ok(())
     }
 }
 
@@ -59,8 +66,14 @@ impl WorldPosition2 {
     }
 }
 
+impl FixedByteSize for WorldPosition2 {
+    fn size_in_bytes() -> usize {
+        6
+    }
+}
+
 impl FromBytes for WorldPosition2 {
-    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta>) -> ConversionResult<Self> {
+    fn from_bytes<Meta>(byte_stream: &mut ByteStream<Meta, impl ByteSource>) -> ConversionResult<Self> {
         let coordinates: Vec<usize> = byte_stream.slice::<Self>(6)?.iter().map(|byte| *byte as usize).collect();
 
         let x1 = (coordinates[1] >> 6) | (coordinates[0] << 2);
@@ -74,8 +87,8 @@ impl FromBytes for WorldPosition2 {
 }
 
 impl ToBytes for WorldPosition2 {
-    fn to_bytes(&self) -> ConversionResult<Vec<u8>> {
-        let mut bytes = vec![0; 6];
+    fn to_bytes<Meta>(&self, byte_writer: &mut ByteWriter<Meta>) -> ConversionResult<()> {
+        let mut bytes = [0; 6];
 
         bytes[0] = (self.x1 >> 2) as u8;
         bytes[1] = ((self.x1 << 6) as u8) | ((self.y1 >> 4) as u8);
@@ -83,13 +96,14 @@ impl ToBytes for WorldPosition2 {
         bytes[3] = ((self.x2 << 2) as u8) | ((self.y2 >> 8) as u8);
         bytes[4] = self.y2 as u8;
 
-        Ok(bytes)
+        byte_writer.extend_from_slice(&bytes);
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod conversion {
-    use ragnarok_bytes::{FromBytes, ToBytes};
+    use ragnarok_bytes::{FromBytes, ToBytesExt};
 
     use crate::{WorldPosition, WorldPosition2};
 
@@ -104,7 +118,7 @@ mod conversion {
             let mut byte_steam = ragnarok_bytes::ByteStream::<()>::without_metadata(&case);
 
             let position = WorldPosition::from_bytes(&mut byte_steam).unwrap();
-            let output = position.to_bytes().unwrap();
+            let output = position.to_bytes_vec::<()>().unwrap();
 
             assert_eq!(case.as_slice(), output.as_slice());
         }
@@ -127,7 +141,7 @@ mod conversion {
             let mut byte_steam = ragnarok_bytes::ByteStream::<()>::without_metadata(&case);
 
             let position = WorldPosition2::from_bytes(&mut byte_steam).unwrap();
-            let output = position.to_bytes().unwrap();
+            let output = position.to_bytes_vec::<()>().unwrap();
 
             assert_eq!(case.as_slice(), output.as_slice());
         }
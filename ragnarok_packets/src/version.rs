@@ -0,0 +1,30 @@
+use ragnarok_bytes::{ByteSource, ByteStream, ByteWriter};
+
+/// The packet version (`PACKETVER`) of a client, which is the date of the
+/// client executable in the format `YYYYMMDD`. Servers are configured for
+/// one packet version and pick packet headers and layouts accordingly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PacketVersion(pub u32);
+
+impl PacketVersion {
+    /// The packet version that rAthena is configured for by default.
+    pub const DEFAULT: Self = Self(20211103);
+
+    /// The packet version that `byte_stream` was created with. Streams with
+    /// other metadata use [`PacketVersion::DEFAULT`].
+    pub fn of_stream<Meta>(byte_stream: &ByteStream<Meta, impl ByteSource>) -> Self {
+        byte_stream.get_metadata::<Self, PacketVersion>().copied().unwrap_or_default()
+    }
+
+    /// The packet version that `byte_writer` was created with. Writers with
+    /// other metadata use [`PacketVersion::DEFAULT`].
+    pub fn of_writer<Meta>(byte_writer: &ByteWriter<Meta>) -> Self {
+        byte_writer.get_metadata::<Self, PacketVersion>().copied().unwrap_or_default()
+    }
+}
+
+impl Default for PacketVersion {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -1,20 +1,88 @@
 use proc_macro::TokenStream as InterfaceTokenStream;
 use proc_macro2::Span;
-use quote::quote;
-use syn::{Attribute, DataEnum, DataStruct, Generics, Ident};
+use quote::{format_ident, quote};
+use syn::{Attribute, DataEnum, DataStruct, Expr, ExprLit, Fields, GenericArgument, Generics, Ident, Lit, PathArguments, Type};
 
-use crate::helper::byte_convertable_helper;
+use crate::helper::{byte_convertable_helper, has_version_attribute};
 use crate::utils::*;
 
+/// Implementation for types generated by `bitflags!`, which are converted
+/// through their bits. Unknown bits are retained.
+fn derive_for_flags(generics: Generics, name: Ident, implement_from: bool, implement_to: bool) -> InterfaceTokenStream {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let from = implement_from.then(|| {
+        quote! {
+            impl #impl_generics ragnarok_bytes::FromBytes for #name #type_generics #where_clause {
+                fn from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta, impl ragnarok_bytes::ByteSource>) -> ragnarok_bytes::ConversionResult<Self> {
+                    let bits = <<Self as bitflags::Flags>::Bits as ragnarok_bytes::FromBytes>::from_bytes(byte_stream);
+                    ragnarok_bytes::ConversionResultExt::trace::<Self>(bits).map(<Self as bitflags::Flags>::from_bits_retain)
+                }
+            }
+        }
+    });
+
+    let to = implement_to.then(|| {
+        quote! {
+            impl #impl_generics ragnarok_bytes::ToBytes for #name #type_generics #where_clause {
+                fn to_bytes<Meta>(&self, byte_writer: &mut ragnarok_bytes::ByteWriter<Meta>) -> ragnarok_bytes::ConversionResult<()> {
+                    ragnarok_bytes::ConversionResultExt::trace::<Self>(ragnarok_bytes::ToBytes::to_bytes(
+                        &bitflags::Flags::bits(self),
+                        byte_writer,
+                    ))
+                }
+            }
+        }
+    });
+
+    quote! {
+        #from
+        #to
+    }
+    .into()
+}
+
 fn derive_for_struct(
     data_struct: DataStruct,
     generics: Generics,
+    mut attributes: Vec<Attribute>,
     name: Ident,
     implement_from: bool,
     implement_to: bool,
 ) -> InterfaceTokenStream {
+    if get_unique_attribute(&mut attributes, "flags").is_some() {
+        return derive_for_flags(generics, name, implement_from, implement_to);
+    }
+
+    // types that are stored exactly like they are laid out in memory can be read in
+    // bulk when they are repeated
+    let is_bulk = get_unique_attribute(&mut attributes, "bulk").is_some();
+    let from_bytes_repeated = is_bulk.then(|| {
+        quote! {
+            fn from_bytes_repeated<Meta>(
+                byte_stream: &mut ragnarok_bytes::ByteStream<Meta, impl ragnarok_bytes::ByteSource>,
+                count: usize,
+            ) -> ragnarok_bytes::ConversionResult<Vec<Self>> {
+                ragnarok_bytes::from_bytes_bulk(byte_stream, count)
+            }
+        }
+    });
+
+    // the in-memory layout only matches the encoding if there is no padding between
+    // the fields
+    let assert_bulk_size = is_bulk.then(|| {
+        let field_types = data_struct.fields.iter().map(|field| &field.ty);
+
+        quote! {
+            const _: () = assert!(
+                std::mem::size_of::<#name>() == 0 #(+ std::mem::size_of::<#field_types>())*,
+                "types read in bulk can't contain padding"
+            );
+        }
+    });
+
     let (new_implementation, from_bytes_implementations, implemented_fields, to_bytes_implementations, delimiter) =
-        byte_convertable_helper(data_struct);
+        byte_convertable_helper(data_struct.fields, None);
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
     let instanciate = match delimiter {
@@ -34,11 +102,13 @@ fn derive_for_struct(
     let from = implement_from.then(|| {
         quote! {
             impl #impl_generics ragnarok_bytes::FromBytes for #name #type_generics #where_clause {
-                fn from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta>) -> ragnarok_bytes::ConversionResult<Self> {
+                fn from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta, impl ragnarok_bytes::ByteSource>) -> ragnarok_bytes::ConversionResult<Self> {
                     let base_offset = byte_stream.get_offset();
                     #(#from_bytes_implementations)*
                     Ok(#instanciate)
                 }
+
+                #from_bytes_repeated
             }
         }
     });
@@ -46,10 +116,9 @@ fn derive_for_struct(
     let to = implement_to.then(|| {
         quote! {
             impl #impl_generics ragnarok_bytes::ToBytes for #name #type_generics #where_clause {
-                // Temporary until serialization is always possible
-                #[allow(unreachable_code)]
-                fn to_bytes(&self) -> ragnarok_bytes::ConversionResult<Vec<u8>> {
-                    Ok([#(#to_bytes_implementations),*].concat())
+                fn to_bytes<Meta>(&self, byte_writer: &mut ragnarok_bytes::ByteWriter<Meta>) -> ragnarok_bytes::ConversionResult<()> {
+                    #(#to_bytes_implementations)*
+                    Ok(())
                 }
             }
         }
@@ -59,10 +128,28 @@ fn derive_for_struct(
         #new
         #from
         #to
+        #assert_bulk_size
     }
     .into()
 }
 
+/// Pattern that binds all fields of a variant, or ignores them if
+/// `bind_fields` is false.
+fn variant_pattern(fields: &Fields, bind_fields: bool) -> proc_macro2::TokenStream {
+    let variables = fields
+        .iter()
+        .enumerate()
+        .map(|(counter, field)| field.ident.clone().unwrap_or_else(|| format_ident!("_{}", counter)));
+
+    match (fields, bind_fields) {
+        (Fields::Unit, _) => quote!(),
+        (Fields::Named(_), true) => quote!({ #(#variables),* }),
+        (Fields::Unnamed(_), true) => quote!(( #(#variables),* )),
+        (Fields::Named(_), false) => quote!({ .. }),
+        (Fields::Unnamed(_), false) => quote!((..)),
+    }
+}
+
 fn derive_for_enum(
     data_enum: DataEnum,
     generics: Generics,
@@ -77,9 +164,15 @@ fn derive_for_enum(
         .map(|attribute| attribute.parse_args().unwrap())
         .unwrap_or_else(|| Ident::new("u8", Span::call_site()));
 
+    let has_data = data_enum.variants.iter().any(|variant| !variant.fields.is_empty());
+
     let mut current_index = 0usize;
     let mut indices = Vec::new();
     let mut values = Vec::new();
+    let mut patterns = Vec::new();
+    let mut bindings = Vec::new();
+    let mut from_bytes_implementations = Vec::new();
+    let mut to_bytes_implementations = Vec::new();
 
     for mut variant in data_enum.variants.into_iter() {
         if let Some(attribute) = get_unique_attribute(&mut variant.attrs, "numeric_value") {
@@ -90,35 +183,119 @@ fn derive_for_enum(
                 .expect("numeric_value failed to parse integer as base 10");
         }
 
+        patterns.push(variant_pattern(&variant.fields, false));
+        bindings.push(variant_pattern(&variant.fields, true));
+
+        let (_, from_implementations, implemented_fields, to_implementations, delimiter) =
+            byte_convertable_helper(variant.fields, Some(&variant.ident));
+        let identifier = &variant.ident;
+
+        let instanciate = match delimiter {
+            proc_macro2::Delimiter::Brace => quote!(Self::#identifier { #(#implemented_fields),* }),
+            proc_macro2::Delimiter::Parenthesis => quote!(Self::#identifier ( #(#implemented_fields),* )),
+            _ => quote!(Self::#identifier),
+        };
+
+        from_bytes_implementations.push(quote! {
+            #(#from_implementations)*
+            Ok(#instanciate)
+        });
+        to_bytes_implementations.push(quote! {
+            #(#to_implementations)*
+            Ok(())
+        });
+
         indices.push(current_index);
         values.push(variant.ident);
         current_index += 1;
     }
 
+    // Enums without any data are only a number, so they don't need to support
+    // reading the discriminant separately.
+    if !has_data {
+        let from = add_from.then(|| {
+            quote! {
+                impl #impl_generics ragnarok_bytes::FromBytes for #name #type_generics #where_clause {
+                    fn from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta, impl ragnarok_bytes::ByteSource>) -> ragnarok_bytes::ConversionResult<Self> {
+                        match ragnarok_bytes::ConversionResultExt::trace::<Self>(#numeric_type::from_bytes(byte_stream))? as usize {
+                            #( #indices => Ok(Self::#values), )*
+                            invalid => Err(ragnarok_bytes::ConversionError::from_message(format!("invalid enum variant {}", invalid))),
+                        }
+                    }
+                }
+            }
+        });
+
+        let to = add_to.then(|| {
+            quote! {
+                impl #impl_generics ragnarok_bytes::ToBytes for #name #type_generics #where_clause {
+                    fn to_bytes<Meta>(&self, byte_writer: &mut ragnarok_bytes::ByteWriter<Meta>) -> ragnarok_bytes::ConversionResult<()> {
+                        match self {
+                            #( #name::#values => ragnarok_bytes::ConversionResultExt::trace::<Self>((#indices as #numeric_type).to_bytes(byte_writer)), )*
+                        }
+                    }
+                }
+            }
+        });
+
+        return quote! {
+            #from
+            #to
+        }
+        .into();
+    }
+
     let from = add_from.then(|| {
         quote! {
-            impl #impl_generics ragnarok_bytes::FromBytes for #name #type_generics #where_clause {
-                fn from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta>) -> ragnarok_bytes::ConversionResult<Self> {
-                    match ragnarok_bytes::ConversionResultExt::trace::<Self>(#numeric_type::from_bytes(byte_stream))? as usize {
-                        #( #indices => Ok(Self::#values), )*
+            impl #impl_generics ragnarok_bytes::FromBytesWithDiscriminant for #name #type_generics #where_clause {
+                fn from_bytes_with_discriminant<Meta>(
+                    byte_stream: &mut ragnarok_bytes::ByteStream<Meta, impl ragnarok_bytes::ByteSource>,
+                    discriminant: usize,
+                ) -> ragnarok_bytes::ConversionResult<Self> {
+                    let base_offset = byte_stream.get_offset();
+
+                    match discriminant {
+                        #( #indices => { #from_bytes_implementations } )*
                         invalid => Err(ragnarok_bytes::ConversionError::from_message(format!("invalid enum variant {}", invalid))),
                     }
                 }
             }
+
+            impl #impl_generics ragnarok_bytes::FromBytes for #name #type_generics #where_clause {
+                fn from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta, impl ragnarok_bytes::ByteSource>) -> ragnarok_bytes::ConversionResult<Self> {
+                    let discriminant = ragnarok_bytes::ConversionResultExt::trace::<Self>(
+                        <#numeric_type as ragnarok_bytes::FromBytes>::from_bytes(byte_stream),
+                    )?;
+
+                    ragnarok_bytes::FromBytesWithDiscriminant::from_bytes_with_discriminant(byte_stream, discriminant as usize)
+                }
+            }
         }
     });
 
     let to = add_to.then(|| {
         quote! {
-            impl #impl_generics ragnarok_bytes::ToBytes for #name #type_generics #where_clause {
-                // Temporary until serialization is always possible
-                #[allow(unreachable_code)]
-                fn to_bytes(&self) -> ragnarok_bytes::ConversionResult<Vec<u8>> {
+            impl #impl_generics ragnarok_bytes::ToBytesWithDiscriminant for #name #type_generics #where_clause {
+                fn discriminant(&self) -> usize {
+                    match self {
+                        #( Self::#values #patterns => #indices, )*
+                    }
+                }
+
+                fn to_bytes_without_discriminant<Meta>(&self, byte_writer: &mut ragnarok_bytes::ByteWriter<Meta>) -> ragnarok_bytes::ConversionResult<()> {
                     match self {
-                        #( #name::#values => ragnarok_bytes::ConversionResultExt::trace::<Self>((#indices as #numeric_type).to_bytes()), )*
+                        #( Self::#values #bindings => { #to_bytes_implementations } )*
                     }
                 }
             }
+
+            impl #impl_generics ragnarok_bytes::ToBytes for #name #type_generics #where_clause {
+                fn to_bytes<Meta>(&self, byte_writer: &mut ragnarok_bytes::ByteWriter<Meta>) -> ragnarok_bytes::ConversionResult<()> {
+                    let discriminant = ragnarok_bytes::ToBytesWithDiscriminant::discriminant(self) as #numeric_type;
+                    ragnarok_bytes::ConversionResultExt::trace::<Self>(ragnarok_bytes::ToBytes::to_bytes(&discriminant, byte_writer))?;
+                    ragnarok_bytes::ToBytesWithDiscriminant::to_bytes_without_discriminant(self, byte_writer)
+                }
+            }
         }
     });
 
@@ -129,8 +306,128 @@ fn derive_for_enum(
     .into()
 }
 
-pub fn derive_byte_convertable_struct(data_struct: DataStruct, generics: Generics, name: Ident) -> InterfaceTokenStream {
-    derive_for_struct(data_struct, generics, name, true, true)
+/// Property test that decodes arbitrary bytes and checks that the value
+/// survives a round trip. Types that depend on a version are tested with
+/// arbitrary versions. The input starts with `prefix`, so that types with a
+/// signature or discriminant decode. Generic types are skipped.
+fn round_trip_test(generics: &Generics, name: &Ident, prefix: proc_macro2::TokenStream, is_versioned: bool) -> proc_macro2::TokenStream {
+    if !generics.params.is_empty() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let module_name = format_ident!("__round_trip_{}", name);
+
+    let metadata = match is_versioned {
+        // All known file formats have a major version below 3. Build numbers are tested
+        // around 186, which is the only one that changes the layout of a known format.
+        true => quote! {
+            ragnarok_bytes::testing::proptest::strategy::Strategy::prop_map(
+                (0u8..4, 0u8..16, 180i32..192),
+                |(major, minor, build_number)| {
+                    Some(ragnarok_formats::version::InternalVersion { major, minor, build_number: Some(build_number) })
+                },
+            )
+        },
+        false => quote!(ragnarok_bytes::testing::proptest::strategy::Just(())),
+    };
+
+    quote! {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module_name {
+            #[test]
+            fn round_trip() {
+                ragnarok_bytes::testing::test_round_trip::<super::#name, _>(#prefix, #metadata);
+            }
+        }
+    }
+}
+
+/// Input prefix for structs, which is either given by
+/// `#[round_trip_prefix(...)]` for structs that only decode with specific
+/// leading bytes, or the signature if the first field is a `Signature<b"...">`.
+/// The expression is evaluated next to the struct.
+fn struct_prefix(fields: &Fields, attributes: &[Attribute]) -> proc_macro2::TokenStream {
+    if let Some(attribute) = attributes.iter().find(|attribute| attribute.path().is_ident("round_trip_prefix")) {
+        let prefix: Expr = attribute.parse_args().expect("round_trip_prefix requires an expression");
+
+        return quote!(ragnarok_bytes::testing::proptest::strategy::Just({
+            #[allow(unused_imports)]
+            use super::*;
+            AsRef::<[u8]>::as_ref(&#prefix).to_vec()
+        }));
+    }
+
+    let magic = fields.iter().next().and_then(|field| signature(&field.ty));
+
+    match magic {
+        Some(magic) => quote!(ragnarok_bytes::testing::proptest::strategy::Just(#magic.to_vec())),
+        None => quote!(ragnarok_bytes::testing::proptest::strategy::Just(Vec::new())),
+    }
+}
+
+/// Input prefix for enums, which is the discriminant of an arbitrary variant.
+fn enum_prefix(data_enum: &DataEnum, attributes: &[Attribute]) -> proc_macro2::TokenStream {
+    let numeric_type = attributes
+        .iter()
+        .find(|attribute| attribute.path().is_ident("numeric_type"))
+        .map(|attribute| attribute.parse_args().unwrap())
+        .unwrap_or_else(|| Ident::new("u8", Span::call_site()));
+
+    let mut current_index = 0usize;
+    let mut discriminants = Vec::new();
+
+    for variant in &data_enum.variants {
+        if let Some(attribute) = variant.attrs.iter().find(|attribute| attribute.path().is_ident("numeric_value")) {
+            current_index = attribute
+                .parse_args::<syn::LitInt>()
+                .expect("numeric_value requires an integer value")
+                .base10_parse()
+                .expect("numeric_value failed to parse integer as base 10");
+        }
+
+        discriminants.push(quote!((#current_index as #numeric_type).to_le_bytes().to_vec()));
+        current_index += 1;
+    }
+
+    quote!(ragnarok_bytes::testing::proptest::sample::select(vec![#(#discriminants),*]))
+}
+
+/// The magic bytes of a `Signature<b"...">` type.
+fn signature(field_type: &Type) -> Option<proc_macro2::TokenStream> {
+    let Type::Path(type_path) = field_type else {
+        return None;
+    };
+
+    let segment = type_path.path.segments.last().filter(|segment| segment.ident == "Signature")?;
+
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first() {
+        Some(GenericArgument::Const(Expr::Lit(ExprLit {
+            lit: Lit::ByteStr(magic), ..
+        }))) => Some(quote!(#magic)),
+        _ => None,
+    }
+}
+
+pub fn derive_byte_convertable_struct(
+    data_struct: DataStruct,
+    generics: Generics,
+    attributes: Vec<Attribute>,
+    name: Ident,
+) -> InterfaceTokenStream {
+    let prefix = struct_prefix(&data_struct.fields, &attributes);
+    let test = round_trip_test(&generics, &name, prefix, has_version_attribute(&data_struct.fields));
+    let implementation = proc_macro2::TokenStream::from(derive_for_struct(data_struct, generics, attributes, name, true, true));
+
+    quote! {
+        #implementation
+        #test
+    }
+    .into()
 }
 
 pub fn derive_byte_convertable_enum(
@@ -139,19 +436,38 @@ pub fn derive_byte_convertable_enum(
     attributes: Vec<Attribute>,
     name: Ident,
 ) -> InterfaceTokenStream {
-    derive_for_enum(data_enum, generics, attributes, name, true, true)
+    let is_versioned = has_version_attribute(data_enum.variants.iter().flat_map(|variant| &variant.fields));
+    let prefix = enum_prefix(&data_enum, &attributes);
+    let test = round_trip_test(&generics, &name, prefix, is_versioned);
+    let implementation = proc_macro2::TokenStream::from(derive_for_enum(data_enum, generics, attributes, name, true, true));
+
+    quote! {
+        #implementation
+        #test
+    }
+    .into()
 }
 
-pub fn derive_from_bytes_struct(data_struct: DataStruct, generics: Generics, name: Ident) -> InterfaceTokenStream {
-    derive_for_struct(data_struct, generics, name, true, false)
+pub fn derive_from_bytes_struct(
+    data_struct: DataStruct,
+    generics: Generics,
+    attributes: Vec<Attribute>,
+    name: Ident,
+) -> InterfaceTokenStream {
+    derive_for_struct(data_struct, generics, attributes, name, true, false)
 }
 
 pub fn derive_from_bytes_enum(data_enum: DataEnum, generics: Generics, attributes: Vec<Attribute>, name: Ident) -> InterfaceTokenStream {
     derive_for_enum(data_enum, generics, attributes, name, true, false)
 }
 
-pub fn derive_to_bytes_struct(data_struct: DataStruct, generics: Generics, name: Ident) -> InterfaceTokenStream {
-    derive_for_struct(data_struct, generics, name, false, true)
+pub fn derive_to_bytes_struct(
+    data_struct: DataStruct,
+    generics: Generics,
+    attributes: Vec<Attribute>,
+    name: Ident,
+) -> InterfaceTokenStream {
+    derive_for_struct(data_struct, generics, attributes, name, false, true)
 }
 
 pub fn derive_to_bytes_enum(data_enum: DataEnum, generics: Generics, attributes: Vec<Attribute>, name: Ident) -> InterfaceTokenStream {
@@ -1,12 +1,29 @@
 use proc_macro::TokenStream as InterfaceTokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{Attribute, DataStruct, Field, Generics, Ident};
+use syn::{Attribute, DataEnum, DataStruct, Field, Generics, Ident};
 
 use crate::utils::get_unique_attribute;
 
-pub fn derive_fixed_byte_size_struct(data_struct: DataStruct, generics: Generics, name: Ident) -> InterfaceTokenStream {
+pub fn derive_fixed_byte_size_struct(
+    data_struct: DataStruct,
+    generics: Generics,
+    mut attributes: Vec<Attribute>,
+    name: Ident,
+) -> InterfaceTokenStream {
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    // types generated by `bitflags!` have the size of their bits
+    if get_unique_attribute(&mut attributes, "flags").is_some() {
+        return quote! {
+            impl #impl_generics ragnarok_bytes::FixedByteSize for #name #type_generics #where_clause {
+                fn size_in_bytes() -> usize {
+                    <<Self as bitflags::Flags>::Bits as ragnarok_bytes::FixedByteSize>::size_in_bytes()
+                }
+            }
+        }
+        .into();
+    }
     let types: Vec<_> = data_struct.fields.iter().map(|field| field.ty.clone()).collect();
 
     let fields: Vec<Field> = match data_struct.fields {
@@ -37,9 +54,18 @@ pub fn derive_fixed_byte_size_struct(data_struct: DataStruct, generics: Generics
     .into()
 }
 
-pub fn derive_fixed_byte_size_enum(generics: Generics, mut attributes: Vec<Attribute>, name: Ident) -> InterfaceTokenStream {
+pub fn derive_fixed_byte_size_enum(
+    data_enum: DataEnum,
+    generics: Generics,
+    mut attributes: Vec<Attribute>,
+    name: Ident,
+) -> InterfaceTokenStream {
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
+    if data_enum.variants.iter().any(|variant| !variant.fields.is_empty()) {
+        panic!("enums with data don't have a fixed size");
+    }
+
     let numeric_type = get_unique_attribute(&mut attributes, "numeric_type")
         .map(|attribute| attribute.parse_args().unwrap())
         .unwrap_or_else(|| Ident::new("u8", Span::call_site()));
@@ -1,31 +1,130 @@
 use std::collections::HashMap;
 
-use proc_macro2::{Delimiter, TokenStream};
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
 use quote::{format_ident, quote};
-use syn::{DataStruct, Field};
+use syn::{Field, Fields};
 
 use crate::utils::{get_unique_attribute, Version};
 
-pub fn byte_convertable_helper(data_struct: DataStruct) -> (TokenStream, Vec<TokenStream>, Vec<TokenStream>, Vec<TokenStream>, Delimiter) {
+/// Read `repeat_count` elements into a vector. Errors are annotated with the
+/// index of the element that failed to parse.
+///
+/// Elements that are read without any length go through
+/// `FromBytes::from_bytes_repeated`, so that types can read all of them at
+/// once.
+fn repeat_implementation(repeat_count: TokenStream, from_implementation: TokenStream, is_plain: bool) -> TokenStream {
+    if is_plain {
+        return quote!(ragnarok_bytes::ConversionResultExt::trace::<Self>(
+            ragnarok_bytes::FromBytes::from_bytes_repeated(byte_stream, #repeat_count)
+        ));
+    }
+
+    quote!({
+        let repeat_count = #repeat_count;
+        let mut vector = ragnarok_bytes::vector_for_untrusted_count(repeat_count);
+        let mut result: ragnarok_bytes::ConversionResult<()> = Ok(());
+
+        for __index in 0..repeat_count {
+            match ragnarok_bytes::ConversionResultExt::index(#from_implementation, __index) {
+                Ok(element) => vector.push(element),
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            }
+        }
+
+        result.map(|_| vector)
+    })
+}
+
+/// Replace the names of fields in an expression with an access to the field,
+/// so that conditions written in terms of the fields can be evaluated when
+/// writing. Identifiers following a `.` are method calls or nested fields and
+/// are kept.
+fn access_fields(
+    expression: TokenStream,
+    field_names: &[syn::Ident],
+    access_field: &impl Fn(&syn::Ident, &TokenStream) -> TokenStream,
+) -> TokenStream {
+    let mut follows_dot = false;
+
+    expression
+        .into_iter()
+        .map(|token| {
+            let token = match token {
+                TokenTree::Ident(ident) if !follows_dot && field_names.contains(&ident) => {
+                    let access = access_field(&ident, &quote!(#ident));
+                    TokenTree::Group(Group::new(Delimiter::Parenthesis, access))
+                }
+                TokenTree::Group(group) => {
+                    let stream = access_fields(group.stream(), field_names, access_field);
+                    let mut new_group = Group::new(group.delimiter(), stream);
+                    new_group.set_span(group.span());
+                    TokenTree::Group(new_group)
+                }
+                token => token,
+            };
+
+            follows_dot = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '.');
+            token
+        })
+        .collect()
+}
+
+/// Whether any of the fields is the version or depends on the version.
+pub fn has_version_attribute<'a>(fields: impl IntoIterator<Item = &'a Field>) -> bool {
+    fields.into_iter().flat_map(|field| &field.attrs).any(|attribute| {
+        [
+            "version",
+            "version_smaller",
+            "version_equals_or_above",
+            "build_number",
+            "build_number_equals_or_above",
+        ]
+        .iter()
+        .any(|name| attribute.path().is_ident(name))
+    })
+}
+
+/// Generate the implementations for the fields of a struct or, if `variant` is
+/// set, of an enum variant. The fields of an enum variant are expected to be
+/// bound by reference when writing them, so they are accessed through their
+/// variables instead of `self`.
+pub fn byte_convertable_helper(
+    fields: Fields,
+    variant: Option<&syn::Ident>,
+) -> (TokenStream, Vec<TokenStream>, Vec<TokenStream>, Vec<TokenStream>, Delimiter) {
     let mut from_bytes_implementations = vec![];
     let mut implemented_fields = vec![];
     let mut to_bytes_implementations = vec![];
     let mut deriveable_map: HashMap<syn::Ident, (syn::Ident, bool)> = HashMap::new();
 
-    let (mut fields, delimiter): (Vec<Field>, _) = match data_struct.fields {
+    let (mut fields, delimiter): (Vec<Field>, _) = match fields {
         syn::Fields::Named(named_fields) => (named_fields.named.into_iter().collect(), Delimiter::Brace),
         syn::Fields::Unnamed(unnamed_fields) => (unnamed_fields.unnamed.into_iter().collect(), Delimiter::Parenthesis),
+        syn::Fields::Unit if variant.is_some() => (Vec::new(), Delimiter::None),
         syn::Fields::Unit => panic!("unit types are not supported"),
     };
 
+    let access_field = |field_variable: &syn::Ident, field_identifier: &TokenStream| match variant {
+        Some(_) => quote!((*#field_variable)),
+        None => quote!(self.#field_identifier),
+    };
+
+    let is_versioned = has_version_attribute(&fields);
+    let field_names: Vec<syn::Ident> = fields.iter().filter_map(|field| field.ident.clone()).collect();
+
     for (counter, field) in fields.iter_mut().enumerate() {
         let counter_ident = format_ident!("_{}", counter);
         let counter_index = syn::Index::from(counter);
         let field_variable = field.ident.clone().unwrap_or(counter_ident);
         let field_identifier = field.ident.as_ref().map(|ident| quote!(#ident)).unwrap_or(quote!(#counter_index));
         let field_type = field.ty.clone();
+        let field_access = access_field(&field_variable, &field_identifier);
 
         let is_version = get_unique_attribute(&mut field.attrs, "version").is_some();
+        let is_build_number = get_unique_attribute(&mut field.attrs, "build_number").is_some();
 
         let length = get_unique_attribute(&mut field.attrs, "length").map(|attribute| match attribute.meta {
             syn::Meta::List(list) => list.tokens,
@@ -34,11 +133,31 @@ pub fn byte_convertable_helper(data_struct: DataStruct) -> (TokenStream, Vec<Tok
         let length_remaining = get_unique_attribute(&mut field.attrs, "length_remaining").is_some();
         let length_remaining_off_by_one = get_unique_attribute(&mut field.attrs, "length_remaining_off_by_one").is_some();
 
-        if (length.is_some() as usize) + (length_remaining as usize) + (length_remaining_off_by_one as usize) > 1 {
-            panic!("only one of `length`, `length_remaining`, or `length_remaining_off_by_one` can be used for one field at a time");
+        let discriminant = get_unique_attribute(&mut field.attrs, "discriminant").map(|attribute| {
+            attribute
+                .parse_args::<syn::Ident>()
+                .expect("discriminant takes a single identifier")
+        });
+
+        if (length.is_some() as usize)
+            + (length_remaining as usize)
+            + (length_remaining_off_by_one as usize)
+            + (discriminant.is_some() as usize)
+            > 1
+        {
+            panic!(
+                "only one of `length`, `length_remaining`, `length_remaining_off_by_one`, or `discriminant` can be used for one field at \
+                 a time"
+            );
         }
 
+        let is_plain = length.is_none() && !length_remaining && !length_remaining_off_by_one && discriminant.is_none();
+
         let from_length = match length.clone() {
+            None if discriminant.is_some() => quote!(ragnarok_bytes::FromBytesWithDiscriminant::from_bytes_with_discriminant(
+                byte_stream,
+                #discriminant as usize
+            )),
             Some(length) => {
                 quote!(ragnarok_bytes::FromBytesExt::from_n_bytes(byte_stream, #length as usize))
             }
@@ -53,12 +172,29 @@ pub fn byte_convertable_helper(data_struct: DataStruct) -> (TokenStream, Vec<Tok
             None => quote!(ragnarok_bytes::FromBytes::from_bytes(byte_stream)),
         };
 
+        let has_length = length.is_some();
         let to_length = match length {
-            Some(length) if syn::parse::<syn::Ident>(length.clone().into()).is_ok() => {
-                quote!(ragnarok_bytes::ToBytesExt::to_n_bytes(&self.#field_identifier, self.#length as usize))
+            Some(length) => match syn::parse::<syn::Ident>(length.clone().into()) {
+                Ok(length) => {
+                    let length = access_field(&length, &quote!(#length));
+                    quote!(ragnarok_bytes::ToBytesExt::to_n_bytes(__value, byte_writer, #length as usize))
+                }
+                Err(_) => quote!(ragnarok_bytes::ToBytesExt::to_n_bytes(__value, byte_writer, #length as usize)),
+            },
+            None if discriminant.is_some() => {
+                let discriminant = discriminant.as_ref().unwrap();
+                let field_name = field_variable.to_string();
+                let discriminant = access_field(discriminant, &quote!(#discriminant));
+
+                quote!(match #discriminant as usize == ragnarok_bytes::ToBytesWithDiscriminant::discriminant(__value) {
+                    true => ragnarok_bytes::ToBytesWithDiscriminant::to_bytes_without_discriminant(__value, byte_writer),
+                    false => Err(ragnarok_bytes::ConversionError::from_message(format!(
+                        "field {} does not match its discriminant",
+                        #field_name
+                    ))),
+                })
             }
-            Some(length) => quote!(ragnarok_bytes::ToBytesExt::to_n_bytes(&self.#field_identifier, #length as usize)),
-            None => quote!(ragnarok_bytes::ToBytes::to_bytes(&self.#field_identifier)),
+            None => quote!(ragnarok_bytes::ToBytes::to_bytes(__value, byte_writer)),
         };
 
         let mut repeating: Option<(syn::Ident, bool)> = None;
@@ -98,10 +234,81 @@ pub fn byte_convertable_helper(data_struct: DataStruct) -> (TokenStream, Vec<Tok
             "version restriction may only be specified once"
         );
         let version_function = version_smaller.or(version_equals_or_above);
-        let version_restricted = version_function.is_some();
+
+        let build_number_equals_or_above = get_unique_attribute(&mut field.attrs, "build_number_equals_or_above")
+            .map(|attribute| attribute.parse_args::<syn::LitInt>().expect("failed to parse build number"))
+            .map(|build_number| quote!(build_number_equals_or_above(#build_number)));
+
+        // all version restrictions of the field have to hold
+        let version_functions: Vec<TokenStream> = version_function.into_iter().chain(build_number_equals_or_above).collect();
+
+        // packets are restricted by the packet version instead, which may be limited in
+        // both directions
+        let since = get_unique_attribute(&mut field.attrs, "since")
+            .map(|attribute| attribute.parse_args::<syn::LitInt>().expect("failed to parse packet version"));
+        let before = get_unique_attribute(&mut field.attrs, "before")
+            .map(|attribute| attribute.parse_args::<syn::LitInt>().expect("failed to parse packet version"));
+
+        assert!(
+            version_functions.is_empty() || (since.is_none() && before.is_none()),
+            "version restrictions can't be combined with packet version restrictions"
+        );
+
+        let packet_version_condition = |packet_version: TokenStream| {
+            let since = since
+                .as_ref()
+                .map(|since| quote!(#packet_version >= ragnarok_packets::PacketVersion(#since)));
+            let before = before
+                .as_ref()
+                .map(|before| quote!(#packet_version < ragnarok_packets::PacketVersion(#before)));
+
+            match (since, before) {
+                (Some(since), Some(before)) => Some(quote!((#since && #before))),
+                (since, before) => since.or(before),
+            }
+        };
+
+        // condition under which a version restricted field is present, once for reading
+        // and once for writing
+        let version_condition = match !version_functions.is_empty() {
+            true => Some((
+                quote! {{
+                    let __version = byte_stream
+                        .get_metadata::<Self, Option<ragnarok_formats::version::InternalVersion>>()?
+                        .ok_or(ragnarok_bytes::ConversionError::from_message("version not set"))?;
+                    #(__version.#version_functions)&&*
+                }},
+                quote! {{
+                    let __version = byte_writer
+                        .get_metadata::<Self, Option<ragnarok_formats::version::InternalVersion>>()?
+                        .ok_or(ragnarok_bytes::ConversionError::from_message("version not set"))?;
+                    #(__version.#version_functions)&&*
+                }},
+            )),
+            false => packet_version_condition(quote!(ragnarok_packets::PacketVersion::of_stream(byte_stream))).zip(
+                packet_version_condition(quote!(ragnarok_packets::PacketVersion::of_writer(byte_writer))),
+            ),
+        };
+
+        let present_if = get_unique_attribute(&mut field.attrs, "present_if").map(|attribute| match attribute.meta {
+            syn::Meta::List(list) => list.tokens,
+            syn::Meta::Path(_) | syn::Meta::NameValue(_) => panic!("expected token stream in attribute"),
+        });
+
+        assert!(
+            present_if.is_none() || version_condition.is_none(),
+            "`present_if` can't be combined with a version restriction"
+        );
+
+        let is_repeating = repeating.is_some() || repeating_remaining || repeating_expr.is_some();
+
+        assert!(
+            !is_repeating || discriminant.is_none(),
+            "`discriminant` can't be combined with a repeating field"
+        );
 
         // base from bytes implementation
-        let from_implementation = quote!(ragnarok_bytes::ConversionResultExt::trace::<Self>(#from_length)?);
+        let from_implementation = quote!(ragnarok_bytes::ConversionResultExt::trace::<Self>(#from_length));
 
         // wrap base implementation in a loop if the element can appear multiple times
         let from_implementation = match repeating {
@@ -113,98 +320,192 @@ pub fn byte_convertable_helper(data_struct: DataStruct) -> (TokenStream, Vec<Tok
                     false => quote!(#repeat_count),
                 };
 
-                quote!({
-                    let repeat_count = #repeat_count_inner;
-                    // TODO: Add check to make sure this allocation is not too big.
-                    let mut vector = Vec::with_capacity(repeat_count as usize);
-
-                    for _ in 0..repeat_count {
-                        vector.push(#from_implementation);
-                    }
-
-                    vector
-                })
+                repeat_implementation(quote!(#repeat_count_inner as usize), from_implementation, is_plain)
             }
             None if repeating_remaining => {
+                let repeat_implementation =
+                    repeat_implementation(quote!((remaining_bytes / struct_size) as usize), from_implementation, is_plain);
+
                 quote!({
-                    let remaining_bytes = __packet_length - ((byte_stream.get_offset() - base_offset) as u16) - 2;
+                    let remaining_bytes = (__packet_length as usize).saturating_sub(2 + (byte_stream.get_offset() - base_offset)) as u16;
                     let struct_size = <#field_type as ragnarok_bytes::FixedByteSizeCollection>::size_in_bytes() as u16;
 
-                    if remaining_bytes % struct_size != 0 {
-                        return Err(ragnarok_bytes::ConversionError::from_message("type doesn't perfectly divide remaining data"));
-                    }
-
-                    let repeat_count = (remaining_bytes / struct_size) as usize;
-                    // TODO: Add check to make sure this allocation is not too big.
-                    let mut vector = Vec::with_capacity(repeat_count);
-
-                    for _ in 0..repeat_count {
-                        vector.push(#from_implementation);
+                    match remaining_bytes % struct_size != 0 {
+                        true => Err(ragnarok_bytes::ConversionError::from_message("type doesn't perfectly divide remaining data")),
+                        false => #repeat_implementation,
                     }
-
-                    vector
                 })
             }
             None if repeating_expr.is_some() => {
                 let repeating_expr = repeating_expr.unwrap();
-
-                quote!({
-                    let repeat_count = (#repeating_expr) as usize;
-
-                    // TODO: Add check to make sure this allocation is not too big.
-                    let mut vector = Vec::with_capacity(repeat_count);
-
-                    for _ in 0..repeat_count {
-                        vector.push(#from_implementation);
-                    }
-
-                    vector
-                })
+                repeat_implementation(quote!((#repeating_expr) as usize), from_implementation, is_plain)
             }
             None => from_implementation,
         };
 
+        // add the field to the path of the error and record the version for
+        // versioned types
+        let field_name = field_variable.to_string();
+        let field_name = field_name
+            .strip_prefix('_')
+            .filter(|_| field.ident.is_none())
+            .unwrap_or(&field_name);
+        let field_name = match variant {
+            Some(variant) => format!("{variant}.{field_name}"),
+            None => field_name.to_owned(),
+        };
+        let add_version = is_versioned.then(|| {
+            quote! {
+                let __result = ragnarok_bytes::ConversionResultExt::version(
+                    __result,
+                    byte_stream
+                        .get_metadata::<Self, Option<ragnarok_formats::version::InternalVersion>>()
+                        .ok()
+                        .and_then(|version| version.as_ref()),
+                );
+            }
+        });
+        let from_implementation = quote!({
+            let __field_offset = byte_stream.get_offset();
+            let __result = #from_implementation;
+            let __result = ragnarok_bytes::ConversionResultExt::field::<Self, _>(__result, byte_stream, __field_offset, #field_name);
+            #add_version
+            __result?
+        });
+
         implemented_fields.push(quote!(#field_variable));
 
         // wrap the potentially looped implementation in an option if it has a version
         // restriction
-        let from_implementation = match version_function {
-            Some(function) => {
+        let from_implementation = match &version_condition {
+            Some((condition, _)) => {
                 quote! {
-                    let #field_variable = match byte_stream
-                            .get_metadata::<Self, Option<ragnarok_formats::version::InternalVersion>>()?
-                            .ok_or(ragnarok_bytes::ConversionError::from_message("version not set"))?
-                            .#function {
+                    let #field_variable = match #condition {
                         true => Some(#from_implementation),
                         false => None,
                     };
                 }
             }
-            None => quote!(let #field_variable = #from_implementation;),
+            None if present_if.is_some() => {
+                let condition = present_if.as_ref().unwrap();
+
+                quote! {
+                    let #field_variable = match #condition {
+                        true => Some(#from_implementation),
+                        false => None,
+                    };
+                }
+            }
+            // the type is spelled out so later `present_if` conditions can call methods on the field
+            None => quote!(let #field_variable: #field_type = #from_implementation;),
         };
         from_bytes_implementations.push(from_implementation);
 
         // base to byte implementation
-        let to_implementation = match version_restricted {
-            true => quote!({
-                panic!("version restricted fields can't be serialized at the moment");
-                [0u8].as_slice()
-            }),
-            false => {
-                quote!(ragnarok_bytes::ConversionResultExt::trace::<Self>(#to_length)?.as_slice())
+        let to_implementation = quote!(ragnarok_bytes::ConversionResultExt::trace::<Self>(#to_length)?;);
+
+        // the length applies to every element of repeating fields
+        let to_implementation = match is_repeating && has_length {
+            true => quote! {
+                for __value in __value.iter() {
+                    #to_implementation
+                }
+            },
+            false => to_implementation,
+        };
+
+        // version restricted fields are only written if they are present, and they have
+        // to be present exactly when the version requires them
+        let to_implementation = match version_condition {
+            Some((_, condition)) => {
+                let field_name = field_variable.to_string();
+
+                quote! {
+                    match (#condition, &#field_access) {
+                        (true, Some(__value)) => {
+                            #to_implementation
+                        }
+                        (false, None) => {}
+                        _ => {
+                            return Err(ragnarok_bytes::ConversionError::from_message(format!(
+                                "field {} does not match the version",
+                                #field_name
+                            )));
+                        }
+                    }
+                }
             }
+            // conditional fields have to be present exactly when their condition holds
+            None if present_if.is_some() => {
+                let condition = access_fields(present_if.clone().unwrap(), &field_names, &access_field);
+                let field_name = field_variable.to_string();
+
+                quote! {
+                    match (#condition, &#field_access) {
+                        (true, Some(__value)) => {
+                            #to_implementation
+                        }
+                        (false, None) => {}
+                        _ => {
+                            return Err(ragnarok_bytes::ConversionError::from_message(format!(
+                                "field {} does not match its condition",
+                                #field_name
+                            )));
+                        }
+                    }
+                }
+            }
+            None => quote! {
+                let __value = &#field_access;
+                #to_implementation
+            },
         };
         to_bytes_implementations.push(to_implementation);
 
+        // the build number is an optional field that follows the version
+        if is_build_number {
+            from_bytes_implementations.push(quote! {
+                byte_stream
+                    .get_metadata_mut::<Self, Option<ragnarok_formats::version::InternalVersion>>()?
+                    .as_mut()
+                    .ok_or(ragnarok_bytes::ConversionError::from_message("version not set"))?
+                    .build_number = #field_variable;
+            });
+            to_bytes_implementations.push(quote! {
+                byte_writer
+                    .get_metadata_mut::<Self, Option<ragnarok_formats::version::InternalVersion>>()?
+                    .as_mut()
+                    .ok_or(ragnarok_bytes::ConversionError::from_message("version not set"))?
+                    .build_number = #field_access;
+            });
+        }
+
         if is_version {
             from_bytes_implementations.push(
                 quote!(*byte_stream.get_metadata_mut::<Self, Option<ragnarok_formats::version::InternalVersion>>()? = Some(ragnarok_formats::version::InternalVersion::from(#field_variable));),
             );
+            to_bytes_implementations.push(
+                quote!(*byte_writer.get_metadata_mut::<Self, Option<ragnarok_formats::version::InternalVersion>>()? = Some(ragnarok_formats::version::InternalVersion::from(#field_access));),
+            );
         }
     }
 
-    // Implement `new` function.
+    // Implement `new` function. Enum variants are constructed directly.
+    let new_implementation = match variant {
+        Some(_) => TokenStream::new(),
+        None => new_implementation(fields, &deriveable_map, delimiter),
+    };
+
+    (
+        new_implementation,
+        from_bytes_implementations,
+        implemented_fields,
+        to_bytes_implementations,
+        delimiter,
+    )
+}
 
+fn new_implementation(fields: Vec<Field>, deriveable_map: &HashMap<syn::Ident, (syn::Ident, bool)>, delimiter: Delimiter) -> TokenStream {
     let mut new_arguments = vec![];
     let mut new_implementations = vec![];
 
@@ -299,7 +600,7 @@ pub fn byte_convertable_helper(data_struct: DataStruct) -> (TokenStream, Vec<Tok
         _ => unreachable!(),
     };
 
-    let new_implementation = quote! {
+    quote! {
         /// Automatically derived `new` function that will fill any fields annotated with any of
         /// the `new` attributes.
         ///
@@ -309,13 +610,5 @@ pub fn byte_convertable_helper(data_struct: DataStruct) -> (TokenStream, Vec<Tok
         pub fn new(#(#new_arguments),*) -> Self {
             #new_implementation_inner
         }
-    };
-
-    (
-        new_implementation,
-        from_bytes_implementations,
-        implemented_fields,
-        to_bytes_implementations,
-        delimiter,
-    )
+    }
 }
@@ -15,7 +15,7 @@ use self::convertable::*;
 use self::fixed_size::{derive_fixed_byte_size_enum, derive_fixed_byte_size_struct};
 use self::packet::*;
 
-#[proc_macro_derive(FixedByteSize, attributes(length))]
+#[proc_macro_derive(FixedByteSize, attributes(flags, length))]
 pub fn derive_fixed_byte_size(token_stream: InterfaceTokenStream) -> InterfaceTokenStream {
     let DeriveInput {
         ident,
@@ -26,8 +26,8 @@ pub fn derive_fixed_byte_size(token_stream: InterfaceTokenStream) -> InterfaceTo
     } = parse(token_stream).expect("failed to parse token stream");
 
     match data {
-        Data::Struct(data_struct) => derive_fixed_byte_size_struct(data_struct, generics, ident),
-        Data::Enum(..) => derive_fixed_byte_size_enum(generics, attrs, ident),
+        Data::Struct(data_struct) => derive_fixed_byte_size_struct(data_struct, generics, attrs, ident),
+        Data::Enum(data_enum) => derive_fixed_byte_size_enum(data_enum, generics, attrs, ident),
         Data::Union(..) => panic!("union types may not be derived"),
     }
 }
@@ -35,15 +35,24 @@ pub fn derive_fixed_byte_size(token_stream: InterfaceTokenStream) -> InterfaceTo
 #[proc_macro_derive(
     ByteConvertable,
     attributes(
+        before,
+        build_number,
+        build_number_equals_or_above,
+        bulk,
+        discriminant,
+        flags,
         length,
         new_default,
         new_derive,
         new_value,
         numeric_type,
         numeric_value,
+        present_if,
         repeating,
         repeating_expr,
         repeating_option,
+        round_trip_prefix,
+        since,
         version,
         version_equals_or_above,
         version_smaller,
@@ -59,7 +68,7 @@ pub fn derive_byte_convertable(token_stream: InterfaceTokenStream) -> InterfaceT
     } = parse(token_stream).expect("failed to parse token stream");
 
     match data {
-        Data::Struct(data_struct) => derive_byte_convertable_struct(data_struct, generics, ident),
+        Data::Struct(data_struct) => derive_byte_convertable_struct(data_struct, generics, attrs, ident),
         Data::Enum(data_enum) => derive_byte_convertable_enum(data_enum, generics, attrs, ident),
         Data::Union(..) => panic!("union types may not be derived"),
     }
@@ -68,12 +77,20 @@ pub fn derive_byte_convertable(token_stream: InterfaceTokenStream) -> InterfaceT
 #[proc_macro_derive(
     FromBytes,
     attributes(
+        before,
+        build_number,
+        build_number_equals_or_above,
+        bulk,
+        discriminant,
+        flags,
         length,
         numeric_type,
         numeric_value,
+        present_if,
         repeating,
         repeating_expr,
         repeating_option,
+        since,
         version,
         version_equals_or_above,
         version_smaller,
@@ -89,7 +106,7 @@ pub fn derive_from_bytes(token_stream: InterfaceTokenStream) -> InterfaceTokenSt
     } = parse(token_stream).expect("failed to parse token stream");
 
     match data {
-        Data::Struct(data_struct) => derive_from_bytes_struct(data_struct, generics, ident),
+        Data::Struct(data_struct) => derive_from_bytes_struct(data_struct, generics, attrs, ident),
         Data::Enum(data_enum) => derive_from_bytes_enum(data_enum, generics, attrs, ident),
         Data::Union(..) => panic!("union types may not be derived"),
     }
@@ -98,12 +115,19 @@ pub fn derive_from_bytes(token_stream: InterfaceTokenStream) -> InterfaceTokenSt
 #[proc_macro_derive(
     ToBytes,
     attributes(
+        before,
+        build_number,
+        build_number_equals_or_above,
+        discriminant,
+        flags,
         length,
         new_default,
         new_derive,
         new_value,
         numeric_type,
         numeric_value,
+        present_if,
+        since,
         version,
         version_equals_or_above,
         version_smaller,
@@ -119,7 +143,7 @@ pub fn derive_to_bytes(token_stream: InterfaceTokenStream) -> InterfaceTokenStre
     } = parse(token_stream).expect("failed to parse token stream");
 
     match data {
-        Data::Struct(data_struct) => derive_to_bytes_struct(data_struct, generics, ident),
+        Data::Struct(data_struct) => derive_to_bytes_struct(data_struct, generics, attrs, ident),
         Data::Enum(data_enum) => derive_to_bytes_enum(data_enum, generics, attrs, ident),
         Data::Union(..) => panic!("union types may not be derived"),
     }
@@ -128,6 +152,8 @@ pub fn derive_to_bytes(token_stream: InterfaceTokenStream) -> InterfaceTokenStre
 #[proc_macro_derive(
     Packet,
     attributes(
+        before,
+        discriminant,
         header,
         length,
         length_remaining,
@@ -136,9 +162,11 @@ pub fn derive_to_bytes(token_stream: InterfaceTokenStream) -> InterfaceTokenStre
         new_derive,
         new_value,
         ping,
+        present_if,
         repeating,
         repeating_option,
         repeating_remaining,
+        since,
         variable_length,
     )
 )]
@@ -1,9 +1,9 @@
 use proc_macro::TokenStream as InterfaceTokenStream;
 use quote::quote;
-use syn::{Attribute, DataStruct, Generics, Ident};
+use syn::{Attribute, DataStruct, Field, GenericArgument, Generics, Ident, PathArguments, Type};
 
 use super::helper::byte_convertable_helper;
-use crate::utils::{get_unique_attribute, PacketSignature};
+use crate::utils::{get_attributes, get_unique_attribute, PacketSignature};
 
 pub fn derive_packet_struct(
     data_struct: DataStruct,
@@ -13,16 +13,62 @@ pub fn derive_packet_struct(
 ) -> InterfaceTokenStream {
     let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
 
-    let packet_signature = get_unique_attribute(&mut attributes, "header")
-        .map(|attribute| attribute.parse_args::<PacketSignature>())
-        .expect("packet needs to specify a signature")
-        .expect("failed to parse packet header");
+    let (mut versioned_signatures, base_signatures): (Vec<PacketSignature>, Vec<PacketSignature>) =
+        get_attributes(&mut attributes, "header")
+            .into_iter()
+            .map(|attribute| attribute.parse_args::<PacketSignature>().expect("failed to parse packet header"))
+            .partition(|packet_signature| packet_signature.since.is_some());
+    let [packet_signature] = base_signatures
+        .try_into()
+        .ok()
+        .expect("packet needs to specify exactly one header without a version");
     let is_ping = get_unique_attribute(&mut attributes, "ping").is_some();
     let is_variable_length = get_unique_attribute(&mut attributes, "variable_length").is_some();
 
     let signature = packet_signature.signature;
+
+    // headers that replace the base header since some packet version, ordered so
+    // that later versions take precedence
+    versioned_signatures.sort_by_key(|packet_signature| packet_signature.since);
+    let versioned_headers = versioned_signatures
+        .iter()
+        .map(|packet_signature| {
+            let since = packet_signature.since;
+            let signature = packet_signature.signature;
+            quote!((ragnarok_packets::PacketVersion(#since), ragnarok_packets::PacketHeader(#signature)))
+        })
+        .collect::<Vec<_>>();
+    let select_header = |packet_version: proc_macro2::TokenStream| {
+        quote! {
+            ragnarok_packets::PacketHeader::for_packet_version(
+                ragnarok_packets::PacketHeader(#signature),
+                &[#(#versioned_headers),*],
+                #packet_version,
+            )
+        }
+    };
+    let header = select_header(quote!(ragnarok_packets::PacketVersion::DEFAULT));
+    let header_function = (!versioned_signatures.is_empty()).then(|| {
+        let header = select_header(quote!(packet_version));
+
+        quote! {
+            fn header(packet_version: ragnarok_packets::PacketVersion) -> ragnarok_packets::PacketHeader {
+                #header
+            }
+        }
+    });
+    // packets without a length field have the combined size of their fields, which
+    // may still depend on the packet version
+    let length = match is_variable_length {
+        true => quote!(ragnarok_packets::PacketLength::Variable),
+        false => {
+            let sizes = data_struct.fields.iter().map(field_size);
+            quote!(ragnarok_packets::PacketLength::Fixed((2 #(+ #sizes)*) as u16))
+        }
+    };
+
     let (new_implementation, from_bytes_implementations, implemented_fields, to_bytes_implementations, delimiter) =
-        byte_convertable_helper(data_struct);
+        byte_convertable_helper(data_struct.fields, None);
 
     let instanciate = match delimiter {
         proc_macro2::Delimiter::Brace => quote!(Self { #(#implemented_fields),* }),
@@ -36,21 +82,27 @@ pub fn derive_packet_struct(
 
     let final_to_bytes = match is_variable_length {
         _ if to_bytes_implementations.is_empty() => quote! {
-            Ok(Vec::new())
+            let _ = byte_writer;
+            Ok(())
         },
         true => {
             quote! {
-                let following_bytes = [#(#to_bytes_implementations),*].concat();
-                let packet_length = following_bytes.len() as u16 + 4;
+                let length_offset = byte_writer.len();
+
+                // the packet length is only known after writing all fields
+                ragnarok_bytes::ToBytes::to_bytes(&0u16, byte_writer)?;
+                #(#to_bytes_implementations)*
 
-                let mut final_bytes = packet_length.to_bytes()?;
-                final_bytes.extend(following_bytes);
+                let packet_length = (byte_writer.len() - length_offset) as u16 + 2;
+                byte_writer.overwrite::<Self>(length_offset, &packet_length.to_le_bytes())?;
 
-                Ok(final_bytes)
+                Ok(())
             }
         }
         false => quote! {
-            Ok([#(#to_bytes_implementations),*].concat())
+            #(#to_bytes_implementations)*
+
+            Ok(())
         },
     };
 
@@ -61,9 +113,16 @@ pub fn derive_packet_struct(
 
         impl #impl_generics ragnarok_packets::Packet for #name #type_generics #where_clause {
             const IS_PING: bool = #is_ping;
-            const HEADER: ragnarok_packets::PacketHeader = ragnarok_packets::PacketHeader(#signature);
+            const HEADER: ragnarok_packets::PacketHeader = #header;
 
-            fn payload_from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta>) -> ragnarok_bytes::ConversionResult<Self> {
+            #header_function
+
+            fn length(packet_version: ragnarok_packets::PacketVersion) -> ragnarok_packets::PacketLength {
+                let _ = packet_version;
+                #length
+            }
+
+            fn payload_from_bytes<Meta>(byte_stream: &mut ragnarok_bytes::ByteStream<Meta, impl ragnarok_bytes::ByteSource>) -> ragnarok_bytes::ConversionResult<Self> {
                 let base_offset = byte_stream.get_offset();
                 #insert_packet_length
                 #(#from_bytes_implementations)*
@@ -72,7 +131,7 @@ pub fn derive_packet_struct(
                 Ok(packet)
             }
 
-            fn payload_to_bytes(&self) -> ragnarok_bytes::ConversionResult<Vec<u8>> {
+            fn payload_to_bytes<Meta>(&self, byte_writer: &mut ragnarok_bytes::ByteWriter<Meta>) -> ragnarok_bytes::ConversionResult<()> {
                 #final_to_bytes
             }
 
@@ -86,3 +145,59 @@ pub fn derive_packet_struct(
     }
     .into()
 }
+
+/// Size of a field of a fixed length packet in bytes. Fields that are
+/// restricted to some packet versions are only counted in those versions.
+fn field_size(field: &Field) -> proc_macro2::TokenStream {
+    let find_attribute = |name: &str| field.attrs.iter().find(|attribute| attribute.path().is_ident(name));
+    let packet_version =
+        |name: &str| find_attribute(name).map(|attribute| attribute.parse_args::<syn::LitInt>().expect("failed to parse packet version"));
+
+    let since = packet_version("since");
+    let before = packet_version("before");
+    let is_version_restricted = since.is_some() || before.is_some();
+
+    // version restricted fields are optional, so their size is the one of the inner
+    // type
+    let field_type = match is_version_restricted {
+        true => option_inner_type(&field.ty).expect("version restricted fields need to be optional"),
+        false => &field.ty,
+    };
+
+    let size = match find_attribute("length") {
+        Some(attribute) => match &attribute.meta {
+            syn::Meta::List(list) => {
+                let length = &list.tokens;
+                quote!(((#length) as usize))
+            }
+            syn::Meta::Path(_) | syn::Meta::NameValue(_) => panic!("expected token stream in attribute"),
+        },
+        None => quote!(<#field_type as ragnarok_bytes::FixedByteSize>::size_in_bytes()),
+    };
+
+    let since = since.map(|since| quote!(packet_version >= ragnarok_packets::PacketVersion(#since)));
+    let before = before.map(|before| quote!(packet_version < ragnarok_packets::PacketVersion(#before)));
+
+    match (since, before) {
+        (Some(since), Some(before)) => quote!(if #since && #before { #size } else { 0 }),
+        (Some(condition), None) | (None, Some(condition)) => quote!(if #condition { #size } else { 0 }),
+        (None, None) => size,
+    }
+}
+
+fn option_inner_type(field_type: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = field_type else {
+        return None;
+    };
+
+    let segment = type_path.path.segments.last().filter(|segment| segment.ident == "Option")?;
+
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first() {
+        Some(GenericArgument::Type(inner_type)) => Some(inner_type),
+        _ => None,
+    }
+}
@@ -1,17 +1,35 @@
 use proc_macro2::Punct;
 use syn::parse::{Parse, ParseStream};
-use syn::{Attribute, Error, LitInt};
+use syn::{Attribute, Error, Ident, LitInt, Token};
 
 #[derive(Clone)]
 pub struct PacketSignature {
     pub signature: u16,
+    /// First packet version that uses the signature.
+    pub since: Option<u32>,
 }
 
 impl Parse for PacketSignature {
     fn parse(input: ParseStream) -> Result<Self, Error> {
         let signature: LitInt = input.parse().expect("packet header must be u16");
+        let since = match input.is_empty() {
+            true => None,
+            false => {
+                input.parse::<Token![,]>()?;
+                let name: Ident = input.parse()?;
+
+                if name != "since" {
+                    return Err(Error::new(name.span(), "expected `since`"));
+                }
+
+                input.parse::<Token![=]>()?;
+                Some(input.parse::<LitInt>()?.base10_parse::<u32>()?)
+            }
+        };
+
         Ok(PacketSignature {
             signature: signature.base10_parse::<u16>()?,
+            since,
         })
     }
 }
@@ -41,3 +59,9 @@ pub fn get_unique_attribute(attributes: &mut Vec<Attribute>, name: &str) -> Opti
 
     return_attribute
 }
+
+pub fn get_attributes(attributes: &mut Vec<Attribute>, name: &str) -> Vec<Attribute> {
+    attributes
+        .extract_if(|attribute| attribute.path().segments[0].ident == name)
+        .collect()
+}